@@ -1,19 +1,20 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod transaction;
 
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::Utf8Error;
 
-use anyhow::Context;
 use anyhow::Result;
 use once_cell::sync::Lazy;
 pub use parser::Hunk;
 pub use parser::ParseError;
 use parser::ParseError::*;
-use parser::UpdateFileChunk;
+pub use parser::UpdateFileChunk;
 pub use parser::parse_patch;
 use similar::TextDiff;
 use thiserror::Error;
@@ -40,6 +41,14 @@ pub enum ApplyPatchError {
     /// Error that occurs while computing replacements when applying patch chunks
     #[error("{0}")]
     ComputeReplacements(String),
+    /// A hunk could not be applied to the current contents of the workspace.
+    /// `index` is 1-based. Nothing was written to disk.
+    #[error("Failed to apply hunk {index} ({header}); no files were modified: {source}")]
+    HunkFailed {
+        index: usize,
+        header: String,
+        source: Box<ApplyPatchError>,
+    },
 }
 
 impl From<std::io::Error> for ApplyPatchError {
@@ -515,13 +524,18 @@ pub fn apply_hunks(
         Err(err) => {
             let msg = err.to_string();
             writeln!(stderr, "{msg}").map_err(ApplyPatchError::from)?;
-            if let Some(io) = err.downcast_ref::<std::io::Error>() {
-                Err(ApplyPatchError::from(io))
-            } else {
-                Err(ApplyPatchError::IoError(IoError {
-                    context: msg,
-                    source: std::io::Error::other(err),
-                }))
+            match err.downcast::<ApplyPatchError>() {
+                Ok(err) => Err(err),
+                Err(err) => {
+                    if let Some(io) = err.downcast_ref::<std::io::Error>() {
+                        Err(ApplyPatchError::from(io))
+                    } else {
+                        Err(ApplyPatchError::IoError(IoError {
+                            context: msg,
+                            source: std::io::Error::other(err),
+                        }))
+                    }
+                }
             }
         }
     }
@@ -537,62 +551,56 @@ pub struct AffectedPaths {
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
+///
+/// Every hunk is checked against the current contents of the workspace before
+/// anything is written, and the resulting writes are committed together: if
+/// any of them fails, the workspace is left as it was.
 fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
+    let mut pending = PendingChanges::default();
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
-    for hunk in hunks {
-        match hunk {
+    for (index, hunk) in hunks.iter().enumerate() {
+        let result = match hunk {
             Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
+                pending.write(path, contents.clone());
                 added.push(path.clone());
+                Ok(())
             }
-            Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
-                    .with_context(|| format!("Failed to delete file {}", path.display()))?;
-                deleted.push(path.clone());
-            }
+            Hunk::DeleteFile { path } => pending.delete(path).map(|()| deleted.push(path.clone())),
             Hunk::UpdateFile {
                 path,
                 move_path,
                 chunks,
-            } => {
+            } => pending.read(path).and_then(|original_contents| {
                 let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                    derive_new_contents_from_original(path, original_contents, chunks)?;
                 if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
-                        .with_context(|| format!("Failed to remove original {}", path.display()))?;
+                    pending.write(dest, new_contents);
+                    pending.delete(path)?;
                     modified.push(dest.clone());
                 } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    pending.write(path, new_contents);
                     modified.push(path.clone());
                 }
+                Ok(())
+            }),
+        };
+        if let Err(source) = result {
+            return Err(ApplyPatchError::HunkFailed {
+                index: index + 1,
+                header: hunk.header(),
+                source: Box::new(source),
             }
+            .into());
         }
     }
+
+    transaction::commit(pending.into_ops())?;
     Ok(AffectedPaths {
         added,
         modified,
@@ -600,6 +608,78 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     })
 }
 
+/// In-memory view of the files touched so far by a patch, layered on top of
+/// the filesystem, so later hunks see the effect of earlier ones without
+/// anything being written yet.
+#[derive(Default)]
+struct PendingChanges {
+    /// Paths in the order they were first touched.
+    order: Vec<PathBuf>,
+    /// `None` marks a file deleted by the patch.
+    contents: HashMap<PathBuf, Option<String>>,
+}
+
+impl PendingChanges {
+    fn read(&self, path: &Path) -> std::result::Result<String, ApplyPatchError> {
+        match self.contents.get(path) {
+            Some(Some(contents)) => Ok(contents.clone()),
+            Some(None) => Err(ApplyPatchError::IoError(IoError {
+                context: format!("Failed to read file to update {}", path.display()),
+                source: std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "file was deleted by an earlier hunk",
+                ),
+            })),
+            None => std::fs::read_to_string(path).map_err(|err| {
+                ApplyPatchError::IoError(IoError {
+                    context: format!("Failed to read file to update {}", path.display()),
+                    source: err,
+                })
+            }),
+        }
+    }
+
+    fn write(&mut self, path: &Path, contents: String) {
+        self.set(path, Some(contents));
+    }
+
+    fn delete(&mut self, path: &Path) -> std::result::Result<(), ApplyPatchError> {
+        let exists = match self.contents.get(path) {
+            Some(contents) => contents.is_some(),
+            None => std::fs::symlink_metadata(path).is_ok_and(|m| !m.is_dir()),
+        };
+        if !exists {
+            return Err(ApplyPatchError::IoError(IoError {
+                context: format!("Failed to delete file {}", path.display()),
+                source: std::io::Error::new(std::io::ErrorKind::NotFound, "no such file"),
+            }));
+        }
+        self.set(path, None);
+        Ok(())
+    }
+
+    fn set(&mut self, path: &Path, contents: Option<String>) {
+        if self.contents.insert(path.to_path_buf(), contents).is_none() {
+            self.order.push(path.to_path_buf());
+        }
+    }
+
+    fn into_ops(mut self) -> Vec<transaction::FileOp> {
+        self.order
+            .into_iter()
+            .filter_map(|path| {
+                match self.contents.remove(&path)? {
+                    Some(contents) => Some(transaction::FileOp::Write { path, contents }),
+                    // A file added and then removed again by the same patch
+                    // never needs to reach the disk.
+                    None if std::fs::symlink_metadata(&path).is_err() => None,
+                    None => Some(transaction::FileOp::Delete { path }),
+                }
+            })
+            .collect()
+    }
+}
+
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
@@ -620,7 +700,16 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    derive_new_contents_from_original(path, original_contents, chunks)
+}
 
+/// Like [`derive_new_contents_from_chunks`], but starting from contents that
+/// have already been read.
+fn derive_new_contents_from_original(
+    path: &Path,
+    original_contents: String,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents
         .split('\n')
         .map(|s| s.to_string())
@@ -736,6 +825,49 @@ fn compute_replacements(
     Ok(replacements)
 }
 
+/// For each of `chunks`, the lines of the current contents of `path` that it
+/// changes, leaving out the context lines it repeats unchanged, as zero-based
+/// line ranges. A chunk that only inserts lines yields an empty range at the
+/// insertion point.
+pub fn changed_line_ranges(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<Vec<Range<usize>>, ApplyPatchError> {
+    let original_contents = std::fs::read_to_string(path).map_err(|err| {
+        ApplyPatchError::IoError(IoError {
+            context: format!("Failed to read file to update {}", path.display()),
+            source: err,
+        })
+    })?;
+    let mut original_lines: Vec<String> = original_contents
+        .split('\n')
+        .map(|s| s.to_string())
+        .collect();
+    if original_lines.last().is_some_and(|s| s.is_empty()) {
+        original_lines.pop();
+    }
+
+    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    Ok(replacements
+        .iter()
+        .map(|(start, old_len, new_lines)| {
+            let old_lines = &original_lines[*start..*start + *old_len];
+            let prefix = old_lines
+                .iter()
+                .zip(new_lines)
+                .take_while(|(old, new)| old == new)
+                .count();
+            let suffix = old_lines[prefix..]
+                .iter()
+                .rev()
+                .zip(new_lines[prefix..].iter().rev())
+                .take_while(|(old, new)| old == new)
+                .count();
+            start + prefix..start + old_len - suffix
+        })
+        .collect())
+}
+
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
 /// returning the modified file contents as a vector of lines.
 fn apply_replacements(
@@ -1545,4 +1677,132 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_failed_hunk_leaves_workspace_untouched() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.txt");
+        let second = dir.path().join("second.txt");
+        let added = dir.path().join("nested/added.txt");
+        fs::write(&first, "one\n").unwrap();
+        fs::write(&second, "two\n").unwrap();
+
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+-one
++uno
+*** Add File: {}
++new
+*** Update File: {}
+@@
+-missing
++gone"#,
+            first.display(),
+            added.display(),
+            second.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut stdout, &mut stderr);
+
+        match result {
+            Err(ApplyPatchError::HunkFailed { index, header, .. }) => {
+                assert_eq!(index, 3);
+                assert_eq!(header, format!("*** Update File: {}", second.display()));
+            }
+            other => panic!("expected HunkFailed, got {other:?}"),
+        }
+        let stderr_str = String::from_utf8(stderr).unwrap();
+        assert!(stderr_str.starts_with(&format!(
+            "Failed to apply hunk 3 (*** Update File: {}); no files were modified:",
+            second.display()
+        )));
+        assert_eq!(String::from_utf8(stdout).unwrap(), "");
+        assert_eq!(fs::read_to_string(&first).unwrap(), "one\n");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "two\n");
+        assert!(!dir.path().join("nested").exists());
+        let mut entries: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec!["first.txt", "second.txt"]);
+    }
+
+    #[test]
+    fn test_later_hunks_see_earlier_hunks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("new.txt");
+        let moved = dir.path().join("moved.txt");
+
+        let patch = wrap_patch(&format!(
+            r#"*** Add File: {}
++hello
+*** Update File: {}
+*** Move to: {}
+@@
+-hello
++world"#,
+            path.display(),
+            path.display(),
+            moved.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(String::from_utf8(stderr).unwrap(), "");
+        assert!(!path.exists());
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "world\n");
+    }
+
+    #[test]
+    fn test_delete_of_missing_file_fails_before_writing() {
+        let dir = tempdir().unwrap();
+        let kept = dir.path().join("kept.txt");
+        let missing = dir.path().join("missing.txt");
+        fs::write(&kept, "keep\n").unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Delete File: {}\n*** Delete File: {}",
+            kept.display(),
+            missing.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, &mut stdout, &mut stderr);
+
+        assert!(matches!(
+            result,
+            Err(ApplyPatchError::HunkFailed { index: 2, .. })
+        ));
+        assert_eq!(fs::read_to_string(&kept).unwrap(), "keep\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_update_preserves_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("script.sh");
+        fs::write(&path, "echo hi\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-echo hi\n+echo bye",
+            path.display()
+        ));
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "echo bye\n");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
 }
@@ -83,6 +83,15 @@ impl Hunk {
             Hunk::UpdateFile { path, .. } => cwd.join(path),
         }
     }
+
+    /// The header line that introduced this hunk, e.g. `*** Update File: foo.rs`.
+    pub fn header(&self) -> String {
+        match self {
+            Hunk::AddFile { path, .. } => format!("{ADD_FILE_MARKER}{}", path.display()),
+            Hunk::DeleteFile { path } => format!("{DELETE_FILE_MARKER}{}", path.display()),
+            Hunk::UpdateFile { path, .. } => format!("{UPDATE_FILE_MARKER}{}", path.display()),
+        }
+    }
 }
 
 use Hunk::*;
@@ -0,0 +1,216 @@
+//! Commits the file operations produced by a patch as a single unit.
+//!
+//! Every new file body is first staged in a temporary file that lives next to
+//! its destination. Only once all of them have been written successfully are
+//! they moved into place with `rename(2)`. Files that get replaced or deleted
+//! are moved aside (again with a rename) rather than removed, so if any step
+//! fails the workspace can be restored to exactly what it was before.
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use tempfile::TempPath;
+
+/// A single change to the filesystem after all hunks of a patch have been
+/// folded together. Each path appears at most once per transaction.
+#[derive(Debug)]
+pub(crate) enum FileOp {
+    Write { path: PathBuf, contents: String },
+    Delete { path: PathBuf },
+}
+
+/// Applies all `ops` or none of them.
+pub(crate) fn commit(ops: Vec<FileOp>) -> anyhow::Result<()> {
+    let mut txn = Transaction::default();
+    let result = txn.stage(ops).and_then(|staged| txn.commit(staged));
+    match result {
+        Ok(()) => {
+            txn.finish();
+            Ok(())
+        }
+        Err(err) => {
+            txn.rollback();
+            Err(err)
+        }
+    }
+}
+
+enum StagedOp {
+    Write { target: PathBuf, temp: TempPath },
+    Delete { target: PathBuf },
+}
+
+#[derive(Default)]
+struct Transaction {
+    /// Directories created while staging, in creation order.
+    created_dirs: Vec<PathBuf>,
+    /// `(original, backup)` pairs for files moved out of the way.
+    backups: Vec<(PathBuf, PathBuf)>,
+    /// Destinations that now hold content written by this transaction.
+    committed: Vec<PathBuf>,
+}
+
+impl Transaction {
+    fn stage(&mut self, ops: Vec<FileOp>) -> anyhow::Result<Vec<StagedOp>> {
+        let mut staged = Vec::with_capacity(ops.len());
+        for op in ops {
+            match op {
+                FileOp::Write { path, contents } => {
+                    let target = resolve_symlink(&path);
+                    let parent = parent_dir(&target);
+                    self.create_dir_all(parent).with_context(|| {
+                        format!("Failed to create parent directories for {}", path.display())
+                    })?;
+
+                    let existing = fs::metadata(&target).ok();
+                    if existing.is_some() {
+                        // Renaming over a file ignores its permission bits, so
+                        // make sure it would have been writable in place.
+                        OpenOptions::new()
+                            .write(true)
+                            .open(&target)
+                            .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    }
+                    let temp = new_temp_file(parent)
+                        .and_then(|mut temp| {
+                            temp.write_all(contents.as_bytes())?;
+                            if let Some(metadata) = &existing {
+                                fs::set_permissions(temp.path(), metadata.permissions())?;
+                            }
+                            temp.as_file().sync_all()?;
+                            Ok(temp)
+                        })
+                        .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    staged.push(StagedOp::Write {
+                        target,
+                        temp: temp.into_temp_path(),
+                    });
+                }
+                FileOp::Delete { path } => {
+                    let metadata = fs::symlink_metadata(&path)
+                        .with_context(|| format!("Failed to delete file {}", path.display()))?;
+                    if metadata.is_dir() {
+                        anyhow::bail!("Failed to delete file {}: is a directory", path.display());
+                    }
+                    staged.push(StagedOp::Delete { target: path });
+                }
+            }
+        }
+        Ok(staged)
+    }
+
+    fn commit(&mut self, staged: Vec<StagedOp>) -> anyhow::Result<()> {
+        for op in staged {
+            match op {
+                StagedOp::Write { target, temp } => {
+                    if fs::symlink_metadata(&target).is_ok() {
+                        self.move_aside(&target).with_context(|| {
+                            format!("Failed to write file {}", target.display())
+                        })?;
+                    }
+                    temp.persist(&target)
+                        .map_err(|err| err.error)
+                        .with_context(|| format!("Failed to write file {}", target.display()))?;
+                    self.committed.push(target);
+                }
+                StagedOp::Delete { target } => {
+                    self.move_aside(&target)
+                        .with_context(|| format!("Failed to delete file {}", target.display()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renames `target` to a fresh sibling path so it can be restored later.
+    fn move_aside(&mut self, target: &Path) -> std::io::Result<()> {
+        let backup = tempfile::Builder::new()
+            .prefix(".apply_patch.")
+            .suffix(".orig")
+            .tempfile_in(parent_dir(target))?
+            .into_temp_path()
+            .keep()
+            .map_err(|err| err.error)?;
+        if let Err(err) = fs::rename(target, &backup) {
+            let _ = fs::remove_file(&backup);
+            return Err(err);
+        }
+        self.backups.push((target.to_path_buf(), backup));
+        Ok(())
+    }
+
+    fn create_dir_all(&mut self, dir: &Path) -> std::io::Result<()> {
+        let mut missing = Vec::new();
+        let mut current = Some(dir);
+        while let Some(path) = current {
+            if path.as_os_str().is_empty() || path.exists() {
+                break;
+            }
+            missing.push(path.to_path_buf());
+            current = path.parent();
+        }
+        for path in missing.into_iter().rev() {
+            match fs::create_dir(&path) {
+                Ok(()) => self.created_dirs.push(path),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the backups once every change is in place.
+    fn finish(self) {
+        for (_, backup) in self.backups {
+            let _ = fs::remove_file(backup);
+        }
+    }
+
+    /// Best-effort restore of the state from before the transaction started.
+    fn rollback(self) {
+        for target in self.committed.iter().rev() {
+            let _ = fs::remove_file(target);
+        }
+        for (original, backup) in self.backups.iter().rev() {
+            let _ = fs::rename(backup, original);
+        }
+        for dir in self.created_dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Writing through a symlink should update the file it points at rather than
+/// replace the link itself.
+fn resolve_symlink(path: &Path) -> PathBuf {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Creates the file that will hold the new contents of a file in `dir`.
+fn new_temp_file(dir: &Path) -> std::io::Result<tempfile::NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(".apply_patch.").suffix(".tmp");
+    // Match what `std::fs::write` would produce for a new file (the umask
+    // still applies) instead of tempfile's owner-only default.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    builder.tempfile_in(dir)
+}
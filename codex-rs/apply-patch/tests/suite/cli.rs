@@ -88,3 +88,37 @@ fn test_apply_patch_cli_stdin_add_and_update() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_apply_patch_cli_failure_leaves_files_untouched() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let first = tmp.path().join("first.txt");
+    let second = tmp.path().join("second.txt");
+    fs::write(&first, "one\n")?;
+    fs::write(&second, "two\n")?;
+
+    let patch = r#"*** Begin Patch
+*** Update File: first.txt
+@@
+-one
++uno
+*** Update File: second.txt
+@@
+-not there
++dos
+*** End Patch"#;
+    let output = Command::cargo_bin("apply_patch")
+        .expect("should find apply_patch binary")
+        .arg(patch)
+        .current_dir(tmp.path())
+        .output()?;
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, "");
+    assert!(String::from_utf8(output.stderr)?.starts_with(
+        "Failed to apply hunk 2 (*** Update File: second.txt); no files were modified:"
+    ));
+    assert_eq!(fs::read_to_string(&first)?, "one\n");
+    assert_eq!(fs::read_to_string(&second)?, "two\n");
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 pub mod debug_sandbox;
 mod exit_status;
 pub mod login;
+pub mod models;
 pub mod proto;
+pub mod sessions;
 
 use clap::Parser;
 use codex_common::CliConfigOverrides;
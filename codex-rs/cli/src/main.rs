@@ -11,9 +11,15 @@ use codex_cli::login::run_login_status;
 use codex_cli::login::run_login_with_api_key;
 use codex_cli::login::run_login_with_chatgpt;
 use codex_cli::login::run_logout;
+use codex_cli::models::run_models_list;
+use codex_cli::models::run_models_pull;
+use codex_cli::models::run_models_remove;
+use codex_cli::models::run_models_show;
 use codex_cli::proto;
+use codex_cli::sessions::run_sessions_prune;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
+use codex_http_server::Cli as ServeCli;
 use codex_tui::Cli as TuiCli;
 use std::path::PathBuf;
 
@@ -56,9 +62,18 @@ enum Subcommand {
     /// Remove stored authentication credentials.
     Logout(LogoutCommand),
 
+    /// Manage recorded sessions and message history.
+    Sessions(SessionsCommand),
+
+    /// Manage models on the local server used by `--oss` (Ollama, llama.cpp, vLLM, LM Studio).
+    Models(ModelsCommand),
+
     /// Experimental: run Codex as an MCP server.
     Mcp,
 
+    /// Experimental: serve conversations over HTTP, streaming events as Server-Sent Events.
+    Serve(ServeCli),
+
     /// Run the Protocol stream via stdin/stdout
     #[clap(visible_alias = "p")]
     Proto(ProtoCli),
@@ -124,6 +139,60 @@ struct LogoutCommand {
     config_overrides: CliConfigOverrides,
 }
 
+#[derive(Debug, Parser)]
+struct SessionsCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    action: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum SessionsSubcommand {
+    /// Apply the `[history]` and `[sessions]` retention limits now.
+    Prune {
+        /// Report what would be removed without changing anything.
+        #[arg(long = "dry-run", default_value_t = false)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Parser)]
+struct ModelsCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    action: ModelsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum ModelsSubcommand {
+    /// List local models with their sizes and context windows.
+    #[clap(visible_alias = "ls")]
+    List,
+
+    /// Download a model (Ollama only).
+    Pull {
+        /// Name of the model, e.g. `gpt-oss:20b`.
+        model: String,
+    },
+
+    /// Delete a downloaded model (Ollama only).
+    #[clap(visible_alias = "remove")]
+    Rm {
+        /// Name of the model.
+        model: String,
+    },
+
+    /// Show a model's size, context window and whether it supports tool calling.
+    Show {
+        /// Name of the model.
+        model: String,
+    },
+}
+
 #[derive(Debug, Parser)]
 struct GenerateTsCommand {
     /// Output directory where .ts files will be written
@@ -180,6 +249,28 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut logout_cli.config_overrides, cli.config_overrides);
             run_logout(logout_cli.config_overrides).await;
         }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(&mut sessions_cli.config_overrides, cli.config_overrides);
+            match sessions_cli.action {
+                SessionsSubcommand::Prune { dry_run } => {
+                    run_sessions_prune(sessions_cli.config_overrides, dry_run).await;
+                }
+            }
+        }
+        Some(Subcommand::Models(mut models_cli)) => {
+            prepend_config_flags(&mut models_cli.config_overrides, cli.config_overrides);
+            let overrides = models_cli.config_overrides;
+            match models_cli.action {
+                ModelsSubcommand::List => run_models_list(overrides).await,
+                ModelsSubcommand::Pull { model } => run_models_pull(overrides, model).await,
+                ModelsSubcommand::Rm { model } => run_models_remove(overrides, model).await,
+                ModelsSubcommand::Show { model } => run_models_show(overrides, model).await,
+            }
+        }
+        Some(Subcommand::Serve(mut serve_cli)) => {
+            prepend_config_flags(&mut serve_cli.config_overrides, cli.config_overrides);
+            codex_http_server::run_main(serve_cli, codex_linux_sandbox_exe).await?;
+        }
         Some(Subcommand::Proto(mut proto_cli)) => {
             prepend_config_flags(&mut proto_cli.config_overrides, cli.config_overrides);
             proto::run_main(proto_cli).await?;
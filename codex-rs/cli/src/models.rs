@@ -0,0 +1,173 @@
+use codex_common::CliConfigOverrides;
+use codex_ollama::CliProgressReporter;
+use codex_ollama::LocalModel;
+use codex_ollama::OllamaClient;
+use codex_ollama::ServerKind;
+
+use crate::login::load_config_or_exit;
+use crate::sessions::format_bytes;
+
+pub async fn run_models_list(cli_config_overrides: CliConfigOverrides) -> ! {
+    let client = connect_or_exit(cli_config_overrides).await;
+    match client.list_models().await {
+        Ok(models) => {
+            println!("{}", format_model_table(client.server_kind(), &models));
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error listing models: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_models_pull(cli_config_overrides: CliConfigOverrides, model: String) -> ! {
+    let client = connect_or_exit(cli_config_overrides).await;
+    let mut reporter = CliProgressReporter::new();
+    match client.pull_with_reporter(&model, &mut reporter).await {
+        Ok(()) => std::process::exit(0),
+        Err(e) => {
+            eprintln!("Error pulling {model}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_models_remove(cli_config_overrides: CliConfigOverrides, model: String) -> ! {
+    let client = connect_or_exit(cli_config_overrides).await;
+    match client.delete_model(&model).await {
+        Ok(()) => {
+            println!("Removed {model}");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error removing {model}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn run_models_show(cli_config_overrides: CliConfigOverrides, model: String) -> ! {
+    let client = connect_or_exit(cli_config_overrides).await;
+    match client.show_model(&model).await {
+        Ok(Some(details)) => {
+            println!("{}", format_model_details(client.server_kind(), &details));
+            std::process::exit(0);
+        }
+        Ok(None) => {
+            eprintln!("{} does not have {model}", client.server_kind());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error inspecting {model}: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Connect to the server behind the `oss` provider, which honors
+/// `CODEX_OSS_BASE_URL` and `[model_providers.oss]` overrides.
+async fn connect_or_exit(cli_config_overrides: CliConfigOverrides) -> OllamaClient {
+    let config = load_config_or_exit(cli_config_overrides);
+    match OllamaClient::try_from_oss_provider(&config).await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn format_model_table(kind: ServerKind, models: &[LocalModel]) -> String {
+    if models.is_empty() {
+        return format!("{kind} has no models");
+    }
+    let rows: Vec<[String; 3]> = models
+        .iter()
+        .map(|m| {
+            [
+                m.name.clone(),
+                m.size_bytes.map(format_bytes).unwrap_or_else(|| "-".into()),
+                m.context_window
+                    .map(|c| c.to_string())
+                    .unwrap_or_else(|| "-".into()),
+            ]
+        })
+        .collect();
+    let name_width = rows.iter().map(|r| r[0].len()).max().unwrap_or(0).max(4);
+    let size_width = rows.iter().map(|r| r[1].len()).max().unwrap_or(0).max(4);
+    let mut lines = vec![format!(
+        "{:<name_width$}  {:<size_width$}  CONTEXT",
+        "NAME", "SIZE"
+    )];
+    for [name, size, context] in rows {
+        lines.push(format!(
+            "{name:<name_width$}  {size:<size_width$}  {context}"
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_model_details(kind: ServerKind, model: &LocalModel) -> String {
+    let size = model
+        .size_bytes
+        .map(format_bytes)
+        .unwrap_or_else(|| "unknown".into());
+    let context = model
+        .context_window
+        .map(|c| format!("{c} tokens"))
+        .unwrap_or_else(|| "unknown".into());
+    let tools = match model.supports_tools {
+        Some(true) => "yes".to_string(),
+        Some(false) => match kind.tool_calling_hint() {
+            Some(hint) => format!("no ({hint})"),
+            None => "no".to_string(),
+        },
+        None => "unknown".to_string(),
+    };
+    format!(
+        "name: {}\nserver: {kind}\nsize: {size}\ncontext window: {context}\ntool calling: {tools}",
+        model.name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn model(name: &str, size_bytes: Option<u64>, context_window: Option<u64>) -> LocalModel {
+        LocalModel {
+            name: name.to_string(),
+            size_bytes,
+            context_window,
+            supports_tools: None,
+        }
+    }
+
+    #[test]
+    fn table_aligns_names_sizes_and_context_windows() {
+        let models = vec![
+            model("gpt-oss:20b", Some(13 * 1024 * 1024 * 1024), Some(131072)),
+            model("mistral", None, None),
+        ];
+        assert_eq!(
+            format_model_table(ServerKind::Ollama, &models),
+            "NAME         SIZE      CONTEXT\ngpt-oss:20b  13.0 GiB  131072\nmistral      -         -"
+        );
+        assert_eq!(
+            format_model_table(ServerKind::LlamaCpp, &[]),
+            "llama.cpp has no models"
+        );
+    }
+
+    #[test]
+    fn details_explain_how_to_enable_tool_calling() {
+        let mut details = model("qwen2.5-coder.gguf", None, Some(16384));
+        details.supports_tools = Some(false);
+        assert_eq!(
+            format_model_details(ServerKind::LlamaCpp, &details),
+            "name: qwen2.5-coder.gguf\nserver: llama.cpp\nsize: unknown\ncontext window: 16384 tokens\ntool calling: no (start llama-server with --jinja)"
+        );
+    }
+}
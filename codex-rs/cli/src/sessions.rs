@@ -0,0 +1,84 @@
+use codex_common::CliConfigOverrides;
+use codex_core::retention::PruneReport;
+use codex_core::retention::prune;
+
+use crate::login::load_config_or_exit;
+
+pub async fn run_sessions_prune(cli_config_overrides: CliConfigOverrides, dry_run: bool) -> ! {
+    let config = load_config_or_exit(cli_config_overrides);
+
+    match prune(&config, dry_run).await {
+        Ok(report) => {
+            println!("{}", format_report(&report, dry_run));
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error pruning sessions: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn format_report(report: &PruneReport, dry_run: bool) -> String {
+    let PruneReport { history, sessions } = report;
+    let (removed, compressed, deleted, freed) = if dry_run {
+        (
+            "would remove",
+            "would compress",
+            "would delete",
+            "would free",
+        )
+    } else {
+        ("removed", "compressed", "deleted", "freed")
+    };
+    format!(
+        "history.jsonl: {removed} {} entries\nsessions: {compressed} {} and {deleted} {} rollouts\n{freed} {}",
+        history.removed_entries,
+        sessions.compressed,
+        sessions.deleted,
+        format_bytes(history.freed_bytes + sessions.freed_bytes),
+    )
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::retention::HistoryPruned;
+    use codex_core::retention::SessionsPruned;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn report_lists_each_store_and_the_space_freed() {
+        let report = PruneReport {
+            history: HistoryPruned {
+                removed_entries: 3,
+                freed_bytes: 512,
+            },
+            sessions: SessionsPruned {
+                compressed: 2,
+                deleted: 5,
+                freed_bytes: 3 * 1024 * 1024 - 512,
+            },
+        };
+        assert_eq!(
+            format_report(&report, false),
+            "history.jsonl: removed 3 entries\nsessions: compressed 2 and deleted 5 rollouts\nfreed 3.0 MiB"
+        );
+        assert!(format_report(&report, true).starts_with("history.jsonl: would remove 3 entries"));
+        assert_eq!(format_bytes(10), "10 B");
+    }
+}
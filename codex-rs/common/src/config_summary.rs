@@ -15,10 +15,11 @@ pub fn create_config_summary_entries(config: &Config) -> Vec<(&'static str, Stri
     if config.model_provider.wire_api == WireApi::Responses
         && config.model_family.supports_reasoning_summaries
     {
-        entries.push((
-            "reasoning effort",
-            config.model_reasoning_effort.to_string(),
-        ));
+        let effort = match config.auto_reasoning_effort {
+            Some(bounds) => format!("auto ({}-{})", bounds.min, bounds.max),
+            None => config.model_reasoning_effort.to_string(),
+        };
+        entries.push(("reasoning effort", effort));
         entries.push((
             "reasoning summaries",
             config.model_reasoning_summary.to_string(),
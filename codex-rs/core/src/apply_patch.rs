@@ -1,14 +1,23 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::codex::rejected_with_feedback;
 use crate::protocol::FileChange;
+use crate::protocol::RejectedChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::UpdateFileChunk;
+use codex_apply_patch::changed_line_ranges;
+use codex_apply_patch::maybe_parse_apply_patch_verified;
+use codex_apply_patch::parse_patch;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::ops::Range;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
@@ -31,6 +40,9 @@ pub(crate) enum InternalApplyPatchInvocation {
 pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) user_explicitly_approved_this_action: bool,
+    /// When the user approved only part of the patch, a description of the
+    /// parts that were left out, to be reported back to the model.
+    pub(crate) rejected_changes: Option<String>,
 }
 
 impl From<ResponseInputItem> for InternalApplyPatchInvocation {
@@ -56,6 +68,7 @@ pub(crate) async fn apply_patch(
             InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                 action,
                 user_explicitly_approved_this_action: false,
+                rejected_changes: None,
             })
         }
         SafetyCheck::AskUser => {
@@ -70,22 +83,66 @@ pub(crate) async fn apply_patch(
                 .request_patch_approval(sub_id.to_owned(), call_id.to_owned(), &action, None, None)
                 .await;
             match rx_approve.await.unwrap_or_default() {
-                ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedForPrefix { .. } => {
                     InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                         action,
                         user_explicitly_approved_this_action: true,
+                        rejected_changes: None,
                     })
                 }
-                ReviewDecision::Denied | ReviewDecision::Abort => {
+                ReviewDecision::ApprovedPartially { rejected } => {
+                    match select_changes(action, &rejected) {
+                        Ok(Some((action, rejected_changes))) => {
+                            InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                                action,
+                                user_explicitly_approved_this_action: true,
+                                rejected_changes,
+                            })
+                        }
+                        Ok(None) => ResponseInputItem::FunctionCallOutput {
+                            call_id: call_id.to_owned(),
+                            output: FunctionCallOutputPayload {
+                                content: "patch rejected by user".to_string(),
+                                success: Some(false),
+                            },
+                        }
+                        .into(),
+                        Err(e) => ResponseInputItem::FunctionCallOutput {
+                            call_id: call_id.to_owned(),
+                            output: FunctionCallOutputPayload {
+                                content: format!(
+                                    "the user approved part of the patch, but it could not be applied on its own: {e}"
+                                ),
+                                success: Some(false),
+                            },
+                        }
+                        .into(),
+                    }
+                }
+                ReviewDecision::DeniedWithFeedback { feedback } => {
                     ResponseInputItem::FunctionCallOutput {
                         call_id: call_id.to_owned(),
                         output: FunctionCallOutputPayload {
-                            content: "patch rejected by user".to_string(),
+                            content: rejected_with_feedback("patch", &feedback),
                             success: Some(false),
                         },
                     }
                     .into()
                 }
+                // Patches are approved or rejected as a whole; an edited
+                // command makes no sense here, so be conservative.
+                ReviewDecision::ApprovedWithEdits { .. }
+                | ReviewDecision::Denied
+                | ReviewDecision::Abort => ResponseInputItem::FunctionCallOutput {
+                    call_id: call_id.to_owned(),
+                    output: FunctionCallOutputPayload {
+                        content: "patch rejected by user".to_string(),
+                        success: Some(false),
+                    },
+                }
+                .into(),
             }
         }
         SafetyCheck::Reject { reason } => ResponseInputItem::FunctionCallOutput {
@@ -99,6 +156,254 @@ pub(crate) async fn apply_patch(
     }
 }
 
+/// Build the action that applies only the parts of `action` the user did not
+/// reject, along with a description of the rejected parts for the model.
+///
+/// Hunk indices in `rejected` refer to the hunks of each file's
+/// `unified_diff`, which is what clients show the user. The chunks of the
+/// original patch that change lines inside a rejected hunk are dropped and
+/// the others are kept verbatim. Returns `Ok(None)` when nothing is left to
+/// apply.
+fn select_changes(
+    action: ApplyPatchAction,
+    rejected: &[RejectedChange],
+) -> Result<Option<(ApplyPatchAction, Option<String>)>, String> {
+    let mut paths: Vec<&PathBuf> = action.changes().keys().collect();
+    paths.sort();
+
+    let parsed = parse_patch(&action.patch).map_err(|e| e.to_string())?;
+    let chunks_by_path: HashMap<PathBuf, &[UpdateFileChunk]> = parsed
+        .hunks
+        .iter()
+        .filter_map(|hunk| match hunk {
+            codex_apply_patch::Hunk::UpdateFile { chunks, .. } => {
+                Some((hunk.resolve_path(&action.cwd), chunks.as_slice()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    let mut patch = String::from("*** Begin Patch\n");
+    let mut notes = String::new();
+    let mut applied_any = false;
+    for path in paths {
+        let Some(change) = action.changes().get(path) else {
+            continue;
+        };
+        let rejection = rejected.iter().find(|r| &r.path == path);
+        let display = path
+            .strip_prefix(&action.cwd)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+
+        if let Some(RejectedChange { hunks: None, .. }) = rejection {
+            let _ = writeln!(notes, "- {display}: the whole file");
+            continue;
+        }
+
+        match change {
+            ApplyPatchFileChange::Add { content } => {
+                let _ = writeln!(patch, "*** Add File: {display}");
+                for line in content.lines() {
+                    let _ = writeln!(patch, "+{line}");
+                }
+            }
+            ApplyPatchFileChange::Delete { .. } => {
+                let _ = writeln!(patch, "*** Delete File: {display}");
+            }
+            ApplyPatchFileChange::Update {
+                unified_diff,
+                move_path,
+                ..
+            } => {
+                let chunks = chunks_by_path
+                    .get(path)
+                    .copied()
+                    .ok_or_else(|| format!("no changes to {display} found in the patch"))?;
+                let hunks = split_hunks(unified_diff);
+                let rejected_hunks: &[usize] = rejection
+                    .and_then(|r| r.hunks.as_deref())
+                    .unwrap_or_default();
+
+                // A chunk is dropped when it changes lines of a rejected hunk;
+                // any other hunk it touches is then left out as well.
+                let hunk_lines: Vec<Option<Range<usize>>> =
+                    hunks.iter().map(Hunk::changed_lines).collect();
+                let chunk_lines = changed_line_ranges(path, chunks).map_err(|e| e.to_string())?;
+                let mut skipped_hunks: Vec<usize> = rejected_hunks.to_vec();
+                let mut accepted: Vec<&UpdateFileChunk> = Vec::new();
+                for (chunk, lines) in chunks.iter().zip(&chunk_lines) {
+                    let touched: Vec<usize> = hunk_lines
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, hunk)| {
+                            hunk.as_ref().is_some_and(|hunk| {
+                                lines.start <= hunk.end && hunk.start <= lines.end
+                            })
+                        })
+                        .map(|(idx, _)| idx)
+                        .collect();
+                    if touched.iter().any(|idx| rejected_hunks.contains(idx)) {
+                        skipped_hunks.extend(touched);
+                    } else {
+                        accepted.push(chunk);
+                    }
+                }
+
+                if accepted.len() < chunks.len() {
+                    let _ = writeln!(notes, "- {display}:");
+                    for (idx, hunk) in hunks.iter().enumerate() {
+                        if skipped_hunks.contains(&idx) {
+                            let _ = writeln!(notes, "```diff\n{}\n```", hunk.text());
+                        }
+                    }
+                }
+                if accepted.is_empty() {
+                    continue;
+                }
+                let _ = writeln!(patch, "*** Update File: {display}");
+                if let Some(move_path) = move_path {
+                    let _ = writeln!(patch, "*** Move to: {}", move_path.display());
+                }
+                for chunk in accepted {
+                    write_chunk(&mut patch, chunk);
+                }
+            }
+        }
+        applied_any = true;
+    }
+    patch.push_str("*** End Patch\n");
+
+    if !applied_any {
+        return Ok(None);
+    }
+    if notes.is_empty() {
+        return Ok(Some((action, None)));
+    }
+
+    let argv = vec!["apply_patch".to_string(), patch];
+    let action = match maybe_parse_apply_patch_verified(&argv, &action.cwd) {
+        MaybeApplyPatchVerified::Body(action) => action,
+        MaybeApplyPatchVerified::CorrectnessError(e) => return Err(e.to_string()),
+        MaybeApplyPatchVerified::ShellParseError(e) => return Err(format!("{e:?}")),
+        MaybeApplyPatchVerified::NotApplyPatch => {
+            return Err("the selected changes do not form a valid patch".to_string());
+        }
+    };
+    let notes = format!(
+        "The user rejected part of this patch. The following changes were NOT applied:\n{notes}"
+    );
+    Ok(Some((action, Some(notes))))
+}
+
+/// A hunk of a unified diff: its `@@` header and the lines that follow it.
+struct Hunk<'a> {
+    header: &'a str,
+    body: Vec<&'a str>,
+}
+
+impl Hunk<'_> {
+    fn text(&self) -> String {
+        std::iter::once(self.header)
+            .chain(self.body.iter().copied())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Hunk<'_> {
+    /// The zero-based range of old lines this hunk removes or inserts before,
+    /// leaving out its context lines. `None` if the header is malformed.
+    fn changed_lines(&self) -> Option<Range<usize>> {
+        let old = self.header.strip_prefix("@@ -")?.split(' ').next()?;
+        let (start, len) = match old.split_once(',') {
+            Some((start, len)) => (start.parse::<usize>().ok()?, len.parse::<usize>().ok()?),
+            None => (old.parse::<usize>().ok()?, 1),
+        };
+        // An empty old side names the line after which lines are inserted.
+        let mut line = if len == 0 {
+            start
+        } else {
+            start.saturating_sub(1)
+        };
+        let mut changed: Option<Range<usize>> = None;
+        for body_line in &self.body {
+            let (first, end) = match body_line.chars().next() {
+                Some('-') => (line, line + 1),
+                Some('+') => (line, line),
+                _ => {
+                    line += 1;
+                    continue;
+                }
+            };
+            changed = Some(match changed {
+                Some(range) => range.start.min(first)..range.end.max(end),
+                None => first..end,
+            });
+            if end > line {
+                line = end;
+            }
+        }
+        changed
+    }
+}
+
+/// Write `chunk` in patch syntax, keeping its change context and every line
+/// it replaces, so that it applies exactly as it would have in the original
+/// patch.
+fn write_chunk(patch: &mut String, chunk: &UpdateFileChunk) {
+    match &chunk.change_context {
+        Some(context) => {
+            let _ = writeln!(patch, "@@ {context}");
+        }
+        None => patch.push_str("@@\n"),
+    }
+    let old = &chunk.old_lines;
+    let new = &chunk.new_lines;
+    let prefix = old.iter().zip(new).take_while(|(o, n)| o == n).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+    for line in &old[..prefix] {
+        let _ = writeln!(patch, " {line}");
+    }
+    for line in &old[prefix..old.len() - suffix] {
+        let _ = writeln!(patch, "-{line}");
+    }
+    for line in &new[prefix..new.len() - suffix] {
+        let _ = writeln!(patch, "+{line}");
+    }
+    for line in &old[old.len() - suffix..] {
+        let _ = writeln!(patch, " {line}");
+    }
+    if chunk.is_end_of_file {
+        patch.push_str("*** End of File\n");
+    }
+}
+
+fn split_hunks(unified_diff: &str) -> Vec<Hunk<'_>> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for line in unified_diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push(Hunk {
+                header: line,
+                body: Vec::new(),
+            });
+        } else if let Some(hunk) = hunks.last_mut()
+            // "\ No newline at end of file" has no equivalent in the patch
+            // format, which always ends files with a newline.
+            && !line.starts_with('\\')
+        {
+            hunk.body.push(line);
+        }
+    }
+    hunks
+}
+
 pub(crate) fn convert_apply_patch_to_protocol(
     action: &ApplyPatchAction,
 ) -> HashMap<PathBuf, FileChange> {
@@ -125,3 +430,109 @@ pub(crate) fn convert_apply_patch_to_protocol(
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn parse(patch: &str, cwd: &std::path::Path) -> ApplyPatchAction {
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        match maybe_parse_apply_patch_verified(&argv, cwd) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected a valid patch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn select_changes_drops_rejected_files_and_hunks() {
+        let dir = TempDir::new().unwrap();
+        let lines: Vec<String> = (1..=12).map(|i| format!("line {i}")).collect();
+        std::fs::write(dir.path().join("a.txt"), lines.join("\n") + "\n").unwrap();
+        let action = parse(
+            "*** Begin Patch\n\
+             *** Update File: a.txt\n\
+             @@\n line 1\n-line 2\n+line two\n line 3\n\
+             @@\n line 10\n-line 11\n+line eleven\n line 12\n\
+             *** Add File: b.txt\n+new\n\
+             *** End Patch",
+            dir.path(),
+        );
+
+        let rejected = vec![
+            RejectedChange {
+                path: dir.path().join("a.txt"),
+                hunks: Some(vec![1]),
+            },
+            RejectedChange {
+                path: dir.path().join("b.txt"),
+                hunks: None,
+            },
+        ];
+        let (selected, notes) = select_changes(action, &rejected).unwrap().unwrap();
+
+        let changes = selected.changes();
+        assert_eq!(changes.len(), 1);
+        let Some(ApplyPatchFileChange::Update { new_content, .. }) =
+            changes.get(&dir.path().join("a.txt"))
+        else {
+            panic!("expected an update of a.txt");
+        };
+        let mut expected = lines.clone();
+        expected[1] = "line two".to_string();
+        assert_eq!(new_content, &(expected.join("\n") + "\n"));
+
+        let notes = notes.unwrap();
+        assert!(notes.contains("- b.txt: the whole file"), "{notes}");
+        assert!(notes.contains("+line eleven"), "{notes}");
+        assert!(!notes.contains("+line two"), "{notes}");
+    }
+
+    #[test]
+    fn select_changes_keeps_the_change_context_of_accepted_chunks() {
+        let dir = TempDir::new().unwrap();
+        let block = |name: &str, call: &str| format!("// {name}\n{{\n    {call};\n}}\n");
+        let source = block("a", "x()") + &block("b", "x()");
+        std::fs::write(dir.path().join("lib.rs"), &source).unwrap();
+        // Both blocks look the same: only the change context tells them apart.
+        let action = parse(
+            "*** Begin Patch\n\
+             *** Update File: lib.rs\n\
+             @@ // a\n-    x();\n+    one();\n\
+             @@ // b\n-    x();\n+    two();\n\
+             *** End Patch",
+            dir.path(),
+        );
+
+        let rejected = vec![RejectedChange {
+            path: dir.path().join("lib.rs"),
+            hunks: Some(vec![0]),
+        }];
+        let (selected, notes) = select_changes(action, &rejected).unwrap().unwrap();
+
+        let Some(ApplyPatchFileChange::Update { new_content, .. }) =
+            selected.changes().get(&dir.path().join("lib.rs"))
+        else {
+            panic!("expected an update of lib.rs");
+        };
+        assert_eq!(new_content, &(block("a", "x()") + &block("b", "two()")));
+        let notes = notes.unwrap();
+        assert!(notes.contains("+    one();"), "{notes}");
+        assert!(!notes.contains("+    two();"), "{notes}");
+    }
+
+    #[test]
+    fn select_changes_with_everything_rejected_applies_nothing() {
+        let dir = TempDir::new().unwrap();
+        let action = parse(
+            "*** Begin Patch\n*** Add File: b.txt\n+new\n*** End Patch",
+            dir.path(),
+        );
+        let rejected = vec![RejectedChange {
+            path: dir.path().join("b.txt"),
+            hunks: None,
+        }];
+        assert!(select_changes(action, &rejected).unwrap().is_none());
+    }
+}
@@ -0,0 +1,500 @@
+//! Persisted command approval rules.
+//!
+//! When the user approves a command "for this prefix", the rule is written to
+//! `CODEX_HOME/approval_rules.toml` so that matching commands are approved
+//! automatically in future sessions as well:
+//!
+//! ```toml
+//! [[rules]]
+//! prefix = ["npm", "test"]
+//!
+//! [[rules]]
+//! prefix = ["cargo", "test"]
+//! project = "/home/me/src/app"
+//! ```
+//!
+//! Rules without a `project` apply everywhere; the others only apply to
+//! commands run inside that project.
+//!
+//! Several sessions may add or remove rules at the same time, so every update
+//! re-reads the file while holding `approval_rules.toml.lock` and applies the
+//! change to what is on disk rather than to this session's copy.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::ApprovalRule;
+use codex_protocol::protocol::ApprovalRuleScope;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::bash::try_parse_bash;
+use crate::bash::try_parse_word_only_commands_sequence;
+use crate::git_info::resolve_root_git_project_for_trust;
+
+/// Name of the rules file inside `CODEX_HOME`.
+pub const APPROVAL_RULES_FILENAME: &str = "approval_rules.toml";
+
+/// Programs that run whatever script or code they are given. Approving one of
+/// these on its own would approve arbitrary code, so a suggested prefix must
+/// also name the script or module to run.
+const INTERPRETERS: &[&str] = &[
+    "bash", "sh", "zsh", "dash", "fish", "python", "python2", "python3", "node", "deno", "bun",
+    "ruby", "perl", "php", "lua",
+];
+
+/// Programs that run another command given in their arguments, such as
+/// `env python3 -c ...` or `xargs sh -c ...`. A prefix rule for one of these
+/// would approve whatever it ends up running, so none is suggested.
+const WRAPPERS: &[&str] = &[
+    "env", "xargs", "sudo", "doas", "su", "nice", "ionice", "nohup", "timeout", "time", "stdbuf",
+    "chroot", "exec", "command", "watch", "strace", "ltrace", "npx", "pnpx", "bunx", "uvx",
+];
+
+/// Sub-commands that run an arbitrary program, like `uv run`.
+const WRAPPER_SUBCOMMANDS: &[(&str, &str)] = &[
+    ("uv", "run"),
+    ("poetry", "run"),
+    ("pipenv", "run"),
+    ("npm", "exec"),
+    ("pnpm", "exec"),
+    ("pnpm", "dlx"),
+    ("yarn", "dlx"),
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<ApprovalRule>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ApprovalRules {
+    path: PathBuf,
+    rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalRules {
+    /// Load the rules stored under `codex_home`. A missing file means no
+    /// rules; a malformed one is logged and ignored rather than failing the
+    /// session.
+    pub(crate) fn load(codex_home: &Path) -> Self {
+        let path = codex_home.join(APPROVAL_RULES_FILENAME);
+        let rules = read_rules(&path).unwrap_or_else(|e| {
+            warn!("failed to load {}: {e}", path.display());
+            Vec::new()
+        });
+        Self { path, rules }
+    }
+
+    /// Rules that apply to commands run in `cwd`.
+    pub(crate) fn rules_for(&self, cwd: &Path) -> Vec<ApprovalRule> {
+        let project = self.needs_project().then(|| project_root(cwd));
+        self.rules
+            .iter()
+            .filter(|rule| applies_in(rule, project.as_deref()))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns `true` if every command that `command` would run starts with
+    /// the prefix of a rule that applies in `cwd`.
+    ///
+    /// `bash -lc` scripts are split into their individual commands, and are
+    /// only approved when the script consists solely of plain commands joined
+    /// by `&&`, `||`, `;` or `|`; anything fancier never matches a rule.
+    pub(crate) fn allows(&self, command: &[String], cwd: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let Some(commands) = split_commands(command) else {
+            return false;
+        };
+        let project = self.needs_project().then(|| project_root(cwd));
+        commands.iter().all(|cmd| {
+            self.rules
+                .iter()
+                .any(|rule| applies_in(rule, project.as_deref()) && matches_prefix(rule, cmd))
+        })
+    }
+
+    /// Add a rule and persist the updated list. Adding a rule that already
+    /// exists is a no-op.
+    pub(crate) fn add(&mut self, rule: ApprovalRule) -> io::Result<()> {
+        if rule.prefix.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "approval rule prefix must not be empty",
+            ));
+        }
+        self.update(|rules| {
+            if rules.contains(&rule) {
+                return false;
+            }
+            rules.push(rule);
+            true
+        })
+        .map(|_| ())
+    }
+
+    /// Remove a rule and persist the updated list. Returns `false` if no such
+    /// rule existed.
+    pub(crate) fn remove(&mut self, rule: &ApprovalRule) -> io::Result<bool> {
+        self.update(|rules| {
+            let before = rules.len();
+            rules.retain(|r| r != rule);
+            rules.len() != before
+        })
+    }
+
+    fn needs_project(&self) -> bool {
+        self.rules.iter().any(|rule| rule.project.is_some())
+    }
+
+    /// Apply `change` to the rules currently on disk and write the result
+    /// back if it reports a change, all while holding the lock file so that
+    /// concurrent sessions do not lose each other's updates. Afterwards this
+    /// session sees the merged rules. Returns what `change` returned.
+    fn update(&mut self, change: impl FnOnce(&mut Vec<ApprovalRule>) -> bool) -> io::Result<bool> {
+        let dir = match self.path.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => PathBuf::from("."),
+        };
+        std::fs::create_dir_all(&dir)?;
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("toml.lock"))?;
+        lock.lock()?;
+
+        // Refuse to overwrite a file we cannot parse: it may hold rules the
+        // user wrote by hand.
+        let mut rules = read_rules(&self.path)?;
+        let changed = change(&mut rules);
+        if changed {
+            let contents = toml::to_string(&RulesFile {
+                rules: rules.clone(),
+            })
+            .map_err(io::Error::other)?;
+            // Write to a uniquely named sibling first so a crash never leaves
+            // a truncated rules file behind.
+            let mut tmp = tempfile::Builder::new()
+                .prefix(".approval_rules")
+                .tempfile_in(&dir)?;
+            tmp.write_all(contents.as_bytes())?;
+            tmp.as_file().sync_all()?;
+            tmp.persist(&self.path).map_err(|e| e.error)?;
+        }
+        self.rules = rules;
+        Ok(changed)
+    }
+}
+
+/// Read the rules stored at `path`; a missing file holds no rules.
+fn read_rules(path: &Path) -> io::Result<Vec<ApprovalRule>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => toml::from_str::<RulesFile>(&contents)
+            .map(|file| file.rules)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Build the rule for `prefix` approved in `cwd` with the given scope.
+pub(crate) fn rule_for_scope(
+    prefix: Vec<String>,
+    scope: ApprovalRuleScope,
+    cwd: &Path,
+) -> ApprovalRule {
+    let project = match scope {
+        ApprovalRuleScope::Project => Some(project_root(cwd)),
+        ApprovalRuleScope::User => None,
+    };
+    ApprovalRule { prefix, project }
+}
+
+/// Suggest the prefix to offer when asking whether to always allow
+/// `command`: the program plus its first sub-command-like argument, e.g.
+/// `cargo test` for `cargo test -p foo` or `ls` for `ls -la`. Interpreters and
+/// shells always come with the script or module they run, e.g.
+/// `python3 -m pytest`. Returns `None` when the command is not something a
+/// prefix rule could match safely, such as a script with redirections, more
+/// than one command, inline code passed to an interpreter, or a wrapper like
+/// `env` or `xargs` that runs another command.
+pub fn suggest_prefix(command: &[String]) -> Option<Vec<String>> {
+    let commands = split_commands(command)?;
+    let [cmd] = commands.as_slice() else {
+        return None;
+    };
+    let program = cmd.first()?;
+    if is_wrapper(cmd) {
+        return None;
+    }
+    if is_interpreter(program) {
+        return match &cmd[1..] {
+            [flag, module, ..] if flag == "-m" => {
+                Some(vec![program.clone(), flag.clone(), module.clone()])
+            }
+            [script, ..] if !script.is_empty() && !script.starts_with('-') => {
+                Some(vec![program.clone(), script.clone()])
+            }
+            _ => None,
+        };
+    }
+    let mut prefix = vec![program.clone()];
+    if let Some(arg) = cmd.get(1)
+        && !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ':')
+        && !arg.starts_with('-')
+    {
+        prefix.push(arg.clone());
+    }
+    Some(prefix)
+}
+
+fn is_interpreter(program: &str) -> bool {
+    let name = program_name(program);
+    // `python3.12` and the like.
+    let name = name.split_once('.').map_or(name, |(name, _)| name);
+    INTERPRETERS.contains(&name)
+}
+
+fn is_wrapper(cmd: &[String]) -> bool {
+    let Some(program) = cmd.first() else {
+        return false;
+    };
+    let name = program_name(program);
+    WRAPPERS.contains(&name)
+        || cmd
+            .get(1)
+            .is_some_and(|sub| WRAPPER_SUBCOMMANDS.contains(&(name, sub.as_str())))
+}
+
+fn program_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+fn split_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    if let [bash, flag, script] = command
+        && bash == "bash"
+        && flag == "-lc"
+    {
+        let tree = try_parse_bash(script)?;
+        let commands = try_parse_word_only_commands_sequence(&tree, script)?;
+        return (!commands.is_empty()).then_some(commands);
+    }
+    (!command.is_empty()).then(|| vec![command.to_vec()])
+}
+
+fn applies_in(rule: &ApprovalRule, project: Option<&Path>) -> bool {
+    match &rule.project {
+        None => true,
+        Some(rule_project) => project == Some(rule_project.as_path()),
+    }
+}
+
+fn matches_prefix(rule: &ApprovalRule, command: &[String]) -> bool {
+    !rule.prefix.is_empty() && command.starts_with(&rule.prefix)
+}
+
+fn project_root(cwd: &Path) -> PathBuf {
+    resolve_root_git_project_for_trust(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn user_rule(prefix: &[&str]) -> ApprovalRule {
+        ApprovalRule {
+            prefix: vec_str(prefix),
+            project: None,
+        }
+    }
+
+    #[test]
+    fn rules_round_trip_through_the_rules_file() {
+        let codex_home = TempDir::new().unwrap();
+        let mut rules = ApprovalRules::load(codex_home.path());
+        assert!(rules.rules.is_empty());
+
+        rules.add(user_rule(&["npm", "test"])).unwrap();
+        rules.add(user_rule(&["npm", "test"])).unwrap();
+        rules
+            .add(ApprovalRule {
+                prefix: vec_str(&["cargo", "test"]),
+                project: Some(PathBuf::from("/src/app")),
+            })
+            .unwrap();
+
+        let reloaded = ApprovalRules::load(codex_home.path());
+        assert_eq!(reloaded.rules, rules.rules);
+        assert_eq!(reloaded.rules.len(), 2);
+
+        let mut reloaded = reloaded;
+        assert!(reloaded.remove(&user_rule(&["npm", "test"])).unwrap());
+        assert!(!reloaded.remove(&user_rule(&["npm", "test"])).unwrap());
+        assert_eq!(ApprovalRules::load(codex_home.path()).rules.len(), 1);
+    }
+
+    #[test]
+    fn malformed_rules_file_is_ignored() {
+        let codex_home = TempDir::new().unwrap();
+        let path = codex_home.path().join(APPROVAL_RULES_FILENAME);
+        std::fs::write(&path, "rules = 3").unwrap();
+        let mut rules = ApprovalRules::load(codex_home.path());
+        assert!(rules.rules.is_empty());
+
+        // ...but never overwritten.
+        assert!(rules.add(user_rule(&["ls"])).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "rules = 3");
+    }
+
+    #[test]
+    fn updates_from_concurrent_sessions_are_merged() {
+        let codex_home = TempDir::new().unwrap();
+        let mut first = ApprovalRules::load(codex_home.path());
+        let mut second = ApprovalRules::load(codex_home.path());
+
+        first.add(user_rule(&["npm", "test"])).unwrap();
+        second.add(user_rule(&["cargo", "test"])).unwrap();
+        assert_eq!(
+            ApprovalRules::load(codex_home.path()).rules,
+            vec![user_rule(&["npm", "test"]), user_rule(&["cargo", "test"])]
+        );
+        assert_eq!(second.rules.len(), 2);
+
+        assert!(first.remove(&user_rule(&["cargo", "test"])).unwrap());
+        assert_eq!(
+            ApprovalRules::load(codex_home.path()).rules,
+            vec![user_rule(&["npm", "test"])]
+        );
+    }
+
+    #[test]
+    fn prefix_rules_match_argv_and_plain_scripts() {
+        let codex_home = TempDir::new().unwrap();
+        let cwd = TempDir::new().unwrap();
+        let mut rules = ApprovalRules::load(codex_home.path());
+        rules.add(user_rule(&["cargo", "test"])).unwrap();
+        rules.add(user_rule(&["git", "status"])).unwrap();
+
+        assert!(rules.allows(&vec_str(&["cargo", "test", "-p", "foo"]), cwd.path()));
+        assert!(!rules.allows(&vec_str(&["cargo", "build"]), cwd.path()));
+        assert!(!rules.allows(&vec_str(&["cargo"]), cwd.path()));
+        assert!(rules.allows(
+            &vec_str(&["bash", "-lc", "git status && cargo test --all"]),
+            cwd.path()
+        ));
+        // Every command in the script must be covered by a rule.
+        assert!(!rules.allows(
+            &vec_str(&["bash", "-lc", "cargo test && rm -rf target"]),
+            cwd.path()
+        ));
+        // Anything beyond plain commands never matches.
+        assert!(!rules.allows(
+            &vec_str(&["bash", "-lc", "cargo test > $(whoami)"]),
+            cwd.path()
+        ));
+    }
+
+    #[test]
+    fn project_rules_only_apply_in_their_project() {
+        let codex_home = TempDir::new().unwrap();
+        let project = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        let mut rules = ApprovalRules::load(codex_home.path());
+        rules
+            .add(rule_for_scope(
+                vec_str(&["make"]),
+                ApprovalRuleScope::Project,
+                project.path(),
+            ))
+            .unwrap();
+
+        assert!(rules.allows(&vec_str(&["make", "all"]), project.path()));
+        assert!(!rules.allows(&vec_str(&["make", "all"]), elsewhere.path()));
+        assert_eq!(rules.rules_for(project.path()).len(), 1);
+        assert!(rules.rules_for(elsewhere.path()).is_empty());
+    }
+
+    #[test]
+    fn suggests_program_and_subcommand() {
+        assert_eq!(
+            suggest_prefix(&vec_str(&["cargo", "test", "-p", "foo"])),
+            Some(vec_str(&["cargo", "test"]))
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["ls", "-la"])),
+            Some(vec_str(&["ls"]))
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["bash", "-lc", "npm test"])),
+            Some(vec_str(&["npm", "test"]))
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["bash", "-lc", "npm test && npm run lint"])),
+            None
+        );
+    }
+
+    #[test]
+    fn interpreters_are_suggested_with_what_they_run() {
+        assert_eq!(
+            suggest_prefix(&vec_str(&["python3", "/tmp/script.py", "-v"])),
+            Some(vec_str(&["python3", "/tmp/script.py"]))
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["/usr/bin/python3.12", "-m", "pytest", "-x"])),
+            Some(vec_str(&["/usr/bin/python3.12", "-m", "pytest"]))
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["bash", "-lc", "bash ./build.sh"])),
+            Some(vec_str(&["bash", "./build.sh"]))
+        );
+        assert_eq!(suggest_prefix(&vec_str(&["python3"])), None);
+        assert_eq!(
+            suggest_prefix(&vec_str(&["python3", "-c", "print(1)"])),
+            None
+        );
+        assert_eq!(
+            suggest_prefix(&vec_str(&["node", "-e", "process.exit()"])),
+            None
+        );
+    }
+
+    #[test]
+    fn wrappers_are_never_suggested() {
+        for command in [
+            vec_str(&["env", "python3", "-c", "print(1)"]),
+            vec_str(&["env", "python3", "x.py"]),
+            vec_str(&["/usr/bin/env", "FOO=1", "ls"]),
+            vec_str(&["xargs", "sh", "-c", "rm -rf \"$1\""]),
+            vec_str(&["bash", "-lc", "sudo ls /root"]),
+            vec_str(&["timeout", "10", "cargo", "test"]),
+            vec_str(&["npx", "prettier", "--write", "."]),
+            vec_str(&["uv", "run", "python", "x.py"]),
+        ] {
+            assert_eq!(suggest_prefix(&command), None, "{command:?}");
+        }
+        // Other sub-commands of the same tools are fine.
+        assert_eq!(
+            suggest_prefix(&vec_str(&["uv", "sync"])),
+            Some(vec_str(&["uv", "sync"]))
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Jobs that run alongside the conversation: compaction, session titles,
+//! commit messages and plan summaries.
+//!
+//! Each job runs on the provider and model that `[task_models]` assigns it,
+//! falling back to the session's, and reports its usage with an
+//! `AuxiliaryTokenCount` event so that it is not mistaken for the
+//! conversation's own context usage.
+
+use std::sync::Arc;
+
+use futures::prelude::*;
+use tracing::warn;
+
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config_types::TaskModel;
+use crate::config_types::TaskModels;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+use crate::git_info::uncommitted_diff;
+use crate::plan_tool::StepStatus;
+use crate::plan_tool::UpdatePlanArgs;
+use crate::protocol::AuxiliaryTask;
+use crate::protocol::AuxiliaryTokenCountEvent;
+use crate::protocol::CommitMessageDraftEvent;
+use crate::protocol::ErrorEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::PlanSummaryEvent;
+use crate::protocol::TokenUsage;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+
+const TITLE_INSTRUCTIONS: &str = "Write a title of at most eight words for a coding session that starts with the request below. Reply with the title only, without quotes or trailing punctuation.";
+
+const COMMIT_MESSAGE_INSTRUCTIONS: &str = "Write a git commit message for the diff below. Start with a summary line of at most 72 characters in the imperative mood. If the summary is not enough, add a blank line and a short body explaining what changed and why. Reply with the commit message only.";
+
+const PLAN_SUMMARY_INSTRUCTIONS: &str = "Summarize the progress on the plan below in two or three sentences: what is done, what is in progress and what remains. Reply with the summary only.";
+
+/// Diffs are cut to this many bytes before being sent for a commit message.
+const MAX_DIFF_BYTES: usize = 64 * 1024;
+
+/// Titles longer than this are cut at a word boundary.
+const MAX_TITLE_CHARS: usize = 80;
+
+/// The client to run `task` with.
+pub(crate) fn client_for_task(primary: &ModelClient, task: AuxiliaryTask) -> ModelClient {
+    let config = primary.config();
+    let Some(task_model) = task_model(&config.task_models, task) else {
+        return primary.clone();
+    };
+    let provider = match &task_model.model_provider {
+        Some(id) => match config.model_providers.get(id) {
+            Some(provider) => provider.clone(),
+            None => {
+                warn!("unknown model provider `{id}` for {task}; using the session's");
+                return primary.clone();
+            }
+        },
+        None => primary.get_provider(),
+    };
+    let model = task_model
+        .model
+        .clone()
+        .unwrap_or_else(|| primary.get_model());
+    let client = primary.with_model(provider, &model);
+    match task_model.model_reasoning_effort {
+        Some(effort) => client.with_reasoning_effort(effort),
+        None => client,
+    }
+}
+
+fn task_model(task_models: &TaskModels, task: AuxiliaryTask) -> Option<&TaskModel> {
+    match task {
+        AuxiliaryTask::Compact => task_models.compact.as_ref(),
+        AuxiliaryTask::Title => task_models.title.as_ref(),
+        AuxiliaryTask::CommitMessage => task_models.commit_message.as_ref(),
+        AuxiliaryTask::PlanSummary => task_models.plan_summary.as_ref(),
+        // Sampling requests always run on the session's model.
+        AuxiliaryTask::McpSampling => None,
+    }
+}
+
+pub(crate) async fn report_token_usage(
+    sess: &Session,
+    sub_id: &str,
+    task: AuxiliaryTask,
+    client: &ModelClient,
+    token_usage: TokenUsage,
+) {
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::AuxiliaryTokenCount(AuxiliaryTokenCountEvent {
+            task,
+            model: client.get_model(),
+            token_usage,
+        }),
+    })
+    .await;
+}
+
+/// Name the session after the request that started it. The title is
+/// recorded in the rollout for the resume picker; failures are only logged.
+pub(crate) async fn generate_title(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    sub_id: String,
+    request: String,
+) {
+    match complete(
+        &sess,
+        &turn_context,
+        &sub_id,
+        AuxiliaryTask::Title,
+        TITLE_INSTRUCTIONS,
+        request,
+    )
+    .await
+    {
+        Ok(text) => {
+            if let Some(title) = clean_title(&text) {
+                sess.set_title(title).await;
+            }
+        }
+        Err(e) => warn!("failed to generate session title: {e}"),
+    }
+}
+
+/// Reply to `Op::DraftCommitMessage`.
+pub(crate) async fn draft_commit_message(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    sub_id: String,
+) {
+    let Some(diff) = uncommitted_diff(&turn_context.cwd).await else {
+        send_error(
+            &sess,
+            &sub_id,
+            "There are no changes to write a commit message for.",
+        )
+        .await;
+        return;
+    };
+    let input = truncate_diff(diff);
+    let task = AuxiliaryTask::CommitMessage;
+    match complete(
+        &sess,
+        &turn_context,
+        &sub_id,
+        task,
+        COMMIT_MESSAGE_INSTRUCTIONS,
+        input,
+    )
+    .await
+    {
+        Ok(message) => {
+            sess.send_event(Event {
+                id: sub_id,
+                msg: EventMsg::CommitMessageDraft(CommitMessageDraftEvent {
+                    message: message.trim().to_string(),
+                }),
+            })
+            .await;
+        }
+        Err(e) => {
+            let message = format!("Failed to draft a commit message: {e}");
+            send_error(&sess, &sub_id, &message).await;
+        }
+    }
+}
+
+/// Reply to `Op::SummarizePlan`.
+pub(crate) async fn summarize_plan(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    sub_id: String,
+) {
+    let Some(plan) = sess.current_plan() else {
+        send_error(&sess, &sub_id, "There is no plan to summarize yet.").await;
+        return;
+    };
+    let input = describe_plan(&plan);
+    let task = AuxiliaryTask::PlanSummary;
+    match complete(
+        &sess,
+        &turn_context,
+        &sub_id,
+        task,
+        PLAN_SUMMARY_INSTRUCTIONS,
+        input,
+    )
+    .await
+    {
+        Ok(summary) => {
+            sess.send_event(Event {
+                id: sub_id,
+                msg: EventMsg::PlanSummary(PlanSummaryEvent {
+                    summary: summary.trim().to_string(),
+                }),
+            })
+            .await;
+        }
+        Err(e) => {
+            let message = format!("Failed to summarize the plan: {e}");
+            send_error(&sess, &sub_id, &message).await;
+        }
+    }
+}
+
+async fn send_error(sess: &Session, sub_id: &str, message: &str) {
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: message.to_string(),
+        }),
+    })
+    .await;
+}
+
+/// Send `input` with `instructions` to the model for `task` and return the
+/// text of its reply.
+async fn complete(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    task: AuxiliaryTask,
+    instructions: &str,
+    input: String,
+) -> CodexResult<String> {
+    let client = client_for_task(&turn_context.client, task);
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text: input }],
+        }],
+        store: !turn_context.disable_response_storage,
+        tools: Vec::new(),
+        base_instructions_override: Some(instructions.to_string()),
+        max_output_tokens: None,
+    };
+
+    let mut stream = client.stream(&prompt).await?;
+    let mut text = String::new();
+    loop {
+        match stream.next().await {
+            Some(Ok(ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }))) => {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            Some(Ok(ResponseEvent::Completed { token_usage, .. })) => {
+                if let Some(token_usage) = token_usage {
+                    report_token_usage(sess, sub_id, task, &client, token_usage).await;
+                }
+                return Ok(text);
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e),
+            None => {
+                return Err(CodexErr::Stream(
+                    "stream closed before response.completed".into(),
+                    None,
+                ));
+            }
+        }
+    }
+}
+
+/// The first line of the model's reply, without quotes, trailing punctuation
+/// or excess length.
+fn clean_title(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | '*'))
+        .trim_end_matches(['.', '!'])
+        .trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return Some(line.to_string());
+    }
+    let cut: String = line.chars().take(MAX_TITLE_CHARS).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) => &cut[..space],
+        None => cut.as_str(),
+    };
+    Some(format!("{cut}…"))
+}
+
+fn truncate_diff(mut diff: String) -> String {
+    if diff.len() <= MAX_DIFF_BYTES {
+        return diff;
+    }
+    let mut end = MAX_DIFF_BYTES;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    diff.truncate(end);
+    diff.push_str("\n[diff truncated]\n");
+    diff
+}
+
+fn describe_plan(plan: &UpdatePlanArgs) -> String {
+    let mut text = String::new();
+    if let Some(explanation) = &plan.explanation {
+        text.push_str(explanation.trim());
+        text.push_str("\n\n");
+    }
+    for item in &plan.plan {
+        let status = match item.status {
+            StepStatus::Completed => "done",
+            StepStatus::InProgress => "in progress",
+            StepStatus::Pending => "pending",
+        };
+        text.push_str(&format!("- [{status}] {}\n", item.step));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan_tool::PlanItemArg;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn titles_are_trimmed_to_one_short_line() {
+        assert_eq!(
+            clean_title("\n\"Fix flaky login test.\"\nExtra words"),
+            Some("Fix flaky login test".to_string())
+        );
+        assert_eq!(clean_title("  \n\"\"  "), None);
+        let long = "word ".repeat(30);
+        let title = clean_title(&long).unwrap_or_default();
+        assert!(title.ends_with("word…"), "{title}");
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn plans_are_described_step_by_step() {
+        let plan = UpdatePlanArgs {
+            explanation: Some("Port the parser.".to_string()),
+            plan: vec![
+                PlanItemArg {
+                    step: "Write tests".to_string(),
+                    status: StepStatus::Completed,
+                },
+                PlanItemArg {
+                    step: "Port the lexer".to_string(),
+                    status: StepStatus::InProgress,
+                },
+                PlanItemArg {
+                    step: "Port the grammar".to_string(),
+                    status: StepStatus::Pending,
+                },
+            ],
+        };
+        assert_eq!(
+            describe_plan(&plan),
+            "Port the parser.\n\n- [done] Write tests\n- [in progress] Port the lexer\n- [pending] Port the grammar\n"
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! Record and replay of model traffic.
+//!
+//! With `CODEX_RS_CASSETTE_RECORD=<dir>` every request sent to the model
+//! provider is saved to `<dir>` together with the SSE stream it got back. With
+//! `CODEX_RS_CASSETTE_REPLAY=<dir>` no request leaves the process: each one is
+//! answered with the first unused recorded response whose request had the same
+//! shape, so a whole multi-turn session can be rerun offline.
+//!
+//! The shape of a request is the model, the base instructions and the
+//! conversation so far, reduced to what stays the same from one run to the
+//! next: message text, tool names and arguments, and which calls were
+//! answered. Environment context, user instructions and the contents of tool
+//! output (which carry paths, timings and the like) are left out.
+//!
+//! Each interaction is stored as a pair of files, `NNNN-request.json` and
+//! `NNNN-response.sse`, numbered in the order the requests were made.
+
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use bytes::Bytes;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::Stream;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::flags::CODEX_RS_CASSETTE_RECORD;
+use crate::flags::CODEX_RS_CASSETTE_REPLAY;
+use crate::model_provider_info::WireApi;
+use crate::protocol::ENVIRONMENT_CONTEXT_OPEN_TAG;
+use crate::protocol::USER_INSTRUCTIONS_OPEN_TAG;
+
+const REQUEST_SUFFIX: &str = "-request.json";
+const RESPONSE_SUFFIX: &str = "-response.sse";
+
+/// The cassette selected by the environment, shared by every `ModelClient`
+/// in the process so that interactions are numbered and consumed in order.
+static ACTIVE: LazyLock<Mutex<Option<Arc<Cassette>>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug)]
+enum Cassette {
+    Record {
+        dir: PathBuf,
+        next: AtomicUsize,
+    },
+    Replay {
+        dir: PathBuf,
+        interactions: Mutex<Vec<Recorded>>,
+    },
+}
+
+#[derive(Debug)]
+struct Recorded {
+    request: RecordedRequest,
+    response: Bytes,
+    used: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedRequest {
+    wire_api: WireApi,
+    request: Value,
+}
+
+impl Cassette {
+    fn from_env() -> Result<Option<Arc<Self>>> {
+        let (record, replay) = (*CODEX_RS_CASSETTE_RECORD, *CODEX_RS_CASSETTE_REPLAY);
+        if record.is_none() && replay.is_none() {
+            return Ok(None);
+        }
+
+        let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(cassette) = active.as_ref() {
+            return Ok(Some(cassette.clone()));
+        }
+        let cassette = match (record, replay) {
+            (Some(_), Some(_)) => {
+                return Err(CodexErr::Cassette(
+                    "CODEX_RS_CASSETTE_RECORD and CODEX_RS_CASSETTE_REPLAY are both set"
+                        .to_string(),
+                ));
+            }
+            (Some(dir), None) => {
+                warn!(dir, "Recording model traffic");
+                Self::record(Path::new(dir))?
+            }
+            (None, Some(dir)) => {
+                warn!(dir, "Replaying model traffic");
+                Self::replay(Path::new(dir))?
+            }
+            (None, None) => return Ok(None),
+        };
+        let cassette = Arc::new(cassette);
+        *active = Some(cassette.clone());
+        Ok(Some(cassette))
+    }
+
+    /// Starts a new recording in `dir`, discarding any previous one.
+    fn record(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        for (request_path, response_path) in interaction_files(dir)? {
+            fs::remove_file(request_path)?;
+            if response_path.exists() {
+                fs::remove_file(response_path)?;
+            }
+        }
+        Ok(Self::Record {
+            dir: dir.to_path_buf(),
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    fn replay(dir: &Path) -> Result<Self> {
+        let mut interactions = Vec::new();
+        for (request_path, response_path) in interaction_files(dir)? {
+            let request: RecordedRequest = serde_json::from_slice(&fs::read(&request_path)?)?;
+            let response = Bytes::from(fs::read(&response_path)?);
+            interactions.push(Recorded {
+                request,
+                response,
+                used: false,
+            });
+        }
+        Ok(Self::Replay {
+            dir: dir.to_path_buf(),
+            interactions: Mutex::new(interactions),
+        })
+    }
+}
+
+/// The request/response file pairs in `dir`, in recording order.
+fn interaction_files(dir: &Path) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let Some(prefix) = name.to_str().and_then(|n| n.strip_suffix(REQUEST_SUFFIX)) else {
+            continue;
+        };
+        files.push((
+            dir.join(&name),
+            dir.join(format!("{prefix}{RESPONSE_SUFFIX}")),
+        ));
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// One request to the model, as seen by the active cassette.
+#[derive(Debug)]
+pub(crate) struct Interaction {
+    cassette: Arc<Cassette>,
+    request: RecordedRequest,
+}
+
+impl Interaction {
+    /// The interaction for sending `prompt` to `model`, or `None` when no
+    /// cassette is active.
+    pub(crate) fn for_request(
+        model: &str,
+        wire_api: WireApi,
+        prompt: &Prompt,
+    ) -> Result<Option<Self>> {
+        Ok(Cassette::from_env()?.map(|cassette| Self {
+            cassette,
+            request: RecordedRequest {
+                wire_api,
+                request: request_shape(model, prompt),
+            },
+        }))
+    }
+
+    /// When replaying, the recorded response to this request, which takes the
+    /// place of sending it. Fails if nothing recorded matches.
+    pub(crate) fn replayed_response(&self) -> Result<Option<BoxStream<'static, Result<Bytes>>>> {
+        let Cassette::Replay { dir, interactions } = self.cassette.as_ref() else {
+            return Ok(None);
+        };
+        let mut interactions = interactions.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(recorded) = interactions
+            .iter_mut()
+            .find(|r| !r.used && r.request == self.request)
+        else {
+            return Err(CodexErr::Cassette(format!(
+                "no unused interaction in {} matches request {}",
+                dir.display(),
+                self.request.request
+            )));
+        };
+        recorded.used = true;
+        let response = recorded.response.clone();
+        Ok(Some(futures::stream::iter([Ok(response)]).boxed()))
+    }
+
+    /// When recording, saves this request and tees `stream` into the
+    /// cassette as it is consumed.
+    pub(crate) fn record<S>(&self, stream: S) -> Result<BoxStream<'static, Result<Bytes>>>
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        let Cassette::Record { dir, next } = self.cassette.as_ref() else {
+            return Ok(stream.boxed());
+        };
+        let index = next.fetch_add(1, Ordering::SeqCst);
+        fs::write(
+            dir.join(format!("{index:04}{REQUEST_SUFFIX}")),
+            serde_json::to_vec_pretty(&self.request)?,
+        )?;
+        let mut file = File::create(dir.join(format!("{index:04}{RESPONSE_SUFFIX}")))?;
+        Ok(stream
+            .inspect(move |chunk| {
+                if let Ok(bytes) = chunk
+                    && let Err(e) = file.write_all(bytes)
+                {
+                    warn!("failed to record model response: {e}");
+                }
+            })
+            .boxed())
+    }
+}
+
+/// The parts of a request that identify it across runs.
+fn request_shape(model: &str, prompt: &Prompt) -> Value {
+    let input: Vec<Value> = prompt.input.iter().filter_map(item_shape).collect();
+    json!({
+        "model": model,
+        "instructions": prompt.base_instructions_override,
+        "input": input,
+    })
+}
+
+fn item_shape(item: &ResponseItem) -> Option<Value> {
+    match item {
+        ResponseItem::Message { role, content, .. } => {
+            let parts: Vec<&str> = content
+                .iter()
+                .map(|c| match c {
+                    ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                        text.as_str()
+                    }
+                    ContentItem::InputImage { .. } => "[image]",
+                })
+                .filter(|text| {
+                    !text.starts_with(ENVIRONMENT_CONTEXT_OPEN_TAG)
+                        && !text.starts_with(USER_INSTRUCTIONS_OPEN_TAG)
+                })
+                .collect();
+            if parts.is_empty() {
+                return None;
+            }
+            Some(json!({"role": role, "text": parts.join("\n")}))
+        }
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => Some(json!({"call": name, "arguments": arguments})),
+        ResponseItem::CustomToolCall { name, input, .. } => {
+            Some(json!({"call": name, "input": input}))
+        }
+        ResponseItem::LocalShellCall { .. } => Some(json!({"call": "local_shell"})),
+        ResponseItem::FunctionCallOutput { call_id, .. }
+        | ResponseItem::CustomToolCallOutput { call_id, .. } => Some(json!({"output": call_id})),
+        ResponseItem::Reasoning { .. }
+        | ResponseItem::WebSearchCall { .. }
+        | ResponseItem::Other => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn prompt(input: Vec<ResponseItem>) -> Prompt {
+        Prompt {
+            input,
+            ..Default::default()
+        }
+    }
+
+    fn user_message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn interaction(cassette: &Arc<Cassette>, prompt: &Prompt) -> Interaction {
+        Interaction {
+            cassette: cassette.clone(),
+            request: RecordedRequest {
+                wire_api: WireApi::Responses,
+                request: request_shape("gpt-5", prompt),
+            },
+        }
+    }
+
+    #[test]
+    fn request_shape_ignores_context_that_differs_between_runs() {
+        let prompt = prompt(vec![
+            user_message("<environment_context>\n  <cwd>/tmp/a</cwd>\n</environment_context>"),
+            user_message("<user_instructions>\n\nbe terse\n\n</user_instructions>"),
+            user_message("add a file"),
+            ResponseItem::FunctionCall {
+                id: Some("fc_123".to_string()),
+                name: "shell".to_string(),
+                arguments: r#"{"command":["ls"]}"#.to_string(),
+                call_id: "call_1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call_1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "took 0.2s".to_string(),
+                    success: Some(true),
+                },
+            },
+        ]);
+        assert_eq!(
+            request_shape("gpt-5", &prompt),
+            json!({
+                "model": "gpt-5",
+                "instructions": null,
+                "input": [
+                    {"role": "user", "text": "add a file"},
+                    {"call": "shell", "arguments": r#"{"command":["ls"]}"#},
+                    {"output": "call_1"},
+                ],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_serves_each_recorded_response_once_in_order() {
+        let dir = TempDir::new().unwrap();
+        let first = prompt(vec![user_message("hi")]);
+        let second = prompt(vec![user_message("hi"), user_message("again")]);
+
+        let recorder = Arc::new(Cassette::record(dir.path()).unwrap());
+        for (prompt, body) in [(&first, "one"), (&first, "two"), (&second, "three")] {
+            let chunks = [Ok(Bytes::from(body)), Ok(Bytes::from("\n\n"))];
+            let stream = interaction(&recorder, prompt)
+                .record(futures::stream::iter(chunks))
+                .unwrap();
+            let _: Vec<_> = stream.collect().await;
+        }
+
+        let player = Arc::new(Cassette::replay(dir.path()).unwrap());
+        let mut served = Vec::new();
+        for prompt in [&second, &first, &first] {
+            let stream = interaction(&player, prompt)
+                .replayed_response()
+                .unwrap()
+                .unwrap();
+            let chunks: Vec<_> = stream.collect().await;
+            served.push(chunks.into_iter().next().unwrap().unwrap());
+        }
+        assert_eq!(served, vec!["three\n\n", "one\n\n", "two\n\n"]);
+
+        assert!(matches!(
+            interaction(&player, &first).replayed_response(),
+            Err(CodexErr::Cassette(_))
+        ));
+    }
+}
@@ -16,6 +16,7 @@ use tracing::debug;
 use tracing::trace;
 
 use crate::ModelProviderInfo;
+use crate::cassette::Interaction;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
@@ -34,7 +35,21 @@ pub(crate) async fn stream_chat_completions(
     model_family: &ModelFamily,
     client: &reqwest::Client,
     provider: &ModelProviderInfo,
+    max_rate_limit_retries: u64,
+    interaction: Option<&Interaction>,
 ) -> Result<ResponseStream> {
+    if let Some(interaction) = interaction
+        && let Some(stream) = interaction.replayed_response()?
+    {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_chat_sse(
+            stream,
+            tx_event,
+            provider.stream_idle_timeout(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
     // Build messages array
     let mut messages = Vec::<serde_json::Value>::new();
 
@@ -268,12 +283,17 @@ pub(crate) async fn stream_chat_completions(
     }
 
     let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-    let payload = json!({
+    let mut payload = json!({
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
         "tools": tools_json,
     });
+    if let Some(max_tokens) = prompt.max_output_tokens
+        && let Some(obj) = payload.as_object_mut()
+    {
+        obj.insert("max_tokens".to_string(), json!(max_tokens));
+    }
 
     debug!(
         "POST to {}: {}",
@@ -283,6 +303,7 @@ pub(crate) async fn stream_chat_completions(
 
     let mut attempt = 0;
     let max_retries = provider.request_max_retries();
+    let mut rate_limited = 0;
     loop {
         attempt += 1;
 
@@ -298,6 +319,10 @@ pub(crate) async fn stream_chat_completions(
             Ok(resp) if resp.status().is_success() => {
                 let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
                 let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                let stream = match interaction {
+                    Some(interaction) => interaction.record(stream)?,
+                    None => stream.boxed(),
+                };
                 tokio::spawn(process_chat_sse(
                     stream,
                     tx_event,
@@ -312,7 +337,10 @@ pub(crate) async fn stream_chat_completions(
                     return Err(CodexErr::UnexpectedStatus(status, body));
                 }
 
-                if attempt > max_retries {
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    rate_limited += 1;
+                }
+                if attempt > max_retries || rate_limited > max_rate_limit_retries {
                     return Err(CodexErr::RetryLimit(status));
                 }
 
@@ -387,12 +415,7 @@ async fn process_chat_sse<S>(
                 return;
             }
             Err(_) => {
-                let _ = tx_event
-                    .send(Err(CodexErr::Stream(
-                        "idle timeout waiting for SSE".into(),
-                        None,
-                    )))
-                    .await;
+                let _ = tx_event.send(Err(CodexErr::StreamIdleTimeout)).await;
                 return;
             }
         };
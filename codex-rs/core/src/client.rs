@@ -21,6 +21,7 @@ use tracing::trace;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::cassette::Interaction;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
 use crate::client_common::Prompt;
@@ -38,7 +39,6 @@ use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
-use crate::openai_model_info::get_model_info;
 use crate::openai_tools::create_tools_json_for_responses_api;
 use crate::protocol::TokenUsage;
 use crate::util::backoff;
@@ -100,15 +100,17 @@ impl ModelClient {
     pub fn get_model_context_window(&self) -> Option<u64> {
         self.config
             .model_context_window
-            .or_else(|| get_model_info(&self.config.model_family).map(|info| info.context_window))
+            .or(self.config.model_family.context_window)
     }
 
     /// Dispatches to either the Responses or Chat implementation depending on
     /// the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let interaction =
+            Interaction::for_request(&self.config.model, self.provider.wire_api, prompt)?;
         match self.provider.wire_api {
-            WireApi::Responses => self.stream_responses(prompt).await,
+            WireApi::Responses => self.stream_responses(prompt, interaction.as_ref()).await,
             WireApi::Chat => {
                 // Create the raw streaming connection first.
                 let response_stream = stream_chat_completions(
@@ -116,6 +118,8 @@ impl ModelClient {
                     &self.config.model_family,
                     &self.client,
                     &self.provider,
+                    self.rate_limit_retries(),
+                    interaction.as_ref(),
                 )
                 .await?;
 
@@ -148,13 +152,29 @@ impl ModelClient {
     }
 
     /// Implementation for the OpenAI *Responses* experimental API.
-    async fn stream_responses(&self, prompt: &Prompt) -> Result<ResponseStream> {
+    async fn stream_responses(
+        &self,
+        prompt: &Prompt,
+        interaction: Option<&Interaction>,
+    ) -> Result<ResponseStream> {
         if let Some(path) = &*CODEX_RS_SSE_FIXTURE {
             // short circuit for tests
             warn!(path, "Streaming from fixture");
             return stream_from_fixture(path, self.provider.clone()).await;
         }
 
+        if let Some(interaction) = interaction
+            && let Some(stream) = interaction.replayed_response()?
+        {
+            let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+            tokio::spawn(process_sse(
+                stream,
+                tx_event,
+                self.provider.stream_idle_timeout(),
+            ));
+            return Ok(ResponseStream { rx_event });
+        }
+
         let auth_manager = self.auth_manager.clone();
 
         let auth_mode = auth_manager
@@ -209,10 +229,13 @@ impl ModelClient {
             include,
             prompt_cache_key: Some(self.session_id.to_string()),
             text,
+            max_output_tokens: prompt.max_output_tokens,
         };
 
         let mut attempt = 0;
         let max_retries = self.provider.request_max_retries();
+        let max_rate_limit_retries = self.rate_limit_retries();
+        let mut rate_limited = 0;
 
         loop {
             attempt += 1;
@@ -262,6 +285,10 @@ impl ModelClient {
 
                     // spawn task to process SSE
                     let stream = resp.bytes_stream().map_err(CodexErr::Reqwest);
+                    let stream = match interaction {
+                        Some(interaction) => interaction.record(stream)?,
+                        None => stream.boxed(),
+                    };
                     tokio::spawn(process_sse(
                         stream,
                         tx_event,
@@ -324,7 +351,10 @@ impl ModelClient {
                         }
                     }
 
-                    if attempt > max_retries {
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        rate_limited += 1;
+                    }
+                    if attempt > max_retries || rate_limited > max_rate_limit_retries {
                         if status == StatusCode::INTERNAL_SERVER_ERROR {
                             return Err(CodexErr::InternalServerError);
                         }
@@ -352,6 +382,46 @@ impl ModelClient {
         self.provider.clone()
     }
 
+    /// Rate-limited retries to allow before giving up, which is lower than
+    /// `request_max_retries` when there are fallbacks to move on to.
+    fn rate_limit_retries(&self) -> u64 {
+        let max_retries = self.provider.request_max_retries();
+        let fallback = &self.config.model_fallback;
+        match fallback.rate_limit_retries {
+            Some(limit) if !fallback.chain.is_empty() => limit.min(max_retries),
+            _ => max_retries,
+        }
+    }
+
+    /// A client for `model` on `provider` that otherwise matches this one.
+    pub(crate) fn with_model(&self, provider: ModelProviderInfo, model: &str) -> ModelClient {
+        let model_family = self.config.model_registry.family_for(model);
+        let mut config = (*self.config).clone();
+        config.model = model.to_string();
+        config.model_context_window = model_family.context_window;
+        config.model_max_output_tokens = model_family.max_output_tokens;
+        config.model_family = model_family;
+        ModelClient::new(
+            Arc::new(config),
+            self.auth_manager.clone(),
+            provider,
+            self.effort,
+            self.summary,
+            self.session_id,
+        )
+    }
+
+    /// This client with `effort` instead of its reasoning effort.
+    pub(crate) fn with_reasoning_effort(mut self, effort: ReasoningEffortConfig) -> ModelClient {
+        self.effort = effort;
+        self
+    }
+
+    /// The configuration this client was created with.
+    pub(crate) fn config(&self) -> &Config {
+        &self.config
+    }
+
     /// Returns the currently configured model slug.
     pub fn get_model(&self) -> String {
         self.config.model.clone()
@@ -473,12 +543,7 @@ async fn process_sse<S>(
                 return;
             }
             Err(_) => {
-                let _ = tx_event
-                    .send(Err(CodexErr::Stream(
-                        "idle timeout waiting for SSE".into(),
-                        None,
-                    )))
-                    .await;
+                let _ = tx_event.send(Err(CodexErr::StreamIdleTimeout)).await;
                 return;
             }
         };
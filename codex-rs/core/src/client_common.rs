@@ -34,6 +34,10 @@ pub struct Prompt {
 
     /// Optional override for the built-in BASE_INSTRUCTIONS.
     pub base_instructions_override: Option<String>,
+
+    /// Upper bound on the tokens the model may generate, when the caller sets
+    /// one.
+    pub(crate) max_output_tokens: Option<u64>,
 }
 
 impl Prompt {
@@ -136,6 +140,8 @@ pub(crate) struct ResponsesApiRequest<'a> {
     pub(crate) prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) text: Option<TextControls>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_output_tokens: Option<u64>,
 }
 
 pub(crate) fn create_reasoning_param_for_request(
@@ -206,6 +212,7 @@ mod tests {
             text: Some(TextControls {
                 verbosity: Some(OpenAiVerbosity::Low),
             }),
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -234,9 +241,11 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: None,
+            max_output_tokens: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
         assert!(v.get("text").is_none());
+        assert!(v.get("max_output_tokens").is_none());
     }
 }
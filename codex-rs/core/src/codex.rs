@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -21,8 +22,10 @@ use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnAbortedEvent;
 use futures::prelude::*;
 use mcp_types::CallToolResult;
+use mcp_types::ToolAnnotations;
 use serde::Serialize;
 use serde_json;
+use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::task::AbortHandle;
 use tracing::debug;
@@ -38,10 +41,14 @@ use crate::apply_patch::ApplyPatchExec;
 use crate::apply_patch::CODEX_APPLY_PATCH_ARG1;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::approval_rules::ApprovalRules;
+use crate::approval_rules::rule_for_scope;
+use crate::auxiliary;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
+use crate::config_types::AutoReasoningEffort;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
 use crate::conversation_manager::InitialHistory;
@@ -62,15 +69,19 @@ use crate::exec_command::ExecSessionManager;
 use crate::exec_command::WRITE_STDIN_TOOL_NAME;
 use crate::exec_command::WriteStdinParams;
 use crate::exec_env::create_env;
+use crate::lsp::LspManager;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::mcp_sampling::McpSamplingRequest;
+use crate::mcp_sampling::handle_mcp_sampling_request;
+use crate::mcp_tool_call::ServerToolPolicies;
 use crate::mcp_tool_call::handle_mcp_tool_call;
-use crate::model_family::find_family_for_model;
-use crate::openai_model_info::get_model_info;
+use crate::model_fallback::ModelChain;
 use crate::openai_tools::ApplyPatchToolArgs;
 use crate::openai_tools::ToolsConfig;
 use crate::openai_tools::ToolsConfigParams;
 use crate::openai_tools::get_openai_tools;
 use crate::parse_command::parse_command;
+use crate::plan_tool::UpdatePlanArgs;
 use crate::plan_tool::handle_update_plan;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageDeltaEvent;
@@ -79,7 +90,9 @@ use crate::protocol::AgentReasoningDeltaEvent;
 use crate::protocol::AgentReasoningRawContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
+use crate::protocol::ApprovalRuleScope;
 use crate::protocol::AskForApproval;
+use crate::protocol::AuxiliaryTask;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
@@ -89,23 +102,35 @@ use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
 use crate::protocol::FileChange;
 use crate::protocol::InputItem;
+use crate::protocol::ListApprovalRulesResponseEvent;
 use crate::protocol::ListCustomPromptsResponseEvent;
+use crate::protocol::McpInvocation;
+use crate::protocol::McpListServersResponseEvent;
+use crate::protocol::McpSamplingApprovalRequestEvent;
+use crate::protocol::McpToolCallApprovalRequestEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::ReasoningEffortChosenEvent;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
 use crate::protocol::TaskCompleteEvent;
+use crate::protocol::TokenUsage;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WebSearchBeginEvent;
+use crate::reasoning_effort::EffortSignals;
+use crate::reasoning_effort::choose_effort;
+use crate::redaction::Redactor;
 use crate::rollout::RolloutRecorder;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_command_safety;
 use crate::safety::assess_safety_for_untrusted_command;
 use crate::shell;
+use crate::symbols::SymbolsToolArgs;
+use crate::symbols::run_symbols_tool;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
@@ -170,6 +195,7 @@ impl Codex {
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
+        let (tx_sampling, rx_sampling) = mpsc::unbounded_channel();
 
         let user_instructions = get_user_instructions(&config).await;
 
@@ -195,6 +221,7 @@ impl Codex {
             config.clone(),
             auth_manager.clone(),
             tx_event.clone(),
+            tx_sampling,
             conversation_history.clone(),
         )
         .await
@@ -213,6 +240,7 @@ impl Codex {
             turn_context,
             config,
             rx_sub,
+            rx_sampling,
         ));
         let codex = Codex {
             next_id: AtomicU64::new(0),
@@ -258,10 +286,22 @@ impl Codex {
 #[derive(Default)]
 struct State {
     approved_commands: HashSet<Vec<String>>,
+    /// (server, tool) pairs the user approved for the rest of the session.
+    approved_mcp_tools: HashSet<(String, String)>,
+    /// MCP servers whose sampling requests the user approved for the rest of
+    /// the session.
+    approved_sampling_servers: HashSet<String>,
     current_task: Option<AgentTask>,
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
     pending_input: Vec<ResponseInputItem>,
     history: ConversationHistory,
+    /// Whether the next task should also generate a title for the session.
+    needs_title: bool,
+    title: Option<String>,
+    /// The latest plan from the `update_plan` tool.
+    plan: Option<UpdatePlanArgs>,
+    /// Usage reported for the latest request to the model.
+    last_token_usage: Option<TokenUsage>,
 }
 
 /// Context for an initialized model agent
@@ -275,6 +315,12 @@ pub(crate) struct Session {
     mcp_connection_manager: McpConnectionManager,
     session_manager: ExecSessionManager,
 
+    /// Language servers that report diagnostics for patched files.
+    lsp_manager: LspManager,
+
+    /// Command approval rules persisted across sessions.
+    approval_rules: Mutex<ApprovalRules>,
+
     /// External notifier command (will be passed as args to exec()). When
     /// `None` this feature is disabled.
     notify: Option<Vec<String>>,
@@ -286,6 +332,8 @@ pub(crate) struct Session {
     codex_linux_sandbox_exe: Option<PathBuf>,
     user_shell: shell::Shell,
     show_raw_agent_reasoning: bool,
+    /// Applied to command output before it is sent to the model.
+    exec_output_redactor: Redactor,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -303,6 +351,9 @@ pub(crate) struct TurnContext {
     pub(crate) shell_environment_policy: ShellEnvironmentPolicy,
     pub(crate) disable_response_storage: bool,
     pub(crate) tools_config: ToolsConfig,
+    /// When set, the reasoning effort of each request is picked within these
+    /// bounds instead of using the client's.
+    pub(crate) auto_reasoning_effort: Option<AutoReasoningEffort>,
 }
 
 impl TurnContext {
@@ -358,6 +409,7 @@ impl Session {
         config: Arc<Config>,
         auth_manager: Arc<AuthManager>,
         tx_event: Sender<Event>,
+        tx_sampling: mpsc::UnboundedSender<McpSamplingRequest>,
         initial_history: InitialHistory,
     ) -> anyhow::Result<(Arc<Self>, TurnContext)> {
         let session_id = Uuid::new_v4();
@@ -390,7 +442,7 @@ impl Session {
         // - load history metadata
         let rollout_fut = RolloutRecorder::new(&config, session_id, user_instructions.clone());
 
-        let mcp_fut = McpConnectionManager::new(config.mcp_servers.clone());
+        let mcp_fut = McpConnectionManager::new(config.mcp_servers.clone(), tx_sampling);
         let default_shell_fut = shell::default_user_shell();
         let history_meta_fut = crate::message_history::history_metadata(&config);
 
@@ -402,9 +454,13 @@ impl Session {
             error!("failed to initialize rollout recorder: {e:#}");
             anyhow::anyhow!("failed to initialize rollout recorder: {e:#}")
         })?;
+        let exec_output_redactor = Redactor::for_model(&config.redaction, &config.codex_home)?;
+        crate::retention::spawn_prune(config.clone());
         // Create the mutable state for the Session.
         let state = State {
             history: ConversationHistory::new(),
+            needs_title: matches!(initial_history, InitialHistory::New)
+                && config.task_models.title.is_some(),
             ..Default::default()
         };
 
@@ -455,6 +511,7 @@ impl Session {
                 include_web_search_request: config.tools_web_search_request,
                 use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                 include_view_image_tool: config.include_view_image_tool,
+                include_symbols_tool: config.include_symbols_tool,
             }),
             user_instructions,
             base_instructions,
@@ -463,18 +520,22 @@ impl Session {
             shell_environment_policy: config.shell_environment_policy.clone(),
             cwd,
             disable_response_storage,
+            auto_reasoning_effort: config.auto_reasoning_effort,
         };
         let sess = Arc::new(Session {
             session_id,
             tx_event: tx_event.clone(),
             mcp_connection_manager,
             session_manager: ExecSessionManager::default(),
+            lsp_manager: LspManager::new(config.lsp_servers.clone(), turn_context.cwd.clone()),
+            approval_rules: Mutex::new(ApprovalRules::load(&config.codex_home)),
             notify,
             state: Mutex::new(state),
             rollout: Mutex::new(Some(rollout_recorder)),
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            exec_output_redactor,
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
@@ -602,6 +663,48 @@ impl Session {
         rx_approve
     }
 
+    pub async fn request_mcp_tool_approval(
+        &self,
+        sub_id: String,
+        call_id: String,
+        invocation: McpInvocation,
+        reason: Option<String>,
+    ) -> oneshot::Receiver<ReviewDecision> {
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event = Event {
+            id: sub_id.clone(),
+            msg: EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+                call_id,
+                invocation,
+                reason,
+            }),
+        };
+        let _ = self.tx_event.send(event).await;
+        {
+            let mut state = self.state.lock_unchecked();
+            state.pending_approvals.insert(sub_id, tx_approve);
+        }
+        rx_approve
+    }
+
+    pub(crate) async fn request_mcp_sampling_approval(
+        &self,
+        call_id: String,
+        request: McpSamplingApprovalRequestEvent,
+    ) -> oneshot::Receiver<ReviewDecision> {
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event = Event {
+            id: call_id.clone(),
+            msg: EventMsg::McpSamplingApprovalRequest(request),
+        };
+        let _ = self.tx_event.send(event).await;
+        {
+            let mut state = self.state.lock_unchecked();
+            state.pending_approvals.insert(call_id, tx_approve);
+        }
+        rx_approve
+    }
+
     pub async fn request_patch_approval(
         &self,
         sub_id: String,
@@ -648,6 +751,112 @@ impl Session {
         state.approved_commands.insert(cmd);
     }
 
+    pub fn add_approved_mcp_tool(&self, server: String, tool: String) {
+        let mut state = self.state.lock_unchecked();
+        state.approved_mcp_tools.insert((server, tool));
+    }
+
+    pub fn is_mcp_tool_approved(&self, server: &str, tool: &str) -> bool {
+        let state = self.state.lock_unchecked();
+        state
+            .approved_mcp_tools
+            .contains(&(server.to_string(), tool.to_string()))
+    }
+
+    pub(crate) fn add_approved_mcp_sampling(&self, server: String) {
+        let mut state = self.state.lock_unchecked();
+        state.approved_sampling_servers.insert(server);
+    }
+
+    pub(crate) fn is_mcp_sampling_approved(&self, server: &str) -> bool {
+        let state = self.state.lock_unchecked();
+        state.approved_sampling_servers.contains(server)
+    }
+
+    /// Returns true once per session if a title should be generated.
+    fn take_title_request(&self) -> bool {
+        std::mem::take(&mut self.state.lock_unchecked().needs_title)
+    }
+
+    pub(crate) async fn set_title(&self, title: String) {
+        self.state.lock_unchecked().title = Some(title);
+        self.record_state_snapshot(&[]).await;
+    }
+
+    pub(crate) fn set_plan(&self, plan: UpdatePlanArgs) {
+        self.state.lock_unchecked().plan = Some(plan);
+    }
+
+    pub(crate) fn current_plan(&self) -> Option<UpdatePlanArgs> {
+        self.state.lock_unchecked().plan.clone()
+    }
+
+    fn set_last_token_usage(&self, usage: TokenUsage) {
+        self.state.lock_unchecked().last_token_usage = Some(usage);
+    }
+
+    /// Persist a rule approving commands that start with `prefix`. Failing to
+    /// save the rule is reported to the client but does not affect the
+    /// approval of the current command.
+    async fn add_approval_rule(
+        &self,
+        sub_id: &str,
+        prefix: Vec<String>,
+        scope: ApprovalRuleScope,
+        cwd: &Path,
+    ) {
+        let rule = rule_for_scope(prefix, scope, cwd);
+        let result = self.update_approval_rules(|rules| rules.add(rule)).await;
+        if let Err(e) = result {
+            self.notify_background_event(sub_id, format!("failed to save approval rule: {e}"))
+                .await;
+        }
+    }
+
+    /// Apply `change` to the approval rules. It locks and rewrites the rules
+    /// file, so it runs on a copy on a blocking thread, and the session adopts
+    /// the updated rules once it is done.
+    async fn update_approval_rules<T: Send + 'static>(
+        &self,
+        change: impl FnOnce(&mut ApprovalRules) -> std::io::Result<T> + Send + 'static,
+    ) -> std::io::Result<T> {
+        let mut rules = self.approval_rules.lock_unchecked().clone();
+        let (rules, result) = tokio::task::spawn_blocking(move || {
+            let result = change(&mut rules);
+            (rules, result)
+        })
+        .await
+        .map_err(std::io::Error::other)?;
+        *self.approval_rules.lock_unchecked() = rules;
+        result
+    }
+
+    fn is_approved_by_rule(&self, command: &[String], cwd: &Path) -> bool {
+        self.approval_rules.lock_unchecked().allows(command, cwd)
+    }
+
+    async fn send_approval_rules(&self, sub_id: String, cwd: &Path) {
+        let rules = self.approval_rules.lock_unchecked().rules_for(cwd);
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListApprovalRulesResponse(ListApprovalRulesResponseEvent { rules }),
+        };
+        if let Err(e) = self.tx_event.send(event).await {
+            warn!("failed to send ListApprovalRulesResponse event: {e}");
+        }
+    }
+
+    async fn send_mcp_servers(&self, sub_id: String) {
+        let servers = self.mcp_connection_manager.list_servers().await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpListServersResponse(McpListServersResponseEvent { servers }),
+        };
+        if let Err(e) = self.tx_event.send(event).await {
+            warn!("failed to send McpListServersResponse event: {e}");
+        }
+    }
+
     /// Records items to both the rollout and the chat completions/ZDR
     /// transcript, if enabled.
     async fn record_conversation_items(&self, items: &[ResponseItem]) {
@@ -658,7 +867,12 @@ impl Session {
     }
 
     async fn record_state_snapshot(&self, items: &[ResponseItem]) {
-        let snapshot = { crate::rollout::SessionStateSnapshot {} };
+        let snapshot = {
+            let state = self.state.lock_unchecked();
+            crate::rollout::SessionStateSnapshot {
+                title: state.title.clone(),
+            }
+        };
 
         let recorder = {
             let guard = self.rollout.lock_unchecked();
@@ -794,7 +1008,7 @@ impl Session {
         self.on_exec_command_begin(turn_diff_tracker, begin_ctx.clone())
             .await;
 
-        let result = process_exec_tool_call(
+        let mut result = process_exec_tool_call(
             exec_args.params,
             exec_args.sandbox_type,
             exec_args.sandbox_policy,
@@ -803,6 +1017,25 @@ impl Session {
         )
         .await;
 
+        // After a successful patch, let the language servers weigh in on the
+        // files it touched so the model does not need a build to find out.
+        if let Some(ApplyPatchCommandContext { changes, .. }) = &begin_ctx.apply_patch
+            && let Ok(output) = &mut result
+            && output.exit_code == 0
+            && let Some(report) = self
+                .lsp_manager
+                .diagnostics_for_paths(&patched_paths(changes))
+                .await
+        {
+            for stream in [&mut output.stdout, &mut output.aggregated_output] {
+                if !stream.text.is_empty() && !stream.text.ends_with('\n') {
+                    stream.text.push('\n');
+                }
+                stream.text.push_str(&report);
+                stream.text.push('\n');
+            }
+        }
+
         let output_stderr;
         let borrowed: &ExecToolCallOutput = match &result {
             Ok(output) => output,
@@ -892,6 +1125,20 @@ impl Session {
             .await
     }
 
+    pub(crate) fn mcp_tool_policies(&self, server: &str) -> Option<&ServerToolPolicies> {
+        self.mcp_connection_manager.tool_policies(server)
+    }
+
+    pub(crate) async fn mcp_tool_annotations(
+        &self,
+        server: &str,
+        tool: &str,
+    ) -> Option<ToolAnnotations> {
+        self.mcp_connection_manager
+            .tool_annotations(server, tool)
+            .await
+    }
+
     fn interrupt_task(&self) {
         info!("interrupt received: abort current task, if any");
         let mut state = self.state.lock_unchecked();
@@ -953,6 +1200,22 @@ pub(crate) struct ApplyPatchCommandContext {
     pub(crate) changes: HashMap<PathBuf, FileChange>,
 }
 
+/// Paths that hold the result of a patch (or, for deletions, used to).
+fn patched_paths(changes: &HashMap<PathBuf, FileChange>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = changes
+        .iter()
+        .flat_map(|(path, change)| match change {
+            FileChange::Update {
+                move_path: Some(dest),
+                ..
+            } => vec![path.clone(), dest.clone()],
+            _ => vec![path.clone()],
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
 /// A series of Turns in response to user input.
 pub(crate) struct AgentTask {
     sess: Arc<Session>,
@@ -967,6 +1230,22 @@ impl AgentTask {
         sub_id: String,
         input: Vec<InputItem>,
     ) -> Self {
+        if sess.take_title_request() {
+            let request = input
+                .iter()
+                .filter_map(|item| match item {
+                    InputItem::Text { text } => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            tokio::spawn(auxiliary::generate_title(
+                sess.clone(),
+                Arc::clone(&turn_context),
+                sub_id.clone(),
+                request,
+            ));
+        }
         let handle = {
             let sess = sess.clone();
             let sub_id = sub_id.clone();
@@ -1025,11 +1304,28 @@ async fn submission_loop(
     turn_context: TurnContext,
     config: Arc<Config>,
     rx_sub: Receiver<Submission>,
+    mut rx_sampling: mpsc::UnboundedReceiver<McpSamplingRequest>,
 ) {
     // Wrap once to avoid cloning TurnContext for each task.
     let mut turn_context = Arc::new(turn_context);
     // To break out of this loop, send Op::Shutdown.
-    while let Ok(sub) = rx_sub.recv().await {
+    loop {
+        let sub = tokio::select! {
+            sub = rx_sub.recv() => match sub {
+                Ok(sub) => sub,
+                Err(_) => break,
+            },
+            Some(request) = rx_sampling.recv() => {
+                // Sampling runs alongside the current task, which may be
+                // the one waiting on the server that asked.
+                tokio::spawn(handle_mcp_sampling_request(
+                    sess.clone(),
+                    Arc::clone(&turn_context),
+                    request,
+                ));
+                continue;
+            }
+        };
         debug!(?sub, "Submission");
         match sub.op {
             Op::Interrupt => {
@@ -1049,8 +1345,7 @@ async fn submission_loop(
 
                 // Effective model + family
                 let (effective_model, effective_family) = if let Some(m) = model {
-                    let fam =
-                        find_family_for_model(&m).unwrap_or_else(|| config.model_family.clone());
+                    let fam = config.model_registry.family_for(&m);
                     (m, fam)
                 } else {
                     (prev.client.get_model(), prev.client.get_model_family())
@@ -1066,8 +1361,8 @@ async fn submission_loop(
                 let mut updated_config = (*config).clone();
                 updated_config.model = effective_model.clone();
                 updated_config.model_family = effective_family.clone();
-                if let Some(model_info) = get_model_info(&effective_family) {
-                    updated_config.model_context_window = Some(model_info.context_window);
+                if let Some(context_window) = effective_family.context_window {
+                    updated_config.model_context_window = Some(context_window);
                 }
 
                 let client = ModelClient::new(
@@ -1094,6 +1389,7 @@ async fn submission_loop(
                     include_web_search_request: config.tools_web_search_request,
                     use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                     include_view_image_tool: config.include_view_image_tool,
+                    include_symbols_tool: config.include_symbols_tool,
                 });
 
                 let new_turn_context = TurnContext {
@@ -1106,6 +1402,12 @@ async fn submission_loop(
                     shell_environment_policy: prev.shell_environment_policy.clone(),
                     cwd: new_cwd.clone(),
                     disable_response_storage: prev.disable_response_storage,
+                    // Choosing an effort turns automatic effort off.
+                    auto_reasoning_effort: if effort.is_some() {
+                        None
+                    } else {
+                        prev.auto_reasoning_effort
+                    },
                 };
 
                 // Install the new persistent context for subsequent tasks/turns.
@@ -1145,16 +1447,15 @@ async fn submission_loop(
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
 
-                    // Derive a model family for the requested model; fall back to the session's.
-                    let model_family = find_family_for_model(&model)
-                        .unwrap_or_else(|| config.model_family.clone());
+                    // Look up the requested model in the registry.
+                    let model_family = config.model_registry.family_for(&model);
 
                     // Create a per‑turn Config clone with the requested model/family.
                     let mut per_turn_config = (*config).clone();
                     per_turn_config.model = model.clone();
                     per_turn_config.model_family = model_family.clone();
-                    if let Some(model_info) = get_model_info(&model_family) {
-                        per_turn_config.model_context_window = Some(model_info.context_window);
+                    if let Some(context_window) = model_family.context_window {
+                        per_turn_config.model_context_window = Some(context_window);
                     }
 
                     // Build a new client with per‑turn reasoning settings.
@@ -1180,6 +1481,7 @@ async fn submission_loop(
                             use_streamable_shell_tool: config
                                 .use_experimental_streamable_shell_tool,
                             include_view_image_tool: config.include_view_image_tool,
+                            include_symbols_tool: config.include_symbols_tool,
                         }),
                         user_instructions: turn_context.user_instructions.clone(),
                         base_instructions: turn_context.base_instructions.clone(),
@@ -1188,6 +1490,8 @@ async fn submission_loop(
                         shell_environment_policy: turn_context.shell_environment_policy.clone(),
                         cwd,
                         disable_response_storage: turn_context.disable_response_storage,
+                        // The turn names its effort explicitly.
+                        auto_reasoning_effort: None,
                     };
                     // TODO: record the new environment context in the conversation history
                     // no current task, spawn a new one with the per‑turn context
@@ -1208,6 +1512,15 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other),
             },
+            Op::McpToolCallApproval { id, decision } => match decision {
+                ReviewDecision::Abort => {
+                    sess.interrupt_task();
+                }
+                other => sess.notify_approval(&id, other),
+            },
+            Op::McpSamplingApproval { id, decision } => {
+                sess.notify_approval(&id, decision);
+            }
             Op::AddToHistory { text } => {
                 let id = sess.session_id;
                 let config = config.clone();
@@ -1259,7 +1572,7 @@ async fn submission_loop(
                 let sub_id = sub.id.clone();
 
                 // This is a cheap lookup from the connection manager's cache.
-                let tools = sess.mcp_connection_manager.list_all_tools();
+                let tools = sess.mcp_connection_manager.list_all_tools().await;
                 let event = Event {
                     id: sub_id,
                     msg: EventMsg::McpListToolsResponse(
@@ -1270,6 +1583,28 @@ async fn submission_loop(
                     warn!("failed to send McpListToolsResponse event: {e}");
                 }
             }
+            Op::ListMcpServers => {
+                sess.send_mcp_servers(sub.id.clone()).await;
+            }
+            Op::RestartMcpServer { server } => {
+                let result = sess.mcp_connection_manager.restart_server(&server).await;
+                if let Err(e) = result {
+                    sess.notify_background_event(&sub.id, format!("{e:#}"))
+                        .await;
+                }
+                sess.send_mcp_servers(sub.id.clone()).await;
+            }
+            Op::SetMcpServerEnabled { server, enabled } => {
+                let result = sess
+                    .mcp_connection_manager
+                    .set_server_enabled(&server, enabled)
+                    .await;
+                if let Err(e) = result {
+                    sess.notify_background_event(&sub.id, format!("{e:#}"))
+                        .await;
+                }
+                sess.send_mcp_servers(sub.id.clone()).await;
+            }
             Op::ListCustomPrompts => {
                 let tx_event = sess.tx_event.clone();
                 let sub_id = sub.id.clone();
@@ -1291,6 +1626,24 @@ async fn submission_loop(
                     warn!("failed to send ListCustomPromptsResponse event: {e}");
                 }
             }
+            Op::ListApprovalRules => {
+                sess.send_approval_rules(sub.id.clone(), &turn_context.cwd)
+                    .await;
+            }
+            Op::RemoveApprovalRule { rule } => {
+                let result = sess
+                    .update_approval_rules(move |rules| rules.remove(&rule))
+                    .await;
+                if let Err(e) = result {
+                    sess.notify_background_event(
+                        &sub.id,
+                        format!("failed to remove approval rule: {e}"),
+                    )
+                    .await;
+                }
+                sess.send_approval_rules(sub.id.clone(), &turn_context.cwd)
+                    .await;
+            }
             Op::Compact => {
                 // Create a summarization request as user input
                 const SUMMARIZATION_PROMPT: &str = include_str!("prompt_for_compact_command.md");
@@ -1309,6 +1662,20 @@ async fn submission_loop(
                     sess.set_task(task);
                 }
             }
+            Op::DraftCommitMessage => {
+                tokio::spawn(auxiliary::draft_commit_message(
+                    sess.clone(),
+                    Arc::clone(&turn_context),
+                    sub.id,
+                ));
+            }
+            Op::SummarizePlan => {
+                tokio::spawn(auxiliary::summarize_plan(
+                    sess.clone(),
+                    Arc::clone(&turn_context),
+                    sub.id,
+                ));
+            }
             Op::Shutdown => {
                 info!("Shutting down Codex instance");
 
@@ -1394,6 +1761,13 @@ async fn run_task(
         return;
     }
 
+    let prompt_chars = input
+        .iter()
+        .map(|item| match item {
+            InputItem::Text { text } => text.chars().count(),
+            _ => 0,
+        })
+        .sum();
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     sess.record_conversation_items(&[initial_input_for_turn.clone().into()])
         .await;
@@ -1402,6 +1776,9 @@ async fn run_task(
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let mut turn_diff_tracker = TurnDiffTracker::new();
+    let mut models = ModelChain::new(&turn_context.client);
+    let mut last_tool_call_failed = false;
+    let mut chosen_effort = None;
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -1434,12 +1811,20 @@ async fn run_task(
                 })
             })
             .collect();
+        let effort_signals = EffortSignals {
+            prompt_chars,
+            last_tool_call_failed,
+            ..Default::default()
+        };
         match run_turn(
             &sess,
             turn_context,
+            &mut models,
             &mut turn_diff_tracker,
             sub_id.clone(),
             turn_input,
+            &effort_signals,
+            &mut chosen_effort,
         )
         .await
         {
@@ -1536,6 +1921,7 @@ async fn run_task(
                         responses.push(response);
                     }
                 }
+                last_tool_call_failed = responses.iter().any(EffortSignals::tool_call_failed);
 
                 // Only attempt to take the lock if there is something to record.
                 if !items_to_record_in_conversation_history.is_empty() {
@@ -1578,37 +1964,117 @@ async fn run_task(
     sess.tx_event.send(event).await.ok();
 }
 
+/// The reasoning effort for the next request to `client` when
+/// `auto_reasoning_effort` is set and its model reasons, announcing it
+/// whenever it differs from `chosen`, the previous choice in the task.
+async fn next_reasoning_effort(
+    sess: &Session,
+    turn_context: &TurnContext,
+    client: &ModelClient,
+    sub_id: &str,
+    signals: &EffortSignals,
+    chosen: &mut Option<ReasoningEffortConfig>,
+) -> Option<ReasoningEffortConfig> {
+    let bounds = turn_context.auto_reasoning_effort.as_ref()?;
+    let model_family = &client.config().model_family;
+    if !model_family.supports_reasoning_summaries {
+        return None;
+    }
+
+    let mut signals = signals.clone();
+    signals.open_plan_steps = EffortSignals::open_plan_steps(sess.current_plan().as_ref());
+    let last_token_usage = sess.state.lock_unchecked().last_token_usage.clone();
+    signals.context_left_percent = last_token_usage
+        .zip(client.get_model_context_window())
+        .map(|(usage, window)| EffortSignals::context_left_percent(&usage, window));
+
+    let (effort, reason) = choose_effort(bounds, model_family, &signals);
+    if *chosen != Some(effort) {
+        *chosen = Some(effort);
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::ReasoningEffortChosen(ReasoningEffortChosenEvent { effort, reason }),
+        };
+        sess.tx_event.send(event).await.ok();
+    }
+    Some(effort)
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn run_turn(
     sess: &Session,
     turn_context: &TurnContext,
+    models: &mut ModelChain,
     turn_diff_tracker: &mut TurnDiffTracker,
     sub_id: String,
     input: Vec<ResponseItem>,
+    effort_signals: &EffortSignals,
+    chosen_effort: &mut Option<ReasoningEffortConfig>,
 ) -> CodexResult<Vec<ProcessedResponseItem>> {
-    let tools = get_openai_tools(
-        &turn_context.tools_config,
-        Some(sess.mcp_connection_manager.list_all_tools()),
-    );
-
-    let prompt = Prompt {
+    let mcp_tools = sess.mcp_connection_manager.list_all_tools().await;
+    let mut prompt = Prompt {
         input,
         store: !turn_context.disable_response_storage,
-        tools,
+        tools: get_openai_tools(models.tools_config(turn_context), Some(mcp_tools.clone())),
         base_instructions_override: turn_context.base_instructions.clone(),
+        max_output_tokens: None,
     };
 
     let mut retries = 0;
     loop {
-        match try_run_turn(sess, turn_context, turn_diff_tracker, &sub_id, &prompt).await {
+        // Chosen for every request, since a fallback may have switched to a
+        // model with different reasoning support.
+        let effort = next_reasoning_effort(
+            sess,
+            turn_context,
+            models.client(turn_context),
+            &sub_id,
+            effort_signals,
+            chosen_effort,
+        )
+        .await;
+        let client = match effort {
+            Some(effort) => Cow::Owned(
+                models
+                    .client(turn_context)
+                    .clone()
+                    .with_reasoning_effort(effort),
+            ),
+            None => Cow::Borrowed(models.client(turn_context)),
+        };
+        let result = try_run_turn(
+            sess,
+            turn_context,
+            &client,
+            turn_diff_tracker,
+            &sub_id,
+            &prompt,
+        )
+        .await;
+        let max_retries = client.get_provider().stream_max_retries();
+        let e = match result {
             Ok(output) => return Ok(output),
-            Err(CodexErr::Interrupted) => return Err(CodexErr::Interrupted),
-            Err(CodexErr::EnvVar(var)) => return Err(CodexErr::EnvVar(var)),
-            Err(e @ (CodexErr::UsageLimitReached(_) | CodexErr::UsageNotIncluded)) => {
+            Err(e) => e,
+        };
+        if let Some(message) = models.fall_back(turn_context, &e) {
+            warn!("{message}");
+            sess.notify_background_event(&sub_id, message).await;
+            // The new model may call for a different set of tools.
+            prompt.tools =
+                get_openai_tools(models.tools_config(turn_context), Some(mcp_tools.clone()));
+            retries = 0;
+            continue;
+        }
+        match e {
+            CodexErr::Interrupted => return Err(CodexErr::Interrupted),
+            CodexErr::EnvVar(var) => return Err(CodexErr::EnvVar(var)),
+            e @ (CodexErr::UsageLimitReached(_)
+            | CodexErr::UsageNotIncluded
+            | CodexErr::Cassette(_)) => {
                 return Err(e);
             }
-            Err(e) => {
+            e => {
                 // Use the configured provider-specific stream retry budget.
-                let max_retries = turn_context.client.get_provider().stream_max_retries();
                 if retries < max_retries {
                     retries += 1;
                     let delay = match e {
@@ -1652,6 +2118,7 @@ struct ProcessedResponseItem {
 async fn try_run_turn(
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     turn_diff_tracker: &mut TurnDiffTracker,
     sub_id: &str,
     prompt: &Prompt,
@@ -1711,7 +2178,7 @@ async fn try_run_turn(
         })
     };
 
-    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+    let mut stream = client.clone().stream(&prompt).await?;
 
     let mut output = Vec::new();
 
@@ -1765,6 +2232,7 @@ async fn try_run_turn(
                 token_usage,
             } => {
                 if let Some(token_usage) = token_usage {
+                    sess.set_last_token_usage(token_usage.clone());
                     sess.tx_event
                         .send(Event {
                             id: sub_id.to_string(),
@@ -1849,13 +2317,15 @@ async fn run_compact_task(
         store: !turn_context.disable_response_storage,
         tools: Vec::new(),
         base_instructions_override: Some(compact_instructions.clone()),
+        max_output_tokens: None,
     };
 
-    let max_retries = turn_context.client.get_provider().stream_max_retries();
+    let client = auxiliary::client_for_task(&turn_context.client, AuxiliaryTask::Compact);
+    let max_retries = client.get_provider().stream_max_retries();
     let mut retries = 0;
 
     loop {
-        let attempt_result = drain_to_completed(&sess, turn_context, &sub_id, &prompt).await;
+        let attempt_result = drain_to_completed(&sess, &client, &sub_id, &prompt).await;
 
         match attempt_result {
             Ok(()) => break,
@@ -2083,6 +2553,38 @@ async fn handle_function_call(
             };
             ResponseInputItem::FunctionCallOutput { call_id, output }
         }
+        "symbols" => {
+            let args = match serde_json::from_str::<SymbolsToolArgs>(&arguments) {
+                Ok(a) => a,
+                Err(e) => {
+                    return ResponseInputItem::FunctionCallOutput {
+                        call_id,
+                        output: FunctionCallOutputPayload {
+                            content: format!("failed to parse function arguments: {e}"),
+                            success: Some(false),
+                        },
+                    };
+                }
+            };
+            let path = turn_context.resolve_path(args.path);
+            let cwd = turn_context.cwd.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                run_symbols_tool(&path, args.name.as_deref(), &cwd)
+            })
+            .await
+            .unwrap_or_else(|e| Err(format!("symbols tool failed: {e}")));
+            let output = match result {
+                Ok(content) => FunctionCallOutputPayload {
+                    content,
+                    success: Some(true),
+                },
+                Err(content) => FunctionCallOutputPayload {
+                    content,
+                    success: Some(false),
+                },
+            };
+            ResponseInputItem::FunctionCallOutput { call_id, output }
+        }
         "apply_patch" => {
             let args = match serde_json::from_str::<ApplyPatchToolArgs>(&arguments) {
                 Ok(a) => a,
@@ -2133,7 +2635,8 @@ async fn handle_function_call(
                 .session_manager
                 .handle_exec_command_request(exec_params)
                 .await;
-            let function_call_output = crate::exec_command::result_into_payload(result);
+            let function_call_output =
+                crate::exec_command::result_into_payload(result, &sess.exec_output_redactor);
             ResponseInputItem::FunctionCallOutput {
                 call_id,
                 output: function_call_output,
@@ -2157,19 +2660,26 @@ async fn handle_function_call(
                 .handle_write_stdin_request(write_stdin_params)
                 .await;
             let function_call_output: FunctionCallOutputPayload =
-                crate::exec_command::result_into_payload(result);
+                crate::exec_command::result_into_payload(result, &sess.exec_output_redactor);
             ResponseInputItem::FunctionCallOutput {
                 call_id,
                 output: function_call_output,
             }
         }
         _ => {
-            match sess.mcp_connection_manager.parse_tool_name(&name) {
+            match sess.mcp_connection_manager.parse_tool_name(&name).await {
                 Some((server, tool_name)) => {
-                    // TODO(mbolin): Determine appropriate timeout for tool call.
+                    // The server's configured `tool_timeout_ms` applies.
                     let timeout = None;
                     handle_mcp_tool_call(
-                        sess, &sub_id, call_id, server, tool_name, arguments, timeout,
+                        sess,
+                        turn_context,
+                        &sub_id,
+                        call_id,
+                        server,
+                        tool_name,
+                        arguments,
+                        timeout,
                     )
                     .await
                 }
@@ -2308,7 +2818,8 @@ async fn handle_container_exec_with_params(
     call_id: String,
 ) -> ResponseInputItem {
     // check if this was a patch, and apply it if so
-    let apply_patch_exec = match maybe_parse_apply_patch_verified(&params.command, &params.cwd) {
+    let mut apply_patch_exec = match maybe_parse_apply_patch_verified(&params.command, &params.cwd)
+    {
         MaybeApplyPatchVerified::Body(changes) => {
             match apply_patch::apply_patch(sess, turn_context, &sub_id, &call_id, changes).await {
                 InternalApplyPatchInvocation::Output(item) => return item,
@@ -2336,10 +2847,16 @@ async fn handle_container_exec_with_params(
         MaybeApplyPatchVerified::NotApplyPatch => None,
     };
 
-    let (params, safety, command_for_display) = match &apply_patch_exec {
+    // The `apply_patch` exec below runs with an empty env; if the user
+    // replaces the patch with a command of their own, it gets the env the
+    // model asked for.
+    let requested_env = params.env.clone();
+    let mut edited_note = None;
+    let (mut params, safety, mut command_for_display) = match &apply_patch_exec {
         Some(ApplyPatchExec {
             action: ApplyPatchAction { patch, cwd, .. },
             user_explicitly_approved_this_action,
+            ..
         }) => {
             let path_to_codex = std::env::current_exe()
                 .ok()
@@ -2384,7 +2901,11 @@ async fn handle_container_exec_with_params(
             )
         }
         None => {
-            let safety = {
+            let safety = if sess.is_approved_by_rule(&params.command, &params.cwd) {
+                SafetyCheck::AutoApprove {
+                    sandbox_type: SandboxType::None,
+                }
+            } else {
                 let state = sess.state.lock_unchecked();
                 assess_command_safety(
                     &params.command,
@@ -2413,10 +2934,42 @@ async fn handle_container_exec_with_params(
                 .await;
             match rx_approve.await.unwrap_or_default() {
                 ReviewDecision::Approved => (),
+                ReviewDecision::ApprovedWithEdits { command } => {
+                    // The user replaced the proposed command, so whatever it
+                    // was (including an `apply_patch`) is no longer what runs.
+                    let env = match apply_patch_exec.take() {
+                        Some(_) => requested_env,
+                        None => params.env,
+                    };
+                    edited_note = Some(edited_by_user(&command));
+                    command_for_display = command.clone();
+                    params = ExecParams {
+                        command,
+                        env,
+                        ..params
+                    };
+                }
                 ReviewDecision::ApprovedForSession => {
                     sess.add_approved_command(params.command.clone());
                 }
-                ReviewDecision::Denied | ReviewDecision::Abort => {
+                ReviewDecision::ApprovedForPrefix { prefix, scope } => {
+                    sess.add_approved_command(params.command.clone());
+                    sess.add_approval_rule(&sub_id, prefix, scope, &params.cwd)
+                        .await;
+                }
+                ReviewDecision::DeniedWithFeedback { feedback } => {
+                    return ResponseInputItem::FunctionCallOutput {
+                        call_id,
+                        output: FunctionCallOutputPayload {
+                            content: rejected_with_feedback("exec command", &feedback),
+                            success: None,
+                        },
+                    };
+                }
+                // Partial approval only applies to patches.
+                ReviewDecision::ApprovedPartially { .. }
+                | ReviewDecision::Denied
+                | ReviewDecision::Abort => {
                     return ResponseInputItem::FunctionCallOutput {
                         call_id,
                         output: FunctionCallOutputPayload {
@@ -2443,6 +2996,9 @@ async fn handle_container_exec_with_params(
         }
     };
 
+    let rejected_changes = apply_patch_exec
+        .as_ref()
+        .and_then(|exec| exec.rejected_changes.clone());
     let exec_command_context = ExecCommandContext {
         sub_id: sub_id.clone(),
         call_id: call_id.clone(),
@@ -2452,6 +3008,7 @@ async fn handle_container_exec_with_params(
             |ApplyPatchExec {
                  action,
                  user_explicitly_approved_this_action,
+                 ..
              }| ApplyPatchCommandContext {
                 user_explicitly_approved_this_action,
                 changes: convert_apply_patch_to_protocol(&action),
@@ -2482,12 +3039,19 @@ async fn handle_container_exec_with_params(
         )
         .await;
 
-    match output_result {
-        Ok(output) => {
+    let output = match output_result {
+        Ok(mut output) => {
+            if let Some(rejected_changes) = rejected_changes {
+                let text = &mut output.aggregated_output.text;
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(&rejected_changes);
+            }
             let ExecToolCallOutput { exit_code, .. } = &output;
 
             let is_success = *exit_code == 0;
-            let content = format_exec_output(&output);
+            let content = format_exec_output(&output, &sess.exec_output_redactor);
             ResponseInputItem::FunctionCallOutput {
                 call_id: call_id.clone(),
                 output: FunctionCallOutputPayload {
@@ -2515,6 +3079,10 @@ async fn handle_container_exec_with_params(
                 success: None,
             },
         },
+    };
+    match edited_note {
+        Some(note) => prepend_to_output(output, &note),
+        None => output,
     }
 }
 
@@ -2584,13 +3152,33 @@ async fn handle_sandbox_error(
         )
         .await;
 
-    match rx_approve.await.unwrap_or_default() {
-        ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
-            // Persist this command as pre‑approved for the
-            // remainder of the session so future
-            // executions skip the sandbox directly.
-            // TODO(ragona): Isn't this a bug? It always saves the command in an | fork?
-            sess.add_approved_command(params.command.clone());
+    let decision = rx_approve.await.unwrap_or_default();
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedForPrefix { .. }
+        | ReviewDecision::ApprovedWithEdits { .. } => {
+            let mut edited_note = None;
+            let params = match decision {
+                ReviewDecision::ApprovedWithEdits { command } => {
+                    edited_note = Some(edited_by_user(&command));
+                    ExecParams { command, ..params }
+                }
+                ReviewDecision::ApprovedForPrefix { prefix, scope } => {
+                    sess.add_approved_command(params.command.clone());
+                    sess.add_approval_rule(&sub_id, prefix, scope, &params.cwd)
+                        .await;
+                    params
+                }
+                _ => {
+                    // Persist this command as pre‑approved for the
+                    // remainder of the session so future
+                    // executions skip the sandbox directly.
+                    // TODO(ragona): Isn't this a bug? It always saves the command in an | fork?
+                    sess.add_approved_command(params.command.clone());
+                    params
+                }
+            };
             // Inform UI we are retrying without sandbox.
             sess.notify_background_event(&sub_id, "retrying command without sandbox")
                 .await;
@@ -2619,12 +3207,12 @@ async fn handle_sandbox_error(
                 )
                 .await;
 
-            match retry_output_result {
+            let output = match retry_output_result {
                 Ok(retry_output) => {
                     let ExecToolCallOutput { exit_code, .. } = &retry_output;
 
                     let is_success = *exit_code == 0;
-                    let content = format_exec_output(&retry_output);
+                    let content = format_exec_output(&retry_output, &sess.exec_output_redactor);
 
                     ResponseInputItem::FunctionCallOutput {
                         call_id: call_id.clone(),
@@ -2641,9 +3229,22 @@ async fn handle_sandbox_error(
                         success: None,
                     },
                 },
+            };
+            match edited_note {
+                Some(note) => prepend_to_output(output, &note),
+                None => output,
             }
         }
-        ReviewDecision::Denied | ReviewDecision::Abort => {
+        ReviewDecision::DeniedWithFeedback { feedback } => ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: rejected_with_feedback("exec command", &feedback),
+                success: None,
+            },
+        },
+        ReviewDecision::ApprovedPartially { .. }
+        | ReviewDecision::Denied
+        | ReviewDecision::Abort => {
             // Fall through to original failure handling.
             ResponseInputItem::FunctionCallOutput {
                 call_id,
@@ -2656,6 +3257,37 @@ async fn handle_sandbox_error(
     }
 }
 
+/// Function call output for a request the user denied with an explanation.
+pub(crate) fn rejected_with_feedback(what: &str, feedback: &str) -> String {
+    format!("{what} rejected by user with feedback: {}", feedback.trim())
+}
+
+/// Note for the model that the user edited its command before approving it,
+/// naming the command that actually ran.
+fn edited_by_user(command: &[String]) -> String {
+    let shown = match command {
+        [bash, flag, script] if bash == "bash" && flag == "-lc" => script.clone(),
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    };
+    format!("The user edited the command before approving it. It ran: {shown}")
+}
+
+fn prepend_to_output(item: ResponseInputItem, note: &str) -> ResponseInputItem {
+    match item {
+        ResponseInputItem::FunctionCallOutput { call_id, output } => {
+            ResponseInputItem::FunctionCallOutput {
+                call_id,
+                output: FunctionCallOutputPayload {
+                    content: format!("{note}\n\n{}", output.content),
+                    ..output
+                },
+            }
+        }
+        other => other,
+    }
+}
+
 fn format_exec_output_str(exec_output: &ExecToolCallOutput) -> String {
     let ExecToolCallOutput {
         aggregated_output, ..
@@ -2757,8 +3389,10 @@ fn take_last_bytes_at_char_boundary(s: &str, maxb: usize) -> &str {
     &s[start..]
 }
 
-/// Exec output is a pre-serialized JSON payload
-fn format_exec_output(exec_output: &ExecToolCallOutput) -> String {
+/// Exec output is a pre-serialized JSON payload. Secrets are scrubbed from
+/// it if `redaction.exec_output` is on; the UI has already received the
+/// original output.
+fn format_exec_output(exec_output: &ExecToolCallOutput, redactor: &Redactor) -> String {
     let ExecToolCallOutput {
         exit_code,
         duration,
@@ -2781,6 +3415,7 @@ fn format_exec_output(exec_output: &ExecToolCallOutput) -> String {
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
     let formatted_output = format_exec_output_str(exec_output);
+    let formatted_output = redactor.redact(&formatted_output);
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -2816,11 +3451,11 @@ fn get_last_assistant_message_from_turn(responses: &[ResponseItem]) -> Option<St
 
 async fn drain_to_completed(
     sess: &Session,
-    turn_context: &TurnContext,
+    client: &ModelClient,
     sub_id: &str,
     prompt: &Prompt,
 ) -> CodexResult<()> {
-    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut stream = client.stream(prompt).await?;
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
@@ -2842,13 +3477,14 @@ async fn drain_to_completed(
                 // some providers don't return token usage, so we default
                 // TODO: consider approximate token usage
                 let token_usage = token_usage.unwrap_or_default();
-                sess.tx_event
-                    .send(Event {
-                        id: sub_id.to_string(),
-                        msg: EventMsg::TokenCount(token_usage),
-                    })
-                    .await
-                    .ok();
+                auxiliary::report_token_usage(
+                    sess,
+                    sub_id,
+                    AuxiliaryTask::Compact,
+                    client,
+                    token_usage,
+                )
+                .await;
 
                 return Ok(());
             }
@@ -2935,6 +3571,40 @@ mod tests {
         assert_eq!(expected, got);
     }
 
+    #[test]
+    fn edited_commands_are_reported_with_their_output() {
+        let output = ResponseInputItem::FunctionCallOutput {
+            call_id: "call".to_string(),
+            output: FunctionCallOutputPayload {
+                content: "ok".to_string(),
+                success: Some(true),
+            },
+        };
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "cargo test -p foo".to_string(),
+        ];
+
+        let ResponseInputItem::FunctionCallOutput { output, .. } =
+            prepend_to_output(output, &edited_by_user(&command))
+        else {
+            panic!("expected a function call output");
+        };
+        assert_eq!(
+            output,
+            FunctionCallOutputPayload {
+                content: "The user edited the command before approving it. It ran: cargo test -p foo\n\nok"
+                    .to_string(),
+                success: Some(true),
+            }
+        );
+        assert_eq!(
+            edited_by_user(&["rg".to_string(), "two words".to_string()]),
+            "The user edited the command before approving it. It ran: rg 'two words'"
+        );
+    }
+
     #[test]
     fn model_truncation_head_tail_by_lines() {
         // Build 400 short lines so line-count limit, not byte budget, triggers truncation
@@ -1,19 +1,28 @@
 use crate::config_profile::ConfigProfile;
+use crate::config_types::AutoReasoningEffort;
 use crate::config_types::History;
+use crate::config_types::LspServerConfig;
 use crate::config_types::McpServerConfig;
+use crate::config_types::ModelFallback;
+use crate::config_types::Redaction;
 use crate::config_types::SandboxWorkspaceWrite;
+use crate::config_types::SessionRetention;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
+use crate::config_types::TaskModel;
+use crate::config_types::TaskModels;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::model_family::ModelEntry;
 use crate::model_family::ModelFamily;
-use crate::model_family::find_family_for_model;
+use crate::model_family::ModelRegistry;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
-use crate::openai_model_info::get_model_info;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::reasoning_effort;
+use crate::redaction::Redactor;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::config_types::SandboxMode;
@@ -47,6 +56,9 @@ pub struct Config {
 
     pub model_family: ModelFamily,
 
+    /// Capabilities of known models: the built-in entries plus `[models]`.
+    pub model_registry: ModelRegistry,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<u64>,
 
@@ -116,9 +128,20 @@ pub struct Config {
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Language servers, keyed by language, whose diagnostics are reported
+    /// after `apply_patch` edits files they handle.
+    pub lsp_servers: HashMap<String, LspServerConfig>,
+
     /// Combined provider map (defaults merged with user-defined overrides).
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Providers and models to fall back to when the configured ones fail.
+    pub model_fallback: ModelFallback,
+
+    /// Providers and models for compaction, titles, commit messages and plan
+    /// summaries.
+    pub task_models: TaskModels,
+
     /// Maximum number of bytes to include from an AGENTS.md project doc file.
     pub project_doc_max_bytes: usize,
 
@@ -129,6 +152,13 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Retention policy for the rollout files under `~/.codex/sessions`.
+    pub sessions: SessionRetention,
+
+    /// Secret redaction for history, rollouts, logs and, optionally, command
+    /// output sent to the model.
+    pub redaction: Redaction,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -148,6 +178,10 @@ pub struct Config {
     /// Responses API.
     pub model_reasoning_effort: ReasoningEffort,
 
+    /// When set, the reasoning effort of each request is picked within these
+    /// bounds instead of always using `model_reasoning_effort`.
+    pub auto_reasoning_effort: Option<AutoReasoningEffort>,
+
     /// If not "none", the value to use for `reasoning.summary` when making a
     /// request using the Responses API.
     pub model_reasoning_summary: ReasoningSummary,
@@ -181,6 +215,10 @@ pub struct Config {
 
     /// Include the `view_image` tool that lets the agent attach a local image path to context.
     pub include_view_image_tool: bool,
+
+    /// Include the `symbols` tool that outlines source files and looks up
+    /// definitions.
+    pub include_symbols_tool: bool,
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -398,6 +436,11 @@ pub struct ConfigToml {
     /// Provider to use from the model_providers map.
     pub model_provider: Option<String>,
 
+    /// Capabilities of models, keyed by slug or by slug prefix ending in `*`.
+    /// Layered on top of the built-in registry.
+    #[serde(default)]
+    pub models: HashMap<String, ModelEntry>,
+
     /// Size of the context window for the model, in tokens.
     pub model_context_window: Option<u64>,
 
@@ -432,10 +475,21 @@ pub struct ConfigToml {
     #[serde(default)]
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
+    /// Language servers used to report diagnostics after `apply_patch`.
+    #[serde(default)]
+    pub lsp_servers: HashMap<String, LspServerConfig>,
+
     /// User-defined provider entries that extend/override the built-in list.
     #[serde(default)]
     pub model_providers: HashMap<String, ModelProviderInfo>,
 
+    /// Providers and models to fall back to when the configured ones fail.
+    pub model_fallback: Option<ModelFallback>,
+
+    /// Providers and models for compaction, titles, commit messages and plan
+    /// summaries.
+    pub task_models: Option<TaskModels>,
+
     /// Maximum number of bytes to include from an AGENTS.md project doc file.
     pub project_doc_max_bytes: Option<usize>,
 
@@ -450,6 +504,14 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Retention policy for the rollout files under `~/.codex/sessions`.
+    #[serde(default)]
+    pub sessions: Option<SessionRetention>,
+
+    /// Secret redaction settings.
+    #[serde(default)]
+    pub redaction: Option<Redaction>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -466,6 +528,7 @@ pub struct ConfigToml {
     pub show_raw_agent_reasoning: Option<bool>,
 
     pub model_reasoning_effort: Option<ReasoningEffort>,
+    pub auto_reasoning_effort: Option<AutoReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
@@ -516,6 +579,11 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// Enable the `symbols` tool that outlines source files and looks up
+    /// definitions by name.
+    #[serde(default)]
+    pub symbols: Option<bool>,
 }
 
 impl ConfigToml {
@@ -675,6 +743,38 @@ impl Config {
             })?
             .clone();
 
+        let model_fallback = cfg.model_fallback.unwrap_or_default();
+        for entry in &model_fallback.chain {
+            if let Some(id) = &entry.model_provider
+                && !model_providers.contains_key(id)
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Model provider `{id}` in model_fallback.chain not found"),
+                ));
+            }
+        }
+
+        let task_models = cfg.task_models.unwrap_or_default();
+        for (task, task_model) in [
+            ("compact", &task_models.compact),
+            ("title", &task_models.title),
+            ("commit_message", &task_models.commit_message),
+            ("plan_summary", &task_models.plan_summary),
+        ] {
+            if let Some(TaskModel {
+                model_provider: Some(id),
+                ..
+            }) = task_model
+                && !model_providers.contains_key(id)
+            {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Model provider `{id}` in task_models.{task} not found"),
+                ));
+            }
+        }
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let resolved_cwd = {
@@ -706,32 +806,22 @@ impl Config {
             .or(cfg.tools.as_ref().and_then(|t| t.view_image))
             .unwrap_or(true);
 
+        let include_symbols_tool = cfg.tools.as_ref().and_then(|t| t.symbols).unwrap_or(false);
+
         let model = model
             .or(config_profile.model)
             .or(cfg.model)
             .unwrap_or_else(default_model);
-        let model_family = find_family_for_model(&model).unwrap_or_else(|| {
-            let supports_reasoning_summaries =
-                cfg.model_supports_reasoning_summaries.unwrap_or(false);
-            ModelFamily {
-                slug: model.clone(),
-                family: model.clone(),
-                needs_special_apply_patch_instructions: false,
-                supports_reasoning_summaries,
-                uses_local_shell_tool: false,
-                apply_patch_tool_type: None,
-            }
-        });
-
-        let openai_model_info = get_model_info(&model_family);
-        let model_context_window = cfg
-            .model_context_window
-            .or_else(|| openai_model_info.as_ref().map(|info| info.context_window));
-        let model_max_output_tokens = cfg.model_max_output_tokens.or_else(|| {
-            openai_model_info
-                .as_ref()
-                .map(|info| info.max_output_tokens)
-        });
+        let model_registry = ModelRegistry::with_user_entries(&cfg.models);
+        let mut model_family = model_registry.family_for(&model);
+        if let Some(supports_reasoning_summaries) = cfg.model_supports_reasoning_summaries {
+            model_family.supports_reasoning_summaries = supports_reasoning_summaries;
+        }
+
+        let model_context_window = cfg.model_context_window.or(model_family.context_window);
+        let model_max_output_tokens = cfg
+            .model_max_output_tokens
+            .or(model_family.max_output_tokens);
 
         let experimental_resume = cfg.experimental_resume;
 
@@ -746,6 +836,16 @@ impl Config {
             Self::get_base_instructions(experimental_instructions_path, &resolved_cwd)?;
         let base_instructions = base_instructions.or(file_base_instructions);
 
+        let redaction = cfg.redaction.unwrap_or_default();
+        Redactor::validate(&redaction)?;
+
+        let auto_reasoning_effort = config_profile
+            .auto_reasoning_effort
+            .or(cfg.auto_reasoning_effort);
+        if let Some(bounds) = &auto_reasoning_effort {
+            reasoning_effort::validate_bounds(bounds)?;
+        }
+
         let responses_originator_header: String = cfg
             .responses_originator_header_internal_override
             .unwrap_or(DEFAULT_RESPONSES_ORIGINATOR_HEADER.to_owned());
@@ -753,6 +853,7 @@ impl Config {
         let config = Self {
             model,
             model_family,
+            model_registry,
             model_context_window,
             model_max_output_tokens,
             model_provider_id,
@@ -773,10 +874,15 @@ impl Config {
             user_instructions,
             base_instructions,
             mcp_servers: cfg.mcp_servers,
+            lsp_servers: cfg.lsp_servers,
             model_providers,
+            model_fallback,
+            task_models,
             project_doc_max_bytes: cfg.project_doc_max_bytes.unwrap_or(PROJECT_DOC_MAX_BYTES),
             codex_home,
             history,
+            sessions: cfg.sessions.unwrap_or_default(),
+            redaction,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             tui: cfg.tui.unwrap_or_default(),
             codex_linux_sandbox_exe,
@@ -790,6 +896,7 @@ impl Config {
                 .model_reasoning_effort
                 .or(cfg.model_reasoning_effort)
                 .unwrap_or_default(),
+            auto_reasoning_effort,
             model_reasoning_summary: config_profile
                 .model_reasoning_summary
                 .or(cfg.model_reasoning_summary)
@@ -810,6 +917,7 @@ impl Config {
                 .experimental_use_exec_command_tool
                 .unwrap_or(false),
             include_view_image_tool,
+            include_symbols_tool,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
             use_experimental_reasoning_summary: cfg
                 .use_experimental_reasoning_summary
@@ -919,7 +1027,13 @@ pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
 
 #[cfg(test)]
 mod tests {
+    use crate::config_types::EditingMode;
     use crate::config_types::HistoryPersistence;
+    use crate::config_types::StatusBarItem;
+    use crate::config_types::TuiColors;
+    use crate::config_types::TuiKeybindings;
+    use crate::config_types::TuiTheme;
+    use crate::model_family::find_family_for_model;
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -937,6 +1051,8 @@ persistence = "save-all"
             Some(History {
                 persistence: HistoryPersistence::SaveAll,
                 max_bytes: None,
+                max_age_days: None,
+                max_entries: None,
             }),
             history_with_persistence_cfg.history
         );
@@ -952,11 +1068,49 @@ persistence = "none"
             Some(History {
                 persistence: HistoryPersistence::None,
                 max_bytes: None,
+                max_age_days: None,
+                max_entries: None,
             }),
             history_no_persistence_cfg.history
         );
     }
 
+    #[test]
+    fn test_tui_config_parsing() {
+        let tui = r#"
+[tui]
+theme = "light"
+editing_mode = "vi"
+timestamps = true
+status_bar = ["model", "context"]
+
+[tui.colors]
+accent = "blue"
+
+[tui.keybindings]
+transcript = "ctrl-o"
+"#;
+        let cfg = toml::from_str::<ConfigToml>(tui).expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(Tui {
+                theme: TuiTheme::Light,
+                colors: TuiColors {
+                    accent: Some("blue".to_string()),
+                    ..Default::default()
+                },
+                editing_mode: EditingMode::Vi,
+                keybindings: TuiKeybindings {
+                    transcript: Some("ctrl-o".to_string()),
+                    ..Default::default()
+                },
+                timestamps: true,
+                status_bar: Some(vec![StatusBarItem::Model, StatusBarItem::Context]),
+                ..Default::default()
+            }),
+            cfg.tui
+        );
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1157,6 +1311,7 @@ model_verbosity = "high"
             Config {
                 model: "o3".to_string(),
                 model_family: find_family_for_model("o3").expect("known model slug"),
+                model_registry: ModelRegistry::built_in().clone(),
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_provider_id: "openai".to_string(),
@@ -1169,16 +1324,22 @@ model_verbosity = "high"
                 notify: None,
                 cwd: fixture.cwd(),
                 mcp_servers: HashMap::new(),
+                lsp_servers: HashMap::new(),
                 model_providers: fixture.model_provider_map.clone(),
+                model_fallback: ModelFallback::default(),
+                task_models: TaskModels::default(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                sessions: SessionRetention::default(),
+                redaction: Redaction::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 tui: Tui::default(),
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
                 model_reasoning_effort: ReasoningEffort::High,
+                auto_reasoning_effort: None,
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_verbosity: None,
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1191,6 +1352,7 @@ model_verbosity = "high"
                 preferred_auth_method: AuthMode::ChatGPT,
                 use_experimental_streamable_shell_tool: false,
                 include_view_image_tool: true,
+                include_symbols_tool: false,
                 disable_paste_burst: false,
                 use_experimental_reasoning_summary: false,
             },
@@ -1216,6 +1378,7 @@ model_verbosity = "high"
         let expected_gpt3_profile_config = Config {
             model: "gpt-3.5-turbo".to_string(),
             model_family: find_family_for_model("gpt-3.5-turbo").expect("known model slug"),
+            model_registry: ModelRegistry::built_in().clone(),
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_provider_id: "openai-chat-completions".to_string(),
@@ -1228,16 +1391,22 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
+            lsp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
+            model_fallback: ModelFallback::default(),
+            task_models: TaskModels::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            redaction: Redaction::default(),
             file_opener: UriBasedFileOpener::VsCode,
             tui: Tui::default(),
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: ReasoningEffort::default(),
+            auto_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_verbosity: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1250,6 +1419,7 @@ model_verbosity = "high"
             preferred_auth_method: AuthMode::ChatGPT,
             use_experimental_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
             disable_paste_burst: false,
             use_experimental_reasoning_summary: false,
         };
@@ -1290,6 +1460,7 @@ model_verbosity = "high"
         let expected_zdr_profile_config = Config {
             model: "o3".to_string(),
             model_family: find_family_for_model("o3").expect("known model slug"),
+            model_registry: ModelRegistry::built_in().clone(),
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_provider_id: "openai".to_string(),
@@ -1302,16 +1473,22 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
+            lsp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
+            model_fallback: ModelFallback::default(),
+            task_models: TaskModels::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            redaction: Redaction::default(),
             file_opener: UriBasedFileOpener::VsCode,
             tui: Tui::default(),
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: ReasoningEffort::default(),
+            auto_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_verbosity: None,
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1324,6 +1501,7 @@ model_verbosity = "high"
             preferred_auth_method: AuthMode::ChatGPT,
             use_experimental_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
             disable_paste_burst: false,
             use_experimental_reasoning_summary: false,
         };
@@ -1350,6 +1528,7 @@ model_verbosity = "high"
         let expected_gpt5_profile_config = Config {
             model: "gpt-5".to_string(),
             model_family: find_family_for_model("gpt-5").expect("known model slug"),
+            model_registry: ModelRegistry::built_in().clone(),
             model_context_window: Some(400_000),
             model_max_output_tokens: Some(128_000),
             model_provider_id: "openai".to_string(),
@@ -1362,16 +1541,22 @@ model_verbosity = "high"
             notify: None,
             cwd: fixture.cwd(),
             mcp_servers: HashMap::new(),
+            lsp_servers: HashMap::new(),
             model_providers: fixture.model_provider_map.clone(),
+            model_fallback: ModelFallback::default(),
+            task_models: TaskModels::default(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             codex_home: fixture.codex_home(),
             history: History::default(),
+            sessions: SessionRetention::default(),
+            redaction: Redaction::default(),
             file_opener: UriBasedFileOpener::VsCode,
             tui: Tui::default(),
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
             model_reasoning_effort: ReasoningEffort::High,
+            auto_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_verbosity: Some(Verbosity::High),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
@@ -1384,6 +1569,7 @@ model_verbosity = "high"
             preferred_auth_method: AuthMode::ChatGPT,
             use_experimental_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
             disable_paste_burst: false,
             use_experimental_reasoning_summary: false,
         };
@@ -1461,5 +1647,83 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn models_table_describes_the_configured_model() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "acme-coder-ft"
+
+[models."acme-coder*"]
+context_window = 32_768
+max_output_tokens = 8_192
+apply_patch_tool_type = "function"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.model_family.family, "acme-coder-ft");
+        assert_eq!(
+            config.model_family.apply_patch_tool_type,
+            Some(crate::tool_apply_patch::ApplyPatchToolType::Function)
+        );
+        assert_eq!(config.model_context_window, Some(32_768));
+        assert_eq!(config.model_max_output_tokens, Some(8_192));
+        assert_eq!(
+            config
+                .model_registry
+                .family_for("acme-coder-base")
+                .context_window,
+            Some(32_768)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inverted_auto_reasoning_effort_bounds_are_rejected() -> std::io::Result<()> {
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[auto_reasoning_effort]
+min = "medium"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let codex_home = TempDir::new()?;
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.auto_reasoning_effort,
+            Some(AutoReasoningEffort {
+                min: ReasoningEffort::Medium,
+                max: ReasoningEffort::High,
+            })
+        );
+
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+[auto_reasoning_effort]
+min = "high"
+max = "low"
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        Ok(())
+    }
+
     // No test enforcing the presence of a standalone [projects] header.
 }
@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use wildmatch::WildMatchPattern;
 
+use codex_protocol::config_types::ReasoningEffort;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -18,6 +19,78 @@ pub struct McpServerConfig {
 
     #[serde(default)]
     pub env: Option<HashMap<String, String>>,
+
+    /// How long the server may take to answer `initialize` and then
+    /// `tools/list` when it starts. Defaults to 10 seconds.
+    #[serde(default)]
+    pub startup_timeout_ms: Option<u64>,
+
+    /// How long a single tool call may take. Unset means no limit.
+    #[serde(default)]
+    pub tool_timeout_ms: Option<u64>,
+
+    /// How many times in a row the server is restarted after it exits or
+    /// fails to start before Codex gives up on it. Defaults to 5.
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
+
+    /// Policy for this server's tools that no entry in `tool_policies`
+    /// matches. When unset, the tool's annotations and the session's approval
+    /// policy decide; the sandbox policy does not apply to MCP servers.
+    #[serde(default)]
+    pub default_tool_policy: Option<McpToolPolicy>,
+
+    /// Policies keyed by tool name. Keys may use `*` and `?` wildcards. An
+    /// exact name takes precedence over patterns, and when several patterns
+    /// match the most restrictive policy applies.
+    #[serde(default)]
+    pub tool_policies: HashMap<String, McpToolPolicy>,
+
+    /// Whether the server may ask Codex's model for completions through
+    /// `sampling/createMessage`. Defaults to asking the user each time.
+    #[serde(default)]
+    pub sampling_policy: Option<McpToolPolicy>,
+}
+
+/// Whether Codex may call an MCP tool without asking the user first. Variants
+/// are ordered from least to most restrictive.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpToolPolicy {
+    /// Call the tool without asking.
+    Allow,
+    /// Ask the user before each call.
+    Ask,
+    /// Never call the tool; the model is told the call was refused.
+    Deny,
+}
+
+/// A language server used to report diagnostics for files changed by
+/// `apply_patch`. Entries are keyed by language name, which is also the
+/// default LSP `languageId`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct LspServerConfig {
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Extra environment variables; the server otherwise inherits the
+    /// environment of Codex.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+
+    /// File extensions (without the leading dot) handled by this server.
+    pub extensions: Vec<String>,
+
+    /// LSP `languageId` sent when opening documents. Defaults to the name of
+    /// the entry.
+    #[serde(default)]
+    pub language_id: Option<String>,
+
+    /// How long to wait for diagnostics after a patch, in milliseconds.
+    #[serde(default)]
+    pub diagnostics_timeout_ms: Option<u64>,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
@@ -57,9 +130,15 @@ pub struct History {
     /// If true, history entries will not be written to disk.
     pub persistence: HistoryPersistence,
 
-    /// If set, the maximum size of the history file in bytes.
-    /// TODO(mbolin): Not currently honored.
+    /// If set, the maximum size of the history file in bytes. Once the file
+    /// grows past it, the oldest entries are dropped.
     pub max_bytes: Option<usize>,
+
+    /// If set, entries older than this many days are dropped.
+    pub max_age_days: Option<u64>,
+
+    /// If set, only this many of the newest entries are kept.
+    pub max_entries: Option<usize>,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
@@ -72,9 +151,240 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Retention policy for the rollout files under `~/.codex/sessions`. Every
+/// limit is optional; with none set, rollouts are kept forever.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SessionRetention {
+    /// Delete rollouts that have not been written to for this many days.
+    pub max_age_days: Option<u64>,
+
+    /// Delete the oldest rollouts once all of them together take up more than
+    /// this many bytes on disk.
+    pub max_total_bytes: Option<u64>,
+
+    /// Keep at most this many rollouts.
+    pub max_count: Option<usize>,
+
+    /// Gzip rollouts that have not been written to for this many days. They
+    /// can still be listed and resumed.
+    pub compress_after_days: Option<u64>,
+}
+
+/// Models to switch a turn to when the session's model keeps failing. See
+/// [`crate::model_fallback`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ModelFallback {
+    /// Tried in order after the session's own provider and model.
+    #[serde(default)]
+    pub chain: Vec<ModelFallbackEntry>,
+
+    /// Give up on a provider after this many rate-limited (429) retries
+    /// instead of its `request_max_retries`. Only applies when `chain` is
+    /// not empty.
+    pub rate_limit_retries: Option<u64>,
+}
+
+/// Bounds within which the reasoning effort of each request is picked
+/// automatically. See [`crate::reasoning_effort`].
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoReasoningEffort {
+    #[serde(default = "default_auto_effort_min")]
+    pub min: ReasoningEffort,
+    #[serde(default = "default_auto_effort_max")]
+    pub max: ReasoningEffort,
+}
+
+fn default_auto_effort_min() -> ReasoningEffort {
+    ReasoningEffort::Low
+}
+
+fn default_auto_effort_max() -> ReasoningEffort {
+    ReasoningEffort::High
+}
+
+/// One provider/model pair in [`ModelFallback::chain`].
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ModelFallbackEntry {
+    /// Key into the `model_providers` map. Defaults to the session's
+    /// provider.
+    pub model_provider: Option<String>,
+
+    /// Defaults to the session's model.
+    pub model: Option<String>,
+}
+
+/// Models for the jobs that run alongside the conversation. A job without
+/// an entry uses the session's provider and model. See [`crate::auxiliary`].
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TaskModels {
+    /// Summarizing the conversation for `/compact`.
+    pub compact: Option<TaskModel>,
+
+    /// Naming sessions for the resume picker. Titles are only generated when
+    /// this is set.
+    pub title: Option<TaskModel>,
+
+    /// Drafting commit messages for `/commit`.
+    pub commit_message: Option<TaskModel>,
+
+    /// Summarizing progress on the plan for `/plan`.
+    pub plan_summary: Option<TaskModel>,
+}
+
+/// The provider and model that run one of the [`TaskModels`] jobs.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TaskModel {
+    /// Key into the `model_providers` map. Defaults to the session's
+    /// provider.
+    pub model_provider: Option<String>,
+
+    /// Defaults to the session's model.
+    pub model: Option<String>,
+
+    /// Defaults to the session's reasoning effort.
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+}
+
+/// Settings for scrubbing secrets such as API keys and tokens out of what
+/// Codex records. See [`crate::redaction`] for the built-in detectors.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Redaction {
+    /// Redact `history.jsonl`, rollout files and the TUI session log.
+    /// Defaults to `true`.
+    #[serde(default = "default_redaction_enabled")]
+    pub enabled: bool,
+
+    /// Additional regular expressions whose matches are redacted.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+
+    /// Also redact command output before it is sent to the model. Defaults
+    /// to `false`.
+    #[serde(default)]
+    pub exec_output: bool,
+}
+
+fn default_redaction_enabled() -> bool {
+    true
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            enabled: default_redaction_enabled(),
+            patterns: Vec::new(),
+            exec_output: false,
+        }
+    }
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
-pub struct Tui {}
+pub struct Tui {
+    /// Theme used to highlight code blocks and diffs. Defaults to `ansi`,
+    /// which only uses the terminal's own palette; `none` disables
+    /// highlighting.
+    #[serde(default)]
+    pub syntax_theme: Option<String>,
+
+    /// Color theme for the interface. `light` suits terminals with a light
+    /// background.
+    #[serde(default)]
+    pub theme: TuiTheme,
+
+    /// Per-role color overrides applied on top of `theme`.
+    #[serde(default)]
+    pub colors: TuiColors,
+
+    /// Key bindings used while editing a message in the composer.
+    #[serde(default)]
+    pub editing_mode: EditingMode,
+
+    /// Replacements for the default global and approval shortcuts.
+    #[serde(default)]
+    pub keybindings: TuiKeybindings,
+
+    /// When true, each history entry starts with the time it was added.
+    #[serde(default)]
+    pub timestamps: bool,
+
+    /// When true, history entries are not separated by blank lines.
+    #[serde(default)]
+    pub compact: bool,
+
+    /// Items shown in the status bar below the composer, in order. Defaults
+    /// to `["keys", "tokens", "context"]`.
+    #[serde(default)]
+    pub status_bar: Option<Vec<StatusBarItem>>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TuiTheme {
+    /// The default styles, tuned for dark backgrounds.
+    #[default]
+    Default,
+    /// Avoids dimmed text and cyan accents, which are hard to read on light
+    /// backgrounds.
+    Light,
+}
+
+/// Colors for the roles used throughout the TUI. Values are color names
+/// (`blue`, `light-red`, ...), 256-color palette indices or `#rrggbb`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TuiColors {
+    /// Selections, key hints and other accents (cyan by default).
+    pub accent: Option<String>,
+    /// Successes and added lines (green by default).
+    pub success: Option<String>,
+    /// Errors and removed lines (red by default).
+    pub error: Option<String>,
+    /// Codex's own messages (magenta by default).
+    pub codex: Option<String>,
+    /// Secondary text, which is dimmed by default.
+    pub secondary: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum EditingMode {
+    /// Emacs-style shortcuts such as Ctrl+A, Ctrl+E and Ctrl+K.
+    #[default]
+    Emacs,
+    /// Modal editing: Esc switches to normal mode, `i`/`a` back to insert.
+    Vi,
+}
+
+/// Key bindings written as `ctrl-t`, `alt-up`, `esc` or a single character.
+/// Unset entries keep their defaults.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TuiKeybindings {
+    /// Open the transcript overlay (default `ctrl-t`).
+    pub transcript: Option<String>,
+    /// Step back through previous messages to edit one (default `esc`).
+    pub backtrack: Option<String>,
+    /// Edit the message in `$VISUAL` or `$EDITOR` (default `ctrl-g`).
+    pub external_editor: Option<String>,
+    /// Approve a command or patch once (default `y`).
+    pub approve: Option<String>,
+    /// Approve a command for the rest of the session (default `a`).
+    pub approve_for_session: Option<String>,
+    /// Reject a command or patch and explain why (default `n`).
+    pub deny: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusBarItem {
+    /// Hints for the main shortcuts.
+    Keys,
+    /// The active model.
+    Model,
+    /// Tokens used so far in the session.
+    Tokens,
+    /// Share of the context window that is still available.
+    Context,
+}
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SandboxWorkspaceWrite {
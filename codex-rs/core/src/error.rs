@@ -48,6 +48,11 @@ pub enum CodexErr {
     #[error("stream disconnected before completion: {0}")]
     Stream(String, Option<Duration>),
 
+    /// Returned when the SSE stream stays silent for longer than the
+    /// provider's `stream_idle_timeout`. Retried like [`CodexErr::Stream`].
+    #[error("stream disconnected before completion: idle timeout waiting for SSE")]
+    StreamIdleTimeout,
+
     #[error("no conversation with id: {0}")]
     ConversationNotFound(Uuid),
 
@@ -98,6 +103,10 @@ pub enum CodexErr {
     #[error("codex-linux-sandbox was required but not provided")]
     LandlockSandboxExecutableNotProvided,
 
+    /// Recording or replaying model traffic failed (see cassette.rs).
+    #[error("cassette: {0}")]
+    Cassette(String),
+
     // -----------------------------------------------------------------
     // Automatic conversions for common external error types
     // -----------------------------------------------------------------
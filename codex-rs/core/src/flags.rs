@@ -13,4 +13,10 @@ env_flags! {
 
     /// Fixture path for offline tests (see client.rs).
     pub CODEX_RS_SSE_FIXTURE: Option<&str> = None;
+
+    /// Directory to record model traffic to (see cassette.rs).
+    pub CODEX_RS_CASSETTE_RECORD: Option<&str> = None;
+
+    /// Directory of recorded model traffic to replay (see cassette.rs).
+    pub CODEX_RS_CASSETTE_REPLAY: Option<&str> = None;
 }
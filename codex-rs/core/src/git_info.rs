@@ -135,6 +135,24 @@ pub async fn git_diff_to_remote(cwd: &Path) -> Option<GitDiffToRemote> {
     })
 }
 
+/// Returns the staged changes, or all changes to tracked files when nothing
+/// is staged. `None` outside a repository or when there are no changes.
+pub(crate) async fn uncommitted_diff(cwd: &Path) -> Option<String> {
+    get_git_repo_root(cwd)?;
+
+    for args in [&["diff", "--cached"][..], &["diff", "HEAD"][..]] {
+        let output = run_git_command_with_timeout(args, cwd).await?;
+        if !output.status.success() {
+            continue;
+        }
+        let diff = String::from_utf8_lossy(&output.stdout).into_owned();
+        if !diff.trim().is_empty() {
+            return Some(diff);
+        }
+    }
+    None
+}
+
 /// Run a git command with a timeout to prevent blocking on large repositories
 async fn run_git_command_with_timeout(args: &[&str], cwd: &Path) -> Option<std::process::Output> {
     let result = timeout(
@@ -711,6 +729,28 @@ mod tests {
         assert_eq!(git_info.branch, Some("feature-branch".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_uncommitted_diff_prefers_staged_changes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        assert_eq!(uncommitted_diff(&repo_path).await, None);
+
+        fs::write(repo_path.join("test.txt"), "unstaged").unwrap();
+        let diff = uncommitted_diff(&repo_path).await.expect("unstaged diff");
+        assert!(diff.contains("+unstaged"));
+
+        fs::write(repo_path.join("staged.txt"), "staged").unwrap();
+        Command::new("git")
+            .args(["add", "staged.txt"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to stage file");
+        let diff = uncommitted_diff(&repo_path).await.expect("staged diff");
+        assert!(diff.contains("+staged"));
+        assert!(!diff.contains("+unstaged"));
+    }
+
     #[tokio::test]
     async fn test_get_git_working_tree_state_clean_repo() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -6,8 +6,11 @@
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
 mod apply_patch;
+pub mod approval_rules;
 pub mod auth;
+mod auxiliary;
 mod bash;
+mod cassette;
 mod chat_completions;
 mod client;
 mod client_common;
@@ -29,9 +32,12 @@ mod flags;
 pub mod git_info;
 mod is_safe_command;
 pub mod landlock;
+mod lsp;
 mod mcp_connection_manager;
+mod mcp_sampling;
 mod mcp_tool_call;
 mod message_history;
+mod model_fallback;
 mod model_provider_info;
 pub mod parse_command;
 mod user_instructions;
@@ -49,15 +55,18 @@ pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
 pub mod model_family;
-mod openai_model_info;
 mod openai_tools;
 pub mod plan_tool;
 pub mod project_doc;
+mod reasoning_effort;
+pub mod redaction;
+pub mod retention;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
 pub mod shell;
 pub mod spawn;
+mod symbols;
 pub mod terminal;
 mod tool_apply_patch;
 pub mod turn_diff_tracker;
@@ -0,0 +1,493 @@
+//! A minimal JSON-RPC client for the Language Server Protocol.
+//!
+//! Only the pieces needed to collect diagnostics are implemented:
+//!   1. Framing messages with `Content-Length` headers over a pair of byte
+//!      streams (usually the STDIO of a spawned language server).
+//!   2. Pairing requests with their responses.
+//!   3. Answering the handful of requests servers send to clients with
+//!      neutral defaults so they do not stall waiting for us.
+//!   4. Recording every `textDocument/publishDiagnostics` notification.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::debug;
+use tracing::warn;
+
+use crate::config_types::LspServerConfig;
+
+/// Capacity of the channel feeding the writer task.
+const CHANNEL_CAPACITY: usize = 128;
+
+/// Many servers publish an empty set for a document as soon as it changes
+/// and the real diagnostics once analysis finishes. Unless the server tags
+/// them with the version we sent, diagnostics are only taken as final once
+/// none have been published for this long.
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+
+type PendingSender = oneshot::Sender<Result<Value, String>>;
+
+/// Zero-based position in a document, as defined by LSP.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+pub(crate) struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+pub(crate) struct Range {
+    pub start: Position,
+}
+
+/// The subset of an LSP `Diagnostic` that we report back to the model.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub(crate) struct Diagnostic {
+    pub range: Range,
+    /// 1 = error, 2 = warning, 3 = information, 4 = hint.
+    #[serde(default)]
+    pub severity: Option<u8>,
+    #[serde(default)]
+    pub code: Option<Value>,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PublishDiagnosticsParams {
+    uri: String,
+    #[serde(default)]
+    version: Option<i32>,
+    #[serde(default)]
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Latest diagnostics published for a single document.
+#[derive(Debug, Clone)]
+struct PublishedDiagnostics {
+    /// Value of the client-wide publish counter when these arrived.
+    seq: u64,
+    /// Document version the server computed these for, if it said.
+    version: Option<i32>,
+    at: Instant,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Default)]
+struct DiagnosticsStore {
+    seq: u64,
+    by_uri: HashMap<String, PublishedDiagnostics>,
+}
+
+/// A connection to a running language server.
+pub(crate) struct LspClient {
+    /// Retain the server process until the client is dropped; it is killed
+    /// along with the client (`kill_on_drop`).
+    #[allow(dead_code)]
+    child: Option<Child>,
+
+    outgoing_tx: mpsc::Sender<Value>,
+    pending: Arc<Mutex<HashMap<i64, PendingSender>>>,
+    id_counter: AtomicI64,
+
+    diagnostics: Arc<Mutex<DiagnosticsStore>>,
+    /// Bumped (to the store's `seq`) whenever diagnostics are published.
+    diagnostics_rx: watch::Receiver<u64>,
+
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl LspClient {
+    /// Spawns the configured server and connects to it over its STDIO. The
+    /// caller is responsible for calling [`initialize`](Self::initialize).
+    pub(crate) fn spawn(config: &LspServerConfig, cwd: &Path) -> std::io::Result<Self> {
+        let mut command = Command::new(&config.command);
+        command
+            .args(&config.args)
+            .current_dir(cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        // Language servers usually need the user's full environment (`PATH`,
+        // toolchain homes, virtualenvs, ...), so only add to it.
+        if let Some(env) = &config.env {
+            command.envs(env);
+        }
+        let mut child = command.spawn()?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| std::io::Error::other("failed to capture child stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| std::io::Error::other("failed to capture child stdout"))?;
+
+        let mut client = Self::new(stdout, stdin);
+        client.child = Some(child);
+        Ok(client)
+    }
+
+    /// Connects to a server that reads from `writer` and writes to `reader`.
+    pub(crate) fn new<R, W>(reader: R, writer: W) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+        W: AsyncWrite + Send + Unpin + 'static,
+    {
+        let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<Value>(CHANNEL_CAPACITY);
+        let pending: Arc<Mutex<HashMap<i64, PendingSender>>> = Arc::default();
+        let diagnostics: Arc<Mutex<DiagnosticsStore>> = Arc::default();
+        let (diagnostics_tx, diagnostics_rx) = watch::channel(0);
+
+        let writer_handle = tokio::spawn(async move {
+            let mut writer = writer;
+            while let Some(message) = outgoing_rx.recv().await {
+                if let Err(e) = write_message(&mut writer, &message).await {
+                    warn!("failed to write message to language server: {e}");
+                    break;
+                }
+            }
+        });
+
+        let reader_handle = {
+            let pending = pending.clone();
+            let diagnostics = diagnostics.clone();
+            let outgoing_tx = outgoing_tx.clone();
+            tokio::spawn(async move {
+                let mut reader = BufReader::new(reader);
+                loop {
+                    let message = match read_message(&mut reader).await {
+                        Ok(Some(message)) => message,
+                        Ok(None) => break,
+                        Err(e) => {
+                            warn!("failed to read message from language server: {e}");
+                            break;
+                        }
+                    };
+                    dispatch_incoming(
+                        message,
+                        &pending,
+                        &diagnostics,
+                        &diagnostics_tx,
+                        &outgoing_tx,
+                    )
+                    .await;
+                }
+                // The server is gone: fail every in-flight request.
+                pending.lock().await.clear();
+            })
+        };
+
+        Self {
+            child: None,
+            outgoing_tx,
+            pending,
+            id_counter: AtomicI64::new(1),
+            diagnostics,
+            diagnostics_rx,
+            tasks: vec![writer_handle, reader_handle],
+        }
+    }
+
+    /// Performs the `initialize` / `initialized` handshake for a workspace
+    /// rooted at `root`.
+    pub(crate) async fn initialize(&self, root: &Path, timeout: Duration) -> anyhow::Result<()> {
+        let root_uri = path_to_uri(root)?;
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| root_uri.clone());
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "codex", "version": env!("CARGO_PKG_VERSION") },
+            "rootUri": root_uri,
+            "rootPath": root,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "publishDiagnostics": { "relatedInformation": false },
+                },
+                "workspace": { "configuration": true, "workspaceFolders": true },
+            },
+        });
+        self.request("initialize", params, timeout)
+            .await
+            .context("initialize request failed")?;
+        self.notify("initialized", json!({})).await
+    }
+
+    /// Sends a request and waits up to `timeout` for its result.
+    pub(crate) async fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> anyhow::Result<Value> {
+        let id = self.id_counter.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        if self.outgoing_tx.send(message).await.is_err() {
+            self.pending.lock().await.remove(&id);
+            return Err(anyhow!("language server connection closed"));
+        }
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(error))) => Err(anyhow!("`{method}` failed: {error}")),
+            Ok(Err(_)) => Err(anyhow!(
+                "language server exited before answering `{method}`"
+            )),
+            Err(_) => {
+                self.pending.lock().await.remove(&id);
+                Err(anyhow!(
+                    "timed out after {}ms waiting for `{method}`",
+                    timeout.as_millis()
+                ))
+            }
+        }
+    }
+
+    /// Sends a notification; there is no response to wait for.
+    pub(crate) async fn notify(&self, method: &str, params: Value) -> anyhow::Result<()> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        self.outgoing_tx
+            .send(message)
+            .await
+            .map_err(|_| anyhow!("language server connection closed"))
+    }
+
+    /// Current value of the publish counter. Diagnostics published after a
+    /// change was sent are the ones with a larger `seq`.
+    pub(crate) fn diagnostics_seq(&self) -> u64 {
+        *self.diagnostics_rx.borrow()
+    }
+
+    /// Waits until the diagnostics for `documents` (URI and the version last
+    /// sent) have settled, or until `timeout` elapses. They have settled once
+    /// diagnostics newer than `after_seq` were published for every document
+    /// and either each carries the version that was sent or nothing new has
+    /// been published for [`QUIET_PERIOD`]. Returns whatever arrived, keyed
+    /// by URI; URIs missing from the result timed out.
+    pub(crate) async fn wait_for_diagnostics(
+        &self,
+        documents: &[(String, i32)],
+        after_seq: u64,
+        timeout: Duration,
+    ) -> HashMap<String, Vec<Diagnostic>> {
+        let mut rx = self.diagnostics_rx.clone();
+        let deadline = Instant::now() + timeout;
+        loop {
+            let (found, settled_at) = self.published_since(documents, after_seq).await;
+            let wake = match settled_at {
+                Some(settled_at) if settled_at <= Instant::now() => return found,
+                Some(settled_at) => settled_at.min(deadline),
+                None => deadline,
+            };
+            match tokio::time::timeout_at(wake, rx.changed()).await {
+                Ok(Ok(())) => {}
+                // The quiet period is over; check again.
+                Err(_) if wake < deadline => {}
+                // Timed out, or the reader task is gone and nothing else
+                // will ever arrive.
+                Ok(Err(_)) | Err(_) => {
+                    return self.published_since(documents, after_seq).await.0;
+                }
+            }
+        }
+    }
+
+    /// The diagnostics published for `documents` since `after_seq`, and when
+    /// they count as settled: `None` while some document has none yet.
+    async fn published_since(
+        &self,
+        documents: &[(String, i32)],
+        after_seq: u64,
+    ) -> (HashMap<String, Vec<Diagnostic>>, Option<Instant>) {
+        let store = self.diagnostics.lock().await;
+        let mut found = HashMap::new();
+        let mut all_current = true;
+        let mut last_published = None;
+        for (uri, version) in documents {
+            let Some(published) = store
+                .by_uri
+                .get(uri)
+                .filter(|published| published.seq > after_seq)
+            else {
+                continue;
+            };
+            found.insert(uri.clone(), published.diagnostics.clone());
+            all_current &= published.version == Some(*version);
+            last_published = last_published.max(Some(published.at));
+        }
+        let settled_at = if found.len() < documents.len() {
+            None
+        } else if all_current {
+            Some(Instant::now())
+        } else {
+            last_published.map(|at| at + QUIET_PERIOD)
+        };
+        (found, settled_at)
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn dispatch_incoming(
+    message: Value,
+    pending: &Mutex<HashMap<i64, PendingSender>>,
+    diagnostics: &Mutex<DiagnosticsStore>,
+    diagnostics_tx: &watch::Sender<u64>,
+    outgoing_tx: &mpsc::Sender<Value>,
+) {
+    let method = message.get("method").and_then(Value::as_str);
+    let id = message.get("id");
+    match (method, id) {
+        // Response to one of our requests.
+        (None, Some(id)) => {
+            let Some(id) = id.as_i64() else {
+                warn!("response with unexpected id from language server: {message}");
+                return;
+            };
+            let result = match message.get("error") {
+                Some(error) => Err(error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| error.to_string(), str::to_string)),
+                None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+            };
+            if let Some(tx) = pending.lock().await.remove(&id) {
+                let _ = tx.send(result);
+            }
+        }
+        // Request from the server. Answer with neutral defaults.
+        (Some(method), Some(id)) => {
+            let result = match method {
+                "workspace/configuration" => {
+                    let items = message
+                        .pointer("/params/items")
+                        .and_then(Value::as_array)
+                        .map_or(0, Vec::len);
+                    Value::Array(vec![Value::Null; items])
+                }
+                "workspace/workspaceFolders" => Value::Array(Vec::new()),
+                _ => Value::Null,
+            };
+            let response = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+            let _ = outgoing_tx.send(response).await;
+        }
+        (Some("textDocument/publishDiagnostics"), None) => {
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            match serde_json::from_value::<PublishDiagnosticsParams>(params) {
+                Ok(params) => {
+                    let seq = {
+                        let mut store = diagnostics.lock().await;
+                        store.seq += 1;
+                        let seq = store.seq;
+                        store.by_uri.insert(
+                            params.uri,
+                            PublishedDiagnostics {
+                                seq,
+                                version: params.version,
+                                at: Instant::now(),
+                                diagnostics: params.diagnostics,
+                            },
+                        );
+                        seq
+                    };
+                    diagnostics_tx.send_replace(seq);
+                }
+                Err(e) => warn!("invalid publishDiagnostics notification: {e}"),
+            }
+        }
+        (Some(method), None) => debug!("ignoring `{method}` notification from language server"),
+        (None, None) => warn!("unexpected message from language server: {message}"),
+    }
+}
+
+/// Reads one `Content-Length` framed message. Returns `Ok(None)` on a clean
+/// end of stream.
+pub(crate) async fn read_message<R>(reader: &mut R) -> std::io::Result<Option<Value>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = Some(value.trim().parse::<usize>().map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{line}: {e}"))
+            })?);
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Writes one `Content-Length` framed message.
+pub(crate) async fn write_message<W>(writer: &mut W, message: &Value) -> std::io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let body = serde_json::to_vec(message)?;
+    let header = format!("Content-Length: {}\r\n\r\n", body.len());
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(&body).await?;
+    writer.flush().await
+}
+
+/// `file://` URI for an absolute path.
+pub(crate) fn path_to_uri(path: &Path) -> anyhow::Result<String> {
+    url::Url::from_file_path(path)
+        .map(String::from)
+        .map_err(|()| anyhow!("cannot convert {} to a file URI", path.display()))
+}
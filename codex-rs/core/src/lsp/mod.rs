@@ -0,0 +1,541 @@
+//! Reports language-server diagnostics for files changed by `apply_patch`.
+//!
+//! Servers are configured per language under `lsp_servers` in `config.toml`
+//! and are only spawned the first time a patch touches a file with one of
+//! their extensions. Every patched file is then synced to the server (full
+//! text `didOpen`/`didChange` followed by `didSave`), and whatever errors and
+//! warnings the server publishes within the configured timeout are appended
+//! to the `apply_patch` output so the model sees them without running a
+//! build.
+
+mod client;
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::config_types::LspServerConfig;
+use client::Diagnostic;
+use client::LspClient;
+use client::path_to_uri;
+
+/// How long to wait for a freshly spawned server to answer `initialize`.
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time to wait for diagnostics after syncing the patched files.
+const DEFAULT_DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum number of diagnostics listed per server in a single report.
+const MAX_DIAGNOSTICS_PER_SERVER: usize = 50;
+
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+
+/// Owns the language servers of a session.
+pub(crate) struct LspManager {
+    /// Workspace root reported to the servers.
+    root: PathBuf,
+    servers: HashMap<String, LspServerConfig>,
+    states: Mutex<HashMap<String, ServerState>>,
+}
+
+enum ServerState {
+    Running(Box<RunningServer>),
+    /// The server could not be started or has died; the failure has already
+    /// been reported once, so it is skipped for the rest of the session.
+    Failed,
+}
+
+struct RunningServer {
+    client: LspClient,
+    /// Last version sent for every document opened on the server.
+    versions: HashMap<PathBuf, i32>,
+}
+
+impl LspManager {
+    pub(crate) fn new(servers: HashMap<String, LspServerConfig>, root: PathBuf) -> Self {
+        Self {
+            root,
+            servers,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Syncs `paths` (absolute paths of files that were just added, updated
+    /// or deleted) to their language servers and returns a report of the
+    /// diagnostics they produced. Returns `None` when no configured server
+    /// handles any of the paths.
+    pub(crate) async fn diagnostics_for_paths(&self, paths: &[PathBuf]) -> Option<String> {
+        let mut by_server: BTreeMap<&str, Vec<PathBuf>> = BTreeMap::new();
+        for path in paths {
+            if let Some(name) = self.server_for_path(path) {
+                by_server.entry(name).or_default().push(path.clone());
+            }
+        }
+        if by_server.is_empty() {
+            return None;
+        }
+
+        let mut sections = Vec::new();
+        let mut states = self.states.lock().await;
+        for (name, paths) in by_server {
+            let config = &self.servers[name];
+            let state = match states.remove(name) {
+                Some(state) => state,
+                None => match self.start_server(config).await {
+                    Ok(server) => ServerState::Running(Box::new(server)),
+                    Err(e) => {
+                        warn!("failed to start language server `{name}`: {e:#}");
+                        sections.push(format!("Language server `{name}` failed to start: {e:#}"));
+                        states.insert(name.to_string(), ServerState::Failed);
+                        continue;
+                    }
+                },
+            };
+            let ServerState::Running(mut server) = state else {
+                states.insert(name.to_string(), state);
+                continue;
+            };
+
+            match self.collect(name, config, &mut server, &paths).await {
+                Ok(section) => {
+                    sections.push(section);
+                    states.insert(name.to_string(), ServerState::Running(server));
+                }
+                Err(e) => {
+                    warn!("language server `{name}` failed: {e:#}");
+                    sections.push(format!("Language server `{name}` failed: {e:#}"));
+                    states.insert(name.to_string(), ServerState::Failed);
+                }
+            }
+        }
+        if sections.is_empty() {
+            None
+        } else {
+            Some(sections.join("\n"))
+        }
+    }
+
+    /// Name of the server configured for the extension of `path`. Ties are
+    /// broken by name so the choice is stable.
+    fn server_for_path(&self, path: &Path) -> Option<&str> {
+        let extension = path.extension()?.to_str()?;
+        self.servers
+            .iter()
+            .filter(|(_, config)| {
+                config
+                    .extensions
+                    .iter()
+                    .any(|ext| ext.trim_start_matches('.') == extension)
+            })
+            .map(|(name, _)| name.as_str())
+            .min()
+    }
+
+    async fn start_server(&self, config: &LspServerConfig) -> anyhow::Result<RunningServer> {
+        let client = LspClient::spawn(config, &self.root)?;
+        client.initialize(&self.root, INITIALIZE_TIMEOUT).await?;
+        Ok(RunningServer {
+            client,
+            versions: HashMap::new(),
+        })
+    }
+
+    async fn collect(
+        &self,
+        name: &str,
+        config: &LspServerConfig,
+        server: &mut RunningServer,
+        paths: &[PathBuf],
+    ) -> anyhow::Result<String> {
+        let language_id = config.language_id.as_deref().unwrap_or(name);
+        let timeout = config
+            .diagnostics_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_DIAGNOSTICS_TIMEOUT);
+
+        let after_seq = server.client.diagnostics_seq();
+        let mut synced = Vec::new();
+        for path in paths {
+            let uri = path_to_uri(path)?;
+            let text = match tokio::fs::read(path).await {
+                Ok(bytes) => String::from_utf8(bytes).ok(),
+                Err(_) => None,
+            };
+            let Some(text) = text else {
+                // Deleted (or not text): the server should forget about it.
+                if server.versions.remove(path).is_some() {
+                    server
+                        .client
+                        .notify(
+                            "textDocument/didClose",
+                            json!({ "textDocument": { "uri": uri } }),
+                        )
+                        .await?;
+                }
+                continue;
+            };
+
+            let version = match server.versions.get_mut(path) {
+                Some(version) => {
+                    *version += 1;
+                    server
+                        .client
+                        .notify(
+                            "textDocument/didChange",
+                            json!({
+                                "textDocument": { "uri": uri, "version": *version },
+                                "contentChanges": [{ "text": text }],
+                            }),
+                        )
+                        .await?;
+                    *version
+                }
+                None => {
+                    server.versions.insert(path.clone(), 1);
+                    server
+                        .client
+                        .notify(
+                            "textDocument/didOpen",
+                            json!({
+                                "textDocument": {
+                                    "uri": uri,
+                                    "languageId": language_id,
+                                    "version": 1,
+                                    "text": text,
+                                },
+                            }),
+                        )
+                        .await?;
+                    1
+                }
+            };
+            server
+                .client
+                .notify(
+                    "textDocument/didSave",
+                    json!({ "textDocument": { "uri": uri } }),
+                )
+                .await?;
+            synced.push((path.as_path(), uri, version));
+        }
+
+        let documents: Vec<(String, i32)> = synced
+            .iter()
+            .map(|(_, uri, version)| (uri.clone(), *version))
+            .collect();
+        let published = server
+            .client
+            .wait_for_diagnostics(&documents, after_seq, timeout)
+            .await;
+
+        let mut report = DiagnosticsReport::default();
+        for (path, uri, _) in &synced {
+            match published.get(uri) {
+                Some(diagnostics) => report.add(&self.display_path(path), diagnostics),
+                None => report.timed_out += 1,
+            }
+        }
+        Ok(report.render(name, timeout))
+    }
+
+    fn display_path(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+#[derive(Default)]
+struct DiagnosticsReport {
+    lines: Vec<String>,
+    omitted: usize,
+    timed_out: usize,
+}
+
+impl DiagnosticsReport {
+    fn add(&mut self, path: &str, diagnostics: &[Diagnostic]) {
+        let mut relevant: Vec<&Diagnostic> = diagnostics
+            .iter()
+            .filter(|d| d.severity.unwrap_or(SEVERITY_ERROR) <= SEVERITY_WARNING)
+            .collect();
+        relevant.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+        for diagnostic in relevant {
+            if self.lines.len() >= MAX_DIAGNOSTICS_PER_SERVER {
+                self.omitted += 1;
+                continue;
+            }
+            self.lines.push(format_diagnostic(path, diagnostic));
+        }
+    }
+
+    fn render(self, name: &str, timeout: Duration) -> String {
+        let mut out = if self.lines.is_empty() {
+            format!("Language server `{name}` reported no errors or warnings in the changed files.")
+        } else {
+            let mut out = format!("Diagnostics from language server `{name}`:");
+            for line in &self.lines {
+                out.push('\n');
+                out.push_str(line);
+            }
+            if self.omitted > 0 {
+                out.push_str(&format!("\n... {} more not shown", self.omitted));
+            }
+            out
+        };
+        if self.timed_out > 0 {
+            out.push_str(&format!(
+                "\n(no diagnostics received for {} file(s) within {}ms)",
+                self.timed_out,
+                timeout.as_millis()
+            ));
+        }
+        out
+    }
+}
+
+fn format_diagnostic(path: &str, diagnostic: &Diagnostic) -> String {
+    let severity = match diagnostic.severity {
+        Some(SEVERITY_WARNING) => "warning",
+        _ => "error",
+    };
+    let line = diagnostic.range.start.line + 1;
+    let column = diagnostic.range.start.character + 1;
+    let message = diagnostic.message.lines().next().unwrap_or_default();
+    match &diagnostic.code {
+        Some(Value::String(code)) => {
+            format!("{path}:{line}:{column}: {severity}[{code}]: {message}")
+        }
+        Some(Value::Number(code)) => {
+            format!("{path}:{line}:{column}: {severity}[{code}]: {message}")
+        }
+        _ => format!("{path}:{line}:{column}: {severity}: {message}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::read_message;
+    use client::write_message;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+    use tokio::io::AsyncRead;
+    use tokio::io::AsyncWrite;
+    use tokio::io::BufReader;
+
+    /// A tiny language server: every line containing `ERROR` or `WARN`
+    /// yields an error or warning at that position. Files whose name starts
+    /// with `silent` never get diagnostics. For files whose name starts with
+    /// `slow`, an empty set is published first and the real diagnostics a
+    /// little later, tagged with the document version if the name also
+    /// contains `versioned`.
+    async fn run_stub_server<R, W>(reader: R, mut writer: W)
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut reader = BufReader::new(reader);
+        while let Ok(Some(message)) = read_message(&mut reader).await {
+            let method = message["method"].as_str().unwrap_or_default();
+            let response = match method {
+                "initialize" => json!({
+                    "jsonrpc": "2.0",
+                    "id": message["id"],
+                    "result": { "capabilities": { "textDocumentSync": 1 } },
+                }),
+                "textDocument/didOpen" | "textDocument/didChange" => {
+                    let uri = message["params"]["textDocument"]["uri"].as_str().unwrap();
+                    let name = uri.rsplit('/').next().unwrap();
+                    if name.starts_with("silent") {
+                        continue;
+                    }
+                    let text = message["params"]["textDocument"]["text"]
+                        .as_str()
+                        .or_else(|| message["params"]["contentChanges"][0]["text"].as_str())
+                        .unwrap();
+                    let mut diagnostics = Vec::new();
+                    for (line, content) in text.lines().enumerate() {
+                        for (marker, severity) in [("ERROR", 1), ("WARN", 2)] {
+                            if let Some(column) = content.find(marker) {
+                                diagnostics.push(json!({
+                                    "range": {
+                                        "start": { "line": line, "character": column },
+                                        "end": { "line": line, "character": column + marker.len() },
+                                    },
+                                    "severity": severity,
+                                    "code": "E1",
+                                    "message": format!("found {marker}"),
+                                }));
+                            }
+                        }
+                    }
+                    let mut params = json!({ "uri": uri, "diagnostics": diagnostics });
+                    if name.starts_with("slow") {
+                        let empty = json!({
+                            "jsonrpc": "2.0",
+                            "method": "textDocument/publishDiagnostics",
+                            "params": { "uri": uri, "diagnostics": [] },
+                        });
+                        write_message(&mut writer, &empty).await.unwrap();
+                        tokio::time::sleep(Duration::from_millis(200)).await;
+                        if name.contains("versioned") {
+                            params["version"] =
+                                message["params"]["textDocument"]["version"].clone();
+                        }
+                    }
+                    json!({
+                        "jsonrpc": "2.0",
+                        "method": "textDocument/publishDiagnostics",
+                        "params": params,
+                    })
+                }
+                _ => continue,
+            };
+            write_message(&mut writer, &response).await.unwrap();
+        }
+    }
+
+    async fn manager_with_stub(timeout_ms: u64) -> (TempDir, LspManager) {
+        let root = TempDir::new().unwrap();
+        let config = LspServerConfig {
+            command: "stub-language-server".to_string(),
+            args: Vec::new(),
+            env: None,
+            extensions: vec!["txt".to_string()],
+            language_id: None,
+            diagnostics_timeout_ms: Some(timeout_ms),
+        };
+        let manager = LspManager::new(
+            HashMap::from([("stub".to_string(), config)]),
+            root.path().to_path_buf(),
+        );
+
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (client_read, client_write) = tokio::io::split(client_io);
+        let (server_read, server_write) = tokio::io::split(server_io);
+        tokio::spawn(run_stub_server(server_read, server_write));
+        let client = LspClient::new(client_read, client_write);
+        client
+            .initialize(root.path(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        manager.states.lock().await.insert(
+            "stub".to_string(),
+            ServerState::Running(Box::new(RunningServer {
+                client,
+                versions: HashMap::new(),
+            })),
+        );
+        (root, manager)
+    }
+
+    #[tokio::test]
+    async fn reports_diagnostics_for_changed_files() {
+        let (root, manager) = manager_with_stub(5_000).await;
+        let path = root.path().join("main.txt");
+        std::fs::write(&path, "fine\n  ERROR here\nWARN\n").unwrap();
+
+        let report = manager
+            .diagnostics_for_paths(std::slice::from_ref(&path))
+            .await;
+        assert_eq!(
+            report.as_deref(),
+            Some(
+                "Diagnostics from language server `stub`:\n\
+                 main.txt:2:3: error[E1]: found ERROR\n\
+                 main.txt:3:1: warning[E1]: found WARN"
+            )
+        );
+
+        // A later edit is sent as a change and replaces the old diagnostics.
+        std::fs::write(&path, "all good\n").unwrap();
+        let report = manager.diagnostics_for_paths(&[path]).await;
+        assert_eq!(
+            report.as_deref(),
+            Some("Language server `stub` reported no errors or warnings in the changed files.")
+        );
+    }
+
+    #[tokio::test]
+    async fn waits_for_diagnostics_published_after_an_empty_set() {
+        let (root, manager) = manager_with_stub(5_000).await;
+        let quiet = root.path().join("slow.txt");
+        let versioned = root.path().join("slow_versioned.txt");
+        std::fs::write(&quiet, "ERROR\n").unwrap();
+        std::fs::write(&versioned, "WARN\n").unwrap();
+
+        for path in [quiet, versioned] {
+            let report = manager
+                .diagnostics_for_paths(std::slice::from_ref(&path))
+                .await
+                .unwrap();
+            assert!(report.contains(": found "), "{report}");
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_files_without_diagnostics_as_timed_out() {
+        let (root, manager) = manager_with_stub(100).await;
+        let silent = root.path().join("silent.txt");
+        let noisy = root.path().join("noisy.txt");
+        std::fs::write(&silent, "ERROR\n").unwrap();
+        std::fs::write(&noisy, "ERROR\n").unwrap();
+
+        let report = manager.diagnostics_for_paths(&[silent, noisy]).await;
+        assert_eq!(
+            report.as_deref(),
+            Some(
+                "Diagnostics from language server `stub`:\n\
+                 noisy.txt:1:1: error[E1]: found ERROR\n\
+                 (no diagnostics received for 1 file(s) within 100ms)"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_paths_without_a_configured_server() {
+        let (root, manager) = manager_with_stub(100).await;
+        let path = root.path().join("main.rs");
+        std::fs::write(&path, "ERROR\n").unwrap();
+
+        assert_eq!(manager.diagnostics_for_paths(&[path]).await, None);
+    }
+
+    #[tokio::test]
+    async fn reports_a_server_that_fails_to_start_once() {
+        let root = TempDir::new().unwrap();
+        let config = LspServerConfig {
+            command: "codex-test-no-such-language-server".to_string(),
+            args: Vec::new(),
+            env: None,
+            extensions: vec!["txt".to_string()],
+            language_id: None,
+            diagnostics_timeout_ms: None,
+        };
+        let manager = LspManager::new(
+            HashMap::from([("missing".to_string(), config)]),
+            root.path().to_path_buf(),
+        );
+        let path = root.path().join("main.txt");
+        std::fs::write(&path, "ERROR\n").unwrap();
+
+        let report = manager
+            .diagnostics_for_paths(std::slice::from_ref(&path))
+            .await
+            .unwrap();
+        assert!(
+            report.starts_with("Language server `missing` failed to start"),
+            "{report}"
+        );
+        assert_eq!(manager.diagnostics_for_paths(&[path]).await, None);
+    }
+}
@@ -1,32 +1,59 @@
 //! Connection manager for Model Context Protocol (MCP) servers.
 //!
-//! The [`McpConnectionManager`] owns one [`codex_mcp_client::McpClient`] per
-//! configured server (keyed by the *server name*). It offers convenience
-//! helpers to query the available tools across *all* servers and returns them
-//! in a single aggregated map using the fully-qualified tool name
-//! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
-
+//! The [`McpConnectionManager`] supervises one [`codex_mcp_client::McpClient`]
+//! per configured server (keyed by the *server name*). A server that exits or
+//! fails to start is restarted with exponential backoff, and its tool list is
+//! refreshed whenever it sends `notifications/tools/list_changed`. Sampling
+//! requests from servers are forwarded to the session as
+//! [`McpSamplingRequest`]s. The manager
+//! offers convenience helpers to query the available tools across *all*
+//! running servers and returns them in a single aggregated map using the
+//! fully-qualified tool name `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the
+//! key.
+
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsString;
+use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
+use codex_mcp_client::METHOD_NOT_FOUND_ERROR_CODE;
 use codex_mcp_client::McpClient;
 use mcp_types::ClientCapabilities;
+use mcp_types::CreateMessageRequest;
 use mcp_types::Implementation;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCNotification;
+use mcp_types::JSONRPCRequest;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ModelContextProtocolRequest;
 use mcp_types::Tool;
+use mcp_types::ToolAnnotations;
+use mcp_types::ToolListChangedNotification;
 
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Mutex;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
 
 use crate::config_types::McpServerConfig;
+use crate::config_types::McpToolPolicy;
+use crate::mcp_sampling::McpSamplingRequest;
+use crate::mcp_tool_call::ServerToolPolicies;
+use crate::protocol::McpServerStatus;
+use crate::protocol::McpServerSummary;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -36,8 +63,28 @@ use crate::config_types::McpServerConfig;
 const MCP_TOOL_NAME_DELIMITER: &str = "__";
 const MAX_TOOL_NAME_LENGTH: usize = 64;
 
-/// Timeout for the `tools/list` request.
-const LIST_TOOLS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Timeout for `initialize` and `tools/list` when `startup_timeout_ms` is not
+/// configured. Also applies to refreshing the tool list.
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON-RPC error code for malformed request params.
+const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
+
+/// Error code the MCP spec uses for sampling requests the user rejected. Also
+/// used when the completion itself fails.
+const SAMPLING_REJECTED_ERROR_CODE: i64 = -1;
+
+/// Consecutive restarts attempted when `max_restarts` is not configured.
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+/// Delay before the first restart, doubled for every further consecutive
+/// failure up to [`MAX_RESTART_BACKOFF`].
+const INITIAL_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A server that ran at least this long before exiting counts as healthy, so
+/// its consecutive failures start over.
+const HEALTHY_UPTIME: Duration = Duration::from_secs(60);
 
 /// Map that holds a startup error for every MCP server that could **not** be
 /// spawned successfully.
@@ -81,39 +128,86 @@ struct ToolInfo {
     tool: Tool,
 }
 
-/// A thin wrapper around a set of running [`McpClient`] instances.
+/// The set of configured MCP servers, each kept running by its own supervisor
+/// task.
 #[derive(Default)]
 pub(crate) struct McpConnectionManager {
-    /// Server-name -> client instance.
+    /// Server-name -> supervised server.
     ///
     /// The server name originates from the keys of the `mcp_servers` map in
     /// the user configuration.
-    clients: HashMap<String, std::sync::Arc<McpClient>>,
+    servers: BTreeMap<String, ServerHandle>,
+
+    /// Server-name -> approval policies configured for its tools.
+    tool_policies: HashMap<String, ServerToolPolicies>,
+}
+
+struct ServerHandle {
+    server: Arc<SupervisedServer>,
+    /// Dropping this stops the supervisor, and with it the server.
+    control_tx: mpsc::UnboundedSender<Control>,
+}
+
+/// A server as seen by both its supervisor task and the manager.
+struct SupervisedServer {
+    config: McpServerConfig,
+    state: Mutex<ServerState>,
+}
+
+struct ServerState {
+    status: McpServerStatus,
+    /// Set while the server is running.
+    client: Option<Arc<McpClient>>,
+    tools: Vec<Tool>,
+    /// Consecutive failures since the server last ran long enough to count as
+    /// healthy.
+    restarts: u32,
+    last_error: Option<String>,
+}
+
+/// Requests from the user to a server's supervisor.
+enum Control {
+    Restart,
+    Disable,
+    Enable,
+}
 
-    /// Fully qualified tool name -> tool instance.
-    tools: HashMap<String, ToolInfo>,
+/// Why a server stopped running.
+enum Stopped {
+    /// It exited or failed to start.
+    Failed(String),
+    Restart,
+    Disable,
+    /// The manager was dropped.
+    Shutdown,
 }
 
 impl McpConnectionManager {
-    /// Spawn a [`McpClient`] for each configured server.
+    /// Start a supervised [`McpClient`] for each configured server and wait
+    /// until each one is running or has failed its first start.
     ///
     /// * `mcp_servers` – Map loaded from the user configuration where *keys*
     ///   are human-readable server identifiers and *values* are the spawn
     ///   instructions.
+    /// * `sampling_tx` – Receives the servers' `sampling/createMessage`
+    ///   requests.
     ///
     /// Servers that fail to start are reported in `ClientStartErrors`: the
-    /// user should be informed about these errors.
+    /// user should be informed about these errors. They keep being retried in
+    /// the background.
     pub async fn new(
         mcp_servers: HashMap<String, McpServerConfig>,
+        sampling_tx: mpsc::UnboundedSender<McpSamplingRequest>,
     ) -> Result<(Self, ClientStartErrors)> {
         // Early exit if no servers are configured.
         if mcp_servers.is_empty() {
             return Ok((Self::default(), ClientStartErrors::default()));
         }
 
-        // Launch all configured servers concurrently.
-        let mut join_set = JoinSet::new();
         let mut errors = ClientStartErrors::new();
+        let mut servers = BTreeMap::new();
+        let mut tool_policies = HashMap::with_capacity(mcp_servers.len());
+        let mut first_starts = JoinSet::new();
 
         for (server_name, cfg) in mcp_servers {
             // Validate server name before spawning
@@ -126,81 +220,65 @@ impl McpConnectionManager {
                 continue;
             }
 
-            join_set.spawn(async move {
-                let McpServerConfig { command, args, env } = cfg;
-                let client_res = McpClient::new_stdio_client(
-                    command.into(),
-                    args.into_iter().map(OsString::from).collect(),
-                    env,
-                )
-                .await;
-                match client_res {
-                    Ok(client) => {
-                        // Initialize the client.
-                        let params = mcp_types::InitializeRequestParams {
-                            capabilities: ClientCapabilities {
-                                experimental: None,
-                                roots: None,
-                                sampling: None,
-                                // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
-                                // indicates this should be an empty object.
-                                elicitation: Some(json!({})),
-                            },
-                            client_info: Implementation {
-                                name: "codex-mcp-client".to_owned(),
-                                version: env!("CARGO_PKG_VERSION").to_owned(),
-                                title: Some("Codex".into()),
-                            },
-                            protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
-                        };
-                        let initialize_notification_params = None;
-                        let timeout = Some(Duration::from_secs(10));
-                        match client
-                            .initialize(params, initialize_notification_params, timeout)
-                            .await
-                        {
-                            Ok(_response) => (server_name, Ok(client)),
-                            Err(e) => (server_name, Err(e)),
-                        }
-                    }
-                    Err(e) => (server_name, Err(e.into())),
-                }
+            tool_policies.insert(server_name.clone(), ServerToolPolicies::from(&cfg));
+            let server = Arc::new(SupervisedServer {
+                config: cfg,
+                state: Mutex::new(ServerState {
+                    status: McpServerStatus::Starting,
+                    client: None,
+                    tools: Vec::new(),
+                    restarts: 0,
+                    last_error: None,
+                }),
             });
+            let (control_tx, control_rx) = mpsc::unbounded_channel();
+            let (started_tx, started_rx) = oneshot::channel();
+            tokio::spawn(supervise(
+                server_name.clone(),
+                server.clone(),
+                sampling_tx.clone(),
+                control_rx,
+                started_tx,
+            ));
+            let name = server_name.clone();
+            first_starts.spawn(async move { (name, started_rx.await) });
+            servers.insert(server_name, ServerHandle { server, control_tx });
         }
 
-        let mut clients: HashMap<String, std::sync::Arc<McpClient>> =
-            HashMap::with_capacity(join_set.len());
-
-        while let Some(res) = join_set.join_next().await {
-            let (server_name, client_res) = res?; // JoinError propagation
-
-            match client_res {
-                Ok(client) => {
-                    clients.insert(server_name, std::sync::Arc::new(client));
-                }
-                Err(e) => {
+        while let Some(res) = first_starts.join_next().await {
+            let (server_name, started) = res?; // JoinError propagation
+            match started {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
                     errors.insert(server_name, e);
                 }
+                Err(_) => {
+                    errors.insert(server_name, anyhow!("MCP server supervisor exited"));
+                }
             }
         }
 
-        let all_tools = list_all_tools(&clients).await?;
-
-        let tools = qualify_tools(all_tools);
-
-        Ok((Self { clients, tools }, errors))
+        Ok((
+            Self {
+                servers,
+                tool_policies,
+            },
+            errors,
+        ))
     }
 
-    /// Returns a single map that contains **all** tools. Each key is the
-    /// fully-qualified name for the tool.
-    pub fn list_all_tools(&self) -> HashMap<String, Tool> {
-        self.tools
-            .iter()
-            .map(|(name, tool)| (name.clone(), tool.tool.clone()))
+    /// Returns a single map that contains the tools of **all** running
+    /// servers. Each key is the fully-qualified name for the tool.
+    pub async fn list_all_tools(&self) -> HashMap<String, Tool> {
+        self.qualified_tools()
+            .await
+            .into_iter()
+            .map(|(name, tool)| (name, tool.tool))
             .collect()
     }
 
-    /// Invoke the tool indicated by the (server, tool) pair.
+    /// Invoke the tool indicated by the (server, tool) pair. Without a
+    /// `timeout`, the server's `tool_timeout_ms` applies.
     pub async fn call_tool(
         &self,
         server: &str,
@@ -208,11 +286,19 @@ impl McpConnectionManager {
         arguments: Option<serde_json::Value>,
         timeout: Option<Duration>,
     ) -> Result<mcp_types::CallToolResult> {
-        let client = self
-            .clients
+        let supervised = &self
+            .servers
             .get(server)
             .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?
-            .clone();
+            .server;
+        let client = {
+            let state = supervised.state.lock().await;
+            state
+                .client
+                .clone()
+                .ok_or_else(|| anyhow!("MCP server '{server}' is {}", state.status))?
+        };
+        let timeout = timeout.or(supervised.config.tool_timeout_ms.map(Duration::from_millis));
 
         client
             .call_tool(tool.to_string(), arguments, timeout)
@@ -220,57 +306,414 @@ impl McpConnectionManager {
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
 
-    pub fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
-        self.tools
-            .get(tool_name)
-            .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
+    pub async fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
+        self.qualified_tools()
+            .await
+            .remove(tool_name)
+            .map(|tool| (tool.server_name, tool.tool_name))
     }
-}
 
-/// Query every server for its available tools and return a single map that
-/// contains **all** tools. Each key is the fully-qualified name for the tool.
-async fn list_all_tools(
-    clients: &HashMap<String, std::sync::Arc<McpClient>>,
-) -> Result<Vec<ToolInfo>> {
-    let mut join_set = JoinSet::new();
-
-    // Spawn one task per server so we can query them concurrently. This
-    // keeps the overall latency roughly at the slowest server instead of
-    // the cumulative latency.
-    for (server_name, client) in clients {
-        let server_name_cloned = server_name.clone();
-        let client_clone = client.clone();
-        join_set.spawn(async move {
-            let res = client_clone
-                .list_tools(None, Some(LIST_TOOLS_TIMEOUT))
-                .await;
-            (server_name_cloned, res)
-        });
+    /// Approval policies configured for `server`'s tools.
+    pub fn tool_policies(&self, server: &str) -> Option<&ServerToolPolicies> {
+        self.tool_policies.get(server)
+    }
+
+    /// Annotations `server` declared for `tool`, if any.
+    pub async fn tool_annotations(&self, server: &str, tool: &str) -> Option<ToolAnnotations> {
+        let state = self.servers.get(server)?.server.state.lock().await;
+        state
+            .tools
+            .iter()
+            .find(|t| t.name == tool)
+            .and_then(|t| t.annotations.clone())
+    }
+
+    /// The status of every server, sorted by name.
+    pub async fn list_servers(&self) -> Vec<McpServerSummary> {
+        let mut servers = Vec::with_capacity(self.servers.len());
+        for (name, handle) in &self.servers {
+            let state = handle.server.state.lock().await;
+            servers.push(McpServerSummary {
+                name: name.clone(),
+                status: state.status,
+                tools: state.tools.iter().map(|tool| tool.name.clone()).collect(),
+                restarts: state.restarts,
+                last_error: state.last_error.clone(),
+            });
+        }
+        servers
     }
 
-    let mut aggregated: Vec<ToolInfo> = Vec::with_capacity(join_set.len());
+    /// Stop `server`, if it is running, and start it again.
+    pub async fn restart_server(&self, server: &str) -> Result<()> {
+        self.send_control(server, Control::Restart, McpServerStatus::Starting)
+            .await
+    }
 
-    while let Some(join_res) = join_set.join_next().await {
-        let (server_name, list_result) = join_res?;
-        let list_result = list_result?;
+    /// Stop `server` and keep it stopped, or start it again.
+    pub async fn set_server_enabled(&self, server: &str, enabled: bool) -> Result<()> {
+        if enabled {
+            self.send_control(server, Control::Enable, McpServerStatus::Starting)
+                .await
+        } else {
+            self.send_control(server, Control::Disable, McpServerStatus::Disabled)
+                .await
+        }
+    }
 
-        for tool in list_result.tools {
-            let tool_info = ToolInfo {
+    /// Hand `control` to `server`'s supervisor. The status is updated right
+    /// away so that a listing requested next reflects the request.
+    async fn send_control(
+        &self,
+        server: &str,
+        control: Control,
+        status: McpServerStatus,
+    ) -> Result<()> {
+        let handle = self
+            .servers
+            .get(server)
+            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+        {
+            let mut state = handle.server.state.lock().await;
+            // Enabling a server that was never disabled leaves it alone.
+            let ignored =
+                matches!(control, Control::Enable) && state.status != McpServerStatus::Disabled;
+            if !ignored {
+                state.status = status;
+            }
+        }
+        handle
+            .control_tx
+            .send(control)
+            .map_err(|_| anyhow!("MCP server '{server}' is no longer supervised"))
+    }
+
+    async fn qualified_tools(&self) -> HashMap<String, ToolInfo> {
+        let mut all_tools = Vec::new();
+        for (server_name, handle) in &self.servers {
+            let state = handle.server.state.lock().await;
+            all_tools.extend(state.tools.iter().map(|tool| ToolInfo {
                 server_name: server_name.clone(),
                 tool_name: tool.name.clone(),
-                tool,
+                tool: tool.clone(),
+            }));
+        }
+        qualify_tools(all_tools)
+    }
+}
+
+/// Keep `server` running until the manager is dropped: start it, restart it
+/// with backoff when it exits, and follow the user's restart and disable
+/// requests. The outcome of the first start is reported on `started_tx`.
+async fn supervise(
+    name: String,
+    server: Arc<SupervisedServer>,
+    sampling_tx: mpsc::UnboundedSender<McpSamplingRequest>,
+    mut control_rx: mpsc::UnboundedReceiver<Control>,
+    started_tx: oneshot::Sender<Result<()>>,
+) {
+    let mut started_tx = Some(started_tx);
+    let max_restarts = server.config.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS);
+    let mut failures: u32 = 0;
+
+    loop {
+        let stopped = match start_server(&server.config).await {
+            Ok(StartedServer {
+                client,
+                notifications,
+                requests,
+                tools,
+            }) => {
+                if let Some(tx) = started_tx.take() {
+                    let _ = tx.send(Ok(()));
+                }
+                info!("MCP server `{name}` started with {} tools", tools.len());
+                {
+                    let mut state = server.state.lock().await;
+                    state.status = McpServerStatus::Running;
+                    state.client = Some(client.clone());
+                    state.tools = tools;
+                }
+                let up_since = Instant::now();
+                let stopped = serve(
+                    &name,
+                    &server,
+                    &client,
+                    notifications,
+                    requests,
+                    &sampling_tx,
+                    &mut control_rx,
+                )
+                .await;
+                {
+                    let mut state = server.state.lock().await;
+                    state.client = None;
+                    state.tools.clear();
+                }
+                if up_since.elapsed() >= HEALTHY_UPTIME {
+                    failures = 0;
+                }
+                stopped
+            }
+            Err(err) => {
+                let stopped = Stopped::Failed(format!("{err:#}"));
+                if let Some(tx) = started_tx.take() {
+                    let _ = tx.send(Err(err));
+                }
+                stopped
+            }
+        };
+
+        let resume = match stopped {
+            Stopped::Failed(message) => {
+                warn!("MCP server `{name}` stopped: {message}");
+                failures += 1;
+                let status = if failures > max_restarts {
+                    McpServerStatus::Failed
+                } else {
+                    McpServerStatus::Restarting
+                };
+                {
+                    let mut state = server.state.lock().await;
+                    state.status = status;
+                    state.restarts = failures;
+                    state.last_error = Some(message);
+                }
+                if status == McpServerStatus::Failed {
+                    wait_until_resumed(&mut control_rx).await
+                } else {
+                    tokio::select! {
+                        () = tokio::time::sleep(restart_backoff(failures)) => true,
+                        control = control_rx.recv() => match control {
+                            Some(Control::Restart | Control::Enable) => true,
+                            Some(Control::Disable) => wait_until_resumed(&mut control_rx).await,
+                            None => false,
+                        },
+                    }
+                }
+            }
+            Stopped::Restart => true,
+            Stopped::Disable => wait_until_resumed(&mut control_rx).await,
+            Stopped::Shutdown => false,
+        };
+        if !resume {
+            return;
+        }
+
+        // A restart the user asked for starts with a clean slate.
+        let mut state = server.state.lock().await;
+        if state.status != McpServerStatus::Restarting {
+            failures = 0;
+            state.restarts = 0;
+            state.status = McpServerStatus::Starting;
+        }
+    }
+}
+
+/// Run until `client`'s server exits or the user stops it, keeping its tool
+/// list current and answering its requests.
+async fn serve(
+    name: &str,
+    server: &SupervisedServer,
+    client: &Arc<McpClient>,
+    mut notifications: broadcast::Receiver<JSONRPCNotification>,
+    mut requests: mpsc::UnboundedReceiver<JSONRPCRequest>,
+    sampling_tx: &mpsc::UnboundedSender<McpSamplingRequest>,
+    control_rx: &mut mpsc::UnboundedReceiver<Control>,
+) -> Stopped {
+    loop {
+        let refresh = tokio::select! {
+            () = client.closed() => return Stopped::Failed("server exited".to_string()),
+            Some(request) = requests.recv() => {
+                handle_server_request(name, server, client, sampling_tx, request);
+                false
+            }
+            notification = notifications.recv() => match notification {
+                Ok(notification) => notification.method == ToolListChangedNotification::METHOD,
+                // One of the missed notifications may have been a change.
+                Err(RecvError::Lagged(_)) => true,
+                Err(RecvError::Closed) => {
+                    return Stopped::Failed("server connection closed".to_string());
+                }
+            },
+            control = control_rx.recv() => match control {
+                Some(Control::Restart) => return Stopped::Restart,
+                Some(Control::Disable) => {
+                    // Mark it right away, in case the server is slow to exit.
+                    server.state.lock().await.status = McpServerStatus::Disabled;
+                    return Stopped::Disable;
+                }
+                Some(Control::Enable) => false,
+                None => return Stopped::Shutdown,
+            },
+        };
+        if refresh {
+            match client
+                .list_tools(None, Some(startup_timeout(&server.config)))
+                .await
+            {
+                Ok(result) => {
+                    info!("MCP server `{name}` now has {} tools", result.tools.len());
+                    server.state.lock().await.tools = result.tools;
+                }
+                Err(e) => warn!("failed to refresh tools of MCP server `{name}`: {e:#}"),
+            }
+        }
+    }
+}
+
+/// Answer `request` in the background. Only `sampling/createMessage` is
+/// supported; it is forwarded to the session on `sampling_tx`.
+fn handle_server_request(
+    name: &str,
+    server: &SupervisedServer,
+    client: &Arc<McpClient>,
+    sampling_tx: &mpsc::UnboundedSender<McpSamplingRequest>,
+    request: JSONRPCRequest,
+) {
+    let JSONRPCRequest {
+        id, method, params, ..
+    } = request;
+    let client = Arc::clone(client);
+    if method != CreateMessageRequest::METHOD {
+        tokio::spawn(async move {
+            let error = JSONRPCErrorError {
+                code: METHOD_NOT_FOUND_ERROR_CODE,
+                message: format!("method not found: {method}"),
+                data: None,
             };
-            aggregated.push(tool_info);
+            let _ = client.send_error(id, error).await;
+        });
+        return;
+    }
+
+    let params = match serde_json::from_value(params.unwrap_or_default()) {
+        Ok(params) => params,
+        Err(e) => {
+            tokio::spawn(async move {
+                let error = JSONRPCErrorError {
+                    code: INVALID_PARAMS_ERROR_CODE,
+                    message: format!("invalid sampling/createMessage params: {e}"),
+                    data: None,
+                };
+                let _ = client.send_error(id, error).await;
+            });
+            return;
         }
+    };
+    let (respond, response) = oneshot::channel();
+    let request = McpSamplingRequest {
+        server: name.to_string(),
+        policy: server.config.sampling_policy.unwrap_or(McpToolPolicy::Ask),
+        params,
+        respond,
+    };
+    if sampling_tx.send(request).is_err() {
+        warn!("dropping sampling request from MCP server `{name}`: session has ended");
     }
+    tokio::spawn(async move {
+        let result = match response.await {
+            Ok(Ok(result)) => serde_json::to_value(result).map_err(|e| e.to_string()),
+            Ok(Err(message)) => Err(message),
+            Err(_) => Err("session ended before the request was answered".to_string()),
+        };
+        let sent = match result {
+            Ok(result) => client.send_response(id, result).await,
+            Err(message) => {
+                let error = JSONRPCErrorError {
+                    code: SAMPLING_REJECTED_ERROR_CODE,
+                    message,
+                    data: None,
+                };
+                client.send_error(id, error).await
+            }
+        };
+        if let Err(e) = sent {
+            warn!("failed to answer sampling request: {e:#}");
+        }
+    });
+}
 
-    info!(
-        "aggregated {} tools from {} servers",
-        aggregated.len(),
-        clients.len()
-    );
+/// Wait for the user to restart or enable a stopped server. Returns `false`
+/// when the manager was dropped instead.
+async fn wait_until_resumed(control_rx: &mut mpsc::UnboundedReceiver<Control>) -> bool {
+    loop {
+        match control_rx.recv().await {
+            Some(Control::Restart | Control::Enable) => return true,
+            Some(Control::Disable) => {}
+            None => return false,
+        }
+    }
+}
 
-    Ok(aggregated)
+/// A freshly initialized server, with everything [`serve`] needs.
+struct StartedServer {
+    client: Arc<McpClient>,
+    notifications: broadcast::Receiver<JSONRPCNotification>,
+    requests: mpsc::UnboundedReceiver<JSONRPCRequest>,
+    tools: Vec<Tool>,
+}
+
+/// Spawn the server, initialize it and fetch its tools.
+async fn start_server(config: &McpServerConfig) -> Result<StartedServer> {
+    let McpServerConfig {
+        command, args, env, ..
+    } = config.clone();
+    let client = McpClient::new_stdio_client(
+        command.into(),
+        args.into_iter().map(OsString::from).collect(),
+        env,
+    )
+    .await?;
+    // Subscribe first so that no change announced during startup is missed.
+    let notifications = client.subscribe_notifications();
+    let requests = client
+        .take_server_requests()
+        .await
+        .context("server requests already taken")?;
+
+    let params = mcp_types::InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: Some(json!({})),
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
+            // indicates this should be an empty object.
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-mcp-client".to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            title: Some("Codex".into()),
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
+    };
+    let initialize_notification_params = None;
+    let timeout = Some(startup_timeout(config));
+    client
+        .initialize(params, initialize_notification_params, timeout)
+        .await?;
+    let tools = client.list_tools(None, timeout).await?.tools;
+    Ok(StartedServer {
+        client: Arc::new(client),
+        notifications,
+        requests,
+        tools,
+    })
+}
+
+fn startup_timeout(config: &McpServerConfig) -> Duration {
+    config
+        .startup_timeout_ms
+        .map_or(DEFAULT_STARTUP_TIMEOUT, Duration::from_millis)
+}
+
+/// Delay before restarting a server that failed `failures` times in a row.
+fn restart_backoff(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    INITIAL_RESTART_BACKOFF
+        .saturating_mul(1 << doublings)
+        .min(MAX_RESTART_BACKOFF)
 }
 
 fn is_valid_mcp_server_name(server_name: &str) -> bool {
@@ -366,4 +809,150 @@ mod tests {
             "my_server__yet_another_e1c3987bd9c50b826cbe1687966f79f0c602d19ca"
         );
     }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_the_limit() {
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(2), Duration::from_secs(2));
+        assert_eq!(restart_backoff(4), Duration::from_secs(8));
+        assert_eq!(restart_backoff(7), MAX_RESTART_BACKOFF);
+        assert_eq!(restart_backoff(u32::MAX), MAX_RESTART_BACKOFF);
+    }
+
+    /// Answers `initialize` and the first `tools/list`, then runs `rest`.
+    #[cfg(unix)]
+    fn fake_server(rest: &str) -> McpServerConfig {
+        let script = format!(
+            r#"read line
+printf '%s\n' '{{"jsonrpc":"2.0","id":1,"result":{{"capabilities":{{}},"protocolVersion":"2025-06-18","serverInfo":{{"name":"fake","version":"0"}}}}}}'
+read line
+read line
+printf '%s\n' '{{"jsonrpc":"2.0","id":2,"result":{{"tools":[]}}}}'
+{rest}"#
+        );
+        McpServerConfig {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script],
+            env: None,
+            startup_timeout_ms: None,
+            tool_timeout_ms: None,
+            max_restarts: Some(0),
+            default_tool_policy: None,
+            tool_policies: HashMap::new(),
+            sampling_policy: None,
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refreshes_tools_when_the_list_changes() {
+        let server = fake_server(
+            r#"printf '%s\n' '{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}'
+read line
+printf '%s\n' '{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object"}}]}}'
+read line"#,
+        );
+        let (sampling_tx, _sampling_rx) = mpsc::unbounded_channel();
+        let (manager, errors) =
+            McpConnectionManager::new(HashMap::from([("fake".to_string(), server)]), sampling_tx)
+                .await
+                .unwrap_or_else(|e| panic!("manager failed to start: {e}"));
+        assert!(errors.is_empty());
+
+        for _ in 0..100 {
+            if !manager.list_all_tools().await.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let tools = manager.list_all_tools().await;
+        assert!(tools.contains_key("fake__echo"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn server_that_keeps_exiting_is_marked_failed() {
+        let (sampling_tx, _sampling_rx) = mpsc::unbounded_channel();
+        let (manager, errors) = McpConnectionManager::new(
+            HashMap::from([("fake".to_string(), fake_server("exit 0"))]),
+            sampling_tx,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("manager failed to start: {e}"));
+        assert!(errors.is_empty());
+
+        for _ in 0..100 {
+            if manager.list_servers().await[0].status == McpServerStatus::Failed {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let servers = manager.list_servers().await;
+        assert_eq!(servers[0].status, McpServerStatus::Failed);
+        assert_eq!(servers[0].restarts, 1);
+        assert_eq!(servers[0].last_error.as_deref(), Some("server exited"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn forwards_sampling_requests_and_answers_the_server() {
+        let dir = tempfile::tempdir().unwrap_or_else(|e| panic!("tempdir: {e}"));
+        let answer = dir.path().join("answer.json");
+        let server = fake_server(&format!(
+            r#"printf '%s\n' '{{"jsonrpc":"2.0","id":"s1","method":"sampling/createMessage","params":{{"maxTokens":10,"messages":[{{"role":"user","content":{{"type":"text","text":"hi"}}}}]}}}}'
+read line
+printf '%s' "$line" > '{}'
+read line"#,
+            answer.display()
+        ));
+        let (sampling_tx, mut sampling_rx) = mpsc::unbounded_channel();
+        let (_manager, errors) =
+            McpConnectionManager::new(HashMap::from([("fake".to_string(), server)]), sampling_tx)
+                .await
+                .unwrap_or_else(|e| panic!("manager failed to start: {e}"));
+        assert!(errors.is_empty());
+
+        let request = tokio::time::timeout(Duration::from_secs(5), sampling_rx.recv())
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| panic!("no sampling request"));
+        assert_eq!(request.server, "fake");
+        assert_eq!(request.policy, McpToolPolicy::Ask);
+        assert_eq!(request.params.max_tokens, 10);
+        let _ = request.respond.send(Ok(mcp_types::CreateMessageResult {
+            content: mcp_types::CreateMessageResultContent::TextContent(mcp_types::TextContent {
+                annotations: None,
+                text: "hello".to_string(),
+                r#type: "text".to_string(),
+            }),
+            model: "mock".to_string(),
+            role: mcp_types::Role::Assistant,
+            stop_reason: None,
+        }));
+
+        let mut received = None;
+        for _ in 0..100 {
+            received = std::fs::read_to_string(&answer)
+                .ok()
+                .and_then(|answer| serde_json::from_str::<serde_json::Value>(&answer).ok());
+            if received.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let answer = received.unwrap_or_else(|| panic!("server got no answer"));
+        assert_eq!(
+            answer,
+            json!({
+                "jsonrpc": "2.0",
+                "id": "s1",
+                "result": {
+                    "content": {"type": "text", "text": "hello"},
+                    "model": "mock",
+                    "role": "assistant",
+                },
+            })
+        );
+    }
 }
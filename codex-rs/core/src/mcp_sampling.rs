@@ -0,0 +1,415 @@
+//! Serves `sampling/createMessage` requests from MCP servers with the
+//! session's model.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use futures::prelude::*;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::Role;
+use mcp_types::SamplingMessage;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::auxiliary;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::rejected_with_feedback;
+use crate::config_types::McpToolPolicy;
+use crate::protocol::AskForApproval;
+use crate::protocol::AuxiliaryTask;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::McpSamplingApprovalRequestEvent;
+use crate::protocol::McpSamplingBeginEvent;
+use crate::protocol::McpSamplingEndEvent;
+use crate::protocol::ReviewDecision;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+
+/// Instructions for servers that do not send a system prompt, so that the
+/// model does not receive Codex's agent instructions instead.
+const DEFAULT_SAMPLING_INSTRUCTIONS: &str = "You are a helpful assistant.";
+
+/// A server's request for a completion, forwarded by the
+/// [`McpConnectionManager`](crate::mcp_connection_manager::McpConnectionManager).
+pub(crate) struct McpSamplingRequest {
+    pub(crate) server: String,
+    pub(crate) policy: McpToolPolicy,
+    pub(crate) params: CreateMessageRequestParams,
+    /// Receives the completion, or the message of the error to return to the
+    /// server.
+    pub(crate) respond: oneshot::Sender<Result<CreateMessageResult, String>>,
+}
+
+/// Ask the user if the policy says so, run the completion with the current
+/// turn's model and send the outcome back to the server.
+pub(crate) async fn handle_mcp_sampling_request(
+    sess: Arc<Session>,
+    turn_context: Arc<TurnContext>,
+    request: McpSamplingRequest,
+) {
+    let McpSamplingRequest {
+        server,
+        policy,
+        params,
+        respond,
+    } = request;
+    let call_id = Uuid::new_v4().to_string();
+
+    let result =
+        match check_approval(&sess, &turn_context, &call_id, &server, policy, &params).await {
+            Ok(()) => sample(&sess, &turn_context, &call_id, &server, &params).await,
+            Err(e) => Err(e),
+        };
+    let _ = respond.send(result);
+}
+
+async fn check_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    server: &str,
+    policy: McpToolPolicy,
+    params: &CreateMessageRequestParams,
+) -> Result<(), String> {
+    match policy {
+        McpToolPolicy::Allow => return Ok(()),
+        McpToolPolicy::Deny => {
+            return Err(format!(
+                "sampling request from `{server}` rejected: denied by configuration"
+            ));
+        }
+        McpToolPolicy::Ask => {}
+    }
+    if sess.is_mcp_sampling_approved(server) {
+        return Ok(());
+    }
+    if turn_context.approval_policy == AskForApproval::Never {
+        return Err(format!(
+            "sampling request from `{server}` rejected: it requires approval, but approval_policy is `never`"
+        ));
+    }
+
+    let rx_approve = sess
+        .request_mcp_sampling_approval(
+            call_id.to_string(),
+            McpSamplingApprovalRequestEvent {
+                call_id: call_id.to_string(),
+                server: server.to_string(),
+                prompt: describe_messages(&params.messages),
+                system_prompt: params.system_prompt.clone(),
+                max_tokens: params.max_tokens,
+            },
+        )
+        .await;
+    match rx_approve.await.unwrap_or_default() {
+        ReviewDecision::Approved => Ok(()),
+        ReviewDecision::ApprovedForSession => {
+            sess.add_approved_mcp_sampling(server.to_string());
+            Ok(())
+        }
+        ReviewDecision::DeniedWithFeedback { feedback } => {
+            Err(rejected_with_feedback("Sampling request", &feedback))
+        }
+        // Edits, prefixes and partial approvals only apply to commands and
+        // patches.
+        ReviewDecision::ApprovedWithEdits { .. }
+        | ReviewDecision::ApprovedForPrefix { .. }
+        | ReviewDecision::ApprovedPartially { .. }
+        | ReviewDecision::Denied
+        | ReviewDecision::Abort => Err("sampling request rejected by user".to_string()),
+    }
+}
+
+/// Run the completion, reporting it with `McpSamplingBegin` and
+/// `McpSamplingEnd` events and its usage with an `AuxiliaryTokenCount` event,
+/// so that it does not count towards the conversation's context.
+async fn sample(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    server: &str,
+    params: &CreateMessageRequestParams,
+) -> Result<CreateMessageResult, String> {
+    sess.send_event(Event {
+        id: call_id.to_string(),
+        msg: EventMsg::McpSamplingBegin(McpSamplingBeginEvent {
+            call_id: call_id.to_string(),
+            server: server.to_string(),
+        }),
+    })
+    .await;
+
+    let start = Instant::now();
+    let result = complete(sess, turn_context, call_id, params).await;
+    sess.send_event(Event {
+        id: call_id.to_string(),
+        msg: EventMsg::McpSamplingEnd(McpSamplingEndEvent {
+            call_id: call_id.to_string(),
+            server: server.to_string(),
+            duration: start.elapsed(),
+            result: result
+                .as_ref()
+                .map(|c| c.text.clone())
+                .map_err(Clone::clone),
+        }),
+    })
+    .await;
+
+    result.map(|Completion { text, stop_reason }| CreateMessageResult {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model: turn_context.client.get_model(),
+        role: Role::Assistant,
+        stop_reason: Some(stop_reason.to_string()),
+    })
+}
+
+#[derive(Debug, PartialEq)]
+struct Completion {
+    text: String,
+    /// The MCP stop reason: `endTurn` or `stopSequence`.
+    stop_reason: &'static str,
+}
+
+/// Run the completion with at most `maxTokens` of output. Generation is cut
+/// off as soon as the text contains one of `stopSequences`, which the model
+/// APIs do not all support, so they are applied to the streamed text.
+async fn complete(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    params: &CreateMessageRequestParams,
+) -> Result<Completion, String> {
+    let max_output_tokens = u64::try_from(params.max_tokens)
+        .ok()
+        .filter(|&max| max > 0)
+        .ok_or_else(|| format!("invalid maxTokens: {}", params.max_tokens))?;
+    let stop_sequences = params.stop_sequences.as_deref().unwrap_or_default();
+    let prompt = Prompt {
+        input: to_response_items(&params.messages)?,
+        store: !turn_context.disable_response_storage,
+        tools: Vec::new(),
+        base_instructions_override: Some(
+            params
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SAMPLING_INSTRUCTIONS.to_string()),
+        ),
+        max_output_tokens: Some(max_output_tokens),
+    };
+
+    let mut stream = turn_context
+        .client
+        .clone()
+        .stream(&prompt)
+        .await
+        .map_err(|e| e.to_string())?;
+    // `text` holds the completed messages and `pending` the deltas of the
+    // message in progress.
+    let mut text = String::new();
+    let mut pending = String::new();
+    loop {
+        match stream.next().await {
+            Some(Ok(ResponseEvent::OutputTextDelta(delta))) => {
+                pending.push_str(&delta);
+                let so_far = format!("{text}{pending}");
+                if let Some(completion) = cut_at_stop_sequence(&so_far, stop_sequences) {
+                    // Dropping the stream ends the request.
+                    return Ok(completion);
+                }
+            }
+            Some(Ok(ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }))) => {
+                pending.clear();
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            Some(Ok(ResponseEvent::Completed { token_usage, .. })) => {
+                if let Some(token_usage) = token_usage {
+                    auxiliary::report_token_usage(
+                        sess,
+                        call_id,
+                        AuxiliaryTask::McpSampling,
+                        &turn_context.client,
+                        token_usage,
+                    )
+                    .await;
+                }
+                return Ok(
+                    cut_at_stop_sequence(&text, stop_sequences).unwrap_or(Completion {
+                        text,
+                        stop_reason: "endTurn",
+                    }),
+                );
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.to_string()),
+            None => return Err("stream closed before response.completed".to_string()),
+        }
+    }
+}
+
+/// `text` up to the earliest of `stop_sequences` it contains, if any.
+fn cut_at_stop_sequence(text: &str, stop_sequences: &[String]) -> Option<Completion> {
+    let end = stop_sequences
+        .iter()
+        .filter(|stop| !stop.is_empty())
+        .filter_map(|stop| text.find(stop.as_str()))
+        .min()?;
+    Some(Completion {
+        text: text[..end].to_string(),
+        stop_reason: "stopSequence",
+    })
+}
+
+fn to_response_items(messages: &[SamplingMessage]) -> Result<Vec<ResponseItem>, String> {
+    messages
+        .iter()
+        .map(|message| {
+            let content = match (&message.role, &message.content) {
+                (Role::User, SamplingMessageContent::TextContent(text)) => ContentItem::InputText {
+                    text: text.text.clone(),
+                },
+                (Role::User, SamplingMessageContent::ImageContent(image)) => {
+                    ContentItem::InputImage {
+                        image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+                    }
+                }
+                (Role::Assistant, SamplingMessageContent::TextContent(text)) => {
+                    ContentItem::OutputText {
+                        text: text.text.clone(),
+                    }
+                }
+                (_, SamplingMessageContent::AudioContent(_)) => {
+                    return Err("audio content is not supported".to_string());
+                }
+                (Role::Assistant, SamplingMessageContent::ImageContent(_)) => {
+                    return Err("assistant messages can only contain text".to_string());
+                }
+            };
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            Ok(ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+            })
+        })
+        .collect()
+}
+
+/// Render the messages for the user deciding whether to allow the request.
+fn describe_messages(messages: &[SamplingMessage]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let role = match message.role {
+                Role::User => "user",
+                Role::Assistant => "assistant",
+            };
+            let content = match &message.content {
+                SamplingMessageContent::TextContent(text) => text.text.as_str(),
+                SamplingMessageContent::ImageContent(_) => "[image]",
+                SamplingMessageContent::AudioContent(_) => "[audio]",
+            };
+            format!("{role}: {content}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ImageContent;
+    use pretty_assertions::assert_eq;
+
+    fn text(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    #[test]
+    fn messages_become_response_items() {
+        let image = SamplingMessage {
+            content: SamplingMessageContent::ImageContent(ImageContent {
+                annotations: None,
+                data: "aGk=".to_string(),
+                mime_type: "image/png".to_string(),
+                r#type: "image".to_string(),
+            }),
+            role: Role::User,
+        };
+        let messages = vec![
+            text(Role::User, "Summarize this."),
+            text(Role::Assistant, "Sure."),
+            image,
+        ];
+
+        assert_eq!(
+            to_response_items(&messages),
+            Ok(vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "Summarize this.".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "Sure.".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputImage {
+                        image_url: "data:image/png;base64,aGk=".to_string(),
+                    }],
+                },
+            ])
+        );
+        assert_eq!(
+            describe_messages(&messages),
+            "user: Summarize this.\nassistant: Sure.\nuser: [image]"
+        );
+    }
+
+    #[test]
+    fn text_is_cut_at_the_earliest_stop_sequence() {
+        let stops = vec!["END".to_string(), "\n\n".to_string(), String::new()];
+        assert_eq!(
+            cut_at_stop_sequence("one\n\ntwo END", &stops),
+            Some(Completion {
+                text: "one".to_string(),
+                stop_reason: "stopSequence",
+            })
+        );
+        assert_eq!(cut_at_stop_sequence("one two", &stops), None);
+        assert_eq!(cut_at_stop_sequence("one END", &[]), None);
+    }
+}
@@ -1,21 +1,32 @@
+use std::collections::HashMap;
 use std::time::Duration;
 use std::time::Instant;
 
+use mcp_types::ToolAnnotations;
 use tracing::error;
+use wildmatch::WildMatchPattern;
 
 use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::rejected_with_feedback;
+use crate::config_types::McpServerConfig;
+use crate::config_types::McpToolPolicy;
+use crate::protocol::AskForApproval;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::ReviewDecision;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
 /// Handles the specified tool call dispatches the appropriate
 /// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn handle_mcp_tool_call(
     sess: &Session,
+    turn_context: &TurnContext,
     sub_id: &str,
     call_id: String,
     server: String,
@@ -49,6 +60,16 @@ pub(crate) async fn handle_mcp_tool_call(
         arguments: arguments_value.clone(),
     };
 
+    if let Err(reason) = check_approval(sess, turn_context, sub_id, &call_id, &invocation).await {
+        return ResponseInputItem::FunctionCallOutput {
+            call_id,
+            output: FunctionCallOutputPayload {
+                content: reason,
+                success: None,
+            },
+        };
+    }
+
     let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
         call_id: call_id.clone(),
         invocation: invocation.clone(),
@@ -73,6 +94,144 @@ pub(crate) async fn handle_mcp_tool_call(
     ResponseInputItem::McpToolCallOutput { call_id, result }
 }
 
+/// Make sure the call described by `invocation` may run, asking the user if
+/// its policy says so. On refusal, returns the message for the model.
+async fn check_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    sub_id: &str,
+    call_id: &str,
+    invocation: &McpInvocation,
+) -> Result<(), String> {
+    let McpInvocation { server, tool, .. } = invocation;
+    let annotations = sess.mcp_tool_annotations(server, tool).await;
+    let policy = resolve_tool_policy(
+        sess.mcp_tool_policies(server)
+            .and_then(|policies| policies.configured(tool)),
+        annotations.as_ref(),
+        turn_context.approval_policy,
+    );
+    match policy {
+        McpToolPolicy::Allow => return Ok(()),
+        McpToolPolicy::Deny => {
+            return Err(format!(
+                "MCP tool call `{server}/{tool}` rejected: denied by configuration"
+            ));
+        }
+        McpToolPolicy::Ask => {}
+    }
+    if sess.is_mcp_tool_approved(server, tool) {
+        return Ok(());
+    }
+    if turn_context.approval_policy == AskForApproval::Never {
+        return Err(format!(
+            "MCP tool call `{server}/{tool}` rejected: it requires approval, but approval_policy is `never`"
+        ));
+    }
+
+    let reason = match annotations.as_ref().and_then(|a| a.destructive_hint) {
+        Some(true) => Some("the server marks this tool as destructive".to_string()),
+        Some(false) => None,
+        None => Some("the server does not mark this tool as read-only".to_string()),
+    };
+    let rx_approve = sess
+        .request_mcp_tool_approval(
+            sub_id.to_string(),
+            call_id.to_string(),
+            invocation.clone(),
+            reason,
+        )
+        .await;
+    match rx_approve.await.unwrap_or_default() {
+        ReviewDecision::Approved => Ok(()),
+        ReviewDecision::ApprovedForSession => {
+            sess.add_approved_mcp_tool(server.clone(), tool.clone());
+            Ok(())
+        }
+        ReviewDecision::DeniedWithFeedback { feedback } => {
+            Err(rejected_with_feedback("MCP tool call", &feedback))
+        }
+        // Edits, prefixes and partial approvals only apply to commands and
+        // patches.
+        ReviewDecision::ApprovedWithEdits { .. }
+        | ReviewDecision::ApprovedForPrefix { .. }
+        | ReviewDecision::ApprovedPartially { .. }
+        | ReviewDecision::Denied
+        | ReviewDecision::Abort => Err("MCP tool call rejected by user".to_string()),
+    }
+}
+
+/// Decide whether a tool may run without asking. A policy configured for
+/// the tool wins. Otherwise only tools the server annotates with
+/// `readOnlyHint: true` run without asking: MCP servers run outside the
+/// sandbox, and per the MCP spec any other tool may be destructive, whether
+/// or not it is annotated. A tool that also claims `destructiveHint: true`
+/// asks anyway, since the server contradicts itself. The sandbox policy plays
+/// no part, as it does not apply to MCP servers. With
+/// `approval_policy = "never"` nothing asks.
+pub(crate) fn resolve_tool_policy(
+    configured: Option<McpToolPolicy>,
+    annotations: Option<&ToolAnnotations>,
+    approval_policy: AskForApproval,
+) -> McpToolPolicy {
+    if let Some(policy) = configured {
+        return policy;
+    }
+    let read_only = annotations.and_then(|a| a.read_only_hint).unwrap_or(false);
+    let destructive = annotations
+        .and_then(|a| a.destructive_hint)
+        .unwrap_or(false);
+    if (read_only && !destructive) || approval_policy == AskForApproval::Never {
+        McpToolPolicy::Allow
+    } else {
+        McpToolPolicy::Ask
+    }
+}
+
+type ToolNamePattern = WildMatchPattern<'*', '?'>;
+
+/// The tool approval policies configured for one MCP server.
+#[derive(Debug, Default)]
+pub(crate) struct ServerToolPolicies {
+    default: Option<McpToolPolicy>,
+    exact: HashMap<String, McpToolPolicy>,
+    patterns: Vec<(ToolNamePattern, McpToolPolicy)>,
+}
+
+impl ServerToolPolicies {
+    /// The policy configured for `tool`, if any.
+    pub(crate) fn configured(&self, tool: &str) -> Option<McpToolPolicy> {
+        if let Some(policy) = self.exact.get(tool) {
+            return Some(*policy);
+        }
+        self.patterns
+            .iter()
+            .filter(|(pattern, _)| pattern.matches(tool))
+            .map(|(_, policy)| *policy)
+            .max()
+            .or(self.default)
+    }
+}
+
+impl From<&McpServerConfig> for ServerToolPolicies {
+    fn from(config: &McpServerConfig) -> Self {
+        let mut policies = Self {
+            default: config.default_tool_policy,
+            ..Self::default()
+        };
+        for (name, policy) in &config.tool_policies {
+            if name.contains(['*', '?']) {
+                policies
+                    .patterns
+                    .push((ToolNamePattern::new(name), *policy));
+            } else {
+                policies.exact.insert(name.clone(), *policy);
+            }
+        }
+        policies
+    }
+}
+
 async fn notify_mcp_tool_call_event(sess: &Session, sub_id: &str, event: EventMsg) {
     sess.send_event(Event {
         id: sub_id.to_string(),
@@ -80,3 +239,105 @@ async fn notify_mcp_tool_call_event(sess: &Session, sub_id: &str, event: EventMs
     })
     .await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn server_config(tool_policies: &[(&str, McpToolPolicy)]) -> McpServerConfig {
+        McpServerConfig {
+            command: "server".to_string(),
+            args: Vec::new(),
+            env: None,
+            startup_timeout_ms: None,
+            tool_timeout_ms: None,
+            max_restarts: None,
+            default_tool_policy: Some(McpToolPolicy::Ask),
+            tool_policies: tool_policies
+                .iter()
+                .map(|(name, policy)| (name.to_string(), *policy))
+                .collect(),
+            sampling_policy: None,
+        }
+    }
+
+    #[test]
+    fn exact_names_win_then_the_most_restrictive_pattern() {
+        let policies = ServerToolPolicies::from(&server_config(&[
+            ("get_*", McpToolPolicy::Allow),
+            ("*_ticket", McpToolPolicy::Deny),
+            ("get_ticket", McpToolPolicy::Allow),
+        ]));
+
+        assert_eq!(
+            policies.configured("get_ticket"),
+            Some(McpToolPolicy::Allow)
+        );
+        assert_eq!(policies.configured("get_user"), Some(McpToolPolicy::Allow));
+        assert_eq!(
+            policies.configured("get_user_ticket"),
+            Some(McpToolPolicy::Deny)
+        );
+        assert_eq!(policies.configured("deploy"), Some(McpToolPolicy::Ask));
+    }
+
+    #[test]
+    fn only_read_only_tools_run_without_asking() {
+        let hints = |read_only, destructive| ToolAnnotations {
+            destructive_hint: Some(destructive),
+            idempotent_hint: None,
+            open_world_hint: None,
+            read_only_hint: Some(read_only),
+            title: None,
+        };
+        let read_only = hints(true, false);
+        let destructive = hints(false, true);
+        let not_destructive = hints(false, false);
+        let resolve =
+            |annotations| resolve_tool_policy(None, annotations, AskForApproval::OnRequest);
+
+        assert_eq!(resolve(Some(&read_only)), McpToolPolicy::Allow);
+        assert_eq!(resolve(Some(&destructive)), McpToolPolicy::Ask);
+        assert_eq!(resolve(Some(&not_destructive)), McpToolPolicy::Ask);
+        // An unannotated tool may be destructive.
+        assert_eq!(resolve(None), McpToolPolicy::Ask);
+        assert_eq!(
+            resolve_tool_policy(None, None, AskForApproval::UnlessTrusted),
+            McpToolPolicy::Ask
+        );
+        assert_eq!(
+            resolve_tool_policy(None, Some(&destructive), AskForApproval::Never),
+            McpToolPolicy::Allow
+        );
+        assert_eq!(
+            resolve_tool_policy(Some(McpToolPolicy::Allow), None, AskForApproval::OnRequest),
+            McpToolPolicy::Allow
+        );
+        assert_eq!(
+            resolve_tool_policy(
+                Some(McpToolPolicy::Deny),
+                Some(&read_only),
+                AskForApproval::Never,
+            ),
+            McpToolPolicy::Deny
+        );
+    }
+
+    #[test]
+    fn destructive_hint_outweighs_read_only_hint() {
+        let contradictory = ToolAnnotations {
+            destructive_hint: Some(true),
+            idempotent_hint: None,
+            open_world_hint: None,
+            read_only_hint: Some(true),
+            title: None,
+        };
+        for approval_policy in [AskForApproval::OnFailure, AskForApproval::OnRequest] {
+            assert_eq!(
+                resolve_tool_policy(None, Some(&contradictory), approval_policy),
+                McpToolPolicy::Ask
+            );
+        }
+    }
+}
@@ -13,11 +13,22 @@
 //! trailing `\n`) and write it with a **single `write(2)` system call** while
 //! the file descriptor is opened with the `O_APPEND` flag. POSIX guarantees
 //! that writes up to `PIPE_BUF` bytes are atomic in that case.
+//!
+//! The `[history]` limits (`max_bytes`, `max_age_days`, `max_entries`) are
+//! enforced by writing the retained entries to a new file and renaming it over
+//! the old one while holding the same exclusive lock that writers take. The new
+//! file has a new inode, so `(log_id, offset)` pairs handed out before the
+//! compaction stop resolving instead of pointing at the wrong entry, and a
+//! writer that was waiting for the lock on the old file reopens the new one.
 
 use std::fs::File;
 use std::fs::OpenOptions;
+use std::io::Read;
 use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use serde::Deserialize;
@@ -28,7 +39,9 @@ use tokio::io::AsyncReadExt;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::config_types::History;
 use crate::config_types::HistoryPersistence;
+use crate::redaction::Redactor;
 
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -41,6 +54,13 @@ const HISTORY_FILENAME: &str = "history.jsonl";
 const MAX_RETRIES: usize = 10;
 const RETRY_SLEEP: Duration = Duration::from_millis(100);
 
+/// When the file outgrows `max_bytes`, it is compacted down to this
+/// percentage of the limit so that the next few appends do not immediately
+/// trigger another rewrite.
+const COMPACT_TARGET_PERCENT: u64 = 80;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HistoryEntry {
     pub session_id: String,
@@ -48,6 +68,13 @@ pub struct HistoryEntry {
     pub text: String,
 }
 
+/// What compacting the history file removed (or would remove, on a dry run).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryPruned {
+    pub removed_entries: usize,
+    pub freed_bytes: u64,
+}
+
 fn history_filepath(config: &Config) -> PathBuf {
     let mut path = config.codex_home.clone();
     path.push(HISTORY_FILENAME);
@@ -68,7 +95,8 @@ pub(crate) async fn append_entry(text: &str, session_id: &Uuid, config: &Config)
         }
     }
 
-    // TODO: check `text` for sensitive patterns
+    let redactor = Redactor::for_disk(&config.redaction, &config.codex_home)?;
+    let text = redactor.redact(text);
 
     // Resolve `~/.codex/history.jsonl` and ensure the parent directory exists.
     let path = history_filepath(config);
@@ -77,16 +105,13 @@ pub(crate) async fn append_entry(text: &str, session_id: &Uuid, config: &Config)
     }
 
     // Compute timestamp (seconds since the Unix epoch).
-    let ts = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map_err(|e| std::io::Error::other(format!("system clock before Unix epoch: {e}")))?
-        .as_secs();
+    let ts = unix_now()?;
 
     // Construct the JSON line first so we can write it in a single syscall.
     let entry = HistoryEntry {
         session_id: session_id.to_string(),
         ts,
-        text: text.to_string(),
+        text: text.into_owned(),
     };
     let mut line = serde_json::to_string(&entry)
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
@@ -100,39 +125,181 @@ pub(crate) async fn append_entry(text: &str, session_id: &Uuid, config: &Config)
         options.mode(0o600);
     }
 
-    let mut history_file = options.open(&path)?;
+    let history_file = options.open(&path)?;
 
     // Ensure permissions.
     ensure_owner_only_permissions(&history_file).await?;
 
     // Perform a blocking write under an advisory write lock using std::fs.
+    let limits = config.history.clone();
     tokio::task::spawn_blocking(move || -> Result<()> {
-        // Retry a few times to avoid indefinite blocking when contended.
-        for _ in 0..MAX_RETRIES {
-            match history_file.try_lock() {
-                Ok(()) => {
-                    // While holding the exclusive lock, write the full line.
-                    history_file.write_all(line.as_bytes())?;
-                    history_file.flush()?;
-                    return Ok(());
-                }
-                Err(std::fs::TryLockError::WouldBlock) => {
-                    std::thread::sleep(RETRY_SLEEP);
-                }
-                Err(e) => return Err(e.into()),
-            }
+        let mut history_file = lock_current(history_file, &path, &options)?;
+        // While holding the exclusive lock, write the full line.
+        history_file.write_all(line.as_bytes())?;
+        history_file.flush()?;
+        if let Some(max_bytes) = limits.max_bytes
+            && history_file.metadata()?.len() > max_bytes as u64
+        {
+            compact_locked(&mut history_file, &path, &limits, ts, false)?;
         }
-
-        Err(std::io::Error::new(
-            std::io::ErrorKind::WouldBlock,
-            "could not acquire exclusive lock on history file after multiple attempts",
-        ))
+        Ok(())
     })
     .await??;
 
     Ok(())
 }
 
+/// Drop the entries that the `[history]` limits no longer allow. With
+/// `dry_run`, only report what would be removed.
+pub(crate) async fn compact(config: &Config, dry_run: bool) -> Result<HistoryPruned> {
+    let path = history_filepath(config);
+    let mut options = OpenOptions::new();
+    options.read(true).append(true);
+    let history_file = match options.open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HistoryPruned::default()),
+        Err(e) => return Err(e),
+    };
+    let limits = config.history.clone();
+    let now = unix_now()?;
+    tokio::task::spawn_blocking(move || {
+        let mut history_file = lock_current(history_file, &path, &options)?;
+        compact_locked(&mut history_file, &path, &limits, now, dry_run)
+    })
+    .await?
+}
+
+/// Take the exclusive advisory lock on the history file, retrying a few times
+/// to avoid indefinite blocking when contended.
+fn lock_exclusive(file: &File) -> Result<()> {
+    for _ in 0..MAX_RETRIES {
+        match file.try_lock() {
+            Ok(()) => return Ok(()),
+            Err(std::fs::TryLockError::WouldBlock) => {
+                std::thread::sleep(RETRY_SLEEP);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::WouldBlock,
+        "could not acquire exclusive lock on history file after multiple attempts",
+    ))
+}
+
+/// Take the exclusive lock on `file`, which was opened from `path` with
+/// `options`. If compaction replaced the file while we waited for the lock,
+/// the lock is dropped and taken again on the file now at `path`, so that
+/// nothing is written to a file that is no longer linked.
+fn lock_current(mut file: File, path: &Path, options: &OpenOptions) -> Result<File> {
+    for _ in 0..MAX_RETRIES {
+        lock_exclusive(&file)?;
+        if is_current(&file, path)? {
+            return Ok(file);
+        }
+        file = options.open(path)?;
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::WouldBlock,
+        "history file kept being replaced while waiting for its lock",
+    ))
+}
+
+/// Whether `file` is still the file at `path`.
+#[cfg(unix)]
+fn is_current(file: &File, path: &Path) -> Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let open = file.metadata()?;
+    match std::fs::metadata(path) {
+        Ok(current) => Ok(current.ino() == open.ino() && current.dev() == open.dev()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(unix))]
+fn is_current(_file: &File, _path: &Path) -> Result<bool> {
+    Ok(true)
+}
+
+/// Replace the history file at `path`, whose current contents are read from
+/// `file`, with one that keeps only the entries `limits` still allows. The
+/// caller must hold the exclusive lock on `file`. The retained entries are
+/// written to a temporary file in the same directory, synced and renamed over
+/// `path`, so a crash mid-way leaves either the old or the new history.
+fn compact_locked(
+    file: &mut File,
+    path: &Path,
+    limits: &History,
+    now: u64,
+    dry_run: bool,
+) -> Result<HistoryPruned> {
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut contents)?;
+
+    let start = retained_start(&contents, limits, now);
+    let pruned = HistoryPruned {
+        removed_entries: contents[..start].iter().filter(|&&b| b == b'\n').count(),
+        freed_bytes: start as u64,
+    };
+    if start == 0 || dry_run {
+        return Ok(pruned);
+    }
+
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut replacement = tempfile::Builder::new()
+        .prefix(".history")
+        .tempfile_in(dir)?;
+    replacement.write_all(&contents[start..])?;
+    replacement.as_file().sync_all()?;
+    replacement.persist(path).map_err(|e| e.error)?;
+    Ok(pruned)
+}
+
+/// Byte offset of the oldest line in `contents` that the limits keep. Entries
+/// are appended in chronological order, so everything before it is dropped.
+fn retained_start(contents: &[u8], limits: &History, now: u64) -> usize {
+    let max_bytes = limits.max_bytes.map(|max| max as u64);
+    let byte_budget = match max_bytes {
+        Some(max) if contents.len() as u64 > max => Some(max * COMPACT_TARGET_PERCENT / 100),
+        other => other,
+    };
+    let cutoff = limits
+        .max_age_days
+        .map(|days| now.saturating_sub(days.saturating_mul(SECONDS_PER_DAY)));
+
+    let mut start = contents.len();
+    for (kept_entries, line) in contents.split_inclusive(|&b| b == b'\n').rev().enumerate() {
+        if limits.max_entries.is_some_and(|max| kept_entries >= max)
+            || byte_budget
+                .is_some_and(|budget| (contents.len() - start + line.len()) as u64 > budget)
+        {
+            break;
+        }
+        // Lines that cannot be parsed have no age; they only count towards
+        // the size and entry limits.
+        if let Some(cutoff) = cutoff
+            && let Ok(entry) = serde_json::from_slice::<HistoryEntry>(line)
+            && entry.ts < cutoff
+        {
+            break;
+        }
+        start -= line.len();
+    }
+    start
+}
+
+fn unix_now() -> Result<u64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| std::io::Error::other(format!("system clock before Unix epoch: {e}")))?
+        .as_secs())
+}
+
 /// Asynchronously fetch the history file's *identifier* (inode on Unix) and
 /// the current number of entries by counting newline characters.
 pub(crate) async fn history_metadata(config: &Config) -> (u64, usize) {
@@ -279,3 +446,116 @@ async fn ensure_owner_only_permissions(_file: &File) -> Result<()> {
     // For now, on non-Unix, simply succeed.
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn history_line(ts: u64, text: &str) -> String {
+        let entry = HistoryEntry {
+            session_id: "s".to_string(),
+            ts,
+            text: text.to_string(),
+        };
+        format!("{}\n", serde_json::to_string(&entry).unwrap())
+    }
+
+    fn limits(
+        max_bytes: Option<usize>,
+        max_age_days: Option<u64>,
+        max_entries: Option<usize>,
+    ) -> History {
+        History {
+            persistence: HistoryPersistence::SaveAll,
+            max_bytes,
+            max_age_days,
+            max_entries,
+        }
+    }
+
+    #[test]
+    fn retained_start_applies_each_limit() {
+        let now = 10 * SECONDS_PER_DAY;
+        let lines = [
+            history_line(now - 5 * SECONDS_PER_DAY, "old"),
+            history_line(now - 2 * SECONDS_PER_DAY, "recent"),
+            history_line(now, "new"),
+        ];
+        let contents = lines.concat();
+        let offset_of = |i: usize| lines[..i].iter().map(String::len).sum::<usize>();
+
+        assert_eq!(
+            retained_start(contents.as_bytes(), &History::default(), now),
+            0
+        );
+        assert_eq!(
+            retained_start(contents.as_bytes(), &limits(None, Some(3), None), now),
+            offset_of(1)
+        );
+        assert_eq!(
+            retained_start(contents.as_bytes(), &limits(None, None, Some(1)), now),
+            offset_of(2)
+        );
+        // Over the size limit, the file shrinks to at most 80% of it.
+        let max_bytes = lines[1].len() + lines[2].len() + 1;
+        assert_eq!(
+            retained_start(
+                contents.as_bytes(),
+                &limits(Some(max_bytes), None, None),
+                now
+            ),
+            offset_of(2)
+        );
+        // Within the size limit, nothing is dropped.
+        assert_eq!(
+            retained_start(
+                contents.as_bytes(),
+                &limits(Some(contents.len()), None, None),
+                now
+            ),
+            0
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn compaction_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(HISTORY_FILENAME);
+        let contents = [
+            history_line(1, "a"),
+            history_line(2, "b"),
+            history_line(3, "c"),
+        ]
+        .concat();
+        std::fs::write(&path, &contents).unwrap();
+        let mut options = OpenOptions::new();
+        options.read(true).append(true);
+        let mut file = options.open(&path).unwrap();
+
+        let limits = limits(None, None, Some(2));
+        let dry_run = compact_locked(&mut file, &path, &limits, 3, true).unwrap();
+        assert_eq!(dry_run.removed_entries, 1);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+        assert!(is_current(&file, &path).unwrap());
+
+        let pruned = compact_locked(&mut file, &path, &limits, 3, false).unwrap();
+        assert_eq!(pruned, dry_run);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            [history_line(2, "b"), history_line(3, "c")].concat()
+        );
+        // The file was replaced rather than rewritten: the old descriptor
+        // still sees the old contents, and a writer holding it moves over to
+        // the new file once it has the lock.
+        assert!(!is_current(&file, &path).unwrap());
+        let mut old = String::new();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.read_to_string(&mut old).unwrap();
+        assert_eq!(old, contents);
+        let file = lock_current(file, &path, &options).unwrap();
+        assert!(is_current(&file, &path).unwrap());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
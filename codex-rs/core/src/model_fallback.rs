@@ -0,0 +1,168 @@
+//! Falling back from a failing provider/model to the next entry of
+//! `model_fallback.chain`.
+//!
+//! A task starts on the session's client. When its provider fails in a way
+//! that retrying the same provider is unlikely to fix soon – server errors or
+//! rate limits that outlast its retries, or a stream that goes idle – the turn
+//! is retried on the next entry, and the task stays there until it completes.
+//! The next task starts on the session's client again.
+
+use std::collections::VecDeque;
+
+use reqwest::StatusCode;
+use tracing::warn;
+
+use crate::client::ModelClient;
+use crate::codex::TurnContext;
+use crate::error::CodexErr;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::openai_tools::ToolsConfig;
+use crate::openai_tools::ToolsConfigParams;
+
+/// The provider/model pairs a task can use, starting with the session's.
+pub(crate) struct ModelChain {
+    /// The fallback the task switched to, with the tools for its model.
+    active: Option<(ModelClient, ToolsConfig)>,
+    active_label: String,
+    /// Entries not tried yet, in order.
+    remaining: VecDeque<FallbackTarget>,
+}
+
+struct FallbackTarget {
+    label: String,
+    provider: ModelProviderInfo,
+    model: String,
+}
+
+impl ModelChain {
+    pub(crate) fn new(primary: &ModelClient) -> Self {
+        let config = primary.config();
+        let remaining = config
+            .model_fallback
+            .chain
+            .iter()
+            .filter_map(|entry| {
+                let provider_id = entry
+                    .model_provider
+                    .clone()
+                    .unwrap_or_else(|| config.model_provider_id.clone());
+                let Some(provider) = config.model_providers.get(&provider_id) else {
+                    warn!("skipping model fallback to unknown provider `{provider_id}`");
+                    return None;
+                };
+                let model = entry.model.clone().unwrap_or_else(|| primary.get_model());
+                Some(FallbackTarget {
+                    label: describe(&provider_id, &model),
+                    provider: provider.clone(),
+                    model,
+                })
+            })
+            .collect();
+        Self {
+            active: None,
+            active_label: describe(&config.model_provider_id, &primary.get_model()),
+            remaining,
+        }
+    }
+
+    /// The client to send the next request with.
+    pub(crate) fn client<'a>(&'a self, turn_context: &'a TurnContext) -> &'a ModelClient {
+        self.active
+            .as_ref()
+            .map_or(&turn_context.client, |(client, _)| client)
+    }
+
+    /// The tools to offer the model returned by [`ModelChain::client`].
+    pub(crate) fn tools_config<'a>(&'a self, turn_context: &'a TurnContext) -> &'a ToolsConfig {
+        self.active
+            .as_ref()
+            .map_or(&turn_context.tools_config, |(_, tools_config)| tools_config)
+    }
+
+    /// Move on to the next entry if `err` calls for it. Returns a message
+    /// announcing the switch, or `None` when the caller should handle `err`
+    /// as usual.
+    pub(crate) fn fall_back(
+        &mut self,
+        turn_context: &TurnContext,
+        err: &CodexErr,
+    ) -> Option<String> {
+        let reason = fallback_reason(err)?;
+        let next = self.remaining.pop_front()?;
+
+        let client = turn_context.client.with_model(next.provider, &next.model);
+        let config = client.config();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &config.model_family,
+            approval_policy: turn_context.approval_policy,
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            include_plan_tool: config.include_plan_tool,
+            include_apply_patch_tool: config.include_apply_patch_tool,
+            include_web_search_request: config.tools_web_search_request,
+            use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
+            include_view_image_tool: config.include_view_image_tool,
+            include_symbols_tool: config.include_symbols_tool,
+        });
+
+        let message = format!(
+            "{} {reason}; switching to {}",
+            self.active_label, next.label
+        );
+        self.active = Some((client, tools_config));
+        self.active_label = next.label;
+        Some(message)
+    }
+}
+
+fn describe(provider_id: &str, model: &str) -> String {
+    format!("`{model}` on `{provider_id}`")
+}
+
+/// Why `err` warrants moving on to the next provider, if it does. These are
+/// the errors a provider returns once its own retries are used up.
+fn fallback_reason(err: &CodexErr) -> Option<&'static str> {
+    match err {
+        CodexErr::InternalServerError => Some("is returning server errors"),
+        CodexErr::RetryLimit(status) if status.is_server_error() => {
+            Some("is returning server errors")
+        }
+        CodexErr::RetryLimit(StatusCode::TOO_MANY_REQUESTS) | CodexErr::UsageLimitReached(_) => {
+            Some("is rate limited")
+        }
+        CodexErr::StreamIdleTimeout => Some("stopped responding"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn falls_back_on_outages_but_not_on_bad_requests() {
+        assert_eq!(
+            fallback_reason(&CodexErr::RetryLimit(StatusCode::BAD_GATEWAY)),
+            Some("is returning server errors")
+        );
+        assert_eq!(
+            fallback_reason(&CodexErr::RetryLimit(StatusCode::TOO_MANY_REQUESTS)),
+            Some("is rate limited")
+        );
+        assert_eq!(
+            fallback_reason(&CodexErr::StreamIdleTimeout),
+            Some("stopped responding")
+        );
+        assert_eq!(
+            fallback_reason(&CodexErr::UnexpectedStatus(
+                StatusCode::BAD_REQUEST,
+                "bad".to_string()
+            )),
+            None
+        );
+        assert_eq!(
+            fallback_reason(&CodexErr::Stream("reset".to_string(), None)),
+            None
+        );
+    }
+}
@@ -1,14 +1,22 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use serde::Deserialize;
+
+use crate::protocol::TokenUsage;
 use crate::tool_apply_patch::ApplyPatchToolType;
 
+/// The built-in registry, in the same format as `[models]` in config.toml.
+const BUILT_IN_MODELS: &str = include_str!("models.toml");
+
 /// A model family is a group of models that share certain characteristics.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ModelFamily {
     /// The full model slug used to derive this model family, e.g.
     /// "gpt-4.1-2025-04-14".
     pub slug: String,
 
-    /// The model family name, e.g. "gpt-4.1". Note this should able to be used
-    /// with [`crate::openai_model_info::get_model_info`].
+    /// The model family name, e.g. "gpt-4.1".
     pub family: String,
 
     /// True if the model needs additional instructions on how to use the
@@ -20,6 +28,10 @@ pub struct ModelFamily {
     // `summary` is optional).
     pub supports_reasoning_summaries: bool,
 
+    /// Whether the model accepts the `minimal` reasoning effort. Older
+    /// reasoning models only go down to `low`.
+    pub supports_minimal_reasoning_effort: bool,
+
     // This should be set to true when the model expects a tool named
     // "local_shell" to be provided. Its contract must be understood natively by
     // the model such that its description can be omitted.
@@ -29,85 +41,292 @@ pub struct ModelFamily {
     /// Present if the model performs better when `apply_patch` is provided as
     /// a tool call instead of just a bash command
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
+
+    /// Whether the model accepts images. The `view_image` tool is only
+    /// offered to models that do.
+    pub supports_image_input: bool,
+
+    /// Size of the context window in tokens, if known.
+    pub context_window: Option<u64>,
+
+    /// Maximum number of output tokens the model can generate, if known.
+    pub max_output_tokens: Option<u64>,
+
+    /// Price of the model's tokens, if known.
+    pub pricing: Option<ModelPricing>,
 }
 
-macro_rules! model_family {
-    (
-        $slug:expr, $family:expr $(, $key:ident : $value:expr )* $(,)?
-    ) => {{
-        // defaults
-        let mut mf = ModelFamily {
-            slug: $slug.to_string(),
-            family: $family.to_string(),
+impl ModelFamily {
+    /// A family for a model nothing is known about.
+    fn unknown(slug: &str) -> Self {
+        Self {
+            slug: slug.to_string(),
+            family: slug.to_string(),
             needs_special_apply_patch_instructions: false,
             supports_reasoning_summaries: false,
+            supports_minimal_reasoning_effort: false,
             uses_local_shell_tool: false,
             apply_patch_tool_type: None,
-        };
-        // apply overrides
-        $(
-            mf.$key = $value;
-        )*
-        Some(mf)
-    }};
+            supports_image_input: true,
+            context_window: None,
+            max_output_tokens: None,
+            pricing: None,
+        }
+    }
 }
 
-macro_rules! simple_model_family {
-    (
-        $slug:expr, $family:expr
-    ) => {{
-        Some(ModelFamily {
-            slug: $slug.to_string(),
-            family: $family.to_string(),
-            needs_special_apply_patch_instructions: false,
-            supports_reasoning_summaries: false,
-            uses_local_shell_tool: false,
-            apply_patch_tool_type: None,
-        })
-    }};
+/// Token prices in USD per million tokens.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    /// Price of input tokens served from the prompt cache. Defaults to
+    /// `input`.
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+    pub output: f64,
+}
+
+impl ModelPricing {
+    /// Estimated cost of `usage` in USD.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cached_input = self.cached_input.unwrap_or(self.input);
+        (usage.non_cached_input() as f64 * self.input
+            + usage.cached_input() as f64 * cached_input
+            + usage.output_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
 }
 
-/// Returns a `ModelFamily` for the given model slug, or `None` if the slug
-/// does not match any known model family.
+/// What the registry knows about the models matching one key. Unset fields
+/// leave the value from less specific entries in place.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModelEntry {
+    pub family: Option<String>,
+    pub context_window: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    pub supports_reasoning_summaries: Option<bool>,
+    pub supports_minimal_reasoning_effort: Option<bool>,
+    pub needs_special_apply_patch_instructions: Option<bool>,
+    pub uses_local_shell_tool: Option<bool>,
+    pub apply_patch_tool_type: Option<ApplyPatchToolType>,
+    pub supports_image_input: Option<bool>,
+    pub pricing: Option<ModelPricing>,
+}
+
+impl ModelEntry {
+    fn apply_to(&self, family: &mut ModelFamily) {
+        if let Some(name) = &self.family {
+            family.family = name.clone();
+        }
+        if let Some(v) = self.context_window {
+            family.context_window = Some(v);
+        }
+        if let Some(v) = self.max_output_tokens {
+            family.max_output_tokens = Some(v);
+        }
+        if let Some(v) = self.supports_reasoning_summaries {
+            family.supports_reasoning_summaries = v;
+        }
+        if let Some(v) = self.supports_minimal_reasoning_effort {
+            family.supports_minimal_reasoning_effort = v;
+        }
+        if let Some(v) = self.needs_special_apply_patch_instructions {
+            family.needs_special_apply_patch_instructions = v;
+        }
+        if let Some(v) = self.uses_local_shell_tool {
+            family.uses_local_shell_tool = v;
+        }
+        if let Some(v) = &self.apply_patch_tool_type {
+            family.apply_patch_tool_type = Some(v.clone());
+        }
+        if let Some(v) = self.supports_image_input {
+            family.supports_image_input = v;
+        }
+        if let Some(v) = self.pricing {
+            family.pricing = Some(v);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ModelsFile {
+    models: HashMap<String, ModelEntry>,
+}
+
+/// Model capabilities keyed by slug (`gpt-4o`) or slug prefix (`gpt-4o*`):
+/// the built-in entries followed by the user's `[models]` entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelRegistry {
+    /// Built-in entries first, each group ordered from least to most
+    /// specific, so that applying them in order lets later entries win.
+    entries: Vec<(String, ModelEntry)>,
+}
+
+static BUILT_IN_REGISTRY: LazyLock<ModelRegistry> = LazyLock::new(|| {
+    #[expect(clippy::expect_used)]
+    let file: ModelsFile = toml::from_str(BUILT_IN_MODELS).expect("models.toml must be valid");
+    ModelRegistry {
+        entries: sorted_by_specificity(file.models),
+    }
+});
+
+impl ModelRegistry {
+    /// The registry with only the built-in entries.
+    pub fn built_in() -> &'static ModelRegistry {
+        &BUILT_IN_REGISTRY
+    }
+
+    /// The built-in registry extended with `user_entries`, which take
+    /// precedence over the built-in ones.
+    pub fn with_user_entries(user_entries: &HashMap<String, ModelEntry>) -> Self {
+        let mut entries = Self::built_in().entries.clone();
+        entries.extend(sorted_by_specificity(user_entries.clone()));
+        Self { entries }
+    }
+
+    /// The family for `slug`, or `None` if no entry matches it.
+    pub fn find(&self, slug: &str) -> Option<ModelFamily> {
+        let mut family = ModelFamily::unknown(slug);
+        let mut matched = false;
+        for (key, entry) in &self.entries {
+            if key_matches(key, slug) {
+                entry.apply_to(&mut family);
+                matched = true;
+            }
+        }
+        matched.then_some(family)
+    }
+
+    /// The family for `slug`, with defaults for whatever the registry does
+    /// not say.
+    pub fn family_for(&self, slug: &str) -> ModelFamily {
+        self.find(slug)
+            .unwrap_or_else(|| ModelFamily::unknown(slug))
+    }
+}
+
+fn key_matches(key: &str, slug: &str) -> bool {
+    match key.strip_suffix('*') {
+        Some(prefix) => slug.starts_with(prefix),
+        None => key == slug,
+    }
+}
+
+/// Prefixes before exact slugs, shorter prefixes before longer ones.
+fn sorted_by_specificity(entries: HashMap<String, ModelEntry>) -> Vec<(String, ModelEntry)> {
+    let mut entries: Vec<_> = entries.into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| {
+        let rank = |key: &str| (!key.ends_with('*'), key.len());
+        rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+    });
+    entries
+}
+
+/// Returns a `ModelFamily` for the given model slug from the built-in
+/// registry, or `None` if the slug does not match any known model family.
 pub fn find_family_for_model(slug: &str) -> Option<ModelFamily> {
-    if slug.starts_with("o3") {
-        model_family!(
-            slug, "o3",
-            supports_reasoning_summaries: true,
-        )
-    } else if slug.starts_with("o4-mini") {
-        model_family!(
-            slug, "o4-mini",
-            supports_reasoning_summaries: true,
-        )
-    } else if slug.starts_with("codex-mini-latest") {
-        model_family!(
-            slug, "codex-mini-latest",
-            supports_reasoning_summaries: true,
-            uses_local_shell_tool: true,
-        )
-    } else if slug.starts_with("codex-") {
-        model_family!(
-            slug, slug,
-            supports_reasoning_summaries: true,
-        )
-    } else if slug.starts_with("gpt-4.1") {
-        model_family!(
-            slug, "gpt-4.1",
-            needs_special_apply_patch_instructions: true,
-        )
-    } else if slug.starts_with("gpt-oss") {
-        model_family!(slug, "gpt-oss", apply_patch_tool_type: Some(ApplyPatchToolType::Function))
-    } else if slug.starts_with("gpt-4o") {
-        simple_model_family!(slug, "gpt-4o")
-    } else if slug.starts_with("gpt-3.5") {
-        simple_model_family!(slug, "gpt-3.5")
-    } else if slug.starts_with("gpt-5") {
-        model_family!(
-            slug, "gpt-5",
-            supports_reasoning_summaries: true,
+    ModelRegistry::built_in().find(slug)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn built_in_entries_layer_prefixes_under_exact_slugs() {
+        let family = find_family_for_model("codex-mini-latest").unwrap();
+        assert_eq!(family.family, "codex-mini-latest");
+        assert!(family.supports_reasoning_summaries);
+        assert!(family.uses_local_shell_tool);
+        assert_eq!(family.context_window, Some(200_000));
+
+        let family = find_family_for_model("codex-foo").unwrap();
+        assert_eq!(family.family, "codex-foo");
+        assert_eq!(family.context_window, Some(400_000));
+        assert!(!family.uses_local_shell_tool);
+
+        let family = find_family_for_model("gpt-4o-2024-05-13").unwrap();
+        assert_eq!(family.family, "gpt-4o");
+        assert_eq!(family.max_output_tokens, Some(4_096));
+
+        let family = find_family_for_model("gpt-oss:20b").unwrap();
+        assert_eq!(
+            family.apply_patch_tool_type,
+            Some(ApplyPatchToolType::Function)
+        );
+        assert!(!family.supports_image_input);
+        assert_eq!(family.context_window, None);
+
+        assert_eq!(find_family_for_model("llama3"), None);
+    }
+
+    #[test]
+    fn user_entries_override_and_extend_the_built_in_ones() {
+        let user: ModelsFile = toml::from_str(
+            r#"
+[models."acme-coder*"]
+family = "acme-coder"
+context_window = 32_768
+supports_image_input = false
+apply_patch_tool_type = "freeform"
+
+[models.acme-coder-ft]
+context_window = 65_536
+pricing = { input = 1.0, output = 2.0 }
+
+[models."gpt-4o*"]
+context_window = 64_000
+"#,
         )
-    } else {
-        None
+        .unwrap();
+        let registry = ModelRegistry::with_user_entries(&user.models);
+
+        let family = registry.family_for("acme-coder-ft");
+        assert_eq!(
+            family,
+            ModelFamily {
+                slug: "acme-coder-ft".to_string(),
+                family: "acme-coder".to_string(),
+                needs_special_apply_patch_instructions: false,
+                supports_reasoning_summaries: false,
+                supports_minimal_reasoning_effort: false,
+                uses_local_shell_tool: false,
+                apply_patch_tool_type: Some(ApplyPatchToolType::Freeform),
+                supports_image_input: false,
+                context_window: Some(65_536),
+                max_output_tokens: None,
+                pricing: Some(ModelPricing {
+                    input: 1.0,
+                    cached_input: None,
+                    output: 2.0,
+                }),
+            }
+        );
+        // User entries win even over more specific built-in ones.
+        assert_eq!(registry.family_for("gpt-4o").context_window, Some(64_000));
+        assert_eq!(
+            registry.family_for("llama3"),
+            ModelFamily::unknown("llama3")
+        );
+    }
+
+    #[test]
+    fn pricing_charges_cached_input_at_its_own_rate() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            cached_input: Some(0.5),
+            output: 8.0,
+        };
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            cached_input_tokens: Some(400_000),
+            output_tokens: 250_000,
+            reasoning_output_tokens: None,
+            total_tokens: 1_250_000,
+        };
+        assert_eq!(pricing.cost(&usage), 3.4);
     }
 }
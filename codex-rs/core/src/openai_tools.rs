@@ -70,6 +70,7 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_symbols_tool: bool,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -81,6 +82,7 @@ pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) include_web_search_request: bool,
     pub(crate) use_streamable_shell_tool: bool,
     pub(crate) include_view_image_tool: bool,
+    pub(crate) include_symbols_tool: bool,
 }
 
 impl ToolsConfig {
@@ -94,6 +96,7 @@ impl ToolsConfig {
             include_web_search_request,
             use_streamable_shell_tool,
             include_view_image_tool,
+            include_symbols_tool,
         } = params;
         let mut shell_type = if *use_streamable_shell_tool {
             ConfigShellToolType::StreamableShell
@@ -125,7 +128,8 @@ impl ToolsConfig {
             plan_tool: *include_plan_tool,
             apply_patch_tool_type,
             web_search_request: *include_web_search_request,
-            include_view_image_tool: *include_view_image_tool,
+            include_view_image_tool: *include_view_image_tool && model_family.supports_image_input,
+            include_symbols_tool: *include_symbols_tool,
         }
     }
 }
@@ -324,6 +328,39 @@ fn create_view_image_tool() -> OpenAiTool {
         },
     })
 }
+fn create_symbols_tool() -> OpenAiTool {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "File or directory to inspect, relative to the working directory (default: the working directory)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "name".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Find the definitions with this name (e.g. `parse`, `Parser::new` or `Parser.parse`) and return their source instead of an outline."
+                    .to_string(),
+            ),
+        },
+    );
+
+    OpenAiTool::Function(ResponsesApiTool {
+        name: "symbols".to_string(),
+        description: "Lists the definitions (functions, methods, types, impls, classes, ...) in a source file or directory with their line ranges, or looks up definitions by name. Supports Rust, Python, TypeScript and Go. Prefer this over reading whole files to find where something is defined.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false),
+        },
+    })
+}
+
 /// TODO(dylan): deprecate once we get rid of json tool
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ApplyPatchToolArgs {
@@ -578,6 +615,10 @@ pub(crate) fn get_openai_tools(
         tools.push(create_view_image_tool());
     }
 
+    if config.include_symbols_tool {
+        tools.push(create_symbols_tool());
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         // Ensure deterministic ordering to maximize prompt cache hits.
         // HashMap iteration order is non-deterministic, so sort by fully-qualified tool name.
@@ -642,12 +683,19 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: true,
         });
         let tools = get_openai_tools(&config, Some(HashMap::new()));
 
         assert_eq_tool_names(
             &tools,
-            &["local_shell", "update_plan", "web_search", "view_image"],
+            &[
+                "local_shell",
+                "update_plan",
+                "web_search",
+                "view_image",
+                "symbols",
+            ],
         );
     }
 
@@ -663,6 +711,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
         let tools = get_openai_tools(&config, Some(HashMap::new()));
 
@@ -684,6 +733,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
         let tools = get_openai_tools(
             &config,
@@ -789,6 +839,7 @@ mod tests {
             include_web_search_request: false,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
 
         // Intentionally construct a map with keys that would sort alphabetically.
@@ -866,6 +917,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
 
         let tools = get_openai_tools(
@@ -928,6 +980,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
 
         let tools = get_openai_tools(
@@ -985,6 +1038,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
 
         let tools = get_openai_tools(
@@ -1042,6 +1096,7 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_symbols_tool: false,
         });
 
         let tools = get_openai_tools(
@@ -0,0 +1,285 @@
+//! Picking the reasoning effort of each request when `auto_reasoning_effort`
+//! is configured.
+//!
+//! The effort starts from the length of the prompt that started the task and
+//! moves up a level when the previous tool call failed or the plan still has
+//! many open steps, and down a level when the context window is nearly full.
+//! The result is clamped to the configured bounds and to the efforts the model
+//! accepts.
+//!
+//! Only the effort is tuned: `model_verbosity` keeps its configured value.
+
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::plan_tool::StepStatus;
+use codex_protocol::plan_tool::UpdatePlanArgs;
+
+use crate::config_types::AutoReasoningEffort;
+use crate::model_family::ModelFamily;
+use crate::protocol::TokenUsage;
+
+/// From least to most effort.
+const LEVELS: [ReasoningEffort; 4] = [
+    ReasoningEffort::Minimal,
+    ReasoningEffort::Low,
+    ReasoningEffort::Medium,
+    ReasoningEffort::High,
+];
+
+/// Prompts up to this many characters are quick questions.
+const SHORT_PROMPT_CHARS: usize = 280;
+
+/// Prompts over this many characters describe involved work.
+const LONG_PROMPT_CHARS: usize = 2_000;
+
+/// A plan with at least this many open steps is a large piece of work.
+const MANY_OPEN_PLAN_STEPS: usize = 4;
+
+/// Below this share of the context window left, effort is lowered so that
+/// reasoning does not use up what remains.
+const LOW_CONTEXT_PERCENT: u8 = 20;
+
+/// What the effort of the next request is based on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct EffortSignals {
+    /// Characters of text in the input that started the task.
+    pub prompt_chars: usize,
+    /// Whether a tool call answered in the previous request failed.
+    pub last_tool_call_failed: bool,
+    /// Steps of the current plan that are not completed.
+    pub open_plan_steps: usize,
+    /// Share of the context window left, when known.
+    pub context_left_percent: Option<u8>,
+}
+
+impl EffortSignals {
+    /// Whether `response` reports a failed tool call. Custom tool calls do
+    /// not report success and are never counted as failed.
+    pub(crate) fn tool_call_failed(response: &ResponseInputItem) -> bool {
+        match response {
+            ResponseInputItem::FunctionCallOutput { output, .. } => output.success == Some(false),
+            ResponseInputItem::McpToolCallOutput { result, .. } => result
+                .as_ref()
+                .map_or(true, |result| result.is_error == Some(true)),
+            ResponseInputItem::Message { .. } | ResponseInputItem::CustomToolCallOutput { .. } => {
+                false
+            }
+        }
+    }
+
+    /// Share of `context_window` left after the request that used `usage`.
+    pub(crate) fn context_left_percent(usage: &TokenUsage, context_window: u64) -> u8 {
+        if context_window == 0 {
+            return 0;
+        }
+        let used = usage.tokens_in_context_window().min(context_window);
+        (100 - used * 100 / context_window) as u8
+    }
+
+    pub(crate) fn open_plan_steps(plan: Option<&UpdatePlanArgs>) -> usize {
+        plan.map_or(0, |plan| {
+            plan.plan
+                .iter()
+                .filter(|item| !matches!(item.status, StepStatus::Completed))
+                .count()
+        })
+    }
+}
+
+/// Reject bounds whose `min` is above their `max`.
+pub(crate) fn validate_bounds(bounds: &AutoReasoningEffort) -> std::io::Result<()> {
+    if level_of(bounds.min) > level_of(bounds.max) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "auto_reasoning_effort.min ({}) must not be above auto_reasoning_effort.max ({})",
+                bounds.min, bounds.max
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// The effort for the next request to `model_family` and a short description
+/// of why.
+pub(crate) fn choose_effort(
+    bounds: &AutoReasoningEffort,
+    model_family: &ModelFamily,
+    signals: &EffortSignals,
+) -> (ReasoningEffort, String) {
+    let mut reasons = Vec::new();
+    let mut level = if signals.prompt_chars <= SHORT_PROMPT_CHARS {
+        reasons.push("short prompt".to_string());
+        level_of(ReasoningEffort::Low)
+    } else if signals.prompt_chars > LONG_PROMPT_CHARS {
+        reasons.push("long prompt".to_string());
+        level_of(ReasoningEffort::High)
+    } else {
+        level_of(ReasoningEffort::Medium)
+    };
+    if signals.last_tool_call_failed {
+        reasons.push("previous tool call failed".to_string());
+        level += 1;
+    }
+    if signals.open_plan_steps >= MANY_OPEN_PLAN_STEPS {
+        reasons.push(format!("{} open plan steps", signals.open_plan_steps));
+        level += 1;
+    }
+    if let Some(percent) = signals.context_left_percent
+        && percent < LOW_CONTEXT_PERCENT
+    {
+        reasons.push(format!("{percent}% context left"));
+        level = level.saturating_sub(1);
+    }
+
+    let lowest = if model_family.supports_minimal_reasoning_effort {
+        level_of(ReasoningEffort::Minimal)
+    } else {
+        level_of(ReasoningEffort::Low)
+    };
+    let level = level
+        .max(level_of(bounds.min))
+        .min(level_of(bounds.max))
+        .min(LEVELS.len() - 1)
+        .max(lowest);
+    let reason = if reasons.is_empty() {
+        "medium-length prompt".to_string()
+    } else {
+        reasons.join(", ")
+    };
+    (LEVELS[level], reason)
+}
+
+fn level_of(effort: ReasoningEffort) -> usize {
+    LEVELS.iter().position(|e| *e == effort).unwrap_or(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_family::ModelRegistry;
+    use codex_protocol::plan_tool::PlanItemArg;
+    use pretty_assertions::assert_eq;
+
+    const FULL_RANGE: AutoReasoningEffort = AutoReasoningEffort {
+        min: ReasoningEffort::Minimal,
+        max: ReasoningEffort::High,
+    };
+
+    fn gpt5() -> ModelFamily {
+        ModelRegistry::built_in().family_for("gpt-5")
+    }
+
+    #[test]
+    fn quick_questions_get_low_effort_and_failures_raise_it() {
+        let mut signals = EffortSignals {
+            prompt_chars: 40,
+            ..Default::default()
+        };
+        assert_eq!(
+            choose_effort(&FULL_RANGE, &gpt5(), &signals),
+            (ReasoningEffort::Low, "short prompt".to_string())
+        );
+
+        signals.last_tool_call_failed = true;
+        signals.open_plan_steps = 5;
+        assert_eq!(
+            choose_effort(&FULL_RANGE, &gpt5(), &signals),
+            (
+                ReasoningEffort::High,
+                "short prompt, previous tool call failed, 5 open plan steps".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn effort_stays_within_bounds() {
+        let bounds = AutoReasoningEffort {
+            min: ReasoningEffort::Medium,
+            max: ReasoningEffort::Medium,
+        };
+        let long = EffortSignals {
+            prompt_chars: 5_000,
+            last_tool_call_failed: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            choose_effort(&bounds, &gpt5(), &long).0,
+            ReasoningEffort::Medium
+        );
+        assert_eq!(
+            choose_effort(&bounds, &gpt5(), &EffortSignals::default()).0,
+            ReasoningEffort::Medium
+        );
+
+        let nearly_full = EffortSignals {
+            prompt_chars: 5_000,
+            context_left_percent: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            choose_effort(&FULL_RANGE, &gpt5(), &nearly_full),
+            (
+                ReasoningEffort::Medium,
+                "long prompt, 10% context left".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn minimal_effort_is_only_chosen_for_models_that_accept_it() {
+        let bounds = AutoReasoningEffort {
+            min: ReasoningEffort::Minimal,
+            max: ReasoningEffort::Minimal,
+        };
+        let signals = EffortSignals::default();
+        assert_eq!(
+            choose_effort(&bounds, &gpt5(), &signals).0,
+            ReasoningEffort::Minimal
+        );
+        for slug in ["o3", "o4-mini", "codex-mini-latest"] {
+            let family = ModelRegistry::built_in().family_for(slug);
+            assert_eq!(
+                choose_effort(&bounds, &family, &signals).0,
+                ReasoningEffort::Low,
+                "{slug}"
+            );
+        }
+    }
+
+    #[test]
+    fn bounds_must_not_be_inverted() {
+        assert!(validate_bounds(&FULL_RANGE).is_ok());
+        let inverted = AutoReasoningEffort {
+            min: ReasoningEffort::High,
+            max: ReasoningEffort::Low,
+        };
+        assert_eq!(
+            validate_bounds(&inverted).unwrap_err().to_string(),
+            "auto_reasoning_effort.min (high) must not be above auto_reasoning_effort.max (low)"
+        );
+    }
+
+    #[test]
+    fn completed_plan_steps_are_not_open() {
+        let plan = UpdatePlanArgs {
+            explanation: None,
+            plan: vec![
+                PlanItemArg {
+                    step: "read".to_string(),
+                    status: StepStatus::Completed,
+                },
+                PlanItemArg {
+                    step: "fix".to_string(),
+                    status: StepStatus::InProgress,
+                },
+                PlanItemArg {
+                    step: "test".to_string(),
+                    status: StepStatus::Pending,
+                },
+            ],
+        };
+        assert_eq!(EffortSignals::open_plan_steps(Some(&plan)), 2);
+        assert_eq!(EffortSignals::open_plan_steps(None), 0);
+    }
+}
@@ -0,0 +1,51 @@
+//! Enforcing the retention limits configured under `[history]` and
+//! `[sessions]`. Sessions apply them in the background when they start;
+//! `codex sessions prune` applies them on demand.
+
+use std::io;
+use std::sync::Arc;
+
+use tracing::debug;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::config_types::SessionRetention;
+use crate::message_history;
+use crate::rollout;
+
+pub use crate::message_history::HistoryPruned;
+pub use crate::rollout::retention::SessionsPruned;
+
+/// What [`prune`] removed from each store.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneReport {
+    pub history: HistoryPruned,
+    pub sessions: SessionsPruned,
+}
+
+/// Apply the configured limits to `history.jsonl` and the session rollouts.
+/// With `dry_run`, nothing is changed and the report says what would be.
+pub async fn prune(config: &Config, dry_run: bool) -> io::Result<PruneReport> {
+    let history = message_history::compact(config, dry_run).await?;
+    let sessions = rollout::retention::prune(&config.codex_home, &config.sessions, dry_run).await?;
+    Ok(PruneReport { history, sessions })
+}
+
+/// Apply the configured limits in the background. Does nothing unless at
+/// least one limit is set.
+pub(crate) fn spawn_prune(config: Arc<Config>) {
+    let history = &config.history;
+    let has_limits = history.max_bytes.is_some()
+        || history.max_age_days.is_some()
+        || history.max_entries.is_some()
+        || config.sessions != SessionRetention::default();
+    if !has_limits {
+        return;
+    }
+    tokio::spawn(async move {
+        match prune(&config, false).await {
+            Ok(report) => debug!("applied retention limits: {report:?}"),
+            Err(e) => warn!("failed to apply retention limits: {e}"),
+        }
+    });
+}
@@ -10,6 +10,10 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use super::SESSIONS_SUBDIR;
+use super::retention::COMPRESSED_SUFFIX;
+use super::retention::is_compressed;
+use super::retention::read_compressed_head;
+use super::retention::read_rollout;
 
 /// Returned page of conversation summaries.
 #[derive(Debug, Default, PartialEq)]
@@ -31,11 +35,17 @@ pub struct ConversationItem {
     pub path: PathBuf,
     /// First up to 5 JSONL records parsed as JSON (includes meta line).
     pub head: Vec<serde_json::Value>,
+    /// The session's generated title, if one was recorded early enough in
+    /// the file to be found.
+    pub title: Option<String>,
 }
 
 /// Hard cap to bound worst‑case work per request.
 const MAX_SCAN_FILES: usize = 10_000;
 const HEAD_RECORD_LIMIT: usize = 10;
+/// Titles are generated while the first task runs, so they land in the state
+/// records among its items rather than in the head.
+const TITLE_SCAN_RECORD_LIMIT: usize = 200;
 
 /// Pagination cursor identifying a file by timestamp and UUID.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -105,12 +115,13 @@ pub(crate) async fn get_conversations(
 /// Returns the entire file contents as a String.
 #[allow(dead_code)]
 pub(crate) async fn get_conversation(path: &Path) -> io::Result<String> {
-    tokio::fs::read_to_string(path).await
+    read_rollout(path).await
 }
 
 /// Load conversation file paths from disk using directory traversal.
 ///
 /// Directory layout: `~/.codex/sessions/YYYY/MM/DD/rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl`
+/// (or `.jsonl.gz` once archived).
 /// Returned newest (latest) first.
 async fn traverse_directories_for_paths(
     root: PathBuf,
@@ -142,10 +153,6 @@ async fn traverse_directories_for_paths(
                     break 'outer;
                 }
                 let mut day_files = collect_files(day_path, |name_str, path| {
-                    if !name_str.starts_with("rollout-") || !name_str.ends_with(".jsonl") {
-                        return None;
-                    }
-
                     parse_timestamp_uuid_from_filename(name_str)
                         .map(|(ts, id)| (ts, id, name_str.to_string(), path.to_path_buf()))
                 })
@@ -167,10 +174,8 @@ async fn traverse_directories_for_paths(
                     if items.len() == page_size {
                         break 'outer;
                     }
-                    let head = read_first_jsonl_records(&path, HEAD_RECORD_LIMIT)
-                        .await
-                        .unwrap_or_default();
-                    items.push(ConversationItem { path, head });
+                    let (head, title) = read_head_and_title(&path).await.unwrap_or_default();
+                    items.push(ConversationItem { path, head, title });
                 }
             }
         }
@@ -256,9 +261,12 @@ where
     Ok(collected)
 }
 
-fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
-    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl
-    let core = name.strip_prefix("rollout-")?.strip_suffix(".jsonl")?;
+pub(super) fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uuid)> {
+    // Expected: rollout-YYYY-MM-DDThh-mm-ss-<uuid>.jsonl, or .jsonl.gz once archived.
+    let core = name.strip_prefix("rollout-")?;
+    let core = core
+        .strip_suffix(".jsonl")
+        .or_else(|| core.strip_suffix(COMPRESSED_SUFFIX))?;
 
     // Scan from the right for a '-' such that the suffix parses as a UUID.
     let (sep_idx, uuid) = core
@@ -273,26 +281,59 @@ fn parse_timestamp_uuid_from_filename(name: &str) -> Option<(OffsetDateTime, Uui
     Some((ts, uuid))
 }
 
-async fn read_first_jsonl_records(
-    path: &Path,
-    max_records: usize,
-) -> io::Result<Vec<serde_json::Value>> {
+/// Read the first [`HEAD_RECORD_LIMIT`] records of a rollout, and keep
+/// reading up to [`TITLE_SCAN_RECORD_LIMIT`] records for its title.
+async fn read_head_and_title(path: &Path) -> io::Result<(Vec<serde_json::Value>, Option<String>)> {
     use tokio::io::AsyncBufReadExt;
 
+    let mut head: Vec<serde_json::Value> = Vec::new();
+    let mut title = None;
+
+    if is_compressed(path) {
+        for line in read_compressed_head(path, TITLE_SCAN_RECORD_LIMIT).await? {
+            if let Ok(v) = serde_json::from_str(line.trim()) {
+                title = keep_record(&mut head, v);
+            }
+            if title.is_some() {
+                break;
+            }
+        }
+        return Ok((head, title));
+    }
+
     let file = tokio::fs::File::open(path).await?;
     let reader = tokio::io::BufReader::new(file);
     let mut lines = reader.lines();
-    let mut head: Vec<serde_json::Value> = Vec::new();
-    while head.len() < max_records {
+    let mut scanned = 0;
+    while scanned < TITLE_SCAN_RECORD_LIMIT && title.is_none() {
         let line_opt = lines.next_line().await?;
         let Some(line) = line_opt else { break };
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
         }
+        scanned += 1;
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(trimmed) {
-            head.push(v);
+            title = keep_record(&mut head, v);
         }
     }
-    Ok(head)
+    Ok((head, title))
+}
+
+/// Add `v` to `head` if there is room, and return the title it records.
+fn keep_record(head: &mut Vec<serde_json::Value>, v: serde_json::Value) -> Option<String> {
+    let title = state_record_title(&v);
+    if head.len() < HEAD_RECORD_LIMIT {
+        head.push(v);
+    }
+    title
+}
+
+fn state_record_title(v: &serde_json::Value) -> Option<String> {
+    if v.get("record_type").and_then(|rt| rt.as_str()) != Some("state") {
+        return None;
+    }
+    v.get("title")
+        .and_then(|title| title.as_str())
+        .map(str::to_string)
 }
@@ -5,6 +5,7 @@ pub(crate) const SESSIONS_SUBDIR: &str = "sessions";
 pub mod list;
 pub(crate) mod policy;
 pub mod recorder;
+pub(crate) mod retention;
 
 pub use recorder::RolloutRecorder;
 pub use recorder::SessionStateSnapshot;
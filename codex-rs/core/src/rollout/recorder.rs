@@ -24,10 +24,12 @@ use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::retention::read_rollout;
 use crate::config::Config;
 use crate::conversation_manager::InitialHistory;
 use crate::git_info::GitInfo;
 use crate::git_info::collect_git_info;
+use crate::redaction::Redactor;
 use codex_protocol::models::ResponseItem;
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -46,7 +48,12 @@ struct SessionMetaWithGit {
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
-pub struct SessionStateSnapshot {}
+pub struct SessionStateSnapshot {
+    /// Short name for the session shown by the resume picker, once one has
+    /// been generated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct SavedSession {
@@ -102,6 +109,7 @@ impl RolloutRecorder {
             session_id,
             timestamp,
         } = create_log_file(config, uuid)?;
+        let redactor = Redactor::for_disk(&config.redaction, &config.codex_home)?;
 
         let timestamp_format: &[FormatItem] = format_description!(
             "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
@@ -124,6 +132,7 @@ impl RolloutRecorder {
         // driver instead of blocking the runtime.
         tokio::task::spawn(rollout_writer(
             tokio::fs::File::from_std(file),
+            redactor,
             rx,
             Some(SessionMeta {
                 timestamp,
@@ -162,9 +171,15 @@ impl RolloutRecorder {
             .map_err(|e| IoError::other(format!("failed to queue rollout state: {e}")))
     }
 
+    /// Read the rollout file at `path` as JSONL text, decompressing it if
+    /// retention has compressed it.
+    pub async fn read_transcript(path: &Path) -> std::io::Result<String> {
+        read_rollout(path).await
+    }
+
     pub async fn get_rollout_history(path: &Path) -> std::io::Result<InitialHistory> {
         info!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
+        let text = read_rollout(path).await?;
         let mut lines = text.lines();
         let _ = lines
             .next()
@@ -259,6 +274,9 @@ fn create_log_file(config: &Config, session_id: Uuid) -> std::io::Result<LogFile
         .append(true)
         .create(true)
         .open(&path)?;
+    // Held for as long as the writer owns the file, so that retention never
+    // archives or deletes the rollout of a running session.
+    file.lock_shared()?;
 
     Ok(LogFileInfo {
         file,
@@ -269,11 +287,12 @@ fn create_log_file(config: &Config, session_id: Uuid) -> std::io::Result<LogFile
 
 async fn rollout_writer(
     file: tokio::fs::File,
+    redactor: Redactor,
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter { file, redactor };
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -322,11 +341,15 @@ async fn rollout_writer(
 
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Secrets are scrubbed from every line before it reaches the disk.
+    redactor: Redactor,
 }
 
 impl JsonlWriter {
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
-        let mut json = serde_json::to_string(item)?;
+        let mut value = serde_json::to_value(item)?;
+        self.redactor.redact_json(&mut value);
+        let mut json = serde_json::to_string(&value)?;
         json.push('\n');
         let _ = self.file.write_all(json.as_bytes()).await;
         self.file.flush().await?;
@@ -0,0 +1,379 @@
+//! Retention for rollout files: gzip archiving of old rollouts and deletion by
+//! age, total size and count, as configured under `[sessions]`.
+//!
+//! Archived rollouts keep their name with a `.gz` suffix
+//! (`rollout-...-<uuid>.jsonl.gz`) and their modification time, so they are
+//! still listed, resumed and aged like the plain files.
+//!
+//! The recorder holds a shared lock on the rollout it writes for the whole
+//! session. Pruning only touches rollouts it can lock exclusively, so the
+//! rollout of another running session is left alone however long it has been
+//! idle.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use super::SESSIONS_SUBDIR;
+use super::list::parse_timestamp_uuid_from_filename;
+use crate::config_types::SessionRetention;
+
+/// Suffix of rollouts that have been archived with gzip.
+pub(crate) const COMPRESSED_SUFFIX: &str = ".jsonl.gz";
+
+/// Rollouts written to within this window are never compressed or deleted,
+/// even when they are not locked (e.g. written by an older version).
+const ACTIVE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What pruning the sessions directory did (or would do, on a dry run).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SessionsPruned {
+    pub compressed: usize,
+    pub deleted: usize,
+    /// Bytes freed by deleting and compressing rollouts. A dry run does not
+    /// know how well rollouts compress and only counts deletions.
+    pub freed_bytes: u64,
+}
+
+struct RolloutFile {
+    path: PathBuf,
+    modified: SystemTime,
+    len: u64,
+}
+
+/// Apply `policy` to the rollouts under `codex_home`. With `dry_run`, only
+/// report what would be compressed and deleted.
+pub(crate) async fn prune(
+    codex_home: &Path,
+    policy: &SessionRetention,
+    dry_run: bool,
+) -> io::Result<SessionsPruned> {
+    let root = codex_home.join(SESSIONS_SUBDIR);
+    let policy = policy.clone();
+    let now = SystemTime::now();
+    tokio::task::spawn_blocking(move || prune_blocking(&root, &policy, now, dry_run)).await?
+}
+
+fn prune_blocking(
+    root: &Path,
+    policy: &SessionRetention,
+    now: SystemTime,
+    dry_run: bool,
+) -> io::Result<SessionsPruned> {
+    let mut pruned = SessionsPruned::default();
+    if !root.is_dir() {
+        return Ok(pruned);
+    }
+    let mut files = Vec::new();
+    collect_rollouts(root, &mut files)?;
+    files.sort_by_key(|file| Reverse(file.modified));
+
+    let age_of = |file: &RolloutFile| now.duration_since(file.modified).unwrap_or_default();
+    let older_than = |age: Duration, days: Option<u64>| {
+        days.is_some_and(|days| age >= Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY)))
+    };
+
+    // Archive first so that the size limit sees the compressed sizes.
+    for file in &mut files {
+        let age = age_of(file);
+        if age < ACTIVE_WINDOW
+            || is_compressed(&file.path)
+            || older_than(age, policy.max_age_days)
+            || !older_than(age, policy.compress_after_days)
+        {
+            continue;
+        }
+        let Some(_guard) = lock_unused(&file.path)? else {
+            continue;
+        };
+        pruned.compressed += 1;
+        if dry_run {
+            continue;
+        }
+        let archived = compress(&file.path, file.modified)?;
+        let archived_len = fs::metadata(&archived)?.len();
+        pruned.freed_bytes += file.len.saturating_sub(archived_len);
+        file.path = archived;
+        file.len = archived_len;
+    }
+
+    // Then walk from newest to oldest; once the count or size limit is
+    // reached, every older rollout goes.
+    let mut kept_count = 0usize;
+    let mut kept_bytes = 0u64;
+    let mut full = false;
+    for file in &files {
+        let age = age_of(file);
+        full = full
+            || policy.max_count.is_some_and(|max| kept_count >= max)
+            || policy
+                .max_total_bytes
+                .is_some_and(|max| kept_bytes + file.len > max);
+        if age >= ACTIVE_WINDOW
+            && (full || older_than(age, policy.max_age_days))
+            && let Some(_guard) = lock_unused(&file.path)?
+        {
+            if !dry_run {
+                fs::remove_file(&file.path)?;
+            }
+            pruned.deleted += 1;
+            pruned.freed_bytes += file.len;
+        } else {
+            kept_count += 1;
+            kept_bytes += file.len;
+        }
+    }
+
+    if !dry_run && pruned.deleted > 0 {
+        remove_empty_dirs(root)?;
+    }
+    Ok(pruned)
+}
+
+/// Lock the rollout at `path` exclusively, or return `None` when a running
+/// session holds it. The lock lasts until the returned file is dropped.
+fn lock_unused(path: &Path) -> io::Result<Option<fs::File>> {
+    let file = fs::File::open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(fs::TryLockError::WouldBlock) => Ok(None),
+        Err(fs::TryLockError::Error(e)) => Err(e),
+    }
+}
+
+/// Collect the rollouts anywhere below `dir` (normally `YYYY/MM/DD`).
+fn collect_rollouts(dir: &Path, files: &mut Vec<RolloutFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_rollouts(&entry.path(), files)?;
+        } else if file_type.is_file()
+            && entry
+                .file_name()
+                .to_str()
+                .and_then(parse_timestamp_uuid_from_filename)
+                .is_some()
+        {
+            let metadata = entry.metadata()?;
+            files.push(RolloutFile {
+                path: entry.path(),
+                modified: metadata.modified()?,
+                len: metadata.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Remove the directories below `dir` that pruning left empty.
+fn remove_empty_dirs(dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            let path = entry.path();
+            remove_empty_dirs(&path)?;
+            // Fails when the directory is not empty, which is fine.
+            let _ = fs::remove_dir(&path);
+        }
+    }
+    Ok(())
+}
+
+/// Replace the rollout at `path` with a gzipped copy that keeps its
+/// modification time, and return the new path.
+fn compress(path: &Path, modified: SystemTime) -> io::Result<PathBuf> {
+    let mut archived = path.as_os_str().to_owned();
+    archived.push(".gz");
+    let archived = PathBuf::from(archived);
+
+    // Write next to the original and rename, so that an interrupted run never
+    // leaves a truncated archive behind.
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut encoder = GzEncoder::new(
+        tempfile::NamedTempFile::new_in(dir)?,
+        Compression::default(),
+    );
+    io::copy(&mut fs::File::open(path)?, &mut encoder)?;
+    let temp = encoder.finish()?;
+    temp.as_file().set_modified(modified)?;
+    temp.persist(&archived).map_err(|e| e.error)?;
+    fs::remove_file(path)?;
+    Ok(archived)
+}
+
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(COMPRESSED_SUFFIX))
+}
+
+/// Read a whole rollout, decompressing it if it has been archived.
+pub(crate) async fn read_rollout(path: &Path) -> io::Result<String> {
+    if !is_compressed(path) {
+        return tokio::fs::read_to_string(path).await;
+    }
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut text = String::new();
+        GzDecoder::new(fs::File::open(path)?).read_to_string(&mut text)?;
+        Ok(text)
+    })
+    .await?
+}
+
+/// Read the first `max_lines` non-empty lines of an archived rollout without
+/// decompressing the rest.
+pub(crate) async fn read_compressed_head(path: &Path, max_lines: usize) -> io::Result<Vec<String>> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let reader = BufReader::new(GzDecoder::new(fs::File::open(path)?));
+        let mut head = Vec::new();
+        for line in reader.lines() {
+            if head.len() >= max_lines {
+                break;
+            }
+            let line = line?;
+            if !line.trim().is_empty() {
+                head.push(line);
+            }
+        }
+        Ok(head)
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+    /// Write a rollout in `root` that was last modified `age` before `now`.
+    fn write_rollout(root: &Path, n: u32, len: usize, now: SystemTime, age: Duration) -> PathBuf {
+        let dir = root.join("2025").join("01").join(format!("{n:02}"));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!(
+            "rollout-2025-01-{n:02}T00-00-00-00000000-0000-0000-0000-0000000000{n:02}.jsonl"
+        ));
+        let line = format!("{{\"n\":{n}}}\n");
+        fs::write(&path, line.repeat(len / line.len())).unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(now - age).unwrap();
+        path
+    }
+
+    #[test]
+    fn deletes_by_age_count_and_size_but_spares_active_rollouts() {
+        let home = TempDir::new().unwrap();
+        let root = home.path();
+        let now = SystemTime::now();
+        let active = write_rollout(root, 1, 1000, now, Duration::ZERO);
+        let day_old = write_rollout(root, 2, 1000, now, DAY);
+        let week_old = write_rollout(root, 3, 1000, now, 7 * DAY);
+        let month_old = write_rollout(root, 4, 1000, now, 30 * DAY);
+
+        let by_age = SessionRetention {
+            max_age_days: Some(14),
+            ..SessionRetention::default()
+        };
+        let report = prune_blocking(root, &by_age, now, true).unwrap();
+        assert_eq!(report.deleted, 1);
+        assert!(month_old.exists(), "dry run must not delete");
+
+        let by_count = SessionRetention {
+            max_count: Some(2),
+            ..SessionRetention::default()
+        };
+        let report = prune_blocking(root, &by_count, now, false).unwrap();
+        assert_eq!(report.deleted, 2);
+        assert!(active.exists() && day_old.exists());
+        assert!(!week_old.exists() && !month_old.exists());
+        assert!(
+            !month_old.parent().unwrap().exists(),
+            "empty day directory is removed"
+        );
+
+        let by_size = SessionRetention {
+            max_total_bytes: Some(10),
+            ..SessionRetention::default()
+        };
+        let report = prune_blocking(root, &by_size, now, false).unwrap();
+        assert_eq!(report.deleted, 1);
+        assert!(active.exists(), "active rollout is never deleted");
+        assert!(!day_old.exists());
+    }
+
+    #[test]
+    fn rollouts_of_running_sessions_are_spared() {
+        let home = TempDir::new().unwrap();
+        let root = home.path();
+        let now = SystemTime::now();
+        let idle = write_rollout(root, 6, 1000, now, 30 * DAY);
+        let writer = fs::File::options().append(true).open(&idle).unwrap();
+        writer.lock_shared().unwrap();
+
+        let policy = SessionRetention {
+            compress_after_days: Some(1),
+            max_age_days: Some(7),
+            ..SessionRetention::default()
+        };
+        let report = prune_blocking(root, &policy, now, false).unwrap();
+        assert_eq!(report, SessionsPruned::default());
+        assert!(idle.exists());
+
+        drop(writer);
+        let report = prune_blocking(root, &policy, now, false).unwrap();
+        assert_eq!(report.deleted, 1);
+        assert!(!idle.exists());
+    }
+
+    #[tokio::test]
+    async fn compressed_rollouts_stay_readable() {
+        let home = TempDir::new().unwrap();
+        let root = home.path();
+        let now = SystemTime::now();
+        let old = write_rollout(root, 5, 10_000, now, 3 * DAY);
+        let original = fs::read_to_string(&old).unwrap();
+
+        let policy = SessionRetention {
+            compress_after_days: Some(2),
+            ..SessionRetention::default()
+        };
+        let report = prune_blocking(root, &policy, now, false).unwrap();
+        assert_eq!(report.compressed, 1);
+        assert!(report.freed_bytes > 0);
+        assert!(!old.exists());
+
+        let archived = PathBuf::from(format!("{}.gz", old.display()));
+        assert!(is_compressed(&archived));
+        assert_eq!(
+            fs::metadata(&archived).unwrap().modified().unwrap(),
+            now - 3 * DAY
+        );
+        assert_eq!(read_rollout(&archived).await.unwrap(), original);
+        assert_eq!(
+            read_compressed_head(&archived, 2).await.unwrap(),
+            vec!["{\"n\":5}".to_string(), "{\"n\":5}".to_string()]
+        );
+
+        // Already archived rollouts are left alone.
+        let report = prune_blocking(root, &policy, now, false).unwrap();
+        assert_eq!(report, SessionsPruned::default());
+    }
+}
@@ -120,14 +120,17 @@ async fn test_list_conversations_latest_first() {
             ConversationItem {
                 path: p1,
                 head: head_3,
+                title: None,
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
+                title: None,
             },
             ConversationItem {
                 path: p3,
                 head: head_1,
+                title: None,
             },
         ],
         next_cursor: Some(expected_cursor),
@@ -185,10 +188,12 @@ async fn test_pagination_cursor() {
             ConversationItem {
                 path: p5,
                 head: head_5,
+                title: None,
             },
             ConversationItem {
                 path: p4,
                 head: head_4,
+                title: None,
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -227,10 +232,12 @@ async fn test_pagination_cursor() {
             ConversationItem {
                 path: p3,
                 head: head_3,
+                title: None,
             },
             ConversationItem {
                 path: p2,
                 head: head_2,
+                title: None,
             },
         ],
         next_cursor: Some(expected_cursor2.clone()),
@@ -258,6 +265,7 @@ async fn test_pagination_cursor() {
         items: vec![ConversationItem {
             path: p1,
             head: head_1,
+            title: None,
         }],
         next_cursor: Some(expected_cursor3.clone()),
         num_scanned_files: 5, // scanned 05, 04 (anchor), 03, 02 (anchor), 01
@@ -297,6 +305,7 @@ async fn test_get_conversation_contents() {
         items: vec![ConversationItem {
             path: expected_path.clone(),
             head: expected_head,
+            title: None,
         }],
         next_cursor: Some(expected_cursor),
         num_scanned_files: 1,
@@ -349,10 +358,12 @@ async fn test_stable_ordering_same_second_pagination() {
             ConversationItem {
                 path: p3,
                 head: head(u3),
+                title: None,
             },
             ConversationItem {
                 path: p2,
                 head: head(u2),
+                title: None,
             },
         ],
         next_cursor: Some(expected_cursor1.clone()),
@@ -375,6 +386,7 @@ async fn test_stable_ordering_same_second_pagination() {
         items: vec![ConversationItem {
             path: p1,
             head: head(u1),
+            title: None,
         }],
         next_cursor: Some(expected_cursor2.clone()),
         num_scanned_files: 3, // scanned u3, u2 (anchor), u1
@@ -382,3 +394,31 @@ async fn test_stable_ordering_same_second_pagination() {
     };
     assert_eq!(page2, expected_page2);
 }
+
+#[tokio::test]
+async fn test_title_is_read_from_state_records_past_the_head() {
+    let temp = TempDir::new().unwrap();
+    let home = temp.path();
+
+    let uuid = Uuid::from_u128(7);
+    let ts = "2025-05-01T08-00-00";
+    write_session_file(home, ts, uuid, 20).unwrap();
+    let path = home
+        .join("sessions")
+        .join("2025")
+        .join("05")
+        .join("01")
+        .join(format!("rollout-{ts}-{uuid}.jsonl"));
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    writeln!(file, "{}", serde_json::json!({"record_type": "state"})).unwrap();
+    writeln!(
+        file,
+        "{}",
+        serde_json::json!({"record_type": "state", "title": "Fix flaky login test"})
+    )
+    .unwrap();
+
+    let page = get_conversations(home, 1, None).await.unwrap();
+    assert_eq!(page.items[0].head.len(), 10);
+    assert_eq!(page.items[0].title.as_deref(), Some("Fix flaky login test"));
+}
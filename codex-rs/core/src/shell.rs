@@ -3,12 +3,34 @@ use serde::Serialize;
 use shlex;
 use std::path::PathBuf;
 
+/// Script that starts the interactive shell reading the user's `.bashrc`; see
+/// [`Shell::format_default_shell_invocation`].
+const BASH_RC_SCRIPT: &str = r#"exec "$0" --rcfile "$1" -ic "$2" 3>&2 2>/dev/null"#;
+
+/// Whether `args` run the interactive shell built for a [`BashShell`],
+/// possibly behind a sandbox wrapper. Without a session of its own, such a
+/// shell would take the user's terminal away from Codex.
+pub(crate) fn is_interactive_shell_invocation(args: &[String]) -> bool {
+    args.iter().any(|arg| arg == BASH_RC_SCRIPT)
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ZshShell {
     shell_path: String,
     zshrc_path: String,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct BashShell {
+    shell_path: String,
+    bashrc_path: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct FishShell {
+    shell_path: String,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct PowerShellConfig {
     exe: String, // Executable name or path, e.g. "pwsh" or "powershell.exe".
@@ -18,6 +40,8 @@ pub struct PowerShellConfig {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Shell {
     Zsh(ZshShell),
+    Bash(BashShell),
+    Fish(FishShell),
     PowerShell(PowerShellConfig),
     Unknown,
 }
@@ -43,6 +67,41 @@ impl Shell {
                 }
                 Some(result)
             }
+            Shell::Bash(bash) => {
+                if !std::path::Path::new(&bash.bashrc_path).exists() {
+                    return None;
+                }
+
+                let joined = strip_bash_lc(&command)
+                    .or_else(|| shlex::try_join(command.iter().map(|s| s.as_str())).ok())?;
+                // Most `.bashrc` files return right away unless the shell is
+                // interactive, so run an interactive (not login) shell that
+                // reads just that file. Without a terminal, such a shell
+                // complains on stderr that it cannot do job control, so
+                // stderr is muted until the command itself starts. Unsetting
+                // HISTFILE keeps it from rewriting the user's history.
+                Some(vec![
+                    bash.shell_path.clone(),
+                    "-c".to_string(),
+                    BASH_RC_SCRIPT.to_string(),
+                    bash.shell_path.clone(),
+                    bash.bashrc_path.clone(),
+                    format!("exec 2>&3 3>&-; unset HISTFILE; {joined}"),
+                ])
+            }
+            Shell::Fish(fish) => {
+                // Commands are written for POSIX shells, which fish cannot
+                // run. Start fish so it loads the user's config, then exec the
+                // command unchanged; it inherits the environment fish set up.
+                // Passing the command as arguments avoids quoting it for fish.
+                let mut result = vec![
+                    fish.shell_path.clone(),
+                    "-lc".to_string(),
+                    "exec $argv".to_string(),
+                ];
+                result.extend(command);
+                Some(result)
+            }
             Shell::PowerShell(ps) => {
                 // If model generated a bash command, prefer a detected bash fallback
                 if let Some(script) = strip_bash_lc(&command) {
@@ -97,6 +156,11 @@ impl Shell {
             Shell::Zsh(zsh) => std::path::Path::new(&zsh.shell_path)
                 .file_name()
                 .map(|s| s.to_string_lossy().to_string()),
+            Shell::Bash(BashShell { shell_path, .. }) | Shell::Fish(FishShell { shell_path }) => {
+                std::path::Path::new(shell_path)
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+            }
             Shell::PowerShell(ps) => Some(ps.exe.clone()),
             Shell::Unknown => None,
         }
@@ -116,6 +180,35 @@ fn strip_bash_lc(command: &Vec<String>) -> Option<String> {
     }
 }
 
+/// The shell at `shell_path`, with its rc files looked up under `home`.
+#[cfg(unix)]
+fn shell_from_path(shell_path: &str, home: &str) -> Shell {
+    let name = std::path::Path::new(shell_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match name.as_str() {
+        "zsh" => {
+            let zdotdir = std::env::var("ZDOTDIR")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .unwrap_or_else(|| home.to_string());
+            Shell::Zsh(ZshShell {
+                shell_path: shell_path.to_string(),
+                zshrc_path: format!("{zdotdir}/.zshrc"),
+            })
+        }
+        "bash" => Shell::Bash(BashShell {
+            shell_path: shell_path.to_string(),
+            bashrc_path: format!("{home}/.bashrc"),
+        }),
+        "fish" => Shell::Fish(FishShell {
+            shell_path: shell_path.to_string(),
+        }),
+        _ => Shell::Unknown,
+    }
+}
+
 #[cfg(target_os = "macos")]
 pub async fn default_user_shell() -> Shell {
     use tokio::process::Command;
@@ -135,13 +228,8 @@ pub async fn default_user_shell() -> Shell {
             }
             let stdout = String::from_utf8_lossy(&o.stdout);
             for line in stdout.lines() {
-                if let Some(shell_path) = line.strip_prefix("UserShell: ")
-                    && shell_path.ends_with("/zsh")
-                {
-                    return Shell::Zsh(ZshShell {
-                        shell_path: shell_path.to_string(),
-                        zshrc_path: format!("{home}/.zshrc"),
-                    });
+                if let Some(shell_path) = line.strip_prefix("UserShell: ") {
+                    return shell_from_path(shell_path, &home);
                 }
             }
 
@@ -151,9 +239,44 @@ pub async fn default_user_shell() -> Shell {
     }
 }
 
+/// The home directory and login shell of `user` in the contents of an
+/// `/etc/passwd` file.
+#[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
+fn passwd_entry(passwd: &str, user: &str) -> Option<(String, String)> {
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        match fields.as_slice() {
+            [name, _, _, _, _, home, shell] if *name == user => {
+                Some((home.to_string(), shell.to_string()))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Detect the user's shell from `$SHELL`, falling back to their entry in
+/// `/etc/passwd` when it is not set.
 #[cfg(all(not(target_os = "macos"), not(target_os = "windows")))]
 pub async fn default_user_shell() -> Shell {
-    Shell::Unknown
+    let user = whoami::username();
+    let passwd = tokio::fs::read_to_string("/etc/passwd")
+        .await
+        .ok()
+        .and_then(|passwd| passwd_entry(&passwd, &user));
+
+    let shell_path = std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .or_else(|| passwd.as_ref().map(|(_, shell)| shell.clone()));
+    let home = std::env::var("HOME")
+        .ok()
+        .filter(|home| !home.is_empty())
+        .or_else(|| passwd.map(|(home, _)| home));
+
+    match (shell_path, home) {
+        (Some(shell_path), Some(home)) => shell_from_path(&shell_path, &home),
+        _ => Shell::Unknown,
+    }
 }
 
 #[cfg(target_os = "windows")]
@@ -337,6 +460,213 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests_linux {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn passwd_entry_finds_home_and_shell() {
+        let passwd = "root:x:0:0:root:/root:/bin/bash\n\
+                      # comment\n\
+                      dev:x:1000:1000:Dev,,,:/home/dev:/usr/bin/fish\n";
+        assert_eq!(
+            passwd_entry(passwd, "dev"),
+            Some(("/home/dev".to_string(), "/usr/bin/fish".to_string()))
+        );
+        assert_eq!(passwd_entry(passwd, "nobody"), None);
+    }
+
+    #[test]
+    fn shells_are_detected_from_their_path() {
+        assert_eq!(
+            shell_from_path("/bin/bash", "/home/dev"),
+            Shell::Bash(BashShell {
+                shell_path: "/bin/bash".to_string(),
+                bashrc_path: "/home/dev/.bashrc".to_string(),
+            })
+        );
+        assert_eq!(
+            shell_from_path("/usr/bin/fish", "/home/dev").name(),
+            Some("fish".to_string())
+        );
+        assert_eq!(
+            shell_from_path("/bin/zsh", "/home/dev").name(),
+            Some("zsh".to_string())
+        );
+        assert_eq!(shell_from_path("/bin/tcsh", "/home/dev"), Shell::Unknown);
+    }
+
+    #[test]
+    fn fish_execs_the_command_unchanged() {
+        let shell = Shell::Fish(FishShell {
+            shell_path: "/usr/bin/fish".to_string(),
+        });
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "ls | wc -l".to_string(),
+        ];
+        assert_eq!(
+            shell.format_default_shell_invocation(command),
+            Some(vec![
+                "/usr/bin/fish".to_string(),
+                "-lc".to_string(),
+                "exec $argv".to_string(),
+                "bash".to_string(),
+                "-lc".to_string(),
+                "ls | wc -l".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn only_the_bashrc_shell_is_interactive() {
+        let temp_home = tempfile::tempdir().unwrap();
+        let bashrc_path = temp_home.path().join(".bashrc");
+        std::fs::write(&bashrc_path, "").unwrap();
+        let shell = Shell::Bash(BashShell {
+            shell_path: "/bin/bash".to_string(),
+            bashrc_path: bashrc_path.to_str().unwrap().to_string(),
+        });
+        let command = vec!["bash".to_string(), "-lc".to_string(), "ls".to_string()];
+
+        let mut sandboxed = vec!["codex-linux-sandbox".to_string(), "--".to_string()];
+        sandboxed.extend(
+            shell
+                .format_default_shell_invocation(command.clone())
+                .unwrap(),
+        );
+        assert!(is_interactive_shell_invocation(&sandboxed));
+        assert!(!is_interactive_shell_invocation(&command));
+    }
+
+    #[tokio::test]
+    async fn only_the_bashrc_shell_leaves_codexs_session() {
+        use std::collections::HashMap;
+
+        use crate::exec::ExecParams;
+        use crate::exec::SandboxType;
+        use crate::exec::process_exec_tool_call;
+        use crate::protocol::SandboxPolicy;
+
+        let temp_home = tempfile::tempdir().unwrap();
+        let bashrc_path = temp_home.path().join(".bashrc");
+        std::fs::write(&bashrc_path, "").unwrap();
+        let shell = Shell::Bash(BashShell {
+            shell_path: "/bin/bash".to_string(),
+            bashrc_path: bashrc_path.to_str().unwrap().to_string(),
+        });
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "ps -o sid= -p $$".to_string(),
+        ];
+        let session_of = |command: Vec<String>| {
+            let cwd = temp_home.path().to_path_buf();
+            async move {
+                let output = process_exec_tool_call(
+                    ExecParams {
+                        command,
+                        cwd,
+                        timeout_ms: None,
+                        env: HashMap::new(),
+                        with_escalated_permissions: None,
+                        justification: None,
+                    },
+                    SandboxType::None,
+                    &SandboxPolicy::DangerFullAccess,
+                    &None,
+                    None,
+                )
+                .await
+                .unwrap();
+                output.stdout.text.trim().parse::<i32>().unwrap()
+            }
+        };
+
+        let codex_session = unsafe { libc::getsid(0) };
+        assert_eq!(session_of(command.clone()).await, codex_session);
+        let interactive = shell.format_default_shell_invocation(command).unwrap();
+        assert_ne!(session_of(interactive).await, codex_session);
+    }
+
+    #[test]
+    fn bash_without_bashrc_is_not_translated() {
+        let shell = Shell::Bash(BashShell {
+            shell_path: "/bin/bash".to_string(),
+            bashrc_path: "/does/not/exist/.bashrc".to_string(),
+        });
+        assert_eq!(
+            shell.format_default_shell_invocation(vec!["myecho".to_string()]),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn bash_loads_bashrc_once_despite_its_interactive_guard() {
+        use std::collections::HashMap;
+
+        use crate::exec::ExecParams;
+        use crate::exec::SandboxType;
+        use crate::exec::process_exec_tool_call;
+        use crate::protocol::SandboxPolicy;
+
+        let temp_home = tempfile::tempdir().unwrap();
+        let bashrc_path = temp_home.path().join(".bashrc");
+        // The guard Debian and Ubuntu ship in every `.bashrc`.
+        std::fs::write(
+            &bashrc_path,
+            "case $- in\n    *i*) ;;\n      *) return;;\nesac\n\
+             echo loaded >> \"$HOME/loads\"\n\
+             myecho() {\n    echo 'It works!'\n}\n",
+        )
+        .unwrap();
+        // A login shell would source `.bashrc` a second time from here.
+        std::fs::write(temp_home.path().join(".profile"), ". \"$HOME/.bashrc\"\n").unwrap();
+        let shell = Shell::Bash(BashShell {
+            shell_path: "/bin/bash".to_string(),
+            bashrc_path: bashrc_path.to_str().unwrap().to_string(),
+        });
+
+        let command = shell
+            .format_default_shell_invocation(vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "myecho && echo oops >&2".to_string(),
+            ])
+            .unwrap();
+
+        let output = process_exec_tool_call(
+            ExecParams {
+                command,
+                cwd: temp_home.path().to_path_buf(),
+                timeout_ms: None,
+                env: HashMap::from([(
+                    "HOME".to_string(),
+                    temp_home.path().to_str().unwrap().to_string(),
+                )]),
+                with_escalated_permissions: None,
+                justification: None,
+            },
+            SandboxType::None,
+            &SandboxPolicy::DangerFullAccess,
+            &None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output.exit_code, 0, "{output:?}");
+        assert_eq!(output.stdout.text, "It works!\n");
+        assert_eq!(output.stderr.text, "oops\n");
+        assert_eq!(
+            std::fs::read_to_string(temp_home.path().join("loads")).unwrap(),
+            "loaded\n"
+        );
+    }
+}
+
 #[cfg(test)]
 #[cfg(target_os = "windows")]
 mod tests_windows {
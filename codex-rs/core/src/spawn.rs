@@ -48,6 +48,10 @@ pub(crate) async fn spawn_child_async(
         "spawn_child_async: {program:?} {args:?} {arg0:?} {cwd:?} {sandbox_policy:?} {stdio_policy:?} {env:?}"
     );
 
+    #[cfg(unix)]
+    let own_session = matches!(stdio_policy, StdioPolicy::RedirectForShellTool)
+        && crate::shell::is_interactive_shell_invocation(&args);
+
     let mut cmd = Command::new(&program);
     #[cfg(unix)]
     cmd.arg0(arg0.map_or_else(|| program.to_string_lossy().to_string(), String::from));
@@ -85,6 +89,22 @@ pub(crate) async fn spawn_child_async(
         });
     }
 
+    // An interactive shell takes over the terminal of its session, so the
+    // shell that loads the user's `.bashrc` runs in a session of its own.
+    // Other commands stay in Codex's session, where prompts that open
+    // `/dev/tty` (ssh, sudo, gpg) still reach the user.
+    #[cfg(unix)]
+    if own_session {
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
     match stdio_policy {
         StdioPolicy::RedirectForShellTool => {
             // Do not create a file descriptor for stdin because otherwise some
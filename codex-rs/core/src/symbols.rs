@@ -0,0 +1,710 @@
+//! Implementation of the `symbols` tool.
+//!
+//! Parses source files with tree-sitter and either prints an outline of the
+//! definitions they contain (functions, types, impls, classes, ...) with
+//! their line ranges, or looks up the definitions with a given name and
+//! returns their source. This lets the model navigate a large codebase
+//! without reading whole files into the context.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+/// Files larger than this are not parsed.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Maximum number of files visited when `path` is a directory.
+const MAX_FILES: usize = 2_000;
+
+/// Maximum number of lines in an outline.
+const MAX_OUTLINE_LINES: usize = 400;
+
+/// Maximum number of definitions returned by a lookup.
+const MAX_DEFINITIONS: usize = 20;
+
+/// Maximum number of source lines shown per definition.
+const MAX_DEFINITION_LINES: usize = 200;
+
+/// Signatures longer than this (in characters) are truncated.
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SymbolsToolArgs {
+    /// File or directory to inspect, relative to the working directory.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// When set, find the definitions with this name instead of printing an
+    /// outline.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceLanguage {
+    Rust,
+    Python,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl SourceLanguage {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+}
+
+/// A definition found in a source file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Symbol {
+    pub kind: &'static str,
+    pub name: String,
+    /// Name of the enclosing impl/class/trait, or the receiver type of a Go
+    /// method, used to match qualified lookups such as `Foo::new`.
+    pub qualifier: Option<String>,
+    /// The definition's header (everything before its body), on one line.
+    pub signature: String,
+    /// 1-based, inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub children: Vec<Symbol>,
+}
+
+/// Runs the tool for `path` (a file or a directory) and returns the text
+/// handed back to the model.
+pub(crate) fn run_symbols_tool(
+    path: &Path,
+    name: Option<&str>,
+    cwd: &Path,
+) -> Result<String, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("cannot access {}: {e}", path.display()))?;
+    let files = if metadata.is_dir() {
+        supported_files_in(path)
+    } else if SourceLanguage::from_path(path).is_some() {
+        vec![path.to_path_buf()]
+    } else {
+        return Err(format!(
+            "unsupported file type: {} (supported: Rust, Python, TypeScript, Go)",
+            path.display()
+        ));
+    };
+
+    match name {
+        Some(name) => Ok(find_definitions(&files, name, cwd)),
+        None => Ok(outline(&files, cwd)),
+    }
+}
+
+/// Parses `source` and returns its top-level definitions (with nested
+/// definitions as children).
+pub(crate) fn parse_symbols(source: &str, path: &Path) -> Option<Vec<Symbol>> {
+    let language = SourceLanguage::from_path(path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language.grammar()).ok()?;
+    let tree = parser.parse(source, None)?;
+    let mut symbols = Vec::new();
+    collect_symbols(tree.root_node(), source, language, None, &mut symbols);
+    Some(symbols)
+}
+
+fn supported_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = ignore::WalkBuilder::new(dir)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .map(ignore::DirEntry::into_path)
+        .filter(|path| SourceLanguage::from_path(path).is_some())
+        .take(MAX_FILES)
+        .collect();
+    files.sort();
+    files
+}
+
+fn read_source(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if metadata.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+fn display_path(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+fn outline(files: &[PathBuf], cwd: &Path) -> String {
+    let mut lines = Vec::new();
+    let mut truncated = false;
+    for path in files {
+        let Some(symbols) = read_source(path).and_then(|source| parse_symbols(&source, path))
+        else {
+            continue;
+        };
+        if symbols.is_empty() {
+            continue;
+        }
+        lines.push(display_path(path, cwd));
+        push_outline_lines(&symbols, 1, &mut lines);
+        if lines.len() > MAX_OUTLINE_LINES {
+            truncated = true;
+            break;
+        }
+    }
+
+    if lines.is_empty() {
+        return "no symbols found".to_string();
+    }
+    lines.truncate(MAX_OUTLINE_LINES);
+    let mut out = lines.join("\n");
+    if truncated {
+        out.push_str("\n... outline truncated; pass a narrower `path` to see more");
+    }
+    out
+}
+
+fn push_outline_lines(symbols: &[Symbol], depth: usize, lines: &mut Vec<String>) {
+    for symbol in symbols {
+        lines.push(format!(
+            "{}{}-{}: {}",
+            "  ".repeat(depth),
+            symbol.start_line,
+            symbol.end_line,
+            symbol.signature
+        ));
+        push_outline_lines(&symbol.children, depth + 1, lines);
+    }
+}
+
+fn find_definitions(files: &[PathBuf], name: &str, cwd: &Path) -> String {
+    let mut out = String::new();
+    let mut found = 0;
+    for path in files {
+        let Some(source) = read_source(path) else {
+            continue;
+        };
+        let Some(symbols) = parse_symbols(&source, path) else {
+            continue;
+        };
+        let mut matches = Vec::new();
+        collect_matches(&symbols, name, &mut matches);
+        for symbol in matches {
+            if found == MAX_DEFINITIONS {
+                out.push_str("... more definitions not shown; pass a narrower `path`\n");
+                return out;
+            }
+            found += 1;
+            let _ = writeln!(
+                out,
+                "{}:{}-{}: {} {}",
+                display_path(path, cwd),
+                symbol.start_line,
+                symbol.end_line,
+                symbol.kind,
+                symbol.name
+            );
+            let last_line = symbol
+                .end_line
+                .min(symbol.start_line + MAX_DEFINITION_LINES - 1);
+            for (number, line) in source
+                .lines()
+                .enumerate()
+                .map(|(index, line)| (index + 1, line))
+                .skip(symbol.start_line - 1)
+                .take(last_line + 1 - symbol.start_line)
+            {
+                let _ = writeln!(out, "{number:>6}  {line}");
+            }
+            if last_line < symbol.end_line {
+                let _ = writeln!(out, "   ...  ({} more lines)", symbol.end_line - last_line);
+            }
+            out.push('\n');
+        }
+    }
+
+    if found == 0 {
+        format!("no definition named `{name}` found")
+    } else {
+        out.trim_end().to_string()
+    }
+}
+
+/// Matches `name` either as a bare name or qualified by the enclosing type,
+/// e.g. `Foo::new` or `Foo.new`.
+fn collect_matches<'a>(symbols: &'a [Symbol], name: &str, out: &mut Vec<&'a Symbol>) {
+    for symbol in symbols {
+        let qualified_match = symbol.qualifier.as_deref().is_some_and(|qualifier| {
+            ["::", "."].iter().any(|sep| {
+                name.strip_prefix(qualifier)
+                    .and_then(|rest| rest.strip_prefix(sep))
+                    == Some(symbol.name.as_str())
+            })
+        });
+        if symbol.name == name || qualified_match {
+            out.push(symbol);
+        }
+        collect_matches(&symbol.children, name, out);
+    }
+}
+
+/// What a syntax node defines, if anything.
+struct Definition<'tree> {
+    kind: &'static str,
+    name: String,
+    /// Node whose line range is reported (includes decorators etc.).
+    range: Node<'tree>,
+    /// Node the signature starts at.
+    header: Node<'tree>,
+    /// Byte offset where the signature ends (usually the start of the body).
+    header_end: Option<usize>,
+    /// Node whose children are nested definitions (impl, class, ...).
+    members: Option<Node<'tree>>,
+    qualifier: Option<String>,
+}
+
+fn collect_symbols(
+    node: Node,
+    source: &str,
+    language: SourceLanguage,
+    qualifier: Option<&str>,
+    out: &mut Vec<Symbol>,
+) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if let Some(definition) = classify(child, source, language) {
+            let mut children = Vec::new();
+            if let Some(members) = definition.members {
+                collect_symbols(
+                    members,
+                    source,
+                    language,
+                    Some(bare_type_name(&definition.name)),
+                    &mut children,
+                );
+            }
+            out.push(Symbol {
+                kind: definition.kind,
+                signature: signature(&definition, source),
+                qualifier: definition
+                    .qualifier
+                    .or_else(|| qualifier.map(str::to_string)),
+                name: definition.name,
+                start_line: definition.range.start_position().row + 1,
+                end_line: definition.range.end_position().row + 1,
+                children,
+            });
+        } else if is_transparent(child.kind(), language) {
+            collect_symbols(child, source, language, qualifier, out);
+        }
+    }
+}
+
+/// Strips generic arguments and the path from a type so that methods of
+/// `impl<T> crate::foo::Foo<T>` can be looked up as `Foo::method`.
+fn bare_type_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name).trim_end();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Nodes that are not definitions themselves but may wrap some.
+fn is_transparent(kind: &str, language: SourceLanguage) -> bool {
+    match language {
+        SourceLanguage::Rust | SourceLanguage::Python => false,
+        SourceLanguage::TypeScript | SourceLanguage::Tsx => matches!(
+            kind,
+            "export_statement"
+                | "lexical_declaration"
+                | "variable_declaration"
+                | "ambient_declaration"
+                | "expression_statement"
+        ),
+        SourceLanguage::Go => kind == "type_declaration",
+    }
+}
+
+fn classify<'tree>(
+    node: Node<'tree>,
+    source: &str,
+    language: SourceLanguage,
+) -> Option<Definition<'tree>> {
+    let text = |node: Node| {
+        source
+            .get(node.byte_range())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let field_text = |name: &str| node.child_by_field_name(name).map(text);
+    let body = node.child_by_field_name("body");
+    let simple = |kind: &'static str, members: Option<Node<'tree>>| {
+        Some(Definition {
+            kind,
+            name: field_text("name")?,
+            range: node,
+            header: node,
+            header_end: body.map(|body| body.start_byte()),
+            members,
+            qualifier: None,
+        })
+    };
+
+    match language {
+        SourceLanguage::Rust => match node.kind() {
+            "function_item" | "function_signature_item" => simple("fn", None),
+            "struct_item" => simple("struct", None),
+            "enum_item" => simple("enum", None),
+            "union_item" => simple("union", None),
+            "type_item" => simple("type", None),
+            "const_item" => simple("const", None),
+            "static_item" => simple("static", None),
+            "macro_definition" => simple("macro", None),
+            "trait_item" => simple("trait", body),
+            "mod_item" => simple("mod", body),
+            "impl_item" => Some(Definition {
+                kind: "impl",
+                name: field_text("type")?,
+                range: node,
+                header: node,
+                header_end: body.map(|body| body.start_byte()),
+                members: body,
+                qualifier: None,
+            }),
+            _ => None,
+        },
+        SourceLanguage::Python => match node.kind() {
+            "function_definition" => simple("def", None),
+            "class_definition" => simple("class", body),
+            "decorated_definition" => {
+                let inner = classify(node.child_by_field_name("definition")?, source, language)?;
+                Some(Definition {
+                    range: node,
+                    ..inner
+                })
+            }
+            _ => None,
+        },
+        SourceLanguage::TypeScript | SourceLanguage::Tsx => match node.kind() {
+            "function_declaration" | "generator_function_declaration" | "function_signature" => {
+                simple("function", None)
+            }
+            "class_declaration" | "abstract_class_declaration" => simple("class", body),
+            "method_definition" | "method_signature" | "abstract_method_signature" => {
+                simple("method", None)
+            }
+            "interface_declaration" => simple("interface", None),
+            "type_alias_declaration" => simple("type", None),
+            "enum_declaration" => simple("enum", None),
+            "internal_module" | "module" => simple("namespace", body),
+            "variable_declarator" => {
+                let value = node.child_by_field_name("value")?;
+                if !matches!(
+                    value.kind(),
+                    "arrow_function" | "function_expression" | "function"
+                ) {
+                    return None;
+                }
+                // Include `const`/`let` in the signature.
+                let header = node
+                    .parent()
+                    .filter(|parent| parent.kind() == "lexical_declaration")
+                    .unwrap_or(node);
+                Some(Definition {
+                    kind: "function",
+                    name: field_text("name")?,
+                    range: header,
+                    header,
+                    header_end: value
+                        .child_by_field_name("body")
+                        .map(|body| body.start_byte()),
+                    members: None,
+                    qualifier: None,
+                })
+            }
+            _ => None,
+        },
+        SourceLanguage::Go => match node.kind() {
+            "function_declaration" => simple("func", None),
+            "method_declaration" => {
+                let receiver_type = node
+                    .child_by_field_name("receiver")
+                    .and_then(|receiver| find_descendant(receiver, "type_identifier"))
+                    .map(text);
+                Some(Definition {
+                    qualifier: receiver_type,
+                    ..simple("method", None)?
+                })
+            }
+            "type_spec" | "type_alias" => {
+                let kind = match node.child_by_field_name("type").map(|ty| ty.kind()) {
+                    Some("struct_type") => "struct",
+                    Some("interface_type") => "interface",
+                    _ => "type",
+                };
+                // Start the signature at the `type` keyword.
+                let header = node
+                    .parent()
+                    .filter(|parent| parent.kind() == "type_declaration")
+                    .filter(|parent| parent.named_child_count() == 1)
+                    .unwrap_or(node);
+                Some(Definition {
+                    header,
+                    ..simple(kind, None)?
+                })
+            }
+            _ => None,
+        },
+    }
+}
+
+fn find_descendant<'tree>(node: Node<'tree>, kind: &str) -> Option<Node<'tree>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find_map(|child| find_descendant(child, kind))
+}
+
+/// The text of the definition up to its body (or its first line), with
+/// whitespace collapsed.
+fn signature(definition: &Definition, source: &str) -> String {
+    let start = definition.header.start_byte();
+    let end = definition
+        .header_end
+        .filter(|end| *end > start)
+        .unwrap_or_else(|| {
+            let rest = &source[start..definition.range.end_byte().max(start)];
+            start + rest.find(['\n', '{']).unwrap_or(rest.len())
+        });
+    let text = source.get(start..end).unwrap_or_default();
+    let mut signature = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if signature.chars().count() > MAX_SIGNATURE_CHARS {
+        signature = signature.chars().take(MAX_SIGNATURE_CHARS).collect();
+        signature.push_str("...");
+    }
+    signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn outline_of(file_name: &str, source: &str) -> Vec<String> {
+        let symbols = parse_symbols(source, Path::new(file_name)).unwrap();
+        let mut lines = Vec::new();
+        push_outline_lines(&symbols, 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn outlines_rust() {
+        let source = r#"
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.x)
+    }
+}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+mod inner {
+    pub(crate) fn helper(
+        a: u32,
+        b: u32,
+    ) -> u32 {
+        a + b
+    }
+}
+"#;
+        assert_eq!(
+            outline_of("lib.rs", source),
+            vec![
+                "3-5: pub struct Point",
+                "7-11: impl Display for Point",
+                "  8-10: fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result",
+                "13-15: pub trait Shape",
+                "  14-14: fn area(&self) -> f64;",
+                "17-24: mod inner",
+                "  18-23: pub(crate) fn helper( a: u32, b: u32, ) -> u32",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_python() {
+        let source = r#"
+import os
+
+@dataclass
+class Config:
+    name: str
+
+    def load(self, path):
+        return path
+
+def main():
+    def nested():
+        pass
+"#;
+        assert_eq!(
+            outline_of("app.py", source),
+            vec![
+                "4-9: class Config:",
+                "  8-9: def load(self, path):",
+                "11-13: def main():",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_typescript() {
+        let source = r#"
+export interface Options {
+  verbose: boolean;
+}
+
+export class Runner {
+  run(opts: Options): void {}
+}
+
+export const start = async (opts: Options) => {
+  return new Runner();
+};
+
+const LIMIT = 10;
+
+namespace Util {
+  export function clamp(n: number): number {
+    return n;
+  }
+}
+"#;
+        assert_eq!(
+            outline_of("index.ts", source),
+            vec![
+                "2-4: interface Options",
+                "6-8: class Runner",
+                "  7-7: run(opts: Options): void",
+                "10-12: const start = async (opts: Options) =>",
+                "16-20: namespace Util",
+                "  17-19: function clamp(n: number): number",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_go() {
+        let source = r#"
+package main
+
+type Server struct {
+	addr string
+}
+
+type Handler interface {
+	Serve()
+}
+
+func (s *Server) Start() error {
+	return nil
+}
+
+func main() {
+}
+"#;
+        assert_eq!(
+            outline_of("main.go", source),
+            vec![
+                "4-6: type Server struct",
+                "8-10: type Handler interface",
+                "12-14: func (s *Server) Start() error",
+                "16-17: func main()",
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_definitions_by_plain_and_qualified_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(
+            src.join("lib.rs"),
+            "struct Foo;\n\nimpl Foo {\n    fn new() -> Self {\n        Foo\n    }\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            src.join("server.go"),
+            "package main\n\nfunc (s *Server) new() {\n}\n",
+        )
+        .unwrap();
+
+        let output = run_symbols_tool(dir.path(), Some("Foo::new"), dir.path()).unwrap();
+        assert_eq!(
+            output,
+            "src/lib.rs:4-6: fn new\n     4      fn new() -> Self {\n     5          Foo\n     6      }"
+        );
+
+        let output = run_symbols_tool(dir.path(), Some("new"), dir.path()).unwrap();
+        assert!(output.contains("src/lib.rs:4-6: fn new"), "{output}");
+        assert!(output.contains("src/server.go:3-4: method new"), "{output}");
+
+        let output = run_symbols_tool(dir.path(), Some("missing"), dir.path()).unwrap();
+        assert_eq!(output, "no definition named `missing` found");
+    }
+
+    #[test]
+    fn qualified_lookup_ignores_generics_of_the_impl_type() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("lib.rs"),
+            "struct Foo<T>(T);\n\nimpl<T: Clone> crate::Foo<T> {\n    fn get(&self) -> T {\n        self.0.clone()\n    }\n}\n",
+        )
+        .unwrap();
+
+        let output = run_symbols_tool(dir.path(), Some("Foo::get"), dir.path()).unwrap();
+        assert!(output.starts_with("lib.rs:4-6: fn get"), "{output}");
+    }
+
+    #[test]
+    fn outlines_directories_and_rejects_unsupported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("b.py"), "def b():\n    pass\n").unwrap();
+        std::fs::write(dir.path().join("a.rs"), "fn a() {}\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "fn not_code() {}\n").unwrap();
+
+        let output = run_symbols_tool(dir.path(), None, dir.path()).unwrap();
+        assert_eq!(output, "a.rs\n  1-1: fn a()\nb.py\n  1-2: def b():");
+
+        let err = run_symbols_tool(&dir.path().join("notes.txt"), None, dir.path()).unwrap_err();
+        assert!(err.starts_with("unsupported file type"), "{err}");
+    }
+}
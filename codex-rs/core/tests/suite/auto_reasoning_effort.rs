@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::config_types::AutoReasoningEffort;
+use codex_core::config_types::ModelFallbackEntry;
+use codex_core::model_family::find_family_for_model;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_protocol::config_types::ReasoningEffort;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::wait_for_event_with_timeout;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn short_prompt_is_sent_with_low_effort() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("\"effort\":\"low\""))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(
+                    load_sse_fixture_with_id("tests/fixtures/completed_template.json", "resp1"),
+                    "text/event-stream",
+                ),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    config.model_reasoning_effort = ReasoningEffort::High;
+    config.auto_reasoning_effort = Some(AutoReasoningEffort {
+        min: ReasoningEffort::Minimal,
+        max: ReasoningEffort::High,
+    });
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "what does ls -a do?".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let chosen = wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::ReasoningEffortChosen(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+    let EventMsg::ReasoningEffortChosen(chosen) = chosen else {
+        unreachable!();
+    };
+    assert_eq!(
+        (chosen.effort, chosen.reason.as_str()),
+        (ReasoningEffort::Low, "short prompt")
+    );
+
+    wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::TaskComplete(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn effort_is_chosen_for_the_model_fallen_back_to() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let primary = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    let backup = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("\"model\":\"gpt-5\""))
+        .and(body_string_contains("\"effort\":\"low\""))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(
+                    load_sse_fixture_with_id("tests/fixtures/completed_template.json", "resp1"),
+                    "text/event-stream",
+                ),
+        )
+        .expect(1)
+        .mount(&backup)
+        .await;
+
+    let provider = |server: &MockServer| ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    // The primary model does not reason, so no effort is chosen for it.
+    config.model = "gpt-4.1".to_string();
+    config.model_family = find_family_for_model("gpt-4.1").unwrap();
+    config.model_provider = provider(&primary);
+    config
+        .model_providers
+        .insert("backup".to_string(), provider(&backup));
+    config.model_fallback.chain = vec![ModelFallbackEntry {
+        model_provider: Some("backup".to_string()),
+        model: Some("gpt-5".to_string()),
+    }];
+    config.auto_reasoning_effort = Some(AutoReasoningEffort {
+        min: ReasoningEffort::Minimal,
+        max: ReasoningEffort::High,
+    });
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "what does ls -a do?".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let chosen = wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::ReasoningEffortChosen(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+    let EventMsg::ReasoningEffortChosen(chosen) = chosen else {
+        unreachable!();
+    };
+    assert_eq!(chosen.effort, ReasoningEffort::Low);
+
+    wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::TaskComplete(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+}
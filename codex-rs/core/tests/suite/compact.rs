@@ -4,6 +4,8 @@ use codex_core::CodexAuth;
 use codex_core::ConversationManager;
 use codex_core::ModelProviderInfo;
 use codex_core::built_in_model_providers;
+use codex_core::config_types::TaskModel;
+use codex_core::protocol::AuxiliaryTask;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
@@ -249,3 +251,57 @@ async fn summarize_context_three_requests_and_instructions() {
         "third request should not include the summarize trigger"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compaction_runs_on_the_model_assigned_to_it() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let server = MockServer::start().await;
+    let summary = sse(vec![
+        ev_assistant_message("m1", SUMMARY_TEXT),
+        ev_completed("r1"),
+    ]);
+    let compact_model_matcher = |req: &wiremock::Request| {
+        let body = std::str::from_utf8(&req.body).unwrap_or("");
+        body.contains("\"model\":\"gpt-4.1-mini\"")
+    };
+    mount_sse_once(&server, compact_model_matcher, summary).await;
+
+    let model_provider = ModelProviderInfo {
+        base_url: Some(format!("{}/v1", server.uri())),
+        ..built_in_model_providers()["openai"].clone()
+    };
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = model_provider;
+    config.task_models.compact = Some(TaskModel {
+        model: Some("gpt-4.1-mini".to_string()),
+        ..Default::default()
+    });
+    let conversation_manager = ConversationManager::with_auth(CodexAuth::from_api_key("dummy"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex.submit(Op::Compact).await.unwrap();
+    let EventMsg::AuxiliaryTokenCount(usage) = wait_for_event(&codex, |ev| {
+        matches!(
+            ev,
+            EventMsg::AuxiliaryTokenCount(_) | EventMsg::TokenCount(_)
+        )
+    })
+    .await
+    else {
+        panic!("compaction usage was reported as conversation usage");
+    };
+    assert_eq!(usage.task, AuxiliaryTask::Compact);
+    assert_eq!(usage.model, "gpt-4.1-mini");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+}
@@ -1,5 +1,6 @@
 // Aggregates all former standalone integration tests as modules.
 
+mod auto_reasoning_effort;
 mod cli_stream;
 mod client;
 mod compact;
@@ -7,6 +8,7 @@ mod exec;
 mod exec_stream_events;
 mod fork_conversation;
 mod live_cli;
+mod model_fallback;
 mod prompt_caching;
 mod seatbelt;
 mod stream_error_allows_next_turn;
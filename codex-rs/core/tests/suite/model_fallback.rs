@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::ModelProviderInfo;
+use codex_core::WireApi;
+use codex_core::config_types::ModelFallbackEntry;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use core_test_support::load_default_config_for_test;
+use core_test_support::load_sse_fixture_with_id;
+use core_test_support::wait_for_event_with_timeout;
+use tempfile::TempDir;
+use wiremock::Mock;
+use wiremock::MockServer;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::body_string_contains;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+fn provider(name: &str, server: &MockServer) -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: name.into(),
+        base_url: Some(format!("{}/v1", server.uri())),
+        // Use an existing env var (PATH) to satisfy the auth plumbing without
+        // requiring a real secret.
+        env_key: Some("PATH".into()),
+        env_key_instructions: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: Some(0),
+        stream_max_retries: Some(0),
+        stream_idle_timeout_ms: Some(2_000),
+        requires_openai_auth: false,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn turn_moves_to_the_next_provider_when_the_first_is_down() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    let primary = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&primary)
+        .await;
+
+    let backup = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .and(body_string_contains("\"model\":\"backup-model\""))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/event-stream")
+                .set_body_raw(
+                    load_sse_fixture_with_id("tests/fixtures/completed_template.json", "resp1"),
+                    "text/event-stream",
+                ),
+        )
+        .expect(1)
+        .mount(&backup)
+        .await;
+
+    let home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&home);
+    config.model_provider = provider("primary", &primary);
+    config
+        .model_providers
+        .insert("backup".to_string(), provider("backup", &backup));
+    config.model_fallback.chain = vec![ModelFallbackEntry {
+        model_provider: Some("backup".to_string()),
+        model: Some("backup-model".to_string()),
+    }];
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .unwrap()
+        .conversation;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await
+        .unwrap();
+
+    let switched = wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::BackgroundEvent(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+    let EventMsg::BackgroundEvent(switched) = switched else {
+        unreachable!();
+    };
+    assert!(
+        switched
+            .message
+            .ends_with("is returning server errors; switching to `backup-model` on `backup`"),
+        "unexpected message: {}",
+        switched.message
+    );
+
+    wait_for_event_with_timeout(
+        &codex,
+        |ev| matches!(ev, EventMsg::TaskComplete(_)),
+        Duration::from_secs(5),
+    )
+    .await;
+}
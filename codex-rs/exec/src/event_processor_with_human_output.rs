@@ -7,7 +7,9 @@ use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
+use codex_core::protocol::AuxiliaryTokenCountEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CommitMessageDraftEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -15,10 +17,14 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpSamplingBeginEvent;
+use codex_core::protocol::McpSamplingEndEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::PlanSummaryEvent;
+use codex_core::protocol::ReasoningEffortChosenEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
@@ -192,6 +198,24 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::TokenCount(token_usage) => {
                 ts_println!(self, "tokens used: {}", token_usage.blended_total());
             }
+            EventMsg::AuxiliaryTokenCount(AuxiliaryTokenCountEvent {
+                task,
+                model,
+                token_usage,
+            }) => {
+                ts_println!(
+                    self,
+                    "{task} tokens used on {model}: {}",
+                    token_usage.blended_total()
+                );
+            }
+            EventMsg::ReasoningEffortChosen(ReasoningEffortChosenEvent { effort, reason }) => {
+                ts_println!(
+                    self,
+                    "{}",
+                    format!("reasoning effort: {effort} ({reason})").style(self.dimmed)
+                );
+            }
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 if !self.answer_started {
                     ts_println!(self, "{}\n", "codex".style(self.italic).style(self.magenta));
@@ -363,6 +387,36 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     }
                 }
             }
+            EventMsg::McpSamplingBegin(McpSamplingBeginEvent { call_id: _, server }) => {
+                ts_println!(
+                    self,
+                    "{} {}",
+                    "sampling".style(self.magenta),
+                    format!("requested by {server}").style(self.bold),
+                );
+            }
+            EventMsg::McpSamplingEnd(McpSamplingEndEvent {
+                call_id: _,
+                server,
+                duration,
+                result,
+            }) => {
+                let duration = format!(" in {}", format_duration(duration));
+                match result {
+                    Ok(text) => {
+                        let title = format!("sampling for {server} succeeded{duration}:");
+                        ts_println!(self, "{}", title.style(self.green));
+                        for line in text.lines().take(MAX_OUTPUT_LINES_FOR_EXEC_TOOL_CALL) {
+                            println!("{}", line.style(self.dimmed));
+                        }
+                    }
+                    Err(message) => {
+                        let title = format!("sampling for {server} failed{duration}:");
+                        ts_println!(self, "{}", title.style(self.red));
+                        println!("{}", message.style(self.dimmed));
+                    }
+                }
+            }
             EventMsg::WebSearchBegin(WebSearchBeginEvent { call_id: _ }) => {}
             EventMsg::WebSearchEnd(WebSearchEndEvent { call_id: _, query }) => {
                 ts_println!(self, "🌐 Searched: {query}");
@@ -494,6 +548,12 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::ApplyPatchApprovalRequest(_) => {
                 // Should we exit?
             }
+            EventMsg::McpToolCallApprovalRequest(_) => {
+                // Should we exit?
+            }
+            EventMsg::McpSamplingApprovalRequest(_) => {
+                // Should we exit?
+            }
             EventMsg::AgentReasoning(agent_reasoning_event) => {
                 if self.show_agent_reasoning {
                     if !self.reasoning_started {
@@ -533,15 +593,27 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 ts_println!(self, "explanation: {explanation:?}");
                 ts_println!(self, "plan: {plan:?}");
             }
+            EventMsg::CommitMessageDraft(CommitMessageDraftEvent { message }) => {
+                ts_println!(self, "commit message:\n{message}");
+            }
+            EventMsg::PlanSummary(PlanSummaryEvent { summary }) => {
+                ts_println!(self, "plan summary: {summary}");
+            }
             EventMsg::GetHistoryEntryResponse(_) => {
                 // Currently ignored in exec output.
             }
             EventMsg::McpListToolsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::McpListServersResponse(_) => {
+                // Currently ignored in exec output.
+            }
             EventMsg::ListCustomPromptsResponse(_) => {
                 // Currently ignored in exec output.
             }
+            EventMsg::ListApprovalRulesResponse(_) => {
+                // Currently ignored in exec output.
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
                 TurnAbortReason::Interrupted => {
                     ts_println!(self, "task interrupted");
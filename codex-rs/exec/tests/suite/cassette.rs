@@ -0,0 +1,51 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use crate::suite::common::exec_command;
+use crate::suite::common::run_e2e_exec_test_with_env;
+use assert_cmd::prelude::*;
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use std::fs;
+use tempfile::tempdir;
+
+/// A multi-turn run recorded against a live server replays offline to the
+/// same result.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn recorded_run_replays_without_a_server() -> anyhow::Result<()> {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return Ok(());
+    }
+
+    let cassette = tempdir()?;
+    let record_cwd = tempdir()?;
+    run_e2e_exec_test_with_env(
+        record_cwd.path(),
+        vec![
+            include_str!("../fixtures/sse_apply_patch_add.json").to_string(),
+            include_str!("../fixtures/sse_apply_patch_update.json").to_string(),
+            include_str!("../fixtures/sse_response_completed.json").to_string(),
+        ],
+        &[("CODEX_RS_CASSETTE_RECORD", cassette.path().as_os_str())],
+    )
+    .await;
+    assert_eq!(
+        fs::read_to_string(record_cwd.path().join("test.md"))?,
+        "Final text\n"
+    );
+    assert_eq!(fs::read_dir(cassette.path())?.count(), 6);
+
+    // Nothing listens on port 1, so any request that is not replayed fails.
+    let replay_cwd = tempdir()?;
+    exec_command(replay_cwd.path(), "http://127.0.0.1:1/v1")
+        .env("CODEX_RS_CASSETTE_REPLAY", cassette.path())
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(replay_cwd.path().join("test.md"))?,
+        "Final text\n"
+    );
+    Ok(())
+}
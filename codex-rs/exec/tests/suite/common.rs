@@ -5,6 +5,7 @@
 use anyhow::Context;
 use assert_cmd::prelude::*;
 use core_test_support::load_sse_fixture_with_id_from_str;
+use std::ffi::OsStr;
 use std::path::Path;
 use std::process::Command;
 use std::sync::atomic::AtomicUsize;
@@ -40,6 +41,16 @@ impl Respond for SeqResponder {
 /// server, and returns the response_streams in order for each api call. Runs
 /// the codex-exec command with the wiremock server as the model server.
 pub(crate) async fn run_e2e_exec_test(cwd: &Path, response_streams: Vec<String>) {
+    run_e2e_exec_test_with_env(cwd, response_streams, &[]).await;
+}
+
+/// Like [`run_e2e_exec_test`], with extra environment variables set for the
+/// codex-exec process.
+pub(crate) async fn run_e2e_exec_test_with_env(
+    cwd: &Path,
+    response_streams: Vec<String>,
+    envs: &[(&str, &OsStr)],
+) {
     let server = MockServer::start().await;
 
     let num_calls = response_streams.len();
@@ -55,19 +66,26 @@ pub(crate) async fn run_e2e_exec_test(cwd: &Path, response_streams: Vec<String>)
         .mount(&server)
         .await;
 
-    let cwd = cwd.to_path_buf();
     let uri = server.uri();
-    Command::cargo_bin("codex-exec")
+    exec_command(cwd, &format!("{uri}/v1"))
+        .envs(envs.iter().copied())
+        .assert()
+        .success();
+}
+
+/// A codex-exec invocation that sends the prompt "foo" from `cwd` to the
+/// model server at `base_url`.
+pub(crate) fn exec_command(cwd: &Path, base_url: &str) -> Command {
+    let mut cmd = Command::cargo_bin("codex-exec")
         .context("should find binary for codex-exec")
-        .expect("should find binary for codex-exec")
-        .current_dir(cwd.clone())
-        .env("CODEX_HOME", cwd.clone())
+        .expect("should find binary for codex-exec");
+    cmd.current_dir(cwd)
+        .env("CODEX_HOME", cwd)
         .env("OPENAI_API_KEY", "dummy")
-        .env("OPENAI_BASE_URL", format!("{uri}/v1"))
+        .env("OPENAI_BASE_URL", base_url)
         .arg("--skip-git-repo-check")
         .arg("-s")
         .arg("danger-full-access")
-        .arg("foo")
-        .assert()
-        .success();
+        .arg("foo");
+    cmd
 }
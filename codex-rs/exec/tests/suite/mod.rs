@@ -1,4 +1,5 @@
 // Aggregates all former standalone integration tests as modules.
 mod apply_patch;
+mod cassette;
 mod common;
 mod sandbox;
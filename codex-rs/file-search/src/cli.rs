@@ -4,7 +4,8 @@ use std::path::PathBuf;
 use clap::ArgAction;
 use clap::Parser;
 
-/// Fuzzy matches filenames under a directory.
+/// Fuzzy matches filenames under a directory, or searches file contents with
+/// `--content`.
 #[derive(Parser)]
 #[command(version)]
 pub struct Cli {
@@ -37,6 +38,24 @@ pub struct Cli {
     #[arg(short, long, action = ArgAction::Append)]
     pub exclude: Vec<String>,
 
+    /// Search file contents for PATTERN (a regular expression) instead of
+    /// fuzzy matching file names. `--limit` then caps the number of matching
+    /// lines.
+    #[arg(long, default_value = "false")]
+    pub content: bool,
+
+    /// Treat PATTERN as a literal string rather than a regular expression.
+    #[arg(long, short = 'F', default_value = "false", requires = "content")]
+    pub fixed_strings: bool,
+
+    /// Match PATTERN case-insensitively.
+    #[arg(long, short = 'i', default_value = "false", requires = "content")]
+    pub ignore_case: bool,
+
+    /// Number of lines of context to show before and after each match.
+    #[arg(long, value_name = "NUM", default_value = "0", requires = "content")]
+    pub context: usize,
+
     /// Search pattern.
     pub pattern: Option<String>,
 }
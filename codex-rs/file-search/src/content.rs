@@ -0,0 +1,544 @@
+//! Searches file *contents* (as opposed to file names) for a regex or literal
+//! pattern, in the spirit of `rg --column`.
+//!
+//! The directory traversal is shared with the fuzzy filename search, so the
+//! same `.gitignore` handling, exclude patterns and worker threads apply. As
+//! with [`crate::run`], each worker keeps a bounded list of results which are
+//! merged once the traversal is done, so the output is deterministic (sorted by
+//! path, then line) regardless of how the work was split across threads.
+
+use std::cell::UnsafeCell;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::Read;
+use std::num::NonZero;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use regex::bytes::Regex;
+use regex::bytes::RegexBuilder;
+use serde::Serialize;
+
+use crate::WorkerCount;
+use crate::create_worker_count;
+use crate::walk_builder;
+
+/// Number of leading bytes inspected to decide whether a file is binary.
+/// Mirrors ripgrep's heuristic of skipping files that contain a NUL byte.
+const BINARY_DETECTION_BYTES: usize = 8 * 1024;
+
+/// Files larger than this are skipped rather than read into memory.
+const MAX_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Options for [`run_content_search`].
+#[derive(Debug, Clone)]
+pub struct ContentSearchOptions {
+    /// Regular expression (or literal string, see `fixed_strings`) to look for.
+    pub pattern: String,
+    /// Treat `pattern` as a literal string rather than a regex.
+    pub fixed_strings: bool,
+    /// Match case-insensitively.
+    pub ignore_case: bool,
+    /// Number of lines of context to include before and after each match.
+    pub context_lines: usize,
+    /// Maximum number of matching lines to return.
+    pub limit: NonZero<usize>,
+    /// Glob patterns for paths to skip, in addition to ignore files.
+    pub exclude: Vec<String>,
+    /// Number of worker threads used for the traversal.
+    pub threads: NonZero<usize>,
+}
+
+/// A single line that matched the pattern.
+///
+/// * `path` – Path to the file (relative to the search directory).
+/// * `line_number` – 1-based line number of the match.
+/// * `column` – 1-based byte column of the first match on the line.
+/// * `line` – The matching line, without its line terminator.
+/// * `submatches` – Byte ranges within `line` of every match, in order.
+/// * `before_context` / `after_context` – Up to `context_lines` neighbouring
+///   lines, closest last / first respectively.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentMatch {
+    pub path: String,
+    pub line_number: usize,
+    pub column: usize,
+    pub line: String,
+    pub submatches: Vec<Submatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before_context: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after_context: Vec<String>,
+}
+
+/// Byte range `[start, end)` of a match within [`ContentMatch::line`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Submatch {
+    pub start: usize,
+    pub end: usize,
+}
+
+pub struct ContentSearchResults {
+    pub matches: Vec<ContentMatch>,
+    pub total_match_count: usize,
+}
+
+/// Searches the contents of every file under `search_directory`.
+///
+/// The worker threads will periodically check `cancel_flag` to see if they
+/// should stop processing files.
+pub fn run_content_search(
+    options: &ContentSearchOptions,
+    search_directory: &Path,
+    cancel_flag: Arc<AtomicBool>,
+) -> anyhow::Result<ContentSearchResults> {
+    let regex = build_regex(options)?;
+    let limit = options.limit.get();
+    let WorkerCount {
+        num_walk_builder_threads,
+        num_best_matches_lists,
+    } = create_worker_count(options.threads);
+    let matches_per_worker: Vec<UnsafeCell<FirstMatchesList>> = (0..num_best_matches_lists)
+        .map(|_| UnsafeCell::new(FirstMatchesList::new(limit)))
+        .collect();
+
+    let walker = walk_builder(search_directory, &options.exclude, num_walk_builder_threads)?
+        .build_parallel();
+
+    let index_counter = AtomicUsize::new(0);
+    walker.run(|| {
+        let index = index_counter.fetch_add(1, Ordering::Relaxed);
+        let list_ptr = matches_per_worker[index].get();
+        let list = unsafe { &mut *list_ptr };
+        let regex = regex.clone();
+        let cancel = cancel_flag.clone();
+        let context_lines = options.context_lines;
+
+        Box::new(move |entry| {
+            // Reading file contents is much more expensive than the filename
+            // matching in `run()`, so check the flag on every entry.
+            if cancel.load(Ordering::Relaxed) {
+                return ignore::WalkState::Quit;
+            }
+            let Ok(entry) = entry else {
+                return ignore::WalkState::Continue;
+            };
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                return ignore::WalkState::Continue;
+            }
+            let Some(rel_path) = entry
+                .path()
+                .strip_prefix(search_directory)
+                .ok()
+                .and_then(|p| p.to_str())
+            else {
+                return ignore::WalkState::Continue;
+            };
+            if let Some(contents) = read_text_file(entry.path()) {
+                search_file(&regex, rel_path, &contents, context_lines, list);
+            }
+            ignore::WalkState::Continue
+        })
+    });
+
+    if cancel_flag.load(Ordering::Relaxed) {
+        return Ok(ContentSearchResults {
+            matches: Vec::new(),
+            total_match_count: 0,
+        });
+    }
+
+    let mut total_match_count = 0;
+    let mut merged = FirstMatchesList::new(limit);
+    for list_cell in matches_per_worker.iter() {
+        let list = unsafe { &mut *list_cell.get() };
+        total_match_count += list.num_matches;
+        for OrderedMatch(content_match) in std::mem::take(&mut list.binary_heap) {
+            merged.insert(content_match);
+        }
+    }
+
+    let mut matches: Vec<ContentMatch> = merged
+        .binary_heap
+        .into_iter()
+        .map(|OrderedMatch(m)| m)
+        .collect();
+    sort_content_matches(&mut matches);
+
+    Ok(ContentSearchResults {
+        matches,
+        total_match_count,
+    })
+}
+
+fn build_regex(options: &ContentSearchOptions) -> anyhow::Result<Regex> {
+    let pattern = if options.fixed_strings {
+        regex::escape(&options.pattern)
+    } else {
+        options.pattern.clone()
+    };
+    Ok(RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case)
+        .build()?)
+}
+
+/// Reads the file at `path`, unless it is larger than [`MAX_FILE_BYTES`] or
+/// looks binary: only the first block is read before that is decided.
+fn read_text_file(path: &Path) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len > MAX_FILE_BYTES {
+        return None;
+    }
+    let mut contents = Vec::with_capacity(len as usize);
+    (&mut file)
+        .take(BINARY_DETECTION_BYTES as u64)
+        .read_to_end(&mut contents)
+        .ok()?;
+    if contents.contains(&0) {
+        return None;
+    }
+    // The file may have grown since its size was checked.
+    file.take(MAX_FILE_BYTES - contents.len() as u64)
+        .read_to_end(&mut contents)
+        .ok()?;
+    Some(contents)
+}
+
+/// Records every matching line of `contents` in `list`.
+fn search_file(
+    regex: &Regex,
+    path: &str,
+    contents: &[u8],
+    context_lines: usize,
+    list: &mut FirstMatchesList,
+) {
+    let lines: Vec<&[u8]> = split_lines(contents);
+    for (idx, line) in lines.iter().enumerate() {
+        let mut submatches: Vec<Submatch> = regex
+            .find_iter(line)
+            .filter(|m| !m.is_empty())
+            .map(|m| Submatch {
+                start: m.start(),
+                end: m.end(),
+            })
+            .collect();
+        if submatches.is_empty() {
+            // Patterns such as `^` only produce empty matches; they still
+            // select the line.
+            let Some(m) = regex.find(line) else {
+                continue;
+            };
+            submatches.push(Submatch {
+                start: m.start(),
+                end: m.start(),
+            });
+        }
+        let column = submatches[0].start + 1;
+        list.num_matches += 1;
+        if !list.would_accept(path, idx + 1) {
+            continue;
+        }
+
+        let before_start = idx.saturating_sub(context_lines);
+        let after_end = (idx + 1 + context_lines).min(lines.len());
+        list.insert(ContentMatch {
+            path: path.to_string(),
+            line_number: idx + 1,
+            column,
+            line: String::from_utf8_lossy(line).into_owned(),
+            submatches,
+            before_context: lines[before_start..idx].iter().map(lossy).collect(),
+            after_context: lines[idx + 1..after_end].iter().map(lossy).collect(),
+        });
+    }
+}
+
+/// Drops context lines that would be shown twice: the trailing context of a
+/// match is cut short before the next match in the same file, and the leading
+/// context of that next match only starts after what was already shown.
+/// `matches` must be sorted by path, then line.
+pub(crate) fn trim_overlapping_context(matches: &mut [ContentMatch]) {
+    for i in 1..matches.len() {
+        let (prev, rest) = matches.split_at_mut(i);
+        let prev = &mut prev[i - 1];
+        let next = &mut rest[0];
+        if prev.path != next.path {
+            continue;
+        }
+        let max_after = next.line_number - prev.line_number - 1;
+        prev.after_context.truncate(max_after);
+        let last_shown = prev.line_number + prev.after_context.len();
+        let first_before = next.line_number - next.before_context.len();
+        if first_before <= last_shown {
+            next.before_context.drain(..=last_shown - first_before);
+        }
+    }
+}
+
+/// Splits on `\n`, dropping the terminator (and a preceding `\r`) from each
+/// line. A trailing newline does not produce an extra empty line.
+fn split_lines(contents: &[u8]) -> Vec<&[u8]> {
+    let mut lines: Vec<&[u8]> = contents
+        .split(|b| *b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect();
+    if contents.ends_with(b"\n") {
+        lines.pop();
+    }
+    lines
+}
+
+fn lossy(line: &&[u8]) -> String {
+    String::from_utf8_lossy(line).into_owned()
+}
+
+/// Sort matches in-place by ascending path, then ascending line number.
+fn sort_content_matches(matches: &mut [ContentMatch]) {
+    matches.sort_by(|a, b| {
+        a.path
+            .cmp(&b.path)
+            .then_with(|| a.line_number.cmp(&b.line_number))
+    });
+}
+
+/// Orders matches so that the *last* one in output order sits at the top of a
+/// `BinaryHeap`, which lets [`FirstMatchesList`] evict it cheaply.
+struct OrderedMatch(ContentMatch);
+
+impl OrderedMatch {
+    fn key(&self) -> (&str, usize) {
+        (&self.0.path, self.0.line_number)
+    }
+}
+
+impl PartialEq for OrderedMatch {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for OrderedMatch {}
+
+impl PartialOrd for OrderedMatch {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedMatch {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// Maintains the `max_count` matches that come first in (path, line) order.
+struct FirstMatchesList {
+    max_count: usize,
+    num_matches: usize,
+    binary_heap: BinaryHeap<OrderedMatch>,
+}
+
+impl FirstMatchesList {
+    fn new(max_count: usize) -> Self {
+        Self {
+            max_count,
+            num_matches: 0,
+            binary_heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Whether a match at `(path, line_number)` would be kept, so callers can
+    /// skip building it otherwise.
+    fn would_accept(&self, path: &str, line_number: usize) -> bool {
+        if self.binary_heap.len() < self.max_count {
+            return true;
+        }
+        self.binary_heap
+            .peek()
+            .is_some_and(|last| (path, line_number) < last.key())
+    }
+
+    fn insert(&mut self, content_match: ContentMatch) {
+        if self.binary_heap.len() < self.max_count {
+            self.binary_heap.push(OrderedMatch(content_match));
+        } else if self.would_accept(&content_match.path, content_match.line_number) {
+            self.binary_heap.pop();
+            self.binary_heap.push(OrderedMatch(content_match));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn options(pattern: &str) -> ContentSearchOptions {
+        ContentSearchOptions {
+            pattern: pattern.to_string(),
+            fixed_strings: false,
+            ignore_case: false,
+            context_lines: 0,
+            limit: NonZero::new(100).unwrap(),
+            exclude: Vec::new(),
+            threads: NonZero::new(2).unwrap(),
+        }
+    }
+
+    fn search(options: &ContentSearchOptions, dir: &Path) -> ContentSearchResults {
+        run_content_search(options, dir, Arc::new(AtomicBool::new(false))).unwrap()
+    }
+
+    #[test]
+    fn finds_regex_matches_with_line_and_column() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("a.rs"),
+            "fn main() {\n    let x = foo(1);\n}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("b.rs"), "foo(2); foo(3)\n").unwrap();
+
+        let results = search(&options(r"foo\(\d\)"), dir.path());
+
+        assert_eq!(results.total_match_count, 2);
+        assert_eq!(
+            results.matches,
+            vec![
+                ContentMatch {
+                    path: "a.rs".to_string(),
+                    line_number: 2,
+                    column: 13,
+                    line: "    let x = foo(1);".to_string(),
+                    submatches: vec![Submatch { start: 12, end: 18 }],
+                    before_context: Vec::new(),
+                    after_context: Vec::new(),
+                },
+                ContentMatch {
+                    path: "b.rs".to_string(),
+                    line_number: 1,
+                    column: 1,
+                    line: "foo(2); foo(3)".to_string(),
+                    submatches: vec![
+                        Submatch { start: 0, end: 6 },
+                        Submatch { start: 8, end: 14 }
+                    ],
+                    before_context: Vec::new(),
+                    after_context: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fixed_strings_escape_regex_metacharacters() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a.b\naxb\n").unwrap();
+
+        let mut opts = options("a.b");
+        opts.fixed_strings = true;
+        let results = search(&opts, dir.path());
+
+        let lines: Vec<_> = results.matches.iter().map(|m| m.line.as_str()).collect();
+        assert_eq!(lines, vec!["a.b"]);
+    }
+
+    #[test]
+    fn includes_context_lines() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+
+        let mut opts = options("three");
+        opts.context_lines = 1;
+        let results = search(&opts, dir.path());
+
+        assert_eq!(results.matches.len(), 1);
+        assert_eq!(results.matches[0].before_context, vec!["two"]);
+        assert_eq!(results.matches[0].after_context, vec!["four"]);
+    }
+
+    #[test]
+    fn overlapping_context_is_only_kept_once() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "1\nhit\n3\nhit\n5\n6\n7\nhit\n").unwrap();
+
+        let mut opts = options("hit");
+        opts.context_lines = 2;
+        let mut results = search(&opts, dir.path());
+        trim_overlapping_context(&mut results.matches);
+
+        let contexts: Vec<_> = results
+            .matches
+            .iter()
+            .map(|m| {
+                (
+                    m.line_number,
+                    m.before_context.clone(),
+                    m.after_context.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            contexts,
+            vec![
+                (2, vec!["1".to_string()], vec!["3".to_string()]),
+                (4, vec![], vec!["5".to_string(), "6".to_string()]),
+                (8, vec!["7".to_string()], vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_keeps_first_matches_and_counts_all() {
+        let dir = tempdir().unwrap();
+        for name in ["c.txt", "a.txt", "b.txt"] {
+            fs::write(dir.path().join(name), "hit\nmiss\nhit\n").unwrap();
+        }
+
+        let mut opts = options("hit");
+        opts.limit = NonZero::new(3).unwrap();
+        let results = search(&opts, dir.path());
+
+        assert_eq!(results.total_match_count, 6);
+        let found: Vec<_> = results
+            .matches
+            .iter()
+            .map(|m| (m.path.as_str(), m.line_number))
+            .collect();
+        assert_eq!(found, vec![("a.txt", 1), ("a.txt", 3), ("b.txt", 1)]);
+    }
+
+    #[test]
+    fn respects_gitignore_and_skips_binary_files() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "ignored.txt\n").unwrap();
+        fs::write(dir.path().join("ignored.txt"), "needle\n").unwrap();
+        fs::write(dir.path().join("binary.bin"), b"needle\0\n").unwrap();
+        fs::write(dir.path().join("kept.txt"), "needle\n").unwrap();
+
+        let results = search(&options("needle"), dir.path());
+
+        let paths: Vec<_> = results.matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["kept.txt"]);
+    }
+
+    #[test]
+    fn skips_files_above_the_size_cap() {
+        let dir = tempdir().unwrap();
+        let mut large = b"needle\n".to_vec();
+        large.resize(MAX_FILE_BYTES as usize + 1, b'x');
+        fs::write(dir.path().join("large.txt"), large).unwrap();
+        fs::write(dir.path().join("small.txt"), "needle\n").unwrap();
+
+        let results = search(&options("needle"), dir.path());
+
+        let paths: Vec<_> = results.matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["small.txt"]);
+    }
+}
@@ -0,0 +1,886 @@
+//! A persistent, incrementally updated index of the files under a directory.
+//!
+//! [`crate::run`] walks the whole tree for every query, which gets slow on
+//! very large repositories. A [`FileIndex`] instead walks the tree once, keeps
+//! the list of files in memory (and on disk, so the next session starts warm)
+//! and then follows filesystem change notifications to stay current. Queries
+//! only need to fuzzy match against the in-memory list.
+//!
+//! The index also remembers which files were used recently (see
+//! [`FileIndex::record_access`]) and ranks those higher.
+//!
+//! The ignore rules of the initial scan apply to every update, and where the
+//! platform needs a watch per directory (inotify, kqueue) ignored directories
+//! such as `target/` or `node_modules/` are never watched, so large build
+//! trees neither end up in the index nor use up the watch limit.
+
+use std::cell::UnsafeCell;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io::BufRead;
+use std::io::BufWriter;
+use std::io::Write;
+use std::num::NonZero;
+use std::path::MAIN_SEPARATOR;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::time::Instant;
+
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use notify::WatcherKind;
+use nucleo_matcher::Matcher;
+
+use crate::BestMatchesList;
+use crate::FileSearchResults;
+use crate::WorkerCount;
+use crate::create_pattern;
+use crate::create_worker_count;
+use crate::merge_best_matches;
+use crate::walk_builder;
+
+/// Number of recently used files that get a ranking boost.
+const MAX_RECENT_FILES: usize = 256;
+
+/// Filesystem events are applied in batches, collected over this window.
+const WATCH_BATCH_WINDOW: Duration = Duration::from_millis(200);
+
+/// Minimum time between rewrites of the on-disk file list while watching.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+const FILES_CACHE_FILENAME: &str = "files.txt";
+const RECENT_CACHE_FILENAME: &str = "recent.json";
+
+/// Changes to these files can affect which paths are indexed, so they trigger
+/// a full rescan rather than an incremental update.
+const IGNORE_FILENAMES: [&str; 3] = [".gitignore", ".ignore", ".rgignore"];
+
+pub struct FileIndex {
+    root: PathBuf,
+    /// Directory holding this index's cache files, if persistence is enabled.
+    cache_dir: Option<PathBuf>,
+    state: RwLock<IndexState>,
+    /// Whether `state.files` reflects at least one scan (possibly from a
+    /// previous session).
+    ready: AtomicBool,
+    /// Whether `state.files` changed since it was last written to disk.
+    dirty: AtomicBool,
+    started: AtomicBool,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    /// Writes the recently used list to disk, so that
+    /// [`FileIndex::record_access`] never blocks its caller on the
+    /// filesystem. Started by the first access.
+    recent_writer: Mutex<Option<RecentWriter>>,
+}
+
+struct RecentWriter {
+    tx: mpsc::Sender<Vec<String>>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct IndexState {
+    /// Paths relative to `root`.
+    files: BTreeSet<String>,
+    /// Relative paths of recently used files, most recent first.
+    recent: Vec<String>,
+}
+
+impl FileIndex {
+    /// Creates the index for `root`, persisted under `cache_root` (pass `None`
+    /// to keep it in memory only). This is cheap: nothing is read or scanned
+    /// until [`FileIndex::start`] is called.
+    pub fn open(root: &Path, cache_root: Option<&Path>) -> Arc<Self> {
+        let cache_dir = cache_root.map(|dir| dir.join(cache_key(root)));
+        let recent = cache_dir
+            .as_ref()
+            .map(|dir| load_recent(&dir.join(RECENT_CACHE_FILENAME)))
+            .unwrap_or_default();
+        Arc::new(Self {
+            root: root.to_path_buf(),
+            cache_dir,
+            state: RwLock::new(IndexState {
+                files: BTreeSet::new(),
+                recent,
+            }),
+            ready: AtomicBool::new(false),
+            dirty: AtomicBool::new(false),
+            started: AtomicBool::new(false),
+            watcher: Mutex::new(None),
+            recent_writer: Mutex::new(None),
+        })
+    }
+
+    /// On a background thread: loads the file list cached by a previous
+    /// session (so searches work right away), rescans the tree to catch up
+    /// with anything that changed in the meantime, and then keeps the index
+    /// current using filesystem notifications. Calling this more than once
+    /// has no effect.
+    pub fn start(self: &Arc<Self>, threads: NonZero<usize>) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel::<notify::Result<notify::Event>>();
+        // If the platform cannot watch this tree (e.g. inotify limits), the
+        // index still works but only refreshes on the next `start()`. Where
+        // each directory needs its own watch, the scan adds them.
+        if let Ok(mut watcher) = notify::recommended_watcher(tx)
+            && (watches_each_directory()
+                || watcher.watch(&self.root, RecursiveMode::Recursive).is_ok())
+            && let Ok(mut slot) = self.watcher.lock()
+        {
+            *slot = Some(watcher);
+        }
+
+        let index = Arc::downgrade(self);
+        std::thread::spawn(move || {
+            if let Some(index) = index.upgrade() {
+                index.load_cache();
+                index.rescan(threads);
+            }
+            watch_loop(index, rx, threads);
+        });
+    }
+
+    /// Whether the index has been populated, either from the cache or by a
+    /// completed scan. Until then, [`FileIndex::search`] returns nothing.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Number of files currently indexed.
+    pub fn len(&self) -> usize {
+        self.read_state().files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fuzzy matches `pattern_text` against the indexed paths, like
+    /// [`crate::run`] but without touching the filesystem. Recently used files
+    /// are ranked higher.
+    pub fn search(
+        &self,
+        pattern_text: &str,
+        limit: NonZero<usize>,
+        threads: NonZero<usize>,
+        cancel_flag: Arc<AtomicBool>,
+        compute_indices: bool,
+    ) -> FileSearchResults {
+        let pattern = create_pattern(pattern_text);
+        let state = self.read_state();
+        let recent_rank: HashMap<&str, usize> = state
+            .recent
+            .iter()
+            .enumerate()
+            .map(|(rank, path)| (path.as_str(), rank))
+            .collect();
+        let files: Vec<&str> = state.files.iter().map(String::as_str).collect();
+        let chunk_size = files.len().div_ceil(threads.get()).max(1);
+
+        let best_lists: Vec<BestMatchesList> = std::thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let pattern = pattern.clone();
+                    let recent_rank = &recent_rank;
+                    let cancel = &cancel_flag;
+                    scope.spawn(move || {
+                        let mut best_list = BestMatchesList::new(
+                            limit.get(),
+                            pattern,
+                            Matcher::new(nucleo_matcher::Config::DEFAULT),
+                        );
+                        const CHECK_INTERVAL: usize = 1024;
+                        for (i, path) in chunk.iter().enumerate() {
+                            if i % CHECK_INTERVAL == 0 && cancel.load(Ordering::Relaxed) {
+                                break;
+                            }
+                            let rank = recent_rank.get(path).copied();
+                            best_list.insert_with_boost(path, |score| recency_boost(score, rank));
+                        }
+                        best_list
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok())
+                .collect()
+        });
+
+        if cancel_flag.load(Ordering::Relaxed) {
+            return FileSearchResults {
+                matches: Vec::new(),
+                total_match_count: 0,
+            };
+        }
+        merge_best_matches(best_lists.iter(), limit, &pattern, compute_indices)
+    }
+
+    /// Marks `paths` (absolute, or relative to the indexed directory) as just
+    /// used, e.g. because they were mentioned or patched, so they rank higher
+    /// in subsequent searches. Earlier entries count as more recent. The
+    /// list is saved to disk in the background.
+    pub fn record_access<P: AsRef<Path>>(&self, paths: &[P]) {
+        let recent = {
+            let mut state = self.write_state();
+            for path in paths.iter().rev() {
+                let path = path.as_ref();
+                let path = path.strip_prefix(&self.root).unwrap_or(path);
+                if path.is_absolute() {
+                    continue;
+                }
+                let Some(path) = path.to_str() else {
+                    continue;
+                };
+                state.recent.retain(|p| p != path);
+                state.recent.insert(0, path.to_string());
+            }
+            state.recent.truncate(MAX_RECENT_FILES);
+            state.recent.clone()
+        };
+        if let Some(cache_dir) = &self.cache_dir {
+            self.save_recent_in_background(cache_dir.join(RECENT_CACHE_FILENAME), recent);
+        }
+    }
+
+    fn save_recent_in_background(&self, path: PathBuf, recent: Vec<String>) {
+        let mut writer = self
+            .recent_writer
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let writer = writer.get_or_insert_with(|| {
+            let (tx, rx) = mpsc::channel::<Vec<String>>();
+            let thread = std::thread::spawn(move || {
+                while let Ok(mut recent) = rx.recv() {
+                    // Only the latest list matters.
+                    while let Ok(newer) = rx.try_recv() {
+                        recent = newer;
+                    }
+                    let _ = save_recent(&path, &recent);
+                }
+            });
+            RecentWriter { tx, thread }
+        });
+        let _ = writer.tx.send(recent);
+    }
+
+    /// Populates the file list from the cache, unless a scan already did.
+    fn load_cache(&self) {
+        let Some(cache_dir) = &self.cache_dir else {
+            return;
+        };
+        let Some(files) = load_files(&cache_dir.join(FILES_CACHE_FILENAME), &self.root) else {
+            return;
+        };
+        let mut state = self.write_state();
+        if !self.is_ready() {
+            state.files = files;
+            self.ready.store(true, Ordering::Release);
+        }
+    }
+
+    /// Walks the whole tree and replaces the indexed file list.
+    fn rescan(&self, threads: NonZero<usize>) {
+        let Ok(Scan { files, dirs }) = scan_tree(&self.root, threads) else {
+            return;
+        };
+        self.watch_dirs(&dirs);
+        self.write_state().files = files;
+        self.ready.store(true, Ordering::Release);
+        self.dirty.store(true, Ordering::Release);
+        self.save_files();
+    }
+
+    /// Brings the entries for `changed` (absolute paths) up to date.
+    fn refresh_paths(&self, changed: &BTreeSet<PathBuf>) {
+        let mut rules = IgnoreCheck::new(&self.root);
+        for path in changed {
+            let Some(rel) = path.strip_prefix(&self.root).ok().and_then(Path::to_str) else {
+                continue;
+            };
+            if rel.is_empty() {
+                continue;
+            }
+            match std::fs::metadata(path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    // A whole directory appeared (or was renamed into place).
+                    // The walk does not filter the directory it starts from,
+                    // so check that first.
+                    if rules.is_ignored(path) {
+                        continue;
+                    }
+                    let Scan { files, dirs } = scan_dir(&self.root, path, None);
+                    self.watch_dirs(&dirs);
+                    let mut state = self.write_state();
+                    remove_subtree(&mut state.files, rel);
+                    state.files.extend(files);
+                }
+                Ok(_) => {
+                    // Re-list the parent so the ignore rules decide whether
+                    // the file belongs in the index.
+                    let parent = path.parent().unwrap_or(&self.root);
+                    if rules.is_ignored(parent) {
+                        continue;
+                    }
+                    let parent_rel = parent
+                        .strip_prefix(&self.root)
+                        .ok()
+                        .and_then(Path::to_str)
+                        .unwrap_or_default();
+                    let found = scan_dir(&self.root, parent, Some(1)).files;
+                    let mut state = self.write_state();
+                    remove_children(&mut state.files, parent_rel);
+                    state.files.extend(found);
+                }
+                Err(_) => {
+                    let mut state = self.write_state();
+                    state.files.remove(rel);
+                    remove_subtree(&mut state.files, rel);
+                }
+            }
+        }
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    /// Adds a watch for each of `dirs` when the platform's watcher needs one
+    /// per directory. Ignored directories are never passed in, so they are
+    /// never watched.
+    fn watch_dirs(&self, dirs: &[PathBuf]) {
+        if !watches_each_directory() {
+            return;
+        }
+        let Ok(mut slot) = self.watcher.lock() else {
+            return;
+        };
+        let Some(watcher) = slot.as_mut() else {
+            return;
+        };
+        for dir in dirs {
+            // Past the watch limit, every further attempt fails too.
+            if watcher.watch(dir, RecursiveMode::NonRecursive).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn save_files(&self) {
+        let Some(cache_dir) = &self.cache_dir else {
+            return;
+        };
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        let state = self.read_state();
+        if save_files(
+            &cache_dir.join(FILES_CACHE_FILENAME),
+            &self.root,
+            &state.files,
+        )
+        .is_err()
+        {
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    fn read_state(&self) -> std::sync::RwLockReadGuard<'_, IndexState> {
+        self.state
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn write_state(&self) -> std::sync::RwLockWriteGuard<'_, IndexState> {
+        self.state
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Drop for FileIndex {
+    fn drop(&mut self) {
+        self.save_files();
+        let writer = self
+            .recent_writer
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        if let Some(RecentWriter { tx, thread }) = writer {
+            // Closing the channel lets the writer finish the pending save.
+            drop(tx);
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Whether the platform's watcher needs a watch per directory rather than
+/// one recursive watch for the whole tree.
+fn watches_each_directory() -> bool {
+    matches!(
+        RecommendedWatcher::kind(),
+        WatcherKind::Inotify | WatcherKind::Kqueue
+    )
+}
+
+/// Applies filesystem events to the index until the index (and with it the
+/// watcher that feeds `rx`) is dropped.
+fn watch_loop(
+    index: Weak<FileIndex>,
+    rx: mpsc::Receiver<notify::Result<notify::Event>>,
+    threads: NonZero<usize>,
+) {
+    let mut last_save = Instant::now();
+    while let Ok(first) = rx.recv() {
+        let mut changed = BTreeSet::new();
+        let mut changed_ignore_files = Vec::new();
+        let mut needs_rescan = false;
+        let mut collect = |event: notify::Result<notify::Event>| match event {
+            Ok(event) => {
+                if matches!(event.kind, EventKind::Access(_)) {
+                    return;
+                }
+                if event.need_rescan() {
+                    needs_rescan = true;
+                }
+                for path in event.paths {
+                    if path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| IGNORE_FILENAMES.contains(&name))
+                    {
+                        changed_ignore_files.push(path.clone());
+                    }
+                    changed.insert(path);
+                }
+            }
+            // Errors usually mean events were dropped.
+            Err(_) => needs_rescan = true,
+        };
+        collect(first);
+        let deadline = Instant::now() + WATCH_BATCH_WINDOW;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match rx.recv_timeout(timeout) {
+                Ok(event) => collect(event),
+                Err(_) => break,
+            }
+        }
+
+        let Some(index) = index.upgrade() else {
+            return;
+        };
+        // Ignore files inside ignored directories (e.g. in `node_modules/`)
+        // do not affect the index.
+        let mut rules = IgnoreCheck::new(&index.root);
+        needs_rescan = needs_rescan
+            || changed_ignore_files
+                .iter()
+                .any(|path| !rules.is_ignored(path.parent().unwrap_or(&index.root)));
+        if needs_rescan {
+            index.rescan(threads);
+        } else {
+            index.refresh_paths(&changed);
+        }
+        if last_save.elapsed() >= SAVE_INTERVAL {
+            index.save_files();
+            last_save = Instant::now();
+        }
+    }
+}
+
+/// Favors recently used files: the most recent one gets a 50% boost, which
+/// tapers off with its position in the list.
+fn recency_boost(score: u32, recent_rank: Option<usize>) -> u32 {
+    match recent_rank {
+        Some(rank) => score.saturating_add(score / (rank as u32 + 2)),
+        None => score,
+    }
+}
+
+/// What a walk found: files relative to the indexed root, and the absolute
+/// paths of the directories it entered (including the one it started from).
+#[derive(Default)]
+struct Scan {
+    files: BTreeSet<String>,
+    dirs: Vec<PathBuf>,
+}
+
+impl Scan {
+    fn add(&mut self, entry: Result<ignore::DirEntry, ignore::Error>, root: &Path) {
+        let Ok(entry) = entry else {
+            return;
+        };
+        if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+            self.dirs.push(entry.into_path());
+        } else if let Some(path) = relative_file_path(&entry, root) {
+            self.files.insert(path);
+        }
+    }
+}
+
+/// Walks `root` in parallel, honoring the same ignore rules as [`crate::run`].
+fn scan_tree(root: &Path, threads: NonZero<usize>) -> anyhow::Result<Scan> {
+    let WorkerCount {
+        num_walk_builder_threads,
+        num_best_matches_lists,
+    } = create_worker_count(threads);
+    let scans_per_worker: Vec<UnsafeCell<Scan>> = (0..num_best_matches_lists)
+        .map(|_| UnsafeCell::new(Scan::default()))
+        .collect();
+    let walker = walk_builder(root, &[], num_walk_builder_threads)?.build_parallel();
+    let index_counter = AtomicUsize::new(0);
+    walker.run(|| {
+        let index = index_counter.fetch_add(1, Ordering::Relaxed);
+        let scan = unsafe { &mut *scans_per_worker[index].get() };
+        Box::new(move |entry| {
+            scan.add(entry, root);
+            ignore::WalkState::Continue
+        })
+    });
+    let mut scan = Scan::default();
+    for worker_scan in scans_per_worker.into_iter().map(UnsafeCell::into_inner) {
+        scan.files.extend(worker_scan.files);
+        scan.dirs.extend(worker_scan.dirs);
+    }
+    Ok(scan)
+}
+
+/// Walks `dir` (inside `root`) on the current thread, with the same ignore
+/// rules as [`scan_tree`]. `dir` itself is never filtered out.
+fn scan_dir(root: &Path, dir: &Path, max_depth: Option<usize>) -> Scan {
+    let mut scan = Scan::default();
+    let Ok(mut builder) = walk_builder(dir, &[], 1) else {
+        return scan;
+    };
+    for entry in builder.max_depth(max_depth).build() {
+        scan.add(entry, root);
+    }
+    scan
+}
+
+/// Answers whether a path is excluded by the ignore rules of [`scan_tree`],
+/// by listing each directory on the way down from the root the way the scan
+/// would. Listings are kept, so checking many paths in one batch of changes
+/// lists each directory once.
+struct IgnoreCheck<'a> {
+    root: &'a Path,
+    listings: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl<'a> IgnoreCheck<'a> {
+    fn new(root: &'a Path) -> Self {
+        Self {
+            root,
+            listings: HashMap::new(),
+        }
+    }
+
+    /// Whether the existing `path` (inside the root) or one of the
+    /// directories above it is ignored.
+    fn is_ignored(&mut self, path: &Path) -> bool {
+        let Ok(rel) = path.strip_prefix(self.root) else {
+            return true;
+        };
+        let mut current = self.root.to_path_buf();
+        for component in rel.components() {
+            let parent = current.clone();
+            current.push(component);
+            let listing = self.listings.entry(parent).or_insert_with_key(|dir| {
+                let Ok(mut builder) = walk_builder(dir, &[], 1) else {
+                    return HashSet::new();
+                };
+                builder
+                    .max_depth(Some(1))
+                    .build()
+                    .filter_map(Result::ok)
+                    .map(ignore::DirEntry::into_path)
+                    .collect()
+            });
+            if !listing.contains(&current) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn relative_file_path(entry: &ignore::DirEntry, root: &Path) -> Option<String> {
+    let rel = entry.path().strip_prefix(root).ok()?.to_str()?;
+    // Newlines would corrupt the line-oriented cache file.
+    if rel.is_empty() || rel.contains('\n') {
+        return None;
+    }
+    Some(rel.to_string())
+}
+
+/// Removes every path below the directory `dir` (relative to the root).
+fn remove_subtree(files: &mut BTreeSet<String>, dir: &str) {
+    let prefix = format!("{dir}{MAIN_SEPARATOR}");
+    let doomed: Vec<String> = files
+        .range(prefix.clone()..)
+        .take_while(|path| path.starts_with(&prefix))
+        .cloned()
+        .collect();
+    for path in doomed {
+        files.remove(&path);
+    }
+}
+
+/// Removes the files directly inside `dir` (relative to the root; empty for
+/// the root itself), leaving subdirectories alone.
+fn remove_children(files: &mut BTreeSet<String>, dir: &str) {
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{dir}{MAIN_SEPARATOR}")
+    };
+    let doomed: Vec<String> = files
+        .range(prefix.clone()..)
+        .take_while(|path| path.starts_with(&prefix))
+        .filter(|path| !path[prefix.len()..].contains(MAIN_SEPARATOR))
+        .cloned()
+        .collect();
+    for path in doomed {
+        files.remove(&path);
+    }
+}
+
+/// Name of the cache directory for `root`: readable, but disambiguated by a
+/// hash of the full path.
+fn cache_key(root: &Path) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    root.hash(&mut hasher);
+    let name: String = root
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{name}-{:016x}", hasher.finish())
+}
+
+/// The cache starts with a header line naming the indexed root, followed by
+/// one relative path per line.
+fn load_files(path: &Path, root: &Path) -> Option<BTreeSet<String>> {
+    let file = std::fs::File::open(path).ok()?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    let header = lines.next()?.ok()?;
+    if header != files_header(root) {
+        return None;
+    }
+    lines.collect::<std::io::Result<BTreeSet<String>>>().ok()
+}
+
+fn save_files(path: &Path, root: &Path, files: &BTreeSet<String>) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    {
+        let mut writer = BufWriter::new(tmp.as_file_mut());
+        writeln!(writer, "{}", files_header(root))?;
+        for file in files {
+            writeln!(writer, "{file}")?;
+        }
+        writer.flush()?;
+    }
+    tmp.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+fn files_header(root: &Path) -> String {
+    format!("# codex file index: {}", root.display())
+}
+
+fn load_recent(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_recent(path: &Path, recent: &[String]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string(recent)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const THREADS: NonZero<usize> = NonZero::new(2).unwrap();
+    const LIMIT: NonZero<usize> = NonZero::new(10).unwrap();
+
+    fn paths(index: &FileIndex, query: &str) -> Vec<String> {
+        index
+            .search(
+                query,
+                LIMIT,
+                THREADS,
+                Arc::new(AtomicBool::new(false)),
+                false,
+            )
+            .matches
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    fn sep(path: &str) -> String {
+        path.replace('/', std::path::MAIN_SEPARATOR_STR)
+    }
+
+    #[test]
+    fn scan_respects_gitignore_and_persists_to_cache() {
+        let root = tempdir().unwrap();
+        let cache = tempdir().unwrap();
+        fs::create_dir(root.path().join("src")).unwrap();
+        fs::write(root.path().join("src/main.rs"), "").unwrap();
+        fs::write(root.path().join("ignored.log"), "").unwrap();
+        fs::write(root.path().join(".gitignore"), "*.log\n").unwrap();
+
+        let index = FileIndex::open(root.path(), Some(cache.path()));
+        assert!(!index.is_ready());
+        index.rescan(THREADS);
+        assert!(index.is_ready());
+        assert_eq!(paths(&index, "main"), vec![sep("src/main.rs")]);
+        assert_eq!(paths(&index, "ignored"), Vec::<String>::new());
+        drop(index);
+
+        // A fresh index picks up the cached list without scanning.
+        fs::remove_file(root.path().join("src/main.rs")).unwrap();
+        let reopened = FileIndex::open(root.path(), Some(cache.path()));
+        reopened.load_cache();
+        assert!(reopened.is_ready());
+        assert_eq!(paths(&reopened, "main"), vec![sep("src/main.rs")]);
+    }
+
+    #[test]
+    fn refresh_paths_applies_additions_and_removals() {
+        let root = tempdir().unwrap();
+        fs::create_dir(root.path().join("a")).unwrap();
+        fs::write(root.path().join("a/one.txt"), "").unwrap();
+        fs::write(root.path().join("a/two.txt"), "").unwrap();
+
+        let index = FileIndex::open(root.path(), None);
+        index.rescan(THREADS);
+        assert_eq!(index.len(), 2);
+
+        fs::create_dir_all(root.path().join("b/c")).unwrap();
+        fs::write(root.path().join("b/c/three.txt"), "").unwrap();
+        fs::write(root.path().join("four.txt"), "").unwrap();
+        fs::remove_dir_all(root.path().join("a")).unwrap();
+        index.refresh_paths(&BTreeSet::from([
+            root.path().join("a"),
+            root.path().join("b"),
+            root.path().join("four.txt"),
+        ]));
+
+        let files: Vec<String> = index.read_state().files.iter().cloned().collect();
+        assert_eq!(files, vec![sep("b/c/three.txt"), "four.txt".to_string()]);
+    }
+
+    #[test]
+    fn ignored_directories_are_neither_indexed_nor_watched() {
+        let root = tempdir().unwrap();
+        fs::write(root.path().join(".gitignore"), "target/\n").unwrap();
+        fs::create_dir(root.path().join("src")).unwrap();
+        fs::write(root.path().join("src/lib.rs"), "").unwrap();
+        fs::create_dir_all(root.path().join("target/debug")).unwrap();
+        fs::write(root.path().join("target/debug/old.o"), "").unwrap();
+
+        let scan = scan_tree(root.path(), THREADS).unwrap();
+        assert!(scan.dirs.contains(&root.path().join("src")));
+        assert!(!scan.dirs.iter().any(|dir| dir.ends_with("target")));
+        assert!(!scan.dirs.iter().any(|dir| dir.ends_with("debug")));
+
+        let index = FileIndex::open(root.path(), None);
+        index.rescan(THREADS);
+        fs::create_dir_all(root.path().join("target/release")).unwrap();
+        fs::write(root.path().join("target/release/new.o"), "").unwrap();
+        fs::write(root.path().join("target/debug/new.o"), "").unwrap();
+        fs::write(root.path().join("src/main.rs"), "").unwrap();
+        index.refresh_paths(&BTreeSet::from([
+            root.path().join("target/release"),
+            root.path().join("target/debug/new.o"),
+            root.path().join("src/main.rs"),
+        ]));
+
+        let files: Vec<String> = index.read_state().files.iter().cloned().collect();
+        assert_eq!(
+            files,
+            vec![
+                ".gitignore".to_string(),
+                sep("src/lib.rs"),
+                sep("src/main.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn recently_used_files_rank_higher() {
+        let root = tempdir().unwrap();
+        let cache = tempdir().unwrap();
+        fs::write(root.path().join("config_a.toml"), "").unwrap();
+        fs::write(root.path().join("config_b.toml"), "").unwrap();
+
+        let index = FileIndex::open(root.path(), Some(cache.path()));
+        index.rescan(THREADS);
+        assert_eq!(
+            paths(&index, "config"),
+            vec!["config_a.toml".to_string(), "config_b.toml".to_string()]
+        );
+
+        index.record_access(&[root.path().join("config_b.toml")]);
+        assert_eq!(
+            paths(&index, "config"),
+            vec!["config_b.toml".to_string(), "config_a.toml".to_string()]
+        );
+        drop(index);
+
+        let reopened = FileIndex::open(root.path(), Some(cache.path()));
+        reopened.rescan(THREADS);
+        assert_eq!(paths(&reopened, "config")[0], "config_b.toml");
+    }
+
+    #[test]
+    fn watcher_picks_up_new_files() {
+        let root = tempdir().unwrap();
+        fs::write(root.path().join("existing.txt"), "").unwrap();
+        let index = FileIndex::open(root.path(), None);
+        index.start(THREADS);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !index.is_ready() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        fs::write(root.path().join("brand_new.txt"), "").unwrap();
+        while paths(&index, "brand_new").is_empty() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert_eq!(
+            paths(&index, "brand_new"),
+            vec!["brand_new.txt".to_string()]
+        );
+    }
+}
@@ -19,8 +19,16 @@ use std::sync::atomic::Ordering;
 use tokio::process::Command;
 
 mod cli;
+mod content;
+mod index;
 
 pub use cli::Cli;
+pub use content::ContentMatch;
+pub use content::ContentSearchOptions;
+pub use content::ContentSearchResults;
+pub use content::Submatch;
+pub use content::run_content_search;
+pub use index::FileIndex;
 
 /// A single match result returned from the search.
 ///
@@ -47,6 +55,9 @@ pub struct FileSearchResults {
 
 pub trait Reporter {
     fn report_match(&self, file_match: &FileMatch);
+    /// Called for every line found by a `--content` search. Reporters that
+    /// only handle filename matches can rely on the default, which ignores it.
+    fn report_content_match(&self, _content_match: &ContentMatch) {}
     fn warn_matches_truncated(&self, total_match_count: usize, shown_match_count: usize);
     fn warn_no_search_pattern(&self, search_directory: &Path);
 }
@@ -60,6 +71,10 @@ pub async fn run_main<T: Reporter>(
         json: _,
         exclude,
         threads,
+        content,
+        fixed_strings,
+        ignore_case,
+        context,
     }: Cli,
     reporter: T,
 ) -> anyhow::Result<()> {
@@ -94,6 +109,31 @@ pub async fn run_main<T: Reporter>(
     };
 
     let cancel_flag = Arc::new(AtomicBool::new(false));
+    if content {
+        let options = ContentSearchOptions {
+            pattern: pattern_text,
+            fixed_strings,
+            ignore_case,
+            context_lines: context,
+            limit,
+            exclude,
+            threads,
+        };
+        let ContentSearchResults {
+            total_match_count,
+            mut matches,
+        } = run_content_search(&options, &search_directory, cancel_flag)?;
+        content::trim_overlapping_context(&mut matches);
+        let match_count = matches.len();
+        for content_match in matches {
+            reporter.report_content_match(&content_match);
+        }
+        if total_match_count > match_count {
+            reporter.warn_matches_truncated(total_match_count, match_count);
+        }
+        return Ok(());
+    }
+
     let FileSearchResults {
         total_match_count,
         matches,
@@ -148,27 +188,8 @@ pub fn run(
         })
         .collect();
 
-    // Use the same tree-walker library that ripgrep uses. We use it directly so
-    // that we can leverage the parallelism it provides.
-    let mut walk_builder = WalkBuilder::new(search_directory);
-    walk_builder
-        .threads(num_walk_builder_threads)
-        // Allow hidden entries.
-        .hidden(false)
-        // Don't require git to be present to apply to apply git-related ignore rules.
-        .require_git(false);
-
-    if !exclude.is_empty() {
-        let mut override_builder = OverrideBuilder::new(search_directory);
-        for exclude in exclude {
-            // The `!` prefix is used to indicate an exclude pattern.
-            let exclude_pattern = format!("!{exclude}");
-            override_builder.add(&exclude_pattern)?;
-        }
-        let override_matcher = override_builder.build()?;
-        walk_builder.overrides(override_matcher);
-    }
-    let walker = walk_builder.build_parallel();
+    let walker =
+        walk_builder(search_directory, &exclude, num_walk_builder_threads)?.build_parallel();
 
     // Each worker created by `WalkParallel::run()` will have its own
     // `BestMatchesList` to update.
@@ -225,11 +246,27 @@ pub fn run(
         });
     }
 
-    // Merge results across best_matchers_per_worker.
+    let best_lists = best_matchers_per_worker
+        .iter()
+        .map(|best_list_cell| unsafe { &*best_list_cell.get() });
+    Ok(merge_best_matches(
+        best_lists,
+        limit,
+        &pattern,
+        compute_indices,
+    ))
+}
+
+/// Merges the per-worker `BestMatchesList`s into the final, sorted results.
+fn merge_best_matches<'a>(
+    best_lists: impl Iterator<Item = &'a BestMatchesList>,
+    limit: NonZero<usize>,
+    pattern: &Pattern,
+    compute_indices: bool,
+) -> FileSearchResults {
     let mut global_heap: BinaryHeap<Reverse<(u32, String)>> = BinaryHeap::new();
     let mut total_match_count = 0;
-    for best_list_cell in best_matchers_per_worker.iter() {
-        let best_list = unsafe { &*best_list_cell.get() };
+    for best_list in best_lists {
         total_match_count += best_list.num_matches;
         for &Reverse((score, ref line)) in best_list.binary_heap.iter() {
             if global_heap.len() < limit.get() {
@@ -279,10 +316,40 @@ pub fn run(
         })
         .collect();
 
-    Ok(FileSearchResults {
+    FileSearchResults {
         matches,
         total_match_count,
-    })
+    }
+}
+
+/// Configures the directory traversal shared by the filename and content
+/// searches.
+pub(crate) fn walk_builder(
+    search_directory: &Path,
+    exclude: &[String],
+    num_walk_builder_threads: usize,
+) -> anyhow::Result<WalkBuilder> {
+    // Use the same tree-walker library that ripgrep uses. We use it directly so
+    // that we can leverage the parallelism it provides.
+    let mut walk_builder = WalkBuilder::new(search_directory);
+    walk_builder
+        .threads(num_walk_builder_threads)
+        // Allow hidden entries.
+        .hidden(false)
+        // Don't require git to be present to apply to apply git-related ignore rules.
+        .require_git(false);
+
+    if !exclude.is_empty() {
+        let mut override_builder = OverrideBuilder::new(search_directory);
+        for exclude in exclude {
+            // The `!` prefix is used to indicate an exclude pattern.
+            let exclude_pattern = format!("!{exclude}");
+            override_builder.add(&exclude_pattern)?;
+        }
+        let override_matcher = override_builder.build()?;
+        walk_builder.overrides(override_matcher);
+    }
+    Ok(walk_builder)
 }
 
 /// Sort matches in-place by descending score, then ascending path.
@@ -318,11 +385,18 @@ impl BestMatchesList {
     }
 
     fn insert(&mut self, line: &str) {
+        self.insert_with_boost(line, |score| score);
+    }
+
+    /// Like `insert`, but lets the caller adjust the score of a match (e.g. to
+    /// favor recently used files) before it competes for a slot.
+    fn insert_with_boost(&mut self, line: &str, boost: impl FnOnce(u32) -> u32) {
         let haystack: Utf32Str<'_> = Utf32Str::new(line, &mut self.utf32buf);
         if let Some(score) = self.pattern.score(haystack, &mut self.matcher) {
             // In the tests below, we verify that score() returns None for a
             // non-match, so we can categorically increment the count here.
             self.num_matches += 1;
+            let score = boost(score);
 
             if self.binary_heap.len() < self.max_count {
                 self.binary_heap.push(Reverse((score, line.to_string())));
@@ -336,12 +410,12 @@ impl BestMatchesList {
     }
 }
 
-struct WorkerCount {
-    num_walk_builder_threads: usize,
-    num_best_matches_lists: usize,
+pub(crate) struct WorkerCount {
+    pub(crate) num_walk_builder_threads: usize,
+    pub(crate) num_best_matches_lists: usize,
 }
 
-fn create_worker_count(num_workers: NonZero<usize>) -> WorkerCount {
+pub(crate) fn create_worker_count(num_workers: NonZero<usize>) -> WorkerCount {
     // It appears that the number of times the function passed to
     // `WalkParallel::run()` is called is: the number of threads specified to
     // the builder PLUS ONE.
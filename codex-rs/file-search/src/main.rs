@@ -1,8 +1,10 @@
+use std::cell::RefCell;
 use std::io::IsTerminal;
 use std::path::Path;
 
 use clap::Parser;
 use codex_file_search::Cli;
+use codex_file_search::ContentMatch;
 use codex_file_search::FileMatch;
 use codex_file_search::Reporter;
 use codex_file_search::run_main;
@@ -14,6 +16,8 @@ async fn main() -> anyhow::Result<()> {
     let reporter = StdioReporter {
         write_output_as_json: cli.json,
         show_indices: cli.compute_indices && std::io::stdout().is_terminal(),
+        show_context_separators: cli.context > 0,
+        last_content_line: RefCell::new(None),
     };
     run_main(cli, reporter).await?;
     Ok(())
@@ -22,6 +26,10 @@ async fn main() -> anyhow::Result<()> {
 struct StdioReporter {
     write_output_as_json: bool,
     show_indices: bool,
+    show_context_separators: bool,
+    /// Path and line number of the last line printed for `--content`, used to
+    /// separate non-contiguous groups of lines with `--` like ripgrep does.
+    last_content_line: RefCell<Option<(String, usize)>>,
 }
 
 impl Reporter for StdioReporter {
@@ -58,6 +66,40 @@ impl Reporter for StdioReporter {
         }
     }
 
+    fn report_content_match(&self, content_match: &ContentMatch) {
+        if self.write_output_as_json {
+            println!("{}", serde_json::to_string(&content_match).unwrap());
+            return;
+        }
+
+        let ContentMatch {
+            path,
+            line_number,
+            column,
+            line,
+            before_context,
+            after_context,
+            ..
+        } = content_match;
+        let first_line = line_number - before_context.len();
+        let mut last_content_line = self.last_content_line.borrow_mut();
+        if self.show_context_separators
+            && let Some((last_path, last_line)) = last_content_line.as_ref()
+            && (last_path != path || first_line > last_line + 1)
+        {
+            println!("--");
+        }
+
+        for (offset, context) in before_context.iter().enumerate() {
+            println!("{path}-{}-{context}", first_line + offset);
+        }
+        println!("{path}:{line_number}:{column}:{line}");
+        for (offset, context) in after_context.iter().enumerate() {
+            println!("{path}-{}-{context}", line_number + 1 + offset);
+        }
+        *last_content_line = Some((path.clone(), line_number + after_context.len()));
+    }
+
     fn warn_matches_truncated(&self, total_match_count: usize, shown_match_count: usize) {
         if self.write_output_as_json {
             let value = json!({"matches_truncated": true});
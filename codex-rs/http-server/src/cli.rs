@@ -0,0 +1,19 @@
+use clap::Parser;
+use codex_common::CliConfigOverrides;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(version)]
+pub struct Cli {
+    /// TCP address to listen on.
+    #[arg(long = "listen", value_name = "ADDR", default_value = "127.0.0.1:8787")]
+    pub listen: SocketAddr,
+
+    /// Listen on a Unix domain socket at this path instead of a TCP address.
+    #[arg(long = "unix-socket", value_name = "PATH")]
+    pub unix_socket: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
@@ -0,0 +1,197 @@
+//! A conversation being served over HTTP: one task drains the conversation's
+//! events into a bounded backlog and fans them out to any number of
+//! event-stream subscribers.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_core::CodexConversation;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// Number of past events kept per conversation, so that a client that
+/// reconnects with `Last-Event-ID` does not miss anything.
+const EVENT_BACKLOG: usize = 1024;
+
+/// An event together with its position in the conversation's event stream.
+#[derive(Debug, Clone)]
+pub(crate) struct SequencedEvent {
+    pub seq: u64,
+    pub event: Arc<Event>,
+}
+
+/// The kind of approval a pending request is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ApprovalKind {
+    Exec,
+    Patch,
+    McpToolCall,
+    McpSampling,
+}
+
+struct EventLog {
+    next_seq: u64,
+    backlog: VecDeque<SequencedEvent>,
+    tx: broadcast::Sender<SequencedEvent>,
+}
+
+pub(crate) struct ServedConversation {
+    conversation: Arc<CodexConversation>,
+    events: Mutex<EventLog>,
+    /// Approval requests not answered yet, keyed by the id of the event that
+    /// asked for them.
+    pending_approvals: Mutex<HashMap<String, ApprovalKind>>,
+}
+
+impl ServedConversation {
+    /// Start serving `conversation`. `first_event` is the `SessionConfigured`
+    /// event that was already consumed when the conversation was created.
+    /// `on_closed` runs once the conversation has no more events to serve.
+    pub(crate) fn spawn(
+        conversation: Arc<CodexConversation>,
+        first_event: Event,
+        on_closed: impl Future<Output = ()> + Send + 'static,
+    ) -> Arc<Self> {
+        let (tx, _) = broadcast::channel(EVENT_BACKLOG);
+        let served = Arc::new(Self {
+            conversation,
+            events: Mutex::new(EventLog {
+                next_seq: 1,
+                backlog: VecDeque::new(),
+                tx,
+            }),
+            pending_approvals: Mutex::new(HashMap::new()),
+        });
+        served.record(first_event);
+
+        let weak = Arc::downgrade(&served);
+        let conversation = served.conversation.clone();
+        tokio::spawn(async move {
+            loop {
+                let event = match conversation.next_event().await {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!("conversation.next_event() failed with: {err}");
+                        break;
+                    }
+                };
+                let done = matches!(event.msg, EventMsg::ShutdownComplete);
+                // Stop once the server has dropped the conversation.
+                let Some(served) = weak.upgrade() else { break };
+                served.record(event);
+                if done {
+                    break;
+                }
+            }
+            on_closed.await;
+        });
+        served
+    }
+
+    pub(crate) async fn submit(&self, op: Op) -> codex_core::error::Result<String> {
+        match &op {
+            Op::ExecApproval { id, .. }
+            | Op::PatchApproval { id, .. }
+            | Op::McpToolCallApproval { id, .. }
+            | Op::McpSamplingApproval { id, .. } => {
+                self.lock_pending_approvals().remove(id);
+            }
+            _ => {}
+        }
+        self.conversation.submit(op).await
+    }
+
+    /// Answer the approval request raised by event `id`. Returns `Ok(false)`
+    /// when no such request is pending.
+    pub(crate) async fn answer_approval(
+        &self,
+        id: &str,
+        decision: ReviewDecision,
+    ) -> codex_core::error::Result<bool> {
+        let Some(kind) = self.lock_pending_approvals().remove(id) else {
+            return Ok(false);
+        };
+        let id = id.to_string();
+        let op = match kind {
+            ApprovalKind::Exec => Op::ExecApproval { id, decision },
+            ApprovalKind::Patch => Op::PatchApproval { id, decision },
+            ApprovalKind::McpToolCall => Op::McpToolCallApproval { id, decision },
+            ApprovalKind::McpSampling => Op::McpSamplingApproval { id, decision },
+        };
+        self.conversation.submit(op).await?;
+        Ok(true)
+    }
+
+    pub(crate) fn pending_approvals(&self) -> Vec<(String, ApprovalKind)> {
+        let mut pending: Vec<_> = self
+            .lock_pending_approvals()
+            .iter()
+            .map(|(id, kind)| (id.clone(), *kind))
+            .collect();
+        pending.sort_by(|a, b| a.0.cmp(&b.0));
+        pending
+    }
+
+    /// The backlog after `last_seen` and a receiver for every later event.
+    /// Both are taken under one lock, so nothing is missed or repeated.
+    pub(crate) fn subscribe(
+        &self,
+        last_seen: Option<u64>,
+    ) -> (Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>) {
+        let log = self.lock_events();
+        let last_seen = last_seen.unwrap_or(0);
+        let backlog = log
+            .backlog
+            .iter()
+            .filter(|event| event.seq > last_seen)
+            .cloned()
+            .collect();
+        (backlog, log.tx.subscribe())
+    }
+
+    fn record(&self, event: Event) {
+        let kind = match &event.msg {
+            EventMsg::ExecApprovalRequest(_) => Some(ApprovalKind::Exec),
+            EventMsg::ApplyPatchApprovalRequest(_) => Some(ApprovalKind::Patch),
+            EventMsg::McpToolCallApprovalRequest(_) => Some(ApprovalKind::McpToolCall),
+            EventMsg::McpSamplingApprovalRequest(_) => Some(ApprovalKind::McpSampling),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            self.lock_pending_approvals().insert(event.id.clone(), kind);
+        }
+
+        let mut log = self.lock_events();
+        let event = SequencedEvent {
+            seq: log.next_seq,
+            event: Arc::new(event),
+        };
+        log.next_seq += 1;
+        if log.backlog.len() == EVENT_BACKLOG {
+            log.backlog.pop_front();
+        }
+        log.backlog.push_back(event.clone());
+        // No subscribers is fine; the backlog still has the event.
+        let _ = log.tx.send(event);
+    }
+
+    fn lock_events(&self) -> std::sync::MutexGuard<'_, EventLog> {
+        self.events
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn lock_pending_approvals(&self) -> std::sync::MutexGuard<'_, HashMap<String, ApprovalKind>> {
+        self.pending_approvals
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,146 @@
+//! `codex serve`: drive Codex conversations over HTTP, with each
+//! conversation's events streamed as Server-Sent Events.
+//!
+//! Every request must carry `Authorization: Bearer <token>`, where the token
+//! is read from `CODEX_SERVE_TOKEN` or generated and printed at startup.
+//!
+//! | Method and path                                | Purpose                               |
+//! | ---------------------------------------------- | ------------------------------------- |
+//! | `POST /v1/conversations`                       | Start a conversation                  |
+//! | `POST /v1/conversations/resume`                | Resume one from a rollout file        |
+//! | `GET /v1/conversations`                        | List the conversations being served   |
+//! | `DELETE /v1/conversations/{id}`                | Shut a conversation down              |
+//! | `POST /v1/conversations/{id}/ops`              | Submit an `Op`                        |
+//! | `GET /v1/conversations/{id}/events`            | Stream `Event`s (SSE)                 |
+//! | `GET /v1/conversations/{id}/approvals`         | List unanswered approval requests     |
+//! | `POST /v1/conversations/{id}/approvals/{event}` | Answer an approval request           |
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use rand::RngCore;
+use tokio::sync::RwLock;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod cli;
+mod conversation;
+mod routes;
+
+pub use cli::Cli;
+
+use crate::conversation::ServedConversation;
+
+/// Environment variable holding the bearer token clients must present.
+pub const TOKEN_ENV_VAR: &str = "CODEX_SERVE_TOKEN";
+
+pub(crate) struct ServerState {
+    manager: ConversationManager,
+    auth_manager: Arc<AuthManager>,
+    conversations: RwLock<HashMap<Uuid, Arc<ServedConversation>>>,
+    /// `-c` overrides from the command line, applied to every conversation.
+    cli_overrides: Vec<(String, toml::Value)>,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    token: String,
+}
+
+pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let cli_overrides = cli
+        .config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides(
+        cli_overrides.clone(),
+        ConfigOverrides {
+            codex_linux_sandbox_exe: codex_linux_sandbox_exe.clone(),
+            ..Default::default()
+        },
+    )?;
+    let auth_manager = AuthManager::shared(
+        config.codex_home.clone(),
+        config.preferred_auth_method,
+        config.responses_originator_header.clone(),
+    );
+
+    let token = match std::env::var(TOKEN_ENV_VAR) {
+        Ok(token) if !token.trim().is_empty() => token,
+        _ => {
+            let token = generate_token();
+            eprintln!(
+                "{TOKEN_ENV_VAR} is not set; clients must send: Authorization: Bearer {token}"
+            );
+            token
+        }
+    };
+
+    let state = Arc::new(ServerState {
+        manager: ConversationManager::new(auth_manager.clone()),
+        auth_manager,
+        conversations: RwLock::new(HashMap::new()),
+        cli_overrides,
+        codex_linux_sandbox_exe,
+        token,
+    });
+    let app = routes::router(state);
+
+    match cli.unix_socket {
+        Some(path) => serve_unix_socket(app, path).await,
+        None => {
+            let listener = tokio::net::TcpListener::bind(cli.listen)
+                .await
+                .with_context(|| format!("failed to listen on {}", cli.listen))?;
+            eprintln!("codex serve listening on http://{}", listener.local_addr()?);
+            axum::serve(listener, app)
+                .with_graceful_shutdown(shutdown_signal())
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn serve_unix_socket(app: axum::Router, path: PathBuf) -> anyhow::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    // A socket left behind by an earlier run would make `bind` fail.
+    if std::fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(&path)
+        .with_context(|| format!("failed to listen on {}", path.display()))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    eprintln!("codex serve listening on {}", path.display());
+
+    let result = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await;
+    let _ = std::fs::remove_file(&path);
+    Ok(result?)
+}
+
+#[cfg(not(unix))]
+async fn serve_unix_socket(_app: axum::Router, _path: PathBuf) -> anyhow::Result<()> {
+    anyhow::bail!("--unix-socket is only supported on Unix")
+}
+
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
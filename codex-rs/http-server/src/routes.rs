@@ -0,0 +1,539 @@
+//! HTTP handlers. Requests and responses are JSON with camelCase fields,
+//! like the MCP server's; `Op`s and events use the protocol's own format.
+
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::Sse;
+use axum::response::sse::KeepAlive;
+use axum::routing::get;
+use axum::routing::post;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::error::CodexErr;
+use codex_core::protocol::Event;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use codex_protocol::mcp_protocol::ConversationId;
+use codex_protocol::mcp_protocol::NewConversationParams;
+use codex_protocol::mcp_protocol::NewConversationResponse;
+use futures::Stream;
+use futures::StreamExt;
+use futures::stream;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::ServerState;
+use crate::conversation::ApprovalKind;
+use crate::conversation::SequencedEvent;
+use crate::conversation::ServedConversation;
+
+pub(crate) fn router(state: Arc<ServerState>) -> Router {
+    Router::new()
+        .route(
+            "/v1/conversations",
+            get(list_conversations).post(new_conversation),
+        )
+        .route("/v1/conversations/resume", post(resume_conversation))
+        .route(
+            "/v1/conversations/{id}",
+            axum::routing::delete(remove_conversation),
+        )
+        .route("/v1/conversations/{id}/ops", post(submit_op))
+        .route("/v1/conversations/{id}/events", get(stream_events))
+        .route("/v1/conversations/{id}/approvals", get(list_approvals))
+        .route(
+            "/v1/conversations/{id}/approvals/{approval_id}",
+            post(answer_approval),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            require_token,
+        ))
+        .with_state(state)
+}
+
+/// An error reply: the status code plus `{"error": "<message>"}`.
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn conversation_not_found(id: Uuid) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            format!("conversation not found: {id}"),
+        )
+    }
+}
+
+impl From<CodexErr> for ApiError {
+    fn from(err: CodexErr) -> Self {
+        let status = match err {
+            CodexErr::ConversationNotFound(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, err.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+async fn require_token(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if constant_time_eq(token.as_bytes(), state.token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid bearer token")
+            .into_response(),
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumeConversationParams {
+    /// Rollout file to resume from.
+    path: PathBuf,
+
+    #[serde(flatten)]
+    params: NewConversationParams,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListConversationsResponse {
+    conversation_ids: Vec<ConversationId>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmitOpResponse {
+    submission_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnswerApprovalParams {
+    decision: ReviewDecision,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingApproval {
+    id: String,
+    kind: ApprovalKind,
+}
+
+async fn new_conversation(
+    State(state): State<Arc<ServerState>>,
+    Json(params): Json<NewConversationParams>,
+) -> Result<(StatusCode, Json<NewConversationResponse>), ApiError> {
+    let config = state.derive_config(params)?;
+    let new_conversation = state.manager.new_conversation(config).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(state.serve(new_conversation).await),
+    ))
+}
+
+async fn resume_conversation(
+    State(state): State<Arc<ServerState>>,
+    Json(ResumeConversationParams { path, params }): Json<ResumeConversationParams>,
+) -> Result<(StatusCode, Json<NewConversationResponse>), ApiError> {
+    let config = state.derive_config(params)?;
+    let new_conversation = state
+        .manager
+        .resume_conversation_from_rollout(config, path, state.auth_manager.clone())
+        .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(state.serve(new_conversation).await),
+    ))
+}
+
+async fn list_conversations(
+    State(state): State<Arc<ServerState>>,
+) -> Json<ListConversationsResponse> {
+    let mut conversation_ids: Vec<ConversationId> = state
+        .conversations
+        .read()
+        .await
+        .keys()
+        .map(|id| ConversationId(*id))
+        .collect();
+    conversation_ids.sort_by_key(|id| id.0);
+    Json(ListConversationsResponse { conversation_ids })
+}
+
+async fn remove_conversation(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let served = state
+        .conversations
+        .write()
+        .await
+        .remove(&id)
+        .ok_or_else(|| ApiError::conversation_not_found(id))?;
+    state.manager.remove_conversation(id).await;
+    served.submit(Op::Shutdown).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn submit_op(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<Uuid>,
+    Json(op): Json<Op>,
+) -> Result<Json<SubmitOpResponse>, ApiError> {
+    let submission_id = state.conversation(id).await?.submit(op).await?;
+    Ok(Json(SubmitOpResponse { submission_id }))
+}
+
+async fn list_approvals(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<PendingApproval>>, ApiError> {
+    let pending = state
+        .conversation(id)
+        .await?
+        .pending_approvals()
+        .into_iter()
+        .map(|(id, kind)| PendingApproval { id, kind })
+        .collect();
+    Ok(Json(pending))
+}
+
+async fn answer_approval(
+    State(state): State<Arc<ServerState>>,
+    Path((id, approval_id)): Path<(Uuid, String)>,
+    Json(AnswerApprovalParams { decision }): Json<AnswerApprovalParams>,
+) -> Result<StatusCode, ApiError> {
+    let served = state.conversation(id).await?;
+    if served.answer_approval(&approval_id, decision).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            format!("no pending approval: {approval_id}"),
+        ))
+    }
+}
+
+/// Stream the conversation's events as Server-Sent Events. Each event's SSE
+/// `id` is its sequence number and its SSE `event` is the `EventMsg` type. A
+/// client that reconnects with `Last-Event-ID` first gets the events it
+/// missed, as far as the backlog reaches.
+async fn stream_events(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>>, ApiError> {
+    let last_seen = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+    let (backlog, rx) = state.conversation(id).await?.subscribe(last_seen);
+
+    // A subscriber that falls too far behind is disconnected; it can
+    // reconnect with `Last-Event-ID` and continue from the backlog.
+    let live = stream::unfold(rx, |mut rx| async move {
+        match rx.recv().await {
+            Ok(event) => Some((event, rx)),
+            Err(RecvError::Lagged(_) | RecvError::Closed) => None,
+        }
+    });
+    let events = stream::iter(backlog)
+        .chain(live)
+        .map(|event| Ok(to_sse_event(&event)));
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn to_sse_event(SequencedEvent { seq, event }: &SequencedEvent) -> axum::response::sse::Event {
+    let sse = axum::response::sse::Event::default()
+        .id(seq.to_string())
+        .event(event.msg.to_string());
+    match serde_json::to_string::<Event>(event) {
+        Ok(data) => sse.data(data),
+        Err(err) => sse.comment(format!("failed to serialize event: {err}")),
+    }
+}
+
+impl ServerState {
+    async fn conversation(&self, id: Uuid) -> Result<Arc<ServedConversation>, ApiError> {
+        self.conversations
+            .read()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| ApiError::conversation_not_found(id))
+    }
+
+    async fn serve(self: &Arc<Self>, new_conversation: NewConversation) -> NewConversationResponse {
+        let NewConversation {
+            conversation_id,
+            conversation,
+            session_configured,
+        } = new_conversation;
+        let model = session_configured.model.clone();
+        let first_event = Event {
+            // Core sends `SessionConfigured` with an empty submission id.
+            id: String::new(),
+            msg: codex_core::protocol::EventMsg::SessionConfigured(session_configured),
+        };
+        // Once the conversation ends, stop serving it so that it and its
+        // backlog do not outlive it.
+        let state = Arc::downgrade(self);
+        let on_closed = async move {
+            if let Some(state) = state.upgrade() {
+                state.forget(conversation_id).await;
+            }
+        };
+        // Spawn under the lock so the conversation is in the map before it
+        // can be forgotten.
+        let mut conversations = self.conversations.write().await;
+        conversations.insert(
+            conversation_id,
+            ServedConversation::spawn(conversation, first_event, on_closed),
+        );
+        drop(conversations);
+        NewConversationResponse {
+            conversation_id: ConversationId(conversation_id),
+            model,
+        }
+    }
+
+    async fn forget(&self, id: Uuid) {
+        self.conversations.write().await.remove(&id);
+        self.manager.remove_conversation(id).await;
+    }
+
+    /// The config for a new conversation: `config.toml`, then the `-c`
+    /// overrides `codex serve` was started with, then the request's.
+    fn derive_config(&self, params: NewConversationParams) -> Result<Config, ApiError> {
+        let NewConversationParams {
+            model,
+            profile,
+            cwd,
+            approval_policy,
+            sandbox: sandbox_mode,
+            config: request_overrides,
+            base_instructions,
+            include_plan_tool,
+            include_apply_patch_tool,
+        } = params;
+        let overrides = ConfigOverrides {
+            model,
+            config_profile: profile,
+            cwd: cwd.map(PathBuf::from),
+            approval_policy,
+            sandbox_mode,
+            model_provider: None,
+            codex_linux_sandbox_exe: self.codex_linux_sandbox_exe.clone(),
+            base_instructions,
+            include_plan_tool,
+            include_apply_patch_tool,
+            include_view_image_tool: None,
+            disable_response_storage: None,
+            show_raw_agent_reasoning: None,
+            tools_web_search_request: None,
+        };
+
+        let mut cli_overrides = self.cli_overrides.clone();
+        for (key, value) in request_overrides.unwrap_or_default() {
+            let value = toml::Value::try_from(value).map_err(|e| {
+                ApiError::new(
+                    StatusCode::BAD_REQUEST,
+                    format!("invalid value for config override `{key}`: {e}"),
+                )
+            })?;
+            cli_overrides.push((key, value));
+        }
+
+        Config::load_with_cli_overrides(cli_overrides, overrides).map_err(|e| {
+            ApiError::new(
+                StatusCode::BAD_REQUEST,
+                format!("error deriving config: {e}"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use codex_core::AuthManager;
+    use codex_core::CodexAuth;
+    use codex_core::ConversationManager;
+    use codex_core::config::ConfigToml;
+    use codex_core::protocol::EventMsg;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tokio::sync::RwLock;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "secret-token";
+
+    fn test_state() -> Arc<ServerState> {
+        let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("sk-test"));
+        Arc::new(ServerState {
+            manager: ConversationManager::new(auth_manager.clone()),
+            auth_manager,
+            conversations: RwLock::new(HashMap::new()),
+            cli_overrides: Vec::new(),
+            codex_linux_sandbox_exe: None,
+            token: TOKEN.to_string(),
+        })
+    }
+
+    fn test_router() -> Router {
+        router(test_state())
+    }
+
+    async fn send(request: axum::http::Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = test_router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn request(
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        body: &str,
+    ) -> axum::http::Request<Body> {
+        let mut builder = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_rejected() {
+        for token in [None, Some("wrong-token")] {
+            let (status, body) = send(request("GET", "/v1/conversations", token, "")).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            assert_eq!(body["error"], "missing or invalid bearer token");
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_conversations_and_reports_unknown_ones() {
+        let (status, body) = send(request("GET", "/v1/conversations", Some(TOKEN), "")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!({ "conversationIds": [] }));
+
+        let id = Uuid::new_v4();
+        let (status, body) = send(request(
+            "POST",
+            &format!("/v1/conversations/{id}/ops"),
+            Some(TOKEN),
+            r#"{"type":"interrupt"}"#,
+        ))
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], format!("conversation not found: {id}"));
+    }
+
+    #[tokio::test]
+    async fn conversations_are_dropped_once_they_shut_down() {
+        let codex_home = tempfile::tempdir().unwrap();
+        let config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .unwrap();
+        let state = test_state();
+        let new_conversation = state.manager.new_conversation(config).await.unwrap();
+        let id = new_conversation.conversation_id;
+        state.serve(new_conversation).await;
+
+        let served = state.conversation(id).await.unwrap();
+        let (_, mut events) = served.subscribe(None);
+        served.submit(Op::Shutdown).await.unwrap();
+        drop(served);
+
+        // Subscribers still get every event up to the shutdown.
+        loop {
+            let event = events.recv().await.unwrap();
+            if matches!(event.event.msg, EventMsg::ShutdownComplete) {
+                break;
+            }
+        }
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while state.conversation(id).await.is_ok() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(state.manager.get_conversation(id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid_config_overrides_are_a_bad_request() {
+        let (status, body) = send(request(
+            "POST",
+            "/v1/conversations",
+            Some(TOKEN),
+            r#"{"config":{"model":null}}"#,
+        ))
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("invalid value for config override `model`")
+        );
+    }
+}
@@ -1,3 +1,4 @@
 mod mcp_client;
 
+pub use mcp_client::METHOD_NOT_FOUND_ERROR_CODE;
 pub use mcp_client::McpClient;
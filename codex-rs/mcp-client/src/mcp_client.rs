@@ -6,6 +6,10 @@
 //!   2. Sending MCP requests and pairing them with their corresponding
 //!      responses.
 //!   3. Offering a convenience helper for the common `tools/list` request.
+//!   4. Fanning out server-initiated notifications and reporting when the
+//!      server exits.
+//!   5. Handing server-initiated requests to a single consumer, which answers
+//!      them with [`McpClient::send_response`] or [`McpClient::send_error`].
 //!
 //! The crate hides all JSON‐RPC framing details behind a typed API. Users
 //! interact with the [`ModelContextProtocolRequest`] trait from `mcp-types` to
@@ -27,6 +31,8 @@ use mcp_types::InitializeRequest;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializedNotification;
 use mcp_types::JSONRPC_VERSION;
+use mcp_types::JSONRPCError;
+use mcp_types::JSONRPCErrorError;
 use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
@@ -44,8 +50,10 @@ use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::broadcast;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
+use tokio::sync::watch;
 use tokio::time;
 use tracing::debug;
 use tracing::error;
@@ -76,6 +84,17 @@ pub struct McpClient {
 
     /// Monotonically increasing counter used to generate request IDs.
     id_counter: AtomicI64,
+
+    /// Server-initiated notifications, for whoever subscribed to them.
+    notifications_tx: broadcast::Sender<JSONRPCNotification>,
+
+    /// Becomes `true` once the server closes its STDOUT, which in practice
+    /// means it exited.
+    closed_rx: watch::Receiver<bool>,
+
+    /// Server-initiated requests, until someone takes them with
+    /// [`take_server_requests`](Self::take_server_requests).
+    server_requests_rx: Mutex<Option<mpsc::UnboundedReceiver<JSONRPCRequest>>>,
 }
 
 impl McpClient {
@@ -111,6 +130,9 @@ impl McpClient {
 
         let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
         let pending: Arc<Mutex<HashMap<i64, PendingSender>>> = Arc::new(Mutex::new(HashMap::new()));
+        let (notifications_tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let (closed_tx, closed_rx) = watch::channel(false);
+        let (server_requests_tx, server_requests_rx) = mpsc::unbounded_channel();
 
         // Spawn writer task. It listens on the `outgoing_rx` channel and
         // writes messages to the child's STDIN.
@@ -141,6 +163,8 @@ impl McpClient {
         // STDOUT and dispatches responses to the pending map.
         let reader_handle = {
             let pending = pending.clone();
+            let notifications_tx = notifications_tx.clone();
+            let outgoing_tx = outgoing_tx.clone();
             let mut lines = BufReader::new(stdout).lines();
 
             tokio::spawn(async move {
@@ -153,20 +177,33 @@ impl McpClient {
                         Ok(JSONRPCMessage::Error(err)) => {
                             Self::dispatch_error(err, &pending).await;
                         }
-                        Ok(JSONRPCMessage::Notification(JSONRPCNotification { .. })) => {
-                            // For now we only log server-initiated notifications.
+                        Ok(JSONRPCMessage::Notification(notification)) => {
                             info!("<- notification: {}", line);
+                            // Nobody listening is fine.
+                            let _ = notifications_tx.send(notification);
                         }
-                        Ok(other) => {
-                            // Batch responses and requests are currently not
-                            // expected from the server – log and ignore.
-                            info!("<- unhandled message: {:?}", other);
+                        Ok(JSONRPCMessage::Request(request)) => {
+                            info!("<- request: {}", line);
+                            if let Err(mpsc::error::SendError(request)) =
+                                server_requests_tx.send(request)
+                            {
+                                // Nobody handles requests; say so rather
+                                // than leaving the server waiting.
+                                let error = method_not_found(request);
+                                let _ = outgoing_tx.send(error).await;
+                            }
                         }
                         Err(e) => {
                             error!("failed to deserialize JSONRPCMessage: {e}; line = {}", line)
                         }
                     }
                 }
+
+                // The server is gone. Dropping the pending senders fails the
+                // requests still waiting for a reply instead of leaving them
+                // hanging.
+                pending.lock().await.clear();
+                let _ = closed_tx.send(true);
             })
         };
 
@@ -181,9 +218,63 @@ impl McpClient {
             outgoing_tx,
             pending,
             id_counter: AtomicI64::new(1),
+            notifications_tx,
+            closed_rx,
+            server_requests_rx: Mutex::new(Some(server_requests_rx)),
         })
     }
 
+    /// Take the requests the server sends, e.g. `sampling/createMessage`.
+    /// Only the first caller gets them, and must answer every one of them
+    /// with [`send_response`](Self::send_response) or
+    /// [`send_error`](Self::send_error).
+    pub async fn take_server_requests(&self) -> Option<mpsc::UnboundedReceiver<JSONRPCRequest>> {
+        self.server_requests_rx.lock().await.take()
+    }
+
+    /// Answer a server-initiated request.
+    pub async fn send_response(&self, id: RequestId, result: serde_json::Value) -> Result<()> {
+        let response = JSONRPCMessage::Response(JSONRPCResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result,
+        });
+        self.outgoing_tx
+            .send(response)
+            .await
+            .context("failed to send response to writer task")
+    }
+
+    /// Reject a server-initiated request.
+    pub async fn send_error(&self, id: RequestId, error: JSONRPCErrorError) -> Result<()> {
+        let error = JSONRPCMessage::Error(JSONRPCError {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            error,
+        });
+        self.outgoing_tx
+            .send(error)
+            .await
+            .context("failed to send error to writer task")
+    }
+
+    /// Receive the notifications the server sends from now on.
+    pub fn subscribe_notifications(&self) -> broadcast::Receiver<JSONRPCNotification> {
+        self.notifications_tx.subscribe()
+    }
+
+    /// Whether the server has exited.
+    pub fn is_closed(&self) -> bool {
+        *self.closed_rx.borrow()
+    }
+
+    /// Wait until the server exits.
+    pub async fn closed(&self) {
+        let mut closed_rx = self.closed_rx.clone();
+        // An error means the reader task is gone, so the server is too.
+        let _ = closed_rx.wait_for(|closed| *closed).await;
+    }
+
     /// Send an arbitrary MCP request and await the typed result.
     ///
     /// If `timeout` is `None` the call waits indefinitely. If `Some(duration)`
@@ -394,6 +485,21 @@ impl McpClient {
     }
 }
 
+/// JSON-RPC error code for a method the client does not implement.
+pub const METHOD_NOT_FOUND_ERROR_CODE: i64 = -32601;
+
+fn method_not_found(request: JSONRPCRequest) -> JSONRPCMessage {
+    JSONRPCMessage::Error(JSONRPCError {
+        jsonrpc: JSONRPC_VERSION.to_string(),
+        id: request.id,
+        error: JSONRPCErrorError {
+            code: METHOD_NOT_FOUND_ERROR_CODE,
+            message: format!("method not found: {}", request.method),
+            data: None,
+        },
+    })
+}
+
 impl Drop for McpClient {
     fn drop(&mut self) {
         // Even though we have already tagged this process with
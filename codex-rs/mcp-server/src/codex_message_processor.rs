@@ -8,6 +8,7 @@ use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::json_to_toml::json_to_toml;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use crate::resources::Resources;
 use codex_core::AuthManager;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
@@ -26,6 +27,9 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem as CoreInputItem;
+use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use codex_login::ServerOptions as LoginServerOptions;
@@ -52,6 +56,12 @@ use codex_protocol::mcp_protocol::InterruptConversationParams;
 use codex_protocol::mcp_protocol::InterruptConversationResponse;
 use codex_protocol::mcp_protocol::LoginChatGptCompleteNotification;
 use codex_protocol::mcp_protocol::LoginChatGptResponse;
+use codex_protocol::mcp_protocol::MCP_SAMPLING_APPROVAL_METHOD;
+use codex_protocol::mcp_protocol::MCP_TOOL_CALL_APPROVAL_METHOD;
+use codex_protocol::mcp_protocol::McpSamplingApprovalParams;
+use codex_protocol::mcp_protocol::McpSamplingApprovalResponse;
+use codex_protocol::mcp_protocol::McpToolCallApprovalParams;
+use codex_protocol::mcp_protocol::McpToolCallApprovalResponse;
 use codex_protocol::mcp_protocol::NewConversationParams;
 use codex_protocol::mcp_protocol::NewConversationResponse;
 use codex_protocol::mcp_protocol::RemoveConversationListenerParams;
@@ -93,6 +103,7 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: Arc<Mutex<HashMap<Uuid, Vec<RequestId>>>>,
+    resources: Arc<Resources>,
 }
 
 impl CodexMessageProcessor {
@@ -102,6 +113,7 @@ impl CodexMessageProcessor {
         outgoing: Arc<OutgoingMessageSender>,
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
+        resources: Arc<Resources>,
     ) -> Self {
         Self {
             auth_manager,
@@ -112,6 +124,7 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            resources,
         }
     }
 
@@ -518,6 +531,7 @@ impl CodexMessageProcessor {
                     session_configured,
                     ..
                 } = conversation_id;
+                self.resources.conversation_started(conversation_id).await;
                 let response = NewConversationResponse {
                     conversation_id: ConversationId(conversation_id),
                     model: session_configured.model,
@@ -684,6 +698,7 @@ impl CodexMessageProcessor {
             .insert(subscription_id, cancel_tx);
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let resources = self.resources.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
@@ -723,6 +738,7 @@ impl CodexMessageProcessor {
                             params: Some(params.into()),
                         })
                         .await;
+                        resources.observe(conversation_id.0, &event.msg).await;
 
                         apply_bespoke_event_handling(event.clone(), conversation_id, conversation.clone(), outgoing_for_task.clone(), pending_interrupts.clone()).await;
                     }
@@ -833,6 +849,57 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+            call_id,
+            invocation:
+                McpInvocation {
+                    server,
+                    tool,
+                    arguments,
+                },
+            reason,
+        }) => {
+            let params = McpToolCallApprovalParams {
+                conversation_id,
+                call_id,
+                server,
+                tool,
+                arguments,
+                reason,
+            };
+            let value = serde_json::to_value(&params).unwrap_or_default();
+            let rx = outgoing
+                .send_request(MCP_TOOL_CALL_APPROVAL_METHOD, Some(value))
+                .await;
+
+            tokio::spawn(async move {
+                on_mcp_tool_call_approval_response(event_id, rx, conversation).await;
+            });
+        }
+        EventMsg::McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent {
+            call_id,
+            server,
+            prompt,
+            system_prompt,
+            max_tokens,
+        }) => {
+            let params = McpSamplingApprovalParams {
+                conversation_id,
+                call_id,
+                server,
+                prompt,
+                system_prompt,
+                max_tokens,
+            };
+            let value = serde_json::to_value(&params).unwrap_or_default();
+            let rx = outgoing
+                .send_request(MCP_SAMPLING_APPROVAL_METHOD, Some(value))
+                .await;
+
+            tokio::spawn(async move {
+                on_mcp_sampling_approval_response(event_id, rx, conversation).await;
+            });
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -971,3 +1038,65 @@ async fn on_exec_approval_response(
         error!("failed to submit ExecApproval: {err}");
     }
 }
+
+async fn on_mcp_tool_call_approval_response(
+    event_id: String,
+    receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
+    conversation: Arc<CodexConversation>,
+) {
+    // A failed request or an unreadable response denies the call, to be
+    // conservative.
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpToolCallApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpToolCallApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::McpToolCallApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpToolCallApproval: {err}");
+    }
+}
+
+async fn on_mcp_sampling_approval_response(
+    event_id: String,
+    receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
+    conversation: Arc<CodexConversation>,
+) {
+    // A failed request or an unreadable response denies the request, to be
+    // conservative.
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpSamplingApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpSamplingApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = conversation
+        .submit(Op::McpSamplingApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpSamplingApproval: {err}");
+    }
+}
@@ -15,6 +15,7 @@ use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
@@ -27,9 +28,12 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::exec_approval::handle_exec_approval_request;
+use crate::mcp_sampling_approval::handle_mcp_sampling_approval_request;
+use crate::mcp_tool_call_approval::handle_mcp_tool_call_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
+use crate::resources::Resources;
 
 pub(crate) const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
 
@@ -44,6 +48,7 @@ pub async fn run_codex_tool_session(
     outgoing: Arc<OutgoingMessageSender>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, Uuid>>>,
+    resources: Arc<Resources>,
 ) {
     let NewConversation {
         conversation_id,
@@ -66,6 +71,8 @@ pub async fn run_codex_tool_session(
         }
     };
 
+    resources.conversation_started(conversation_id).await;
+
     let session_configured_event = Event {
         // Use a fake id value for now.
         id: "".to_string(),
@@ -110,6 +117,8 @@ pub async fn run_codex_tool_session(
         outgoing,
         id,
         running_requests_id_to_codex_uuid,
+        resources,
+        conversation_id,
     )
     .await;
 }
@@ -120,6 +129,7 @@ pub async fn run_codex_tool_session_reply(
     request_id: RequestId,
     prompt: String,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, Uuid>>>,
+    resources: Arc<Resources>,
     session_id: Uuid,
 ) {
     running_requests_id_to_codex_uuid
@@ -146,6 +156,8 @@ pub async fn run_codex_tool_session_reply(
         outgoing,
         request_id,
         running_requests_id_to_codex_uuid,
+        resources,
+        session_id,
     )
     .await;
 }
@@ -155,6 +167,8 @@ async fn run_codex_tool_session_inner(
     outgoing: Arc<OutgoingMessageSender>,
     request_id: RequestId,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, Uuid>>>,
+    resources: Arc<Resources>,
+    conversation_id: Uuid,
 ) {
     let request_id_str = match &request_id {
         RequestId::String(s) => s.clone(),
@@ -172,6 +186,7 @@ async fn run_codex_tool_session_inner(
                         Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
                     )
                     .await;
+                resources.observe(conversation_id, &event.msg).await;
 
                 match event.msg {
                     EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
@@ -221,6 +236,36 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
+                    EventMsg::McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent {
+                        call_id,
+                        invocation,
+                        reason,
+                    }) => {
+                        handle_mcp_tool_call_approval_request(
+                            invocation,
+                            reason,
+                            outgoing.clone(),
+                            codex.clone(),
+                            request_id.clone(),
+                            request_id_str.clone(),
+                            event.id.clone(),
+                            call_id,
+                        )
+                        .await;
+                        continue;
+                    }
+                    EventMsg::McpSamplingApprovalRequest(request) => {
+                        handle_mcp_sampling_approval_request(
+                            request,
+                            outgoing.clone(),
+                            codex.clone(),
+                            request_id.clone(),
+                            request_id_str.clone(),
+                            event.id.clone(),
+                        )
+                        .await;
+                        continue;
+                    }
                     EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
                         let text = match last_agent_message {
                             Some(msg) => msg.clone(),
@@ -259,12 +304,18 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::AgentReasoningRawContentDelta(_)
                     | EventMsg::TaskStarted(_)
                     | EventMsg::TokenCount(_)
+                    | EventMsg::AuxiliaryTokenCount(_)
+                    | EventMsg::ReasoningEffortChosen(_)
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
+                    | EventMsg::McpSamplingBegin(_)
+                    | EventMsg::McpSamplingEnd(_)
                     | EventMsg::McpListToolsResponse(_)
+                    | EventMsg::McpListServersResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListApprovalRulesResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
@@ -277,6 +328,8 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
+                    | EventMsg::CommitMessageDraft(_)
+                    | EventMsg::PlanSummary(_)
                     | EventMsg::TurnAborted(_)
                     | EventMsg::ConversationHistory(_)
                     | EventMsg::UserMessage(_)
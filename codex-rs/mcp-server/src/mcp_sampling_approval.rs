@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use codex_core::CodexConversation;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use mcp_types::ElicitRequest;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::JSONRPCErrorError;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tracing::error;
+
+use crate::codex_tool_runner::INVALID_PARAMS_ERROR_CODE;
+
+/// Conforms to [`mcp_types::ElicitRequestParams`] so that it can be used as the
+/// `params` field of an [`ElicitRequest`].
+#[derive(Debug, Deserialize, Serialize)]
+pub struct McpSamplingApprovalElicitRequestParams {
+    // These fields are required so that `params`
+    // conforms to ElicitRequestParams.
+    pub message: String,
+
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+
+    // These are additional fields the client can use to
+    // correlate the request with the codex tool call.
+    pub codex_elicitation: String,
+    pub codex_mcp_tool_call_id: String,
+    pub codex_event_id: String,
+    pub codex_call_id: String,
+    pub codex_server: String,
+    pub codex_prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codex_system_prompt: Option<String>,
+    pub codex_max_tokens: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpSamplingApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
+pub(crate) async fn handle_mcp_sampling_approval_request(
+    request: McpSamplingApprovalRequestEvent,
+    outgoing: Arc<crate::outgoing_message::OutgoingMessageSender>,
+    codex: Arc<CodexConversation>,
+    request_id: RequestId,
+    tool_call_id: String,
+    event_id: String,
+) {
+    let McpSamplingApprovalRequestEvent {
+        call_id,
+        server,
+        prompt,
+        system_prompt,
+        max_tokens,
+    } = request;
+
+    let params = McpSamplingApprovalElicitRequestParams {
+        message: format!("Allow `{server}` to ask Codex's model for a completion?"),
+        requested_schema: ElicitRequestParamsRequestedSchema {
+            r#type: "object".to_string(),
+            properties: json!({}),
+            required: None,
+        },
+        codex_elicitation: "mcp-sampling-approval".to_string(),
+        codex_mcp_tool_call_id: tool_call_id,
+        codex_event_id: event_id.clone(),
+        codex_call_id: call_id,
+        codex_server: server,
+        codex_prompt: prompt,
+        codex_system_prompt: system_prompt,
+        codex_max_tokens: max_tokens,
+    };
+    let params_json = match serde_json::to_value(&params) {
+        Ok(value) => value,
+        Err(err) => {
+            let message =
+                format!("Failed to serialize McpSamplingApprovalElicitRequestParams: {err}");
+            error!("{message}");
+
+            outgoing
+                .send_error(
+                    request_id.clone(),
+                    JSONRPCErrorError {
+                        code: INVALID_PARAMS_ERROR_CODE,
+                        message,
+                        data: None,
+                    },
+                )
+                .await;
+
+            return;
+        }
+    };
+
+    let on_response = outgoing
+        .send_request(ElicitRequest::METHOD, Some(params_json))
+        .await;
+
+    // Listen for the response on a separate task so we don't block the main agent loop.
+    tokio::spawn(async move {
+        on_mcp_sampling_approval_response(event_id, on_response, codex).await;
+    });
+}
+
+async fn on_mcp_sampling_approval_response(
+    event_id: String,
+    receiver: tokio::sync::oneshot::Receiver<mcp_types::Result>,
+    codex: Arc<CodexConversation>,
+) {
+    // If the request fails or the response cannot be deserialized, we deny
+    // the request to be conservative.
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpSamplingApprovalResponse>(value)
+            .map(|response| response.decision)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpSamplingApprovalResponse: {err}");
+                ReviewDecision::Denied
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            ReviewDecision::Denied
+        }
+    };
+
+    if let Err(err) = codex
+        .submit(Op::McpSamplingApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit McpSamplingApproval: {err}");
+    }
+}
@@ -6,8 +6,11 @@ use crate::codex_tool_config::CodexToolCallParam;
 use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
+use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::error_code::RESOURCE_NOT_FOUND_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::resources::Resources;
 use codex_protocol::mcp_protocol::ClientRequest;
 
 use codex_core::AuthManager;
@@ -26,6 +29,7 @@ use mcp_types::JSONRPCResponse;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
 use mcp_types::RequestId;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
@@ -42,6 +46,7 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, Uuid>>>,
+    resources: Arc<Resources>,
 }
 
 impl MessageProcessor {
@@ -59,12 +64,14 @@ impl MessageProcessor {
             config.responses_originator_header.clone(),
         );
         let conversation_manager = Arc::new(ConversationManager::new(auth_manager.clone()));
+        let resources = Arc::new(Resources::new(config.clone(), outgoing.clone()));
         let codex_message_processor = CodexMessageProcessor::new(
             auth_manager,
             conversation_manager.clone(),
             outgoing.clone(),
             codex_linux_sandbox_exe.clone(),
             config,
+            resources.clone(),
         );
         Self {
             codex_message_processor,
@@ -73,6 +80,7 @@ impl MessageProcessor {
             codex_linux_sandbox_exe,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            resources,
         }
     }
 
@@ -108,19 +116,20 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
-                self.handle_list_resource_templates(params);
+                self.handle_list_resource_templates(request_id, params)
+                    .await;
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
-                self.handle_subscribe(params);
+                self.handle_subscribe(request_id, params).await;
             }
             McpClientRequest::UnsubscribeRequest(params) => {
-                self.handle_unsubscribe(params);
+                self.handle_unsubscribe(request_id, params).await;
             }
             McpClientRequest::ListPromptsRequest(params) => {
                 self.handle_list_prompts(params);
@@ -219,7 +228,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: Some(ServerCapabilitiesResources {
+                    list_changed: Some(true),
+                    subscribe: Some(true),
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -255,40 +267,92 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        match self.resources.list().await {
+            Ok(result) => {
+                self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+                    .await;
+            }
+            Err(e) => {
+                self.send_internal_error(id, format!("failed to list resources: {e}"))
+                    .await;
+            }
+        }
     }
 
-    fn handle_list_resource_templates(
+    async fn handle_list_resource_templates(
         &self,
+        id: RequestId,
         params:
             <mcp_types::ListResourceTemplatesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/templates/list -> params: {:?}", params);
+        let result = self.resources.list_templates();
+        self.send_response::<mcp_types::ListResourceTemplatesRequest>(id, result)
+            .await;
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        match self.resources.read(&params.uri).await {
+            Ok(Some(result)) => {
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Ok(None) => {
+                let error = JSONRPCErrorError {
+                    code: RESOURCE_NOT_FOUND_ERROR_CODE,
+                    message: format!("resource not found: {}", params.uri),
+                    data: Some(json!({ "uri": params.uri })),
+                };
+                self.outgoing.send_error(id, error).await;
+            }
+            Err(e) => {
+                self.send_internal_error(id, format!("failed to read {}: {e}", params.uri))
+                    .await;
+            }
+        }
     }
 
-    fn handle_subscribe(
+    async fn handle_subscribe(
         &self,
+        id: RequestId,
         params: <mcp_types::SubscribeRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/subscribe -> params: {:?}", params);
+        self.resources.subscribe(params.uri).await;
+        self.send_response::<mcp_types::SubscribeRequest>(id, json!({}))
+            .await;
     }
 
-    fn handle_unsubscribe(
+    async fn handle_unsubscribe(
         &self,
+        id: RequestId,
         params: <mcp_types::UnsubscribeRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/unsubscribe -> params: {:?}", params);
+        self.resources.unsubscribe(&params.uri).await;
+        self.send_response::<mcp_types::UnsubscribeRequest>(id, json!({}))
+            .await;
+    }
+
+    async fn send_internal_error(&self, id: RequestId, message: String) {
+        tracing::error!("{message}");
+        let error = JSONRPCErrorError {
+            code: INTERNAL_ERROR_CODE,
+            message,
+            data: None,
+        };
+        self.outgoing.send_error(id, error).await;
     }
 
     fn handle_list_prompts(
@@ -411,6 +475,7 @@ impl MessageProcessor {
         let outgoing = self.outgoing.clone();
         let conversation_manager = self.conversation_manager.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let resources = self.resources.clone();
 
         // Spawn an async task to handle the Codex session so that we do not
         // block the synchronous message-processing loop.
@@ -423,6 +488,7 @@ impl MessageProcessor {
                 outgoing,
                 conversation_manager,
                 running_requests_id_to_codex_uuid,
+                resources,
             )
             .await;
         });
@@ -520,6 +586,7 @@ impl MessageProcessor {
             let outgoing = outgoing.clone();
             let prompt = prompt.clone();
             let running_requests_id_to_codex_uuid = running_requests_id_to_codex_uuid.clone();
+            let resources = self.resources.clone();
 
             async move {
                 crate::codex_tool_runner::run_codex_tool_session_reply(
@@ -528,6 +595,7 @@ impl MessageProcessor {
                     request_id,
                     prompt,
                     running_requests_id_to_codex_uuid,
+                    resources,
                     session_id,
                 )
                 .await;
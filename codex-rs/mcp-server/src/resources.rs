@@ -0,0 +1,488 @@
+//! Resources published by the MCP server: the transcripts of recent
+//! conversations, the current diff and plan of conversations started through
+//! the `codex` tool or `newConversation`, and the `AGENTS.md` project docs.
+//!
+//! Clients that subscribe to a resource receive
+//! `notifications/resources/updated` whenever a running conversation changes
+//! it. The diff and plan of a conversation disappear once it shuts down, or
+//! once [`MAX_LIVE_CONVERSATIONS`] newer conversations have started.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::ConversationItem;
+use codex_core::Cursor;
+use codex_core::RolloutRecorder;
+use codex_core::config::Config;
+use codex_core::plan_tool::UpdatePlanArgs;
+use codex_core::project_doc::discover_project_doc_paths;
+use codex_core::protocol::EventMsg;
+use mcp_types::ListResourceTemplatesResult;
+use mcp_types::ListResourcesResult;
+use mcp_types::ModelContextProtocolNotification;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::ResourceListChangedNotification;
+use mcp_types::ResourceTemplate;
+use mcp_types::ResourceUpdatedNotification;
+use mcp_types::ResourceUpdatedNotificationParams;
+use mcp_types::TextResourceContents;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
+
+const CONVERSATION_URI_PREFIX: &str = "codex://conversations/";
+
+/// How many recent conversations `resources/list` returns.
+const RECENT_CONVERSATIONS: usize = 20;
+
+/// How many conversations' diffs and plans are kept. Conversations run through
+/// the `codex` tool are never shut down, so the oldest are forgotten instead.
+const MAX_LIVE_CONVERSATIONS: usize = 64;
+
+const TRANSCRIPT_MIME_TYPE: &str = "application/jsonl";
+const DIFF_MIME_TYPE: &str = "text/x-diff";
+const PLAN_MIME_TYPE: &str = "application/json";
+const PROJECT_DOC_MIME_TYPE: &str = "text/markdown";
+
+/// A resource that belongs to a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConversationResource {
+    /// The rollout file, one JSON record per line.
+    Transcript,
+    /// The unified diff of the files changed in the current turn.
+    Diff,
+    /// The latest plan passed to `update_plan`.
+    Plan,
+}
+
+impl ConversationResource {
+    fn uri(self, conversation_id: Uuid) -> String {
+        match self {
+            Self::Transcript => format!("{CONVERSATION_URI_PREFIX}{conversation_id}"),
+            Self::Diff => format!("{CONVERSATION_URI_PREFIX}{conversation_id}/diff"),
+            Self::Plan => format!("{CONVERSATION_URI_PREFIX}{conversation_id}/plan"),
+        }
+    }
+
+    fn uri_template(self) -> String {
+        match self {
+            Self::Transcript => format!("{CONVERSATION_URI_PREFIX}{{conversationId}}"),
+            Self::Diff => format!("{CONVERSATION_URI_PREFIX}{{conversationId}}/diff"),
+            Self::Plan => format!("{CONVERSATION_URI_PREFIX}{{conversationId}}/plan"),
+        }
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            Self::Transcript => TRANSCRIPT_MIME_TYPE,
+            Self::Diff => DIFF_MIME_TYPE,
+            Self::Plan => PLAN_MIME_TYPE,
+        }
+    }
+}
+
+/// Split a `codex://conversations/...` URI into the conversation and the
+/// resource it names.
+fn parse_conversation_uri(uri: &str) -> Option<(Uuid, ConversationResource)> {
+    let rest = uri.strip_prefix(CONVERSATION_URI_PREFIX)?;
+    let (id, resource) = match rest.split_once('/') {
+        None => (rest, ConversationResource::Transcript),
+        Some((id, "diff")) => (id, ConversationResource::Diff),
+        Some((id, "plan")) => (id, ConversationResource::Plan),
+        Some(_) => return None,
+    };
+    Uuid::parse_str(id).ok().map(|id| (id, resource))
+}
+
+/// What the server knows about a conversation it is running.
+#[derive(Default)]
+struct LiveConversation {
+    diff: Option<String>,
+    plan: Option<UpdatePlanArgs>,
+}
+
+#[derive(Default)]
+struct ResourcesState {
+    live: HashMap<Uuid, LiveConversation>,
+    /// The keys of `live`, oldest first.
+    started: VecDeque<Uuid>,
+    subscriptions: HashSet<String>,
+}
+
+impl ResourcesState {
+    fn start(&mut self, conversation_id: Uuid) {
+        self.live
+            .insert(conversation_id, LiveConversation::default());
+        self.started.push_back(conversation_id);
+        while self.started.len() > MAX_LIVE_CONVERSATIONS {
+            if let Some(oldest) = self.started.pop_front() {
+                self.live.remove(&oldest);
+            }
+        }
+    }
+
+    /// Stop tracking `conversation_id`. Returns whether it was tracked.
+    fn forget(&mut self, conversation_id: Uuid) -> bool {
+        self.started.retain(|id| *id != conversation_id);
+        self.live.remove(&conversation_id).is_some()
+    }
+}
+
+pub(crate) struct Resources {
+    config: Arc<Config>,
+    outgoing: Arc<OutgoingMessageSender>,
+    state: Mutex<ResourcesState>,
+}
+
+impl Resources {
+    pub(crate) fn new(config: Arc<Config>, outgoing: Arc<OutgoingMessageSender>) -> Self {
+        Self {
+            config,
+            outgoing,
+            state: Mutex::new(ResourcesState::default()),
+        }
+    }
+
+    /// Start tracking a conversation run through the `codex` tool or
+    /// `newConversation`.
+    pub(crate) async fn conversation_started(&self, conversation_id: Uuid) {
+        self.state.lock().await.start(conversation_id);
+        self.send_notification(ResourceListChangedNotification::METHOD, None)
+            .await;
+    }
+
+    /// Update the resources of a running conversation from one of its events
+    /// and notify subscribers of those that changed. Once the conversation
+    /// shuts down, its diff and plan are dropped.
+    pub(crate) async fn observe(&self, conversation_id: Uuid, msg: &EventMsg) {
+        if matches!(msg, EventMsg::ShutdownComplete) {
+            if self.state.lock().await.forget(conversation_id) {
+                self.send_notification(ResourceListChangedNotification::METHOD, None)
+                    .await;
+            }
+            return;
+        }
+
+        let changed = {
+            let mut state = self.state.lock().await;
+            match msg {
+                EventMsg::TurnDiff(event) => {
+                    let Some(live) = state.live.get_mut(&conversation_id) else {
+                        return;
+                    };
+                    live.diff = Some(event.unified_diff.clone());
+                    ConversationResource::Diff
+                }
+                EventMsg::PlanUpdate(plan) => {
+                    let Some(live) = state.live.get_mut(&conversation_id) else {
+                        return;
+                    };
+                    live.plan = Some(plan.clone());
+                    ConversationResource::Plan
+                }
+                EventMsg::UserMessage(_)
+                | EventMsg::AgentMessage(_)
+                | EventMsg::ExecCommandEnd(_)
+                | EventMsg::PatchApplyEnd(_)
+                | EventMsg::McpToolCallEnd(_)
+                | EventMsg::TaskComplete(_) => ConversationResource::Transcript,
+                _ => return,
+            }
+        };
+
+        let uri = changed.uri(conversation_id);
+        if self.state.lock().await.subscriptions.contains(&uri) {
+            let params = ResourceUpdatedNotificationParams { uri };
+            self.send_notification(
+                ResourceUpdatedNotification::METHOD,
+                serde_json::to_value(params).ok(),
+            )
+            .await;
+        }
+    }
+
+    pub(crate) async fn subscribe(&self, uri: String) {
+        self.state.lock().await.subscriptions.insert(uri);
+    }
+
+    pub(crate) async fn unsubscribe(&self, uri: &str) {
+        self.state.lock().await.subscriptions.remove(uri);
+    }
+
+    pub(crate) async fn list(&self) -> std::io::Result<ListResourcesResult> {
+        let mut resources: Vec<Resource> = self
+            .project_docs()
+            .into_iter()
+            .map(|path| Resource {
+                annotations: None,
+                description: Some("Project instructions read by Codex".to_string()),
+                mime_type: Some(PROJECT_DOC_MIME_TYPE.to_string()),
+                name: path.display().to_string(),
+                size: None,
+                title: None,
+                uri: file_uri(&path),
+            })
+            .collect();
+
+        let page = RolloutRecorder::list_conversations(
+            &self.config.codex_home,
+            RECENT_CONVERSATIONS,
+            None,
+        )
+        .await?;
+        let mut listed = HashSet::new();
+        for item in &page.items {
+            if let Some(id) = conversation_id(item) {
+                listed.insert(id);
+                resources.push(conversation_resource(id, ConversationResource::Transcript));
+            }
+        }
+
+        let state = self.state.lock().await;
+        for (id, live) in &state.live {
+            if !listed.contains(id) {
+                resources.push(conversation_resource(*id, ConversationResource::Transcript));
+            }
+            if live.diff.is_some() {
+                resources.push(conversation_resource(*id, ConversationResource::Diff));
+            }
+            if live.plan.is_some() {
+                resources.push(conversation_resource(*id, ConversationResource::Plan));
+            }
+        }
+
+        Ok(ListResourcesResult {
+            next_cursor: None,
+            resources,
+        })
+    }
+
+    pub(crate) fn list_templates(&self) -> ListResourceTemplatesResult {
+        let template =
+            |resource: ConversationResource, name: &str, description: &str| ResourceTemplate {
+                annotations: None,
+                description: Some(description.to_string()),
+                mime_type: Some(resource.mime_type().to_string()),
+                name: name.to_string(),
+                title: None,
+                uri_template: resource.uri_template(),
+            };
+        ListResourceTemplatesResult {
+            next_cursor: None,
+            resource_templates: vec![
+                template(
+                    ConversationResource::Transcript,
+                    "transcript",
+                    "Rollout of a conversation, one JSON record per line",
+                ),
+                template(
+                    ConversationResource::Diff,
+                    "diff",
+                    "Unified diff of the files changed in the conversation's current turn",
+                ),
+                template(
+                    ConversationResource::Plan,
+                    "plan",
+                    "The conversation's latest plan",
+                ),
+            ],
+        }
+    }
+
+    /// Read the resource at `uri`. Returns `Ok(None)` when there is no such
+    /// resource.
+    pub(crate) async fn read(&self, uri: &str) -> std::io::Result<Option<ReadResourceResult>> {
+        let (text, mime_type) = if let Some((id, resource)) = parse_conversation_uri(uri) {
+            let text = match resource {
+                ConversationResource::Transcript => match self.find_rollout(id).await? {
+                    Some(path) => RolloutRecorder::read_transcript(&path).await?,
+                    None => return Ok(None),
+                },
+                ConversationResource::Diff | ConversationResource::Plan => {
+                    let state = self.state.lock().await;
+                    let Some(live) = state.live.get(&id) else {
+                        return Ok(None);
+                    };
+                    if resource == ConversationResource::Diff {
+                        live.diff.clone().unwrap_or_default()
+                    } else {
+                        match &live.plan {
+                            Some(plan) => serde_json::to_string_pretty(plan)?,
+                            None => "null".to_string(),
+                        }
+                    }
+                }
+            };
+            (text, resource.mime_type())
+        } else if let Some(path) = self
+            .project_docs()
+            .into_iter()
+            .find(|path| file_uri(path) == uri)
+        {
+            (
+                tokio::fs::read_to_string(&path).await?,
+                PROJECT_DOC_MIME_TYPE,
+            )
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some(mime_type.to_string()),
+                    text,
+                    uri: uri.to_string(),
+                },
+            )],
+        }))
+    }
+
+    fn project_docs(&self) -> Vec<PathBuf> {
+        discover_project_doc_paths(&self.config).unwrap_or_else(|e| {
+            tracing::warn!("failed to discover project docs: {e}");
+            Vec::new()
+        })
+    }
+
+    /// Find the rollout file of `conversation_id`, newest conversations first.
+    async fn find_rollout(&self, conversation_id: Uuid) -> std::io::Result<Option<PathBuf>> {
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let page = RolloutRecorder::list_conversations(
+                &self.config.codex_home,
+                RECENT_CONVERSATIONS,
+                cursor.as_ref(),
+            )
+            .await?;
+            if let Some(item) = page
+                .items
+                .into_iter()
+                .find(|item| conversation_id_from_path(item) == Some(conversation_id))
+            {
+                return Ok(Some(item.path));
+            }
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    async fn send_notification(&self, method: &str, params: Option<serde_json::Value>) {
+        self.outgoing
+            .send_notification(OutgoingNotification {
+                method: method.to_string(),
+                params,
+            })
+            .await;
+    }
+}
+
+fn conversation_resource(conversation_id: Uuid, resource: ConversationResource) -> Resource {
+    let (name, description) = match resource {
+        ConversationResource::Transcript => ("transcript", "Rollout of the conversation"),
+        ConversationResource::Diff => ("diff", "Files changed in the current turn"),
+        ConversationResource::Plan => ("plan", "The conversation's latest plan"),
+    };
+    Resource {
+        annotations: None,
+        description: Some(description.to_string()),
+        mime_type: Some(resource.mime_type().to_string()),
+        name: format!("{conversation_id}/{name}"),
+        size: None,
+        title: None,
+        uri: resource.uri(conversation_id),
+    }
+}
+
+/// The conversation a listed rollout belongs to, from its session meta record.
+fn conversation_id(item: &ConversationItem) -> Option<Uuid> {
+    item.head
+        .first()
+        .and_then(|meta| meta.get("id"))
+        .and_then(|id| id.as_str())
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .or_else(|| conversation_id_from_path(item))
+}
+
+/// Rollout files are named `rollout-<timestamp>-<conversation id>.jsonl`.
+fn conversation_id_from_path(item: &ConversationItem) -> Option<Uuid> {
+    let name = item.path.file_name()?.to_str()?;
+    let stem = name.split('.').next()?;
+    let id = stem.get(stem.len().checked_sub(36)?..)?;
+    Uuid::parse_str(id).ok()
+}
+
+fn file_uri(path: &std::path::Path) -> String {
+    format!("file://{}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn conversation_uris_round_trip() {
+        let id = Uuid::new_v4();
+        for resource in [
+            ConversationResource::Transcript,
+            ConversationResource::Diff,
+            ConversationResource::Plan,
+        ] {
+            assert_eq!(
+                parse_conversation_uri(&resource.uri(id)),
+                Some((id, resource))
+            );
+        }
+        assert_eq!(
+            parse_conversation_uri(&format!("{CONVERSATION_URI_PREFIX}{id}/other")),
+            None
+        );
+        assert_eq!(
+            parse_conversation_uri("codex://conversations/not-a-uuid"),
+            None
+        );
+    }
+
+    #[test]
+    fn conversation_id_comes_from_rollout_file_name() {
+        let id = Uuid::new_v4();
+        let item = ConversationItem {
+            path: PathBuf::from(format!(
+                "/home/me/.codex/sessions/2025/01/02/rollout-2025-01-02T03-04-05-{id}.jsonl.gz"
+            )),
+            head: Vec::new(),
+            title: None,
+        };
+        assert_eq!(conversation_id(&item), Some(id));
+    }
+
+    #[test]
+    fn live_conversations_are_forgotten_when_they_end_or_get_old() {
+        let mut state = ResourcesState::default();
+        let ended = Uuid::new_v4();
+        state.start(ended);
+        assert!(state.forget(ended));
+        assert!(!state.forget(ended));
+        assert!(state.live.is_empty());
+
+        let ids: Vec<Uuid> = (0..=MAX_LIVE_CONVERSATIONS)
+            .map(|_| Uuid::new_v4())
+            .collect();
+        for id in &ids {
+            state.start(*id);
+        }
+        assert_eq!(state.live.len(), MAX_LIVE_CONVERSATIONS);
+        assert!(!state.live.contains_key(&ids[0]));
+        assert!(state.live.contains_key(&ids[MAX_LIVE_CONVERSATIONS]));
+    }
+}
@@ -132,6 +132,10 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "resources": {
+                            "listChanged": true,
+                            "subscribe": true
+                        },
                         "tools": {
                             "listChanged": true
                         },
@@ -175,6 +179,30 @@ impl McpProcess {
         .await
     }
 
+    /// Send a `resources/list` JSON-RPC request.
+    pub async fn send_list_resources_request(&mut self) -> anyhow::Result<i64> {
+        self.send_request(mcp_types::ListResourcesRequest::METHOD, None)
+            .await
+    }
+
+    /// Send a `resources/read` JSON-RPC request.
+    pub async fn send_read_resource_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        self.send_request(
+            mcp_types::ReadResourceRequest::METHOD,
+            Some(serde_json::json!({ "uri": uri })),
+        )
+        .await
+    }
+
+    /// Send a `resources/subscribe` JSON-RPC request.
+    pub async fn send_subscribe_resource_request(&mut self, uri: &str) -> anyhow::Result<i64> {
+        self.send_request(
+            mcp_types::SubscribeRequest::METHOD,
+            Some(serde_json::json!({ "uri": uri })),
+        )
+        .await
+    }
+
     /// Send a `newConversation` JSON-RPC request.
     pub async fn send_new_conversation_request(
         &mut self,
@@ -163,6 +163,153 @@ async fn shell_command_approval_triggers_elicitation() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Test that the client can approve an edited version of the command, in
+/// which case the edited command runs instead of the proposed one.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_shell_command_approval_with_edits_runs_edited_command() {
+    if env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    if let Err(err) = shell_command_approval_with_edits_runs_edited_command().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn shell_command_approval_with_edits_runs_edited_command() -> anyhow::Result<()> {
+    let workdir = TempDir::new()?;
+    let touch = |name: &str| {
+        vec![
+            "python3".to_string(),
+            "-c".to_string(),
+            format!("import pathlib; pathlib.Path('{name}').touch()"),
+        ]
+    };
+
+    let McpHandle {
+        process: mut mcp_process,
+        server: _server,
+        dir: _dir,
+    } = create_mcp_process(vec![
+        create_shell_sse_response(
+            touch("proposed.txt"),
+            Some(workdir.path()),
+            Some(5_000),
+            "call1234",
+        )?,
+        create_final_assistant_message_sse_response("File created!")?,
+    ])
+    .await?;
+
+    let codex_request_id = mcp_process
+        .send_codex_tool_call(CodexToolCallParam {
+            prompt: "create a file".to_string(),
+            ..Default::default()
+        })
+        .await?;
+    let elicitation_request = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_request_message(),
+    )
+    .await??;
+
+    mcp_process
+        .send_response(
+            elicitation_request.id,
+            serde_json::to_value(ExecApprovalResponse {
+                decision: ReviewDecision::ApprovedWithEdits {
+                    command: touch("edited.txt"),
+                },
+            })?,
+        )
+        .await?;
+
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_response_message(RequestId::Integer(codex_request_id)),
+    )
+    .await??;
+
+    assert!(workdir.path().join("edited.txt").is_file());
+    assert!(!workdir.path().join("proposed.txt").exists());
+
+    Ok(())
+}
+
+/// Test that feedback attached to a denial is returned to the model as the
+/// output of the rejected tool call.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_shell_command_denied_with_feedback_reports_feedback_to_model() {
+    if let Err(err) = shell_command_denied_with_feedback_reports_feedback_to_model().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn shell_command_denied_with_feedback_reports_feedback_to_model() -> anyhow::Result<()> {
+    let workdir = TempDir::new()?;
+    let McpHandle {
+        process: mut mcp_process,
+        server,
+        dir: _dir,
+    } = create_mcp_process(vec![
+        create_shell_sse_response(
+            vec!["rm".to_string(), "-rf".to_string(), "target".to_string()],
+            Some(workdir.path()),
+            Some(5_000),
+            "call1234",
+        )?,
+        create_final_assistant_message_sse_response("Okay, using cargo clean.")?,
+    ])
+    .await?;
+
+    let codex_request_id = mcp_process
+        .send_codex_tool_call(CodexToolCallParam {
+            prompt: "clean the build".to_string(),
+            ..Default::default()
+        })
+        .await?;
+    let elicitation_request = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_request_message(),
+    )
+    .await??;
+
+    mcp_process
+        .send_response(
+            elicitation_request.id,
+            serde_json::to_value(ExecApprovalResponse {
+                decision: ReviewDecision::DeniedWithFeedback {
+                    feedback: "use cargo clean instead".to_string(),
+                },
+            })?,
+        )
+        .await?;
+
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp_process.read_stream_until_response_message(RequestId::Integer(codex_request_id)),
+    )
+    .await??;
+
+    let requests = server
+        .received_requests()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("mock server should record requests"))?;
+    let follow_up = requests
+        .last()
+        .ok_or_else(|| anyhow::anyhow!("expected a follow-up request"))?;
+    let body = String::from_utf8_lossy(&follow_up.body);
+    assert!(
+        body.contains("use cargo clean instead"),
+        "feedback should be sent to the model: {body}"
+    );
+
+    Ok(())
+}
+
 fn create_expected_elicitation_request(
     elicitation_request_id: RequestId,
     command: Vec<String>,
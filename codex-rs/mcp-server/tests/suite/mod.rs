@@ -6,4 +6,5 @@ mod config;
 mod create_conversation;
 mod interrupt;
 mod login;
+mod resources;
 mod send_message;
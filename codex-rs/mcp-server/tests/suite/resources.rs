@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_mcp_server::CodexToolCallParam;
+use codex_protocol::mcp_protocol::AddConversationListenerParams;
+use codex_protocol::mcp_protocol::InputItem;
+use codex_protocol::mcp_protocol::NewConversationParams;
+use codex_protocol::mcp_protocol::NewConversationResponse;
+use codex_protocol::mcp_protocol::SendUserMessageParams;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::RequestId;
+use mcp_types::ResourceUpdatedNotificationParams;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_final_assistant_message_sse_response;
+use mcp_test_support::create_mock_chat_completions_server;
+use mcp_test_support::to_response;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_conversation_transcript_is_a_resource() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    if let Err(err) = conversation_transcript_is_a_resource().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn conversation_transcript_is_a_resource() -> anyhow::Result<()> {
+    let server =
+        create_mock_chat_completions_server(vec![create_final_assistant_message_sse_response(
+            "Done",
+        )?])
+        .await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let prompt = "summarize the resources test";
+    let tool_call_id = mcp
+        .send_codex_tool_call(CodexToolCallParam {
+            prompt: prompt.to_string(),
+            ..Default::default()
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(tool_call_id)),
+    )
+    .await??;
+
+    let list_id = mcp.send_list_resources_request().await?;
+    let list: ListResourcesResult = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+        )
+        .await??,
+    )?;
+    let transcript_uri = list
+        .resources
+        .iter()
+        .map(|resource| resource.uri.clone())
+        .find(|uri| {
+            uri.strip_prefix("codex://conversations/")
+                .is_some_and(|rest| !rest.contains('/'))
+        })
+        .ok_or_else(|| anyhow::anyhow!("no transcript in {list:?}"))?;
+
+    // The rollout is written in the background, so give it a moment.
+    for _ in 0..20 {
+        let read_id = mcp.send_read_resource_request(&transcript_uri).await?;
+        let read: ReadResourceResult = to_response(
+            timeout(
+                DEFAULT_READ_TIMEOUT,
+                mcp.read_stream_until_response_message(RequestId::Integer(read_id)),
+            )
+            .await??,
+        )?;
+        let [ReadResourceResultContents::TextResourceContents(contents)] = read.contents.as_slice()
+        else {
+            anyhow::bail!("unexpected contents: {read:?}");
+        };
+        if contents.text.contains(prompt) {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    anyhow::bail!("transcript never contained the prompt")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_new_conversation_resources_are_observed() {
+    if std::env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return;
+    }
+
+    if let Err(err) = new_conversation_resources_are_observed().await {
+        panic!("failure: {err}");
+    }
+}
+
+async fn new_conversation_resources_are_observed() -> anyhow::Result<()> {
+    let server =
+        create_mock_chat_completions_server(vec![create_final_assistant_message_sse_response(
+            "Done",
+        )?])
+        .await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let new_conv_id = mcp
+        .send_new_conversation_request(NewConversationParams::default())
+        .await?;
+    let NewConversationResponse {
+        conversation_id, ..
+    } = to_response(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(new_conv_id)),
+        )
+        .await??,
+    )?;
+    let transcript_uri = format!("codex://conversations/{}", conversation_id.0);
+
+    let subscribe_id = mcp.send_subscribe_resource_request(&transcript_uri).await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await??;
+    let add_listener_id = mcp
+        .send_add_conversation_listener_request(AddConversationListenerParams { conversation_id })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(add_listener_id)),
+    )
+    .await??;
+    let send_id = mcp
+        .send_send_user_message_request(SendUserMessageParams {
+            conversation_id,
+            items: vec![InputItem::Text {
+                text: "Hello".to_string(),
+            }],
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(send_id)),
+    )
+    .await??;
+
+    // The reply from the model lands in the transcript after the response
+    // above, so subscribers hear about it.
+    let notification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("notifications/resources/updated"),
+    )
+    .await??;
+    let params: ResourceUpdatedNotificationParams =
+        serde_json::from_value(notification.params.unwrap_or_default())?;
+    assert_eq!(params.uri, transcript_uri);
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "danger-full-access"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "chat"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
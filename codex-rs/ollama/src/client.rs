@@ -5,9 +5,16 @@ use serde_json::Value as JsonValue;
 use std::collections::VecDeque;
 use std::io;
 
+use crate::models::LocalModel;
+use crate::models::apply_ollama_show;
+use crate::models::llama_cpp_context_window;
+use crate::models::models_from_ollama_tags;
+use crate::models::models_from_openai_list;
+use crate::models::ollama_running_context_windows;
 use crate::parser::pull_events_from_value;
 use crate::pull::PullEvent;
 use crate::pull::PullProgressReporter;
+use crate::server::ServerKind;
 use crate::url::base_url_to_host_root;
 use crate::url::is_openai_compatible_base_url;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
@@ -15,13 +22,16 @@ use codex_core::ModelProviderInfo;
 use codex_core::WireApi;
 use codex_core::config::Config;
 
-const OLLAMA_CONNECTION_ERROR: &str = "No running Ollama server detected. Start it with: `ollama serve` (after installing). Install instructions: https://github.com/ollama/ollama?tab=readme-ov-file#ollama";
+const OLLAMA_CONNECTION_ERROR: &str = "No running local model server detected. Start Ollama with: `ollama serve` (after installing; instructions: https://github.com/ollama/ollama?tab=readme-ov-file#ollama), or set CODEX_OSS_BASE_URL to a running OpenAI-compatible server such as llama.cpp's `llama-server`.";
 
-/// Client for interacting with a local Ollama instance.
+/// Client for interacting with the local model server behind the `oss`
+/// provider: Ollama, or an OpenAI-compatible server such as llama.cpp, vLLM
+/// or LM Studio.
 pub struct OllamaClient {
     client: reqwest::Client,
     host_root: String,
     uses_openai_compat: bool,
+    kind: ServerKind,
 }
 
 impl OllamaClient {
@@ -66,15 +76,68 @@ impl OllamaClient {
             .connect_timeout(std::time::Duration::from_secs(5))
             .build()
             .unwrap_or_else(|_| reqwest::Client::new());
-        let client = Self {
+        let mut client = Self {
             client,
             host_root,
             uses_openai_compat,
+            kind: ServerKind::Ollama,
         };
         client.probe_server().await?;
+        if uses_openai_compat {
+            client.kind = client.detect_kind().await;
+        }
         Ok(client)
     }
 
+    /// Tell the servers apart by the endpoints only they expose.
+    async fn detect_kind(&self) -> ServerKind {
+        if self
+            .get_json("/api/version")
+            .await
+            .is_some_and(|v| v.get("version").is_some())
+        {
+            return ServerKind::Ollama;
+        }
+        if self
+            .get_json("/props")
+            .await
+            .is_some_and(|v| v.get("default_generation_settings").is_some())
+        {
+            return ServerKind::LlamaCpp;
+        }
+        if self
+            .get_json("/api/v0/models")
+            .await
+            .is_some_and(|v| v.get("data").is_some())
+        {
+            return ServerKind::LmStudio;
+        }
+        let owned_by_vllm = self.get_json("/v1/models").await.is_some_and(|v| {
+            v.get("data")
+                .and_then(|d| d.as_array())
+                .and_then(|d| d.first())
+                .and_then(|m| m.get("owned_by"))
+                .and_then(|o| o.as_str())
+                == Some("vllm")
+        });
+        if owned_by_vllm {
+            ServerKind::Vllm
+        } else {
+            ServerKind::OpenAiCompatible
+        }
+    }
+
+    /// GET `path` on the server and parse the body as JSON. `None` when the
+    /// request fails or the server does not know the endpoint.
+    async fn get_json(&self, path: &str) -> Option<JsonValue> {
+        self.require_json(path).await.ok()
+    }
+
+    /// The kind of server this client talks to.
+    pub fn server_kind(&self) -> ServerKind {
+        self.kind
+    }
+
     /// Probe whether the server is reachable by hitting the appropriate health endpoint.
     async fn probe_server(&self) -> io::Result<()> {
         let url = if self.uses_openai_compat {
@@ -98,6 +161,187 @@ impl OllamaClient {
         }
     }
 
+    /// List the models the server can run, with whatever it reports about
+    /// their size and context window.
+    pub async fn list_models(&self) -> io::Result<Vec<LocalModel>> {
+        match self.kind {
+            ServerKind::Ollama => {
+                let tags = self.require_json("/api/tags").await?;
+                let mut models = models_from_ollama_tags(&tags);
+                for model in &mut models {
+                    match self.ollama_show(&model.name).await {
+                        Ok(show) => apply_ollama_show(model, &show),
+                        Err(err) => tracing::warn!("Failed to inspect {}: {err}", model.name),
+                    }
+                }
+                // A loaded model reports the context it actually runs with,
+                // which also covers `OLLAMA_CONTEXT_LENGTH` and request
+                // options that `/api/show` knows nothing about.
+                let running = self
+                    .get_json("/api/ps")
+                    .await
+                    .map(|ps| ollama_running_context_windows(&ps))
+                    .unwrap_or_default();
+                for (name, context_window) in running {
+                    if let Some(model) = models.iter_mut().find(|m| m.name == name) {
+                        model.context_window = Some(context_window);
+                    }
+                }
+                Ok(models)
+            }
+            ServerKind::LmStudio => {
+                let list = self.require_json("/api/v0/models").await?;
+                Ok(models_from_openai_list(&list))
+            }
+            ServerKind::LlamaCpp => {
+                let list = self.require_json("/v1/models").await?;
+                let mut models = models_from_openai_list(&list);
+                // `/v1/models` reports the context the model was trained
+                // with; `/props` reports the one the server actually runs.
+                if let Some(n_ctx) = self
+                    .get_json("/props")
+                    .await
+                    .as_ref()
+                    .and_then(llama_cpp_context_window)
+                {
+                    for model in &mut models {
+                        model.context_window = Some(n_ctx);
+                    }
+                }
+                Ok(models)
+            }
+            ServerKind::Vllm | ServerKind::OpenAiCompatible => {
+                let list = self.require_json("/v1/models").await?;
+                Ok(models_from_openai_list(&list))
+            }
+        }
+    }
+
+    /// Describe a single model, probing for tool calling when the server
+    /// does not say whether the model supports it. `None` if the server does
+    /// not have the model.
+    pub async fn show_model(&self, name: &str) -> io::Result<Option<LocalModel>> {
+        let Some(mut model) = self
+            .list_models()
+            .await?
+            .into_iter()
+            .find(|m| m.name == name)
+        else {
+            return Ok(None);
+        };
+        if model.supports_tools.is_none() {
+            model.supports_tools = Some(self.probe_tool_calling(name).await?);
+        }
+        Ok(Some(model))
+    }
+
+    /// Ask the model for a one-token completion with a tool attached. Servers
+    /// reject the request when the model (or the server's configuration)
+    /// cannot handle tools.
+    pub async fn probe_tool_calling(&self, model: &str) -> io::Result<bool> {
+        let url = format!(
+            "{}/v1/chat/completions",
+            self.host_root.trim_end_matches('/')
+        );
+        let body = serde_json::json!({
+            "model": model,
+            "messages": [{"role": "user", "content": "Reply with OK."}],
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "noop",
+                    "description": "Does nothing.",
+                    "parameters": {"type": "object", "properties": {}},
+                },
+            }],
+            "max_tokens": 1,
+            "stream": false,
+        });
+        let resp = self
+            .client
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            tracing::info!(
+                "{model} rejected a request with tools: HTTP {}",
+                resp.status()
+            );
+        }
+        Ok(resp.status().is_success())
+    }
+
+    /// Delete a downloaded model. Only Ollama manages models through its API.
+    pub async fn delete_model(&self, model: &str) -> io::Result<()> {
+        self.ensure_manages_models("remove")?;
+        let url = format!("{}/api/delete", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .delete(url)
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        match resp.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::NOT_FOUND => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("model {model} not found"),
+            )),
+            status => Err(io::Error::other(format!(
+                "failed to remove {model}: HTTP {status}"
+            ))),
+        }
+    }
+
+    fn ensure_manages_models(&self, action: &str) -> io::Result<()> {
+        if self.kind.manages_models() {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} cannot {action} models; it serves the models it was started with",
+                self.kind
+            ),
+        ))
+    }
+
+    async fn ollama_show(&self, model: &str) -> io::Result<JsonValue> {
+        let url = format!("{}/api/show", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(url)
+            .json(&serde_json::json!({"model": model}))
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!("HTTP {}", resp.status())));
+        }
+        resp.json::<JsonValue>().await.map_err(io::Error::other)
+    }
+
+    /// Like `get_json`, but failures are errors.
+    async fn require_json(&self, path: &str) -> io::Result<JsonValue> {
+        let url = format!("{}{path}", self.host_root.trim_end_matches('/'));
+        let resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(io::Error::other)?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "GET {path} failed: HTTP {}",
+                resp.status()
+            )));
+        }
+        resp.json::<JsonValue>().await.map_err(io::Error::other)
+    }
+
     /// Return the list of model names known to the local Ollama instance.
     pub async fn fetch_models(&self) -> io::Result<Vec<String>> {
         let tags_url = format!("{}/api/tags", self.host_root.trim_end_matches('/'));
@@ -189,6 +433,7 @@ impl OllamaClient {
         model: &str,
         reporter: &mut dyn PullProgressReporter,
     ) -> io::Result<()> {
+        self.ensure_manages_models("pull")?;
         reporter.on_event(&PullEvent::Status(format!("Pulling model {model}...")))?;
         let mut stream = self.pull_model_stream(model).await?;
         while let Some(event) = stream.next().await {
@@ -228,6 +473,7 @@ impl OllamaClient {
             client,
             host_root: host_root.into(),
             uses_openai_compat: false,
+            kind: ServerKind::Ollama,
         }
     }
 }
@@ -346,4 +592,118 @@ mod tests {
             .expect("expected error");
         assert_eq!(OLLAMA_CONNECTION_ERROR, err.to_string());
     }
+
+    #[tokio::test]
+    async fn test_list_and_delete_ollama_models() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping test_list_and_delete_ollama_models",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/tags"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"models": [{"name": "gpt-oss:20b", "size": 13_780_173_839u64}]}),
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/api/show"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "model_info": {"gptoss.context_length": 131072},
+                    "capabilities": ["completion", "tools"],
+                })),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/api/ps"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"models": [{"name": "gpt-oss:20b", "context_length": 8192}]}),
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("DELETE"))
+            .and(wiremock::matchers::path("/api/delete"))
+            .and(wiremock::matchers::body_json(
+                serde_json::json!({"model": "gpt-oss:20b"}),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::from_host_root(server.uri());
+        let model = client
+            .show_model("gpt-oss:20b")
+            .await
+            .expect("show model")
+            .expect("model is listed");
+        assert_eq!(
+            model,
+            LocalModel {
+                name: "gpt-oss:20b".to_string(),
+                size_bytes: Some(13_780_173_839),
+                context_window: Some(8192),
+                supports_tools: Some(true),
+            }
+        );
+        client
+            .delete_model("gpt-oss:20b")
+            .await
+            .expect("delete model");
+        let err = client.delete_model("mistral").await.expect_err("not found");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_vllm_is_detected_and_cannot_pull() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            tracing::info!(
+                "{} set; skipping test_vllm_is_detected_and_cannot_pull",
+                codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR
+            );
+            return;
+        }
+
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .and(wiremock::matchers::path("/v1/models"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_json(
+                serde_json::json!({"data": [{"id": "Qwen/Qwen2.5-7B-Instruct", "owned_by": "vllm", "max_model_len": 32768}]}),
+            ))
+            .mount(&server)
+            .await;
+        // vLLM rejects tools unless started with --enable-auto-tool-choice.
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .and(wiremock::matchers::path("/v1/chat/completions"))
+            .respond_with(wiremock::ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::try_from_provider_with_base_url(&format!("{}/v1", server.uri()))
+            .await
+            .expect("probe vLLM");
+        assert_eq!(client.server_kind(), ServerKind::Vllm);
+
+        let model = client
+            .show_model("Qwen/Qwen2.5-7B-Instruct")
+            .await
+            .expect("show model")
+            .expect("model is listed");
+        assert_eq!(model.context_window, Some(32768));
+        assert_eq!(model.supports_tools, Some(false));
+
+        let mut reporter = crate::CliProgressReporter::new();
+        let err = client
+            .pull_with_reporter("llama3", &mut reporter)
+            .await
+            .expect_err("vLLM cannot pull");
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+    }
 }
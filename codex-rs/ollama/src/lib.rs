@@ -1,44 +1,215 @@
 mod client;
+mod models;
 mod parser;
 mod pull;
+mod server;
 mod url;
 
 pub use client::OllamaClient;
 use codex_core::config::Config;
+pub use models::LocalModel;
 pub use pull::CliProgressReporter;
 pub use pull::PullEvent;
 pub use pull::PullProgressReporter;
 pub use pull::TuiProgressReporter;
+pub use server::ServerKind;
 
 /// Default OSS model to use when `--oss` is passed without an explicit `-m`.
 pub const DEFAULT_OSS_MODEL: &str = "gpt-oss:20b";
 
 /// Prepare the local OSS environment when `--oss` is selected.
 ///
-/// - Ensures a local Ollama server is reachable.
-/// - Checks if the model exists locally and pulls it if missing.
-pub async fn ensure_oss_ready(config: &Config) -> std::io::Result<()> {
-    // Only download when the requested model is the default OSS model (or when -m is not provided).
-    let model = config.model.as_ref();
-
-    // Verify local Ollama is reachable.
-    let ollama_client = crate::OllamaClient::try_from_oss_provider(config).await?;
-
-    // If the model is not present locally, pull it.
-    match ollama_client.fetch_models().await {
-        Ok(models) => {
-            if !models.iter().any(|m| m == model) {
-                let mut reporter = crate::CliProgressReporter::new();
-                ollama_client
-                    .pull_with_reporter(model, &mut reporter)
-                    .await?;
+/// - Ensures a local model server (Ollama, llama.cpp, vLLM, LM Studio, ...)
+///   is reachable.
+/// - With Ollama, pulls the model if it is missing. Other servers serve the
+///   models they were started with; if the default model was requested and
+///   the server does not have it, the server's first model is used instead.
+/// - Sets `model_context_window` from what the server reports unless it is
+///   already configured, and warns when the model cannot call tools.
+pub async fn ensure_oss_ready(config: &mut Config) -> std::io::Result<()> {
+    // Verify the local server is reachable.
+    let client = crate::OllamaClient::try_from_oss_provider(config).await?;
+    let kind = client.server_kind();
+
+    if kind.manages_models() {
+        // If the model is not present locally, pull it.
+        let model = config.model.as_str();
+        match client.fetch_models().await {
+            Ok(models) => {
+                if !models.iter().any(|m| m == model) {
+                    let mut reporter = crate::CliProgressReporter::new();
+                    client.pull_with_reporter(model, &mut reporter).await?;
+                }
+            }
+            Err(err) => {
+                // Not fatal; higher layers may still proceed and surface errors later.
+                tracing::warn!("Failed to query local models from Ollama: {}.", err);
             }
         }
-        Err(err) => {
-            // Not fatal; higher layers may still proceed and surface errors later.
-            tracing::warn!("Failed to query local models from Ollama: {}.", err);
-        }
+    } else {
+        select_served_model(config, &client).await?;
+    }
+
+    match client.show_model(&config.model).await {
+        Ok(Some(model)) => apply_capabilities(config, kind, &model),
+        Ok(None) => {}
+        Err(err) => tracing::warn!("Failed to probe {} on {kind}: {err}", config.model),
     }
 
     Ok(())
 }
+
+/// Make sure the configured model is one the server is serving.
+async fn select_served_model(config: &mut Config, client: &OllamaClient) -> std::io::Result<()> {
+    let kind = client.server_kind();
+    let models = client.list_models().await?;
+    if models.iter().any(|m| m.name == config.model) {
+        return Ok(());
+    }
+    match models.first() {
+        Some(served) if config.model == DEFAULT_OSS_MODEL => {
+            eprintln!("Using {}, the model {kind} is serving.", served.name);
+            set_model(config, &served.name);
+            Ok(())
+        }
+        _ => {
+            let available = models
+                .iter()
+                .map(|m| m.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "{kind} is not serving {}; pass one of these with -m: {available}",
+                    config.model
+                ),
+            ))
+        }
+    }
+}
+
+fn set_model(config: &mut Config, model: &str) {
+    config.model = model.to_string();
+    config.model_family = config.model_registry.family_for(model);
+}
+
+fn apply_capabilities(config: &mut Config, kind: ServerKind, model: &LocalModel) {
+    if config.model_context_window.is_none() {
+        config.model_context_window = model.context_window;
+    }
+    if model.supports_tools == Some(false) {
+        let hint = kind
+            .tool_calling_hint()
+            .map(|hint| format!(" To enable it, {hint}."))
+            .unwrap_or_default();
+        eprintln!(
+            "Warning: {} on {kind} does not accept tool calls, so Codex will not be able to run commands or edit files.{hint}",
+            model.name
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+    use codex_core::config::ConfigOverrides;
+    use codex_core::config::ConfigToml;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+    use wiremock::matchers::path;
+
+    fn oss_config(server: &MockServer, codex_home: &tempfile::TempDir) -> Config {
+        let mut config = Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                model: Some(DEFAULT_OSS_MODEL.to_string()),
+                model_provider: Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .unwrap();
+        let provider =
+            codex_core::create_oss_provider_with_base_url(&format!("{}/v1", server.uri()));
+        config
+            .model_providers
+            .insert(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string(), provider.clone());
+        config.model_provider = provider;
+        config
+    }
+
+    #[tokio::test]
+    async fn llama_cpp_server_supplies_the_model_and_its_context_window() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            return;
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "object": "list",
+                "data": [{"id": "qwen2.5-coder-7b.gguf", "meta": {"n_ctx_train": 32768}}],
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/props"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "default_generation_settings": {"n_ctx": 16384},
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": "OK"}}],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let codex_home = tempfile::TempDir::new().unwrap();
+        let mut config = oss_config(&server, &codex_home);
+        ensure_oss_ready(&mut config).await.unwrap();
+
+        assert_eq!(config.model, "qwen2.5-coder-7b.gguf");
+        assert_eq!(config.model_family.slug, "qwen2.5-coder-7b.gguf");
+        assert_eq!(config.model_context_window, Some(16384));
+    }
+
+    #[tokio::test]
+    async fn unknown_model_on_a_fixed_server_lists_what_is_available() {
+        if std::env::var(codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+            return;
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    {"id": "Qwen/Qwen2.5-7B-Instruct", "owned_by": "vllm", "max_model_len": 32768},
+                ],
+            })))
+            .mount(&server)
+            .await;
+
+        let codex_home = tempfile::TempDir::new().unwrap();
+        let mut config = oss_config(&server, &codex_home);
+        config.model = "llama3".to_string();
+        let err = ensure_oss_ready(&mut config).await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "vLLM is not serving llama3; pass one of these with -m: Qwen/Qwen2.5-7B-Instruct"
+        );
+    }
+}
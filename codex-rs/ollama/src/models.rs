@@ -0,0 +1,232 @@
+use serde_json::Value as JsonValue;
+
+/// A model available on the local server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalModel {
+    pub name: String,
+    /// Size of the model on disk, when the server reports it.
+    pub size_bytes: Option<u64>,
+    /// Context window in tokens, when the server reports it.
+    pub context_window: Option<u64>,
+    /// Whether the model accepts tool definitions. `None` until probed.
+    pub supports_tools: Option<bool>,
+}
+
+impl LocalModel {
+    fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            size_bytes: None,
+            context_window: None,
+            supports_tools: None,
+        }
+    }
+}
+
+/// Models listed by Ollama's `/api/tags`.
+pub(crate) fn models_from_ollama_tags(value: &JsonValue) -> Vec<LocalModel> {
+    value
+        .get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let name = v.get("name").and_then(|n| n.as_str())?;
+                    let mut model = LocalModel::named(name);
+                    model.size_bytes = v.get("size").and_then(JsonValue::as_u64);
+                    Some(model)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fill in what Ollama's `/api/show` reports about `model`: the context size
+/// its Modelfile sets with `num_ctx`, if any, and, on servers new enough to
+/// report `capabilities`, whether it accepts tools.
+///
+/// `model_info.*.context_length` is deliberately ignored: it is the context
+/// the model was trained with, while Ollama runs it with `num_ctx` (a few
+/// thousand tokens by default) and silently drops whatever does not fit.
+pub(crate) fn apply_ollama_show(model: &mut LocalModel, value: &JsonValue) {
+    model.context_window =
+        value
+            .get("parameters")
+            .and_then(|p| p.as_str())
+            .and_then(|parameters| {
+                parameters.lines().find_map(|line| {
+                    let mut words = line.split_whitespace();
+                    match (words.next(), words.next()) {
+                        (Some("num_ctx"), Some(value)) => value.parse().ok(),
+                        _ => None,
+                    }
+                })
+            });
+    if let Some(capabilities) = value.get("capabilities").and_then(|c| c.as_array()) {
+        model.supports_tools = Some(capabilities.iter().any(|c| c.as_str() == Some("tools")));
+    }
+}
+
+/// The context size each model loaded by Ollama runs with, from `/api/ps`.
+pub(crate) fn ollama_running_context_windows(ps: &JsonValue) -> Vec<(String, u64)> {
+    ps.get("models")
+        .and_then(|m| m.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| {
+                    let name = v
+                        .get("name")
+                        .or_else(|| v.get("model"))
+                        .and_then(|n| n.as_str())?;
+                    let context = v.get("context_length").and_then(JsonValue::as_u64)?;
+                    Some((name.to_string(), context))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Models listed by an OpenAI-style `/v1/models` (or LM Studio's
+/// `/api/v0/models`). Each server reports the context window under its own
+/// name: LM Studio `loaded_context_length` / `max_context_length`, vLLM
+/// `max_model_len`, llama.cpp `meta.n_ctx_train`.
+pub(crate) fn models_from_openai_list(value: &JsonValue) -> Vec<LocalModel> {
+    value
+        .get("data")
+        .and_then(|d| d.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|v| {
+                    // LM Studio lists embedding models alongside chat models.
+                    v.get("type").and_then(|t| t.as_str()) != Some("embeddings")
+                })
+                .filter_map(|v| {
+                    let name = v.get("id").and_then(|n| n.as_str())?;
+                    let mut model = LocalModel::named(name);
+                    let meta = v.get("meta");
+                    model.context_window = [
+                        v.get("loaded_context_length"),
+                        v.get("max_model_len"),
+                        v.get("context_length"),
+                        v.get("max_context_length"),
+                        meta.and_then(|m| m.get("n_ctx_train")),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .find_map(JsonValue::as_u64);
+                    model.size_bytes = meta.and_then(|m| m.get("size")).and_then(JsonValue::as_u64);
+                    Some(model)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The context size llama.cpp's server was started with (`-c`), from `/props`.
+pub(crate) fn llama_cpp_context_window(props: &JsonValue) -> Option<u64> {
+    props
+        .get("default_generation_settings")
+        .and_then(|s| s.get("n_ctx"))
+        .or_else(|| props.get("n_ctx"))
+        .and_then(JsonValue::as_u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ollama_tags_and_show_describe_a_model() {
+        let tags = serde_json::json!({
+            "models": [
+                {"name": "gpt-oss:20b", "size": 13_780_173_839u64},
+                {"name": "mistral"},
+            ]
+        });
+        let mut models = models_from_ollama_tags(&tags);
+        apply_ollama_show(
+            &mut models[0],
+            &serde_json::json!({
+                "parameters": "temperature 1\nnum_ctx 16384",
+                "model_info": {"general.architecture": "gptoss", "gptoss.context_length": 131072},
+                "capabilities": ["completion", "tools"],
+            }),
+        );
+        // Without `num_ctx`, the trained context length says nothing about
+        // the context the model runs with.
+        apply_ollama_show(
+            &mut models[1],
+            &serde_json::json!({"model_info": {"llama.context_length": 32768}}),
+        );
+        assert_eq!(
+            models,
+            vec![
+                LocalModel {
+                    name: "gpt-oss:20b".to_string(),
+                    size_bytes: Some(13_780_173_839),
+                    context_window: Some(16384),
+                    supports_tools: Some(true),
+                },
+                LocalModel::named("mistral"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ollama_ps_reports_the_running_context_size() {
+        let ps = serde_json::json!({
+            "models": [
+                {"name": "gpt-oss:20b", "model": "gpt-oss:20b", "context_length": 8192},
+                {"name": "mistral:latest"},
+            ]
+        });
+        assert_eq!(
+            ollama_running_context_windows(&ps),
+            vec![("gpt-oss:20b".to_string(), 8192)]
+        );
+        assert_eq!(
+            ollama_running_context_windows(&serde_json::json!({})),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn openai_lists_report_context_windows_under_each_servers_name() {
+        let list = serde_json::json!({
+            "object": "list",
+            "data": [
+                {"id": "qwen2.5-coder", "object": "model", "meta": {"n_ctx_train": 32768, "size": 4_683_073_536u64}},
+                {"id": "Qwen/Qwen2.5-7B-Instruct", "owned_by": "vllm", "max_model_len": 16384},
+                {"id": "openai/gpt-oss-20b", "type": "llm", "max_context_length": 131072, "loaded_context_length": 8192},
+                {"id": "text-embedding-nomic", "type": "embeddings", "max_context_length": 2048},
+                {"id": "plain"},
+            ]
+        });
+        let models = models_from_openai_list(&list);
+        let summary: Vec<_> = models
+            .iter()
+            .map(|m| (m.name.as_str(), m.context_window, m.size_bytes))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("qwen2.5-coder", Some(32768), Some(4_683_073_536)),
+                ("Qwen/Qwen2.5-7B-Instruct", Some(16384), None),
+                ("openai/gpt-oss-20b", Some(8192), None),
+                ("plain", None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn llama_cpp_props_report_the_running_context_size() {
+        let props = serde_json::json!({"default_generation_settings": {"n_ctx": 8192}});
+        assert_eq!(llama_cpp_context_window(&props), Some(8192));
+        assert_eq!(
+            llama_cpp_context_window(&serde_json::json!({"n_ctx": 4096})),
+            Some(4096)
+        );
+        assert_eq!(llama_cpp_context_window(&serde_json::json!({})), None);
+    }
+}
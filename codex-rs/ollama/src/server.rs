@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// The kind of local server answering at the `oss` provider's address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerKind {
+    Ollama,
+    LlamaCpp,
+    Vllm,
+    LmStudio,
+    /// Any other server that speaks the OpenAI chat completions API.
+    OpenAiCompatible,
+}
+
+impl ServerKind {
+    /// Only Ollama can download and delete models through its API; the other
+    /// servers serve whatever they were started with.
+    pub fn manages_models(self) -> bool {
+        matches!(self, ServerKind::Ollama)
+    }
+
+    /// How to enable tool calling on this server, if it needs a flag for it.
+    pub fn tool_calling_hint(self) -> Option<&'static str> {
+        match self {
+            ServerKind::LlamaCpp => Some("start llama-server with --jinja"),
+            ServerKind::Vllm => Some(
+                "start vllm serve with --enable-auto-tool-choice and a --tool-call-parser for the model",
+            ),
+            ServerKind::Ollama | ServerKind::LmStudio | ServerKind::OpenAiCompatible => None,
+        }
+    }
+}
+
+impl fmt::Display for ServerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ServerKind::Ollama => "Ollama",
+            ServerKind::LlamaCpp => "llama.cpp",
+            ServerKind::Vllm => "vLLM",
+            ServerKind::LmStudio => "LM Studio",
+            ServerKind::OpenAiCompatible => "OpenAI-compatible server",
+        };
+        f.write_str(name)
+    }
+}
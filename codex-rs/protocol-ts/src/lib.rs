@@ -46,6 +46,10 @@ pub fn generate_ts(out_dir: &Path, prettier: Option<&Path>) -> Result<()> {
     codex_protocol::mcp_protocol::ApplyPatchApprovalResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ExecCommandApprovalParams::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ExecCommandApprovalResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::McpToolCallApprovalParams::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::McpToolCallApprovalResponse::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::McpSamplingApprovalParams::export_all_to(out_dir)?;
+    codex_protocol::mcp_protocol::McpSamplingApprovalResponse::export_all_to(out_dir)?;
     codex_protocol::mcp_protocol::ServerNotification::export_all_to(out_dir)?;
 
     generate_index_ts(out_dir)?;
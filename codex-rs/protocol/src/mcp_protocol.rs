@@ -353,6 +353,8 @@ pub enum InputItem {
 
 pub const APPLY_PATCH_APPROVAL_METHOD: &str = "applyPatchApproval";
 pub const EXEC_COMMAND_APPROVAL_METHOD: &str = "execCommandApproval";
+pub const MCP_TOOL_CALL_APPROVAL_METHOD: &str = "mcpToolCallApproval";
+pub const MCP_SAMPLING_APPROVAL_METHOD: &str = "mcpSamplingApproval";
 
 /// Request initiated from the server and sent to the client.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -370,6 +372,18 @@ pub enum ServerRequest {
         request_id: RequestId,
         params: ExecCommandApprovalParams,
     },
+    /// Request to call a tool on an MCP server.
+    McpToolCallApproval {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: McpToolCallApprovalParams,
+    },
+    /// Request to let an MCP server sample the conversation's model.
+    McpSamplingApproval {
+        #[serde(rename = "id")]
+        request_id: RequestId,
+        params: McpSamplingApprovalParams,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
@@ -405,6 +419,47 @@ pub struct ExecCommandApprovalResponse {
     pub decision: ReviewDecision,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpToolCallApprovalParams {
+    pub conversation_id: ConversationId,
+    /// Use to correlate this with [codex_core::protocol::McpToolCallBeginEvent]
+    /// and [codex_core::protocol::McpToolCallEndEvent].
+    pub call_id: String,
+    /// Name of the MCP server as defined in the config.
+    pub server: String,
+    /// Name of the tool as given by the MCP server.
+    pub tool: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpToolCallApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpSamplingApprovalParams {
+    pub conversation_id: ConversationId,
+    /// Use to correlate this with [codex_core::protocol::McpSamplingBeginEvent]
+    /// and [codex_core::protocol::McpSamplingEndEvent].
+    pub call_id: String,
+    /// Name of the MCP server as defined in the config.
+    pub server: String,
+    /// The messages to complete, one `role: text` line each.
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    pub max_tokens: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+pub struct McpSamplingApprovalResponse {
+    pub decision: ReviewDecision,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 pub struct ApplyPatchApprovalResponse {
     pub decision: ReviewDecision,
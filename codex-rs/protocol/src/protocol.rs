@@ -134,6 +134,22 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve an MCP tool call
+    McpToolCallApproval {
+        /// The id of the submission we are approving
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
+    /// Approve an MCP server's request to sample the model
+    McpSamplingApproval {
+        /// The id of the `McpSamplingApprovalRequest` event we are answering
+        id: String,
+        /// The user's decision in response to the request.
+        decision: ReviewDecision,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -154,13 +170,44 @@ pub enum Op {
     /// Reply is delivered via `EventMsg::McpListToolsResponse`.
     ListMcpTools,
 
+    /// Request the status of every configured MCP server.
+    /// Reply is delivered via `EventMsg::McpListServersResponse`.
+    ListMcpServers,
+
+    /// Stop an MCP server, if it is running, and start it again. The updated
+    /// list is delivered via `EventMsg::McpListServersResponse`.
+    RestartMcpServer { server: String },
+
+    /// Stop an MCP server and keep it stopped for the rest of the session, or
+    /// start a disabled one again. The updated list is delivered via
+    /// `EventMsg::McpListServersResponse`.
+    SetMcpServerEnabled { server: String, enabled: bool },
+
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the persisted command approval rules that apply to this
+    /// session. Reply is delivered via `EventMsg::ListApprovalRulesResponse`.
+    ListApprovalRules,
+
+    /// Revoke a persisted command approval rule. The updated list is
+    /// delivered via `EventMsg::ListApprovalRulesResponse`.
+    RemoveApprovalRule { rule: ApprovalRule },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
     Compact,
+
+    /// Request a commit message for the repository's staged changes, or for
+    /// all uncommitted changes when nothing is staged. Reply is delivered via
+    /// `EventMsg::CommitMessageDraft`.
+    DraftCommitMessage,
+
+    /// Request a short summary of the progress on the current plan. Reply is
+    /// delivered via `EventMsg::PlanSummary`.
+    SummarizePlan,
+
     /// Request to shut down codex instance.
     Shutdown,
 }
@@ -421,6 +468,16 @@ pub enum EventMsg {
     /// used in the current session.
     TokenCount(TokenUsage),
 
+    /// Tokens used by a job that runs alongside the conversation, such as
+    /// compaction. Reported separately because the job may use another model
+    /// and does not add to the conversation's context.
+    AuxiliaryTokenCount(AuxiliaryTokenCountEvent),
+
+    /// The reasoning effort picked for the next request to the model when
+    /// `auto_reasoning_effort` is configured. Sent whenever it changes
+    /// within a task.
+    ReasoningEffortChosen(ReasoningEffortChosenEvent),
+
     /// Agent text output message
     AgentMessage(AgentMessageEvent),
 
@@ -451,6 +508,11 @@ pub enum EventMsg {
 
     McpToolCallEnd(McpToolCallEndEvent),
 
+    /// An MCP server's sampling request is being sent to the model.
+    McpSamplingBegin(McpSamplingBeginEvent),
+
+    McpSamplingEnd(McpSamplingEndEvent),
+
     WebSearchBegin(WebSearchBeginEvent),
 
     WebSearchEnd(WebSearchEndEvent),
@@ -467,6 +529,10 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    McpToolCallApprovalRequest(McpToolCallApprovalRequestEvent),
+
+    McpSamplingApprovalRequest(McpSamplingApprovalRequestEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     /// Notification that a model stream experienced an error or disconnect
@@ -488,11 +554,23 @@ pub enum EventMsg {
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
+    /// Status of the configured MCP servers.
+    McpListServersResponse(McpListServersResponseEvent),
+
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// List of persisted command approval rules.
+    ListApprovalRulesResponse(ListApprovalRulesResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
+    /// Response to `Op::DraftCommitMessage`.
+    CommitMessageDraft(CommitMessageDraftEvent),
+
+    /// Response to `Op::SummarizePlan`.
+    PlanSummary(PlanSummaryEvent),
+
     TurnAborted(TurnAbortedEvent),
 
     /// Notification that the agent is shutting down.
@@ -518,6 +596,40 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<u64>,
 }
 
+/// Jobs that run alongside the conversation. All but MCP sampling can be
+/// routed to their own model with `[task_models]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AuxiliaryTask {
+    /// Summarizing the conversation for `Op::Compact`.
+    Compact,
+    /// Naming the session for the resume picker.
+    Title,
+    CommitMessage,
+    PlanSummary,
+    /// Answering a `sampling/createMessage` request from an MCP server.
+    McpSampling,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuxiliaryTokenCountEvent {
+    pub task: AuxiliaryTask,
+    /// The model that ran the job.
+    pub model: String,
+    pub token_usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommitMessageDraftEvent {
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlanSummaryEvent {
+    pub summary: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct TokenUsage {
     pub input_tokens: u64,
@@ -805,6 +917,50 @@ pub struct ExecApprovalRequestEvent {
     pub reason: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpSamplingBeginEvent {
+    /// Identifier so this can be paired with the McpSamplingEnd event.
+    pub call_id: String,
+    /// Name of the MCP server that asked for the completion.
+    pub server: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpSamplingEndEvent {
+    /// Identifier for the corresponding McpSamplingBegin that finished.
+    pub call_id: String,
+    pub server: String,
+    pub duration: Duration,
+    /// The completion returned to the server, or why there is none.
+    pub result: Result<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpSamplingApprovalRequestEvent {
+    /// Identifier for the associated sampling request.
+    pub call_id: String,
+    /// Name of the MCP server that asked for the completion.
+    pub server: String,
+    /// The messages the server wants completed, one `role: text` line each.
+    pub prompt: String,
+    /// The server's system prompt, if it sent one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Most tokens the server asked for.
+    pub max_tokens: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpToolCallApprovalRequestEvent {
+    /// Identifier for the associated MCP tool call.
+    pub call_id: String,
+    pub invocation: McpInvocation,
+    /// Optional human-readable reason for the approval (e.g. the server marks
+    /// the tool as destructive).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ApplyPatchApprovalRequestEvent {
     /// Responses API call id for the associated patch apply call, if available.
@@ -850,6 +1006,13 @@ pub struct PatchApplyEndEvent {
     pub success: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ReasoningEffortChosenEvent {
+    pub effort: ReasoningEffortConfig,
+    /// What the choice was based on, e.g. "previous tool call failed".
+    pub reason: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TurnDiffEvent {
     pub unified_diff: String,
@@ -871,12 +1034,52 @@ pub struct McpListToolsResponseEvent {
     pub tools: std::collections::HashMap<String, McpTool>,
 }
 
+/// Response payload for `Op::ListMcpServers`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpListServersResponseEvent {
+    /// Servers sorted by name.
+    pub servers: Vec<McpServerSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct McpServerSummary {
+    /// Name of the MCP server as defined in the config.
+    pub name: String,
+    pub status: McpServerStatus,
+    /// Names of the tools the server offers, as given by the server.
+    pub tools: Vec<String>,
+    /// Restarts since the server last ran long enough to count as healthy.
+    pub restarts: u32,
+    /// Why the server last exited or failed to start.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum McpServerStatus {
+    Starting,
+    Running,
+    /// Waiting to be started again after exiting or failing to start.
+    Restarting,
+    /// Gave up restarting after too many consecutive failures.
+    Failed,
+    Disabled,
+}
+
 /// Response payload for `Op::ListCustomPrompts`.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListApprovalRules`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ListApprovalRulesResponseEvent {
+    pub rules: Vec<ApprovalRule>,
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 pub struct SessionConfiguredEvent {
     /// Unique id for this session.
@@ -898,27 +1101,80 @@ pub struct SessionConfiguredEvent {
 }
 
 /// User's decision in response to an ExecApprovalRequest.
-#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ReviewDecision {
     /// User has approved this command and the agent should execute it.
     Approved,
 
+    /// User has edited the command and approved the edited version, which the
+    /// agent should execute instead of the one it proposed. Only meaningful
+    /// for command approvals.
+    ApprovedWithEdits { command: Vec<String> },
+
     /// User has approved this command and wants to automatically approve any
     /// future identical instances (`command` and `cwd` match exactly) for the
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants to automatically approve any
+    /// command starting with `prefix` from now on, including in future
+    /// sessions. The rule is persisted under `CODEX_HOME`.
+    ApprovedForPrefix {
+        prefix: Vec<String>,
+        scope: ApprovalRuleScope,
+    },
+
+    /// User has approved only part of a patch: the changes in `rejected` are
+    /// left out and everything else is applied. Only meaningful for patch
+    /// approvals.
+    ApprovedPartially { rejected: Vec<RejectedChange> },
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
     Denied,
 
+    /// Like [`ReviewDecision::Denied`], but with an explanation from the user
+    /// that is returned to the model so it can adjust its approach.
+    DeniedWithFeedback { feedback: String },
+
     /// User has denied this command and the agent should not do anything until
     /// the user's next command.
     Abort,
 }
 
+/// Part of a patch the user chose not to apply.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct RejectedChange {
+    pub path: PathBuf,
+    /// Zero-based indices of the rejected hunks within the file's
+    /// `unified_diff`. `None` rejects the whole file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hunks: Option<Vec<usize>>,
+}
+
+/// Where a persisted approval rule applies.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleScope {
+    /// Only in the project (git repository, or directory outside of one) the
+    /// command was approved in.
+    Project,
+    /// In every project.
+    User,
+}
+
+/// A persisted rule that auto-approves commands starting with `prefix`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct ApprovalRule {
+    /// Leading argv words a command must start with to match.
+    pub prefix: Vec<String>,
+    /// Project root the rule is limited to; `None` applies everywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum FileChange {
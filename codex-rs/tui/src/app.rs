@@ -2,9 +2,12 @@ use crate::app_backtrack::BacktrackState;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::chatwidget::ChatWidget;
+use crate::external_editor;
 use crate::file_search::FileSearchManager;
+use crate::keymap::Keymap;
 use crate::pager_overlay::Overlay;
 use crate::resume_picker::ResumeSelection;
+use crate::theme::Palette;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_ansi_escape::ansi_escape_line;
@@ -49,9 +52,17 @@ pub(crate) struct App {
 
     pub(crate) enhanced_keys_supported: bool,
 
+    /// Global shortcuts, resolved from `tui.keybindings`.
+    pub(crate) keymap: Keymap,
+
     /// Controls the animation thread that sends CommitTick events.
     pub(crate) commit_anim_running: Arc<AtomicBool>,
 
+    /// Set when the composer should be opened in the external editor. The
+    /// run loop handles it because terminal events must stop being read
+    /// while the editor owns the terminal.
+    external_editor_requested: bool,
+
     // Esc-backtracking state grouped
     pub(crate) backtrack: crate::app_backtrack::BacktrackState,
 }
@@ -112,7 +123,9 @@ impl App {
             }
         };
 
-        let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let file_search =
+            FileSearchManager::new(config.cwd.clone(), &config.codex_home, app_event_tx.clone());
+        let keymap = Keymap::from_config(&config.tui);
 
         let mut app = Self {
             server: conversation_manager,
@@ -121,16 +134,17 @@ impl App {
             config,
             file_search,
             enhanced_keys_supported,
+            keymap,
             transcript_lines: Vec::new(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
+            external_editor_requested: false,
             backtrack: BacktrackState::default(),
         };
 
-        let tui_events = tui.event_stream();
-        tokio::pin!(tui_events);
+        let mut tui_events = tui.event_stream();
 
         tui.frame_requester().schedule_frame();
 
@@ -141,7 +155,15 @@ impl App {
             Some(event) = tui_events.next() => {
                 app.handle_tui_event(tui, event).await?
             }
-        } {}
+        } {
+            if app.external_editor_requested {
+                // The event stream reads stdin on a background thread; drop it
+                // so the editor gets every keystroke.
+                drop(tui_events);
+                app.open_external_editor(tui)?;
+                tui_events = tui.event_stream();
+            }
+        }
         tui.terminal.clear()?;
         Ok(app.token_usage())
     }
@@ -222,12 +244,19 @@ impl App {
                 }
                 self.transcript_lines.extend(cell_transcript.clone());
                 let mut display = cell.display_lines(tui.terminal.last_known_screen_size.width);
+                // Timestamps and compact spacing only apply to the scrollback:
+                // backtracking relies on the transcript's bare `user` headers
+                // and blank separators.
+                if self.config.tui.timestamps && !cell.is_stream_continuation() {
+                    let timestamp = chrono::Local::now().format("%H:%M:%S ").to_string();
+                    prefix_first_line(&mut display, &timestamp);
+                }
                 if !display.is_empty() {
                     // Only insert a separating blank line for new cells that are not
                     // part of an ongoing stream. Streaming continuations should not
                     // accrue extra blank lines between chunks.
                     if !cell.is_stream_continuation() {
-                        if self.has_emitted_history_lines {
+                        if self.has_emitted_history_lines && !self.config.tui.compact {
                             display.insert(0, Line::from(""));
                         } else {
                             self.has_emitted_history_lines = true;
@@ -272,6 +301,9 @@ impl App {
                 return Ok(false);
             }
             AppEvent::CodexOp(op) => self.chat_widget.submit_op(op),
+            AppEvent::OpenExternalEditor => {
+                self.external_editor_requested = true;
+            }
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
                 self.chat_widget.on_diff_complete();
@@ -296,6 +328,9 @@ impl App {
             AppEvent::FileSearchResult { query, matches } => {
                 self.chat_widget.apply_file_search_result(query, matches);
             }
+            AppEvent::RecordFileAccess(paths) => {
+                self.file_search.record_file_access(&paths);
+            }
             AppEvent::UpdateReasoningEffort(effort) => {
                 self.chat_widget.set_reasoning_effort(effort);
             }
@@ -316,27 +351,46 @@ impl App {
         self.chat_widget.token_usage().clone()
     }
 
+    fn open_external_editor(&mut self, tui: &mut tui::Tui) -> Result<()> {
+        self.external_editor_requested = false;
+        match external_editor::edit(&self.chat_widget.composer_text()) {
+            Ok(text) => self.chat_widget.apply_external_edit(text),
+            Err(err) => self.chat_widget.add_error_message(format!("{err:#}")),
+        }
+        // The editor drew over the viewport; repaint all of it.
+        tui.terminal.clear()?;
+        tui.frame_requester().schedule_frame();
+        Ok(())
+    }
+
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
             KeyEvent {
-                code: KeyCode::Char('t'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
                 kind: KeyEventKind::Press,
                 ..
-            } => {
+            } if self.keymap.transcript.matches(&key_event) => {
                 // Enter alternate screen and set viewport to full size.
                 let _ = tui.enter_alt_screen();
-                self.overlay = Some(Overlay::new_transcript(self.transcript_lines.clone()));
+                self.overlay = Some(Overlay::new_transcript(
+                    self.transcript_lines.clone(),
+                    self.keymap,
+                ));
                 tui.frame_requester().schedule_frame();
             }
-            // Esc primes/advances backtracking only in normal (not working) mode
-            // with an empty composer. In any other state, forward Esc so the
-            // active UI (e.g. status indicator, modals, popups) handles it.
             KeyEvent {
-                code: KeyCode::Esc,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.keymap.external_editor.matches(&key_event) => {
+                self.external_editor_requested = true;
+            }
+            // Esc (or its replacement) primes/advances backtracking only in
+            // normal (not working) mode with an empty composer. In any other
+            // state, forward the key so the active UI (e.g. status indicator,
+            // modals, popups) handles it.
+            KeyEvent {
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
-            } => {
+            } if self.keymap.backtrack.matches(&key_event) => {
                 if self.chat_widget.is_normal_backtrack_mode()
                     && self.chat_widget.composer_is_empty()
                 {
@@ -361,10 +415,10 @@ impl App {
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
                 ..
             } => {
-                // Any non-Esc key press should cancel a primed backtrack.
+                // Any other key press should cancel a primed backtrack.
                 // This avoids stale "Esc-primed" state after the user starts typing
                 // (even if they later backspace to empty).
-                if key_event.code != KeyCode::Esc && self.backtrack.primed {
+                if self.backtrack.primed {
                     self.reset_backtrack_state();
                 }
                 self.chat_widget.handle_key_event(key_event);
@@ -375,3 +429,10 @@ impl App {
         };
     }
 }
+
+/// Prepend a dimmed timestamp to the first line of a history cell.
+fn prefix_first_line(lines: &mut [Line<'static>], timestamp: &str) {
+    if let Some(first) = lines.first_mut() {
+        first.spans.insert(0, timestamp.to_string().secondary());
+    }
+}
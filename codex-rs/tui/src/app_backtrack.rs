@@ -35,11 +35,12 @@ impl App {
     ) -> Result<bool> {
         if self.backtrack.overlay_preview_active {
             match event {
-                TuiEvent::Key(KeyEvent {
-                    code: KeyCode::Esc,
-                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                    ..
-                }) => {
+                TuiEvent::Key(
+                    key_event @ KeyEvent {
+                        kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                        ..
+                    },
+                ) if self.keymap.backtrack.matches(&key_event) => {
                     self.overlay_step_backtrack(tui, event)?;
                     Ok(true)
                 }
@@ -57,11 +58,13 @@ impl App {
                     Ok(true)
                 }
             }
-        } else if let TuiEvent::Key(KeyEvent {
-            code: KeyCode::Esc,
-            kind: KeyEventKind::Press | KeyEventKind::Repeat,
-            ..
-        }) = event
+        } else if let TuiEvent::Key(
+            key_event @ KeyEvent {
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            },
+        ) = event
+            && self.keymap.backtrack.matches(&key_event)
         {
             // First Esc in transcript overlay: begin backtrack preview at latest user message.
             self.begin_overlay_backtrack_preview(tui);
@@ -103,7 +106,10 @@ impl App {
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.enter_alt_screen();
-        self.overlay = Some(Overlay::new_transcript(self.transcript_lines.clone()));
+        self.overlay = Some(Overlay::new_transcript(
+            self.transcript_lines.clone(),
+            self.keymap,
+        ));
         tui.frame_requester().schedule_frame();
     }
 
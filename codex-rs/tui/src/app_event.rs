@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use codex_core::protocol::ConversationHistoryResponseEvent;
 use codex_core::protocol::Event;
 use codex_file_search::FileMatch;
@@ -36,6 +38,13 @@ pub(crate) enum AppEvent {
         matches: Vec<FileMatch>,
     },
 
+    /// Files were just mentioned or edited; rank them higher in subsequent
+    /// `@` file searches.
+    RecordFileAccess(Vec<PathBuf>),
+
+    /// Suspend the UI and edit the composer text in the user's editor.
+    OpenExternalEditor,
+
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
@@ -4,6 +4,7 @@ use ratatui::layout::Rect;
 use ratatui::widgets::WidgetRef;
 
 use crate::app_event_sender::AppEventSender;
+use crate::keymap::Keymap;
 use crate::user_approval_widget::ApprovalRequest;
 use crate::user_approval_widget::UserApprovalWidget;
 
@@ -16,6 +17,7 @@ pub(crate) struct ApprovalModalView {
     current: UserApprovalWidget,
     queue: Vec<ApprovalRequest>,
     app_event_tx: AppEventSender,
+    keymap: Keymap,
 }
 
 impl ApprovalModalView {
@@ -24,9 +26,17 @@ impl ApprovalModalView {
             current: UserApprovalWidget::new(request, app_event_tx.clone()),
             queue: Vec::new(),
             app_event_tx,
+            keymap: Keymap::default(),
         }
     }
 
+    /// Use the approval shortcuts from `tui.keybindings` for this and every
+    /// queued request.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.current.set_keymap(keymap);
+    }
+
     pub fn enqueue_request(&mut self, req: ApprovalRequest) {
         self.queue.push(req);
     }
@@ -37,6 +47,7 @@ impl ApprovalModalView {
             && let Some(req) = self.queue.pop()
         {
             self.current = UserApprovalWidget::new(req, self.app_event_tx.clone());
+            self.current.set_keymap(self.keymap);
         }
     }
 }
@@ -47,6 +58,10 @@ impl BottomPaneView for ApprovalModalView {
         self.maybe_advance();
     }
 
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        self.current.handle_paste(pasted)
+    }
+
     fn on_ctrl_c(&mut self, _pane: &mut BottomPane) -> CancellationEvent {
         self.current.on_ctrl_c();
         self.queue.clear();
@@ -75,6 +90,7 @@ impl BottomPaneView for ApprovalModalView {
 mod tests {
     use super::*;
     use crate::app_event::AppEvent;
+    use codex_core::config_types::EditingMode;
     use tokio::sync::mpsc::unbounded_channel;
 
     fn make_exec_request() -> ApprovalRequest {
@@ -101,6 +117,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
         assert_eq!(CancellationEvent::Handled, view.on_ctrl_c(&mut pane));
         assert!(view.queue.is_empty());
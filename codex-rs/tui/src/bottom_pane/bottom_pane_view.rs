@@ -12,6 +12,12 @@ pub(crate) trait BottomPaneView {
     /// scheduled after this call.
     fn handle_key_event(&mut self, _pane: &mut BottomPane, _key_event: KeyEvent) {}
 
+    /// Handle pasted text while the view is active. Return `true` if the
+    /// paste was consumed and a redraw is needed.
+    fn handle_paste(&mut self, _pasted: String) -> bool {
+        false
+    }
+
     /// Return `true` if the view has finished and should be removed.
     fn is_complete(&self) -> bool {
         false
@@ -1,3 +1,5 @@
+use codex_core::config_types::EditingMode;
+use codex_core::config_types::StatusBarItem;
 use codex_core::protocol::TokenUsage;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
@@ -8,11 +10,8 @@ use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Margin;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
-use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Styled;
-use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Block;
@@ -35,8 +34,11 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::textarea::TextArea;
 use crate::bottom_pane::textarea::TextAreaState;
+use crate::bottom_pane::textarea::ViMode;
 use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
+use crate::keymap::Keymap;
+use crate::theme::Palette;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -99,8 +101,18 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    keymap: Keymap,
+    status_bar: Vec<StatusBarItem>,
+    model: String,
 }
 
+/// Status bar items shown when `tui.status_bar` is not set.
+const DEFAULT_STATUS_BAR: [StatusBarItem; 3] = [
+    StatusBarItem::Keys,
+    StatusBarItem::Tokens,
+    StatusBarItem::Context,
+];
+
 /// Popup state – at most one can be visible at any time.
 enum ActivePopup {
     None,
@@ -137,6 +149,9 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            keymap: Keymap::default(),
+            status_bar: DEFAULT_STATUS_BAR.to_vec(),
+            model: String::new(),
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -278,11 +293,51 @@ impl ChatComposer {
     }
 
     /// Get the current composer text.
-    #[cfg(test)]
     pub(crate) fn current_text(&self) -> String {
         self.textarea.text().to_string()
     }
 
+    /// Replace the composer content with `text` returned from an external
+    /// editor. Paste and image placeholders that survived the edit stay
+    /// attached; the ones that were deleted are dropped.
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        let placeholders: Vec<String> = self
+            .pending_pastes
+            .iter()
+            .map(|(placeholder, _)| placeholder.clone())
+            .chain(
+                self.attached_images
+                    .iter()
+                    .map(|img| img.placeholder.clone()),
+            )
+            .collect();
+
+        self.textarea.set_text("");
+        let mut rest = text.as_str();
+        loop {
+            let next = placeholders
+                .iter()
+                .filter_map(|ph| rest.find(ph.as_str()).map(|idx| (idx, ph)))
+                .min_by_key(|(idx, _)| *idx);
+            let Some((idx, placeholder)) = next else {
+                break;
+            };
+            self.textarea.insert_str(&rest[..idx]);
+            self.textarea.insert_element(placeholder);
+            rest = &rest[idx + placeholder.len()..];
+        }
+        self.textarea.insert_str(rest);
+        self.textarea.set_cursor(self.textarea.text().len());
+
+        self.retain_attachments_in(&text);
+        self.sync_command_popup();
+        if matches!(self.active_popup, ActivePopup::Command(_)) {
+            self.dismissed_file_popup_token = None;
+        } else {
+            self.sync_file_search_popup();
+        }
+    }
+
     /// Attempt to start a burst by retro-capturing recent chars before the cursor.
     pub fn attach_image(&mut self, path: PathBuf, width: u32, height: u32, format_label: &str) {
         let placeholder = format!("[image {width}x{height} {format_label}]");
@@ -530,6 +585,8 @@ impl ChatComposer {
                 };
 
                 let sel_path = sel.to_string();
+                self.app_event_tx
+                    .send(AppEvent::RecordFileAccess(vec![PathBuf::from(&sel_path)]));
                 // If selected path looks like an image (png/jpeg), attach as image instead of inserting text.
                 let is_image = Self::is_image_path(&sel_path);
                 if is_image {
@@ -899,7 +956,9 @@ impl ChatComposer {
         {
             let has_ctrl_or_alt =
                 modifiers.contains(KeyModifiers::CONTROL) || modifiers.contains(KeyModifiers::ALT);
-            if !has_ctrl_or_alt {
+            // In vi normal mode characters are commands, never pasted text.
+            let vi_normal = self.textarea.vi_mode() == Some(ViMode::Normal);
+            if !has_ctrl_or_alt && !vi_normal {
                 // Non-ASCII characters (e.g., from IMEs) can arrive in quick bursts and be
                 // misclassified by paste heuristics. Flush any active burst buffer and insert
                 // non-ASCII characters directly.
@@ -983,9 +1042,17 @@ impl ChatComposer {
             }
         }
 
+        self.retain_attachments_in(&text_after);
+
+        (InputResult::None, true)
+    }
+
+    /// Drop pending pastes and attached images whose placeholder is no longer
+    /// part of `text`.
+    fn retain_attachments_in(&mut self, text: &str) {
         // Check if any placeholders were removed and remove their corresponding pending pastes
         self.pending_pastes
-            .retain(|(placeholder, _)| text_after.contains(placeholder));
+            .retain(|(placeholder, _)| text.contains(placeholder));
 
         // Keep attached images in proportion to how many matching placeholders exist in the text.
         // This handles duplicate placeholders that share the same visible label.
@@ -994,7 +1061,7 @@ impl ChatComposer {
             for img in &self.attached_images {
                 needed
                     .entry(img.placeholder.clone())
-                    .or_insert_with(|| text_after.matches(&img.placeholder).count());
+                    .or_insert_with(|| text.matches(&img.placeholder).count());
             }
 
             let mut used: HashMap<String, usize> = HashMap::new();
@@ -1009,8 +1076,6 @@ impl ChatComposer {
             }
             self.attached_images = kept;
         }
-
-        (InputResult::None, true)
     }
 
     /// Attempts to remove an image or paste placeholder if the cursor is at the end of one.
@@ -1239,93 +1304,146 @@ impl ChatComposer {
     pub(crate) fn set_esc_backtrack_hint(&mut self, show: bool) {
         self.esc_backtrack_hint = show;
     }
-}
 
-impl WidgetRef for ChatComposer {
-    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
-        let popup_height = match &self.active_popup {
-            ActivePopup::Command(popup) => popup.calculate_required_height(),
-            ActivePopup::File(popup) => popup.calculate_required_height(),
-            ActivePopup::None => 1,
-        };
-        let [textarea_rect, popup_rect] =
-            Layout::vertical([Constraint::Min(1), Constraint::Max(popup_height)]).areas(area);
-        match &self.active_popup {
-            ActivePopup::Command(popup) => {
-                popup.render_ref(popup_rect, buf);
-            }
-            ActivePopup::File(popup) => {
-                popup.render_ref(popup_rect, buf);
-            }
-            ActivePopup::None => {
-                let bottom_line_rect = popup_rect;
-                let key_hint_style = Style::default().fg(Color::Cyan);
-                let mut hint = if self.ctrl_c_quit_hint {
-                    vec![
-                        " ".into(),
-                        "Ctrl+C again".set_style(key_hint_style),
-                        " to quit".into(),
-                    ]
-                } else {
+    /// Shortcuts shown in the status bar.
+    pub(crate) fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub(crate) fn set_editing_mode(&mut self, mode: EditingMode) {
+        self.textarea.set_vi_enabled(mode == EditingMode::Vi);
+    }
+
+    /// Choose which items the status bar shows, in order.
+    pub(crate) fn set_status_bar(&mut self, items: Vec<StatusBarItem>) {
+        self.status_bar = items;
+    }
+
+    pub(crate) fn set_model(&mut self, model: String) {
+        self.model = model;
+    }
+
+    /// Whether Esc is needed by the composer itself: in vi insert mode it
+    /// switches to normal mode as long as there is text to edit.
+    pub(crate) fn wants_esc(&self) -> bool {
+        self.textarea.vi_mode() == Some(ViMode::Insert) && !self.textarea.is_empty()
+    }
+
+    /// Spans of the status bar below the composer.
+    fn footer_spans(&self) -> Vec<Span<'static>> {
+        let key_hint_style = Style::default().accent();
+        let dim = Style::default().secondary();
+
+        let mut sections: Vec<Vec<Span<'static>>> = Vec::new();
+        if self.textarea.vi_mode() == Some(ViMode::Normal) {
+            sections.push(vec!["-- NORMAL --".set_style(key_hint_style)]);
+        }
+        if self.ctrl_c_quit_hint {
+            sections.push(vec![
+                "Ctrl+C again".set_style(key_hint_style),
+                " to quit".into(),
+            ]);
+        }
+        for item in &self.status_bar {
+            match item {
+                StatusBarItem::Keys if !self.ctrl_c_quit_hint => {
                     let newline_hint_key = if self.use_shift_enter_hint {
                         "Shift+⏎"
                     } else {
                         "Ctrl+J"
                     };
-                    vec![
-                        " ".into(),
+                    let mut keys = vec![
                         "⏎".set_style(key_hint_style),
                         " send   ".into(),
                         newline_hint_key.set_style(key_hint_style),
                         " newline   ".into(),
-                        "Ctrl+T".set_style(key_hint_style),
+                        self.keymap.transcript.label().set_style(key_hint_style),
                         " transcript   ".into(),
                         "Ctrl+C".set_style(key_hint_style),
                         " quit".into(),
-                    ]
-                };
-
-                if !self.ctrl_c_quit_hint && self.esc_backtrack_hint {
-                    hint.push("   ".into());
-                    hint.push("Esc".set_style(key_hint_style));
-                    hint.push(" edit prev".into());
+                    ];
+                    if self.esc_backtrack_hint {
+                        keys.push("   ".into());
+                        keys.push(self.keymap.backtrack.label().set_style(key_hint_style));
+                        keys.push(" edit prev".into());
+                    }
+                    sections.push(keys);
                 }
-
-                // Append token/context usage info to the footer hints when available.
-                if let Some(token_usage_info) = &self.token_usage_info {
-                    let token_usage = &token_usage_info.total_token_usage;
-                    hint.push("   ".into());
-                    hint.push(
-                        Span::from(format!("{} tokens used", token_usage.blended_total()))
-                            .style(Style::default().add_modifier(Modifier::DIM)),
-                    );
-                    let last_token_usage = &token_usage_info.last_token_usage;
-                    if let Some(context_window) = token_usage_info.model_context_window {
+                StatusBarItem::Keys => {}
+                StatusBarItem::Model => {
+                    if !self.model.is_empty() {
+                        sections.push(vec![Span::styled(self.model.clone(), dim)]);
+                    }
+                }
+                StatusBarItem::Tokens => {
+                    if let Some(token_usage_info) = &self.token_usage_info {
+                        let token_usage = &token_usage_info.total_token_usage;
+                        sections.push(vec![Span::styled(
+                            format!("{} tokens used", token_usage.blended_total()),
+                            dim,
+                        )]);
+                    }
+                }
+                StatusBarItem::Context => {
+                    if let Some(token_usage_info) = &self.token_usage_info
+                        && let Some(context_window) = token_usage_info.model_context_window
+                    {
                         let percent_remaining: u8 = if context_window > 0 {
-                            last_token_usage.percent_of_context_window_remaining(
-                                context_window,
-                                token_usage_info.initial_prompt_tokens,
-                            )
+                            token_usage_info
+                                .last_token_usage
+                                .percent_of_context_window_remaining(
+                                    context_window,
+                                    token_usage_info.initial_prompt_tokens,
+                                )
                         } else {
                             100
                         };
-                        hint.push("   ".into());
-                        hint.push(
-                            Span::from(format!("{percent_remaining}% context left"))
-                                .style(Style::default().add_modifier(Modifier::DIM)),
-                        );
+                        sections.push(vec![Span::styled(
+                            format!("{percent_remaining}% context left"),
+                            dim,
+                        )]);
                     }
                 }
+            }
+        }
 
-                Line::from(hint)
-                    .style(Style::default().dim())
-                    .render_ref(bottom_line_rect, buf);
+        let mut spans = vec![" ".into()];
+        for (idx, section) in sections.into_iter().enumerate() {
+            if idx > 0 {
+                spans.push("   ".into());
+            }
+            spans.extend(section);
+        }
+        spans
+    }
+}
+
+impl WidgetRef for ChatComposer {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let popup_height = match &self.active_popup {
+            ActivePopup::Command(popup) => popup.calculate_required_height(),
+            ActivePopup::File(popup) => popup.calculate_required_height(),
+            ActivePopup::None => 1,
+        };
+        let [textarea_rect, popup_rect] =
+            Layout::vertical([Constraint::Min(1), Constraint::Max(popup_height)]).areas(area);
+        match &self.active_popup {
+            ActivePopup::Command(popup) => {
+                popup.render_ref(popup_rect, buf);
+            }
+            ActivePopup::File(popup) => {
+                popup.render_ref(popup_rect, buf);
+            }
+            ActivePopup::None => {
+                Line::from(self.footer_spans())
+                    .style(Style::default().secondary())
+                    .render_ref(popup_rect, buf);
             }
         }
         let border_style = if self.has_focus {
-            Style::default().fg(Color::Cyan)
+            Style::default().accent()
         } else {
-            Style::default().add_modifier(Modifier::DIM)
+            Style::default().secondary()
         };
         Block::default()
             .borders(Borders::LEFT)
@@ -1343,7 +1461,7 @@ impl WidgetRef for ChatComposer {
         StatefulWidgetRef::render_ref(&(&self.textarea), textarea_rect, buf, &mut state);
         if self.textarea.text().is_empty() {
             Line::from(self.placeholder_text.as_str())
-                .style(Style::default().dim())
+                .style(Style::default().secondary())
                 .render_ref(textarea_rect.inner(Margin::new(1, 0)), buf);
         }
     }
@@ -1544,6 +1662,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn status_bar_follows_configured_items() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_model("gpt-5".to_string());
+        composer.set_status_bar(vec![StatusBarItem::Model, StatusBarItem::Keys]);
+        let footer: String = composer
+            .footer_spans()
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(
+            footer,
+            " gpt-5   ⏎ send   Ctrl+J newline   Ctrl+T transcript   Ctrl+C quit"
+        );
+    }
+
+    #[test]
+    fn vi_normal_mode_keys_are_not_inserted() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        composer.set_editing_mode(EditingMode::Vi);
+        composer.handle_paste("hello".to_string());
+        assert!(composer.wants_esc());
+        composer.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!composer.wants_esc());
+        for c in ['0', 'x', 'x'] {
+            composer.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(composer.textarea.text(), "llo");
+        assert!(composer.footer_spans()[1].content.contains("NORMAL"));
+    }
+
     #[test]
     fn empty_enter_returns_none() {
         use crossterm::event::KeyCode;
@@ -1605,6 +1774,48 @@ mod tests {
         assert!(composer.pending_pastes.is_empty());
     }
 
+    #[test]
+    fn external_edit_keeps_surviving_placeholders() {
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEvent;
+        use crossterm::event::KeyModifiers;
+
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        let first = "a".repeat(LARGE_PASTE_CHAR_THRESHOLD + 1);
+        let second = "b".repeat(LARGE_PASTE_CHAR_THRESHOLD + 2);
+        composer.handle_paste(first.clone());
+        composer.handle_paste(second);
+        let kept = composer.pending_pastes[0].0.clone();
+
+        composer.apply_external_edit(format!("look at @src/main.rs and\n{kept}"));
+        assert_eq!(
+            composer.current_text(),
+            format!("look at @src/main.rs and\n{kept}")
+        );
+        assert_eq!(composer.pending_pastes.len(), 1);
+        assert_eq!(composer.textarea.cursor(), composer.current_text().len());
+
+        // The surviving placeholder is still an atomic element that expands
+        // on submit.
+        let (result, _) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        match result {
+            InputResult::Submitted(text) => {
+                assert_eq!(text, format!("look at @src/main.rs and\n{first}"))
+            }
+            other => panic!("expected Submitted, got: {other:?}"),
+        }
+    }
+
     #[test]
     fn edit_clears_pending_paste() {
         use crossterm::event::KeyCode;
@@ -10,6 +10,7 @@ use ratatui::widgets::Paragraph;
 use ratatui::widgets::Widget;
 
 use crate::app_event_sender::AppEventSender;
+use crate::theme::Palette;
 
 use super::BottomPane;
 use super::CancellationEvent;
@@ -41,7 +42,7 @@ pub(crate) struct ListSelectionView {
 
 impl ListSelectionView {
     fn dim_prefix_span() -> Span<'static> {
-        "▌ ".dim()
+        "▌ ".secondary()
     }
 
     fn render_dim_prefix_line(area: Rect, buf: &mut Buffer) {
@@ -175,7 +176,7 @@ impl BottomPaneView for ListSelectionView {
                 height: 1,
             };
             let subtitle_spans: Vec<Span<'static>> =
-                vec![Self::dim_prefix_span(), sub.clone().dim()];
+                vec![Self::dim_prefix_span(), sub.clone().secondary()];
             let subtitle_para = Paragraph::new(Line::from(subtitle_spans));
             subtitle_para.render(subtitle_area, buf);
             // Render the extra spacer line with the dimmed prefix to align with title/subtitle
@@ -240,7 +241,7 @@ impl BottomPaneView for ListSelectionView {
                 width: area.width,
                 height: 1,
             };
-            let footer_para = Paragraph::new(hint.clone().dim());
+            let footer_para = Paragraph::new(hint.clone().secondary());
             footer_para.render(footer_area, buf);
         }
     }
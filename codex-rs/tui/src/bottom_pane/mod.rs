@@ -2,9 +2,12 @@
 use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
+use crate::keymap::Keymap;
 use crate::tui::FrameRequester;
 use crate::user_approval_widget::ApprovalRequest;
 use bottom_pane_view::BottomPaneView;
+use codex_core::config_types::EditingMode;
+use codex_core::config_types::StatusBarItem;
 use codex_core::protocol::TokenUsage;
 use codex_file_search::FileMatch;
 use crossterm::event::KeyEvent;
@@ -36,6 +39,7 @@ pub(crate) enum CancellationEvent {
 
 pub(crate) use chat_composer::ChatComposer;
 pub(crate) use chat_composer::InputResult;
+use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::custom_prompts::CustomPrompt;
 
 use crate::status_indicator_widget::StatusIndicatorWidget;
@@ -64,6 +68,12 @@ pub(crate) struct BottomPane {
     status: Option<StatusIndicatorWidget>,
     /// Queued user messages to show under the status indicator.
     queued_user_messages: Vec<String>,
+    /// Reasoning effort picked for the running task, shown in the status
+    /// indicator.
+    reasoning_effort: Option<ReasoningEffort>,
+
+    /// Shortcuts passed on to approval modals.
+    keymap: Keymap,
 }
 
 pub(crate) struct BottomPaneParams {
@@ -73,20 +83,32 @@ pub(crate) struct BottomPaneParams {
     pub(crate) enhanced_keys_supported: bool,
     pub(crate) placeholder_text: String,
     pub(crate) disable_paste_burst: bool,
+    pub(crate) keymap: Keymap,
+    pub(crate) editing_mode: EditingMode,
+    /// Items of the composer's status bar; `None` shows the default set.
+    pub(crate) status_bar: Option<Vec<StatusBarItem>>,
+    pub(crate) model: String,
 }
 
 impl BottomPane {
     const BOTTOM_PAD_LINES: u16 = 1;
     pub fn new(params: BottomPaneParams) -> Self {
         let enhanced_keys_supported = params.enhanced_keys_supported;
+        let mut composer = ChatComposer::new(
+            params.has_input_focus,
+            params.app_event_tx.clone(),
+            enhanced_keys_supported,
+            params.placeholder_text,
+            params.disable_paste_burst,
+        );
+        composer.set_keymap(params.keymap);
+        composer.set_editing_mode(params.editing_mode);
+        if let Some(items) = params.status_bar {
+            composer.set_status_bar(items);
+        }
+        composer.set_model(params.model);
         Self {
-            composer: ChatComposer::new(
-                params.has_input_focus,
-                params.app_event_tx.clone(),
-                enhanced_keys_supported,
-                params.placeholder_text,
-                params.disable_paste_burst,
-            ),
+            composer,
             active_view: None,
             app_event_tx: params.app_event_tx,
             frame_requester: params.frame_requester,
@@ -95,7 +117,9 @@ impl BottomPane {
             ctrl_c_quit_hint: false,
             status: None,
             queued_user_messages: Vec::new(),
+            reasoning_effort: None,
             esc_backtrack_hint: false,
+            keymap: params.keymap,
         }
     }
 
@@ -167,8 +191,10 @@ impl BottomPane {
             InputResult::None
         } else {
             // If a task is running and a status line is visible, allow Esc to
-            // send an interrupt even while the composer has focus.
+            // send an interrupt even while the composer has focus, unless the
+            // composer uses it to leave vi insert mode.
             if matches!(key_event.code, crossterm::event::KeyCode::Esc)
+                && !self.composer.wants_esc()
                 && self.is_task_running
                 && let Some(status) = &self.status
             {
@@ -212,11 +238,12 @@ impl BottomPane {
     }
 
     pub fn handle_paste(&mut self, pasted: String) {
-        if self.active_view.is_none() {
-            let needs_redraw = self.composer.handle_paste(pasted);
-            if needs_redraw {
-                self.request_redraw();
-            }
+        let needs_redraw = match self.active_view.as_mut() {
+            Some(view) => view.handle_paste(pasted),
+            None => self.composer.handle_paste(pasted),
+        };
+        if needs_redraw {
+            self.request_redraw();
         }
     }
 
@@ -231,12 +258,28 @@ impl BottomPane {
         self.request_redraw();
     }
 
-    /// Get the current composer text (for tests and programmatic checks).
-    #[cfg(test)]
+    /// Get the current composer text.
     pub(crate) fn composer_text(&self) -> String {
         self.composer.current_text()
     }
 
+    /// Replace the composer text with the result of an external edit,
+    /// keeping the pastes and images whose placeholders survived.
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        self.composer.apply_external_edit(text);
+        self.request_redraw();
+    }
+
+    /// Show the reasoning effort picked for the running task, or hide it
+    /// with `None`.
+    pub(crate) fn set_reasoning_effort(&mut self, effort: Option<ReasoningEffort>) {
+        self.reasoning_effort = effort;
+        if let Some(status) = self.status.as_mut() {
+            status.set_reasoning_effort(effort);
+            self.request_redraw();
+        }
+    }
+
     /// Update the animated header shown to the left of the brackets in the
     /// status indicator (defaults to "Working"). No-ops if the status
     /// indicator is not active.
@@ -295,11 +338,13 @@ impl BottomPane {
             }
             if let Some(status) = self.status.as_mut() {
                 status.set_queued_messages(self.queued_user_messages.clone());
+                status.set_reasoning_effort(self.reasoning_effort);
             }
             self.request_redraw();
         } else {
             // Hide the status indicator when a task completes, but keep other modal views.
             self.status = None;
+            self.reasoning_effort = None;
         }
     }
 
@@ -352,6 +397,12 @@ impl BottomPane {
         !self.is_task_running && self.active_view.is_none() && !self.composer.popup_active()
     }
 
+    /// Update the model shown in the status bar.
+    pub(crate) fn set_model(&mut self, model: String) {
+        self.composer.set_model(model);
+        self.request_redraw();
+    }
+
     /// Update the *context-window remaining* indicator in the composer. This
     /// is forwarded directly to the underlying `ChatComposer`.
     pub(crate) fn set_token_usage(
@@ -380,7 +431,8 @@ impl BottomPane {
         };
 
         // Otherwise create a new approval modal overlay.
-        let modal = ApprovalModalView::new(request, self.app_event_tx.clone());
+        let mut modal = ApprovalModalView::new(request, self.app_event_tx.clone());
+        modal.set_keymap(self.keymap);
         self.active_view = Some(Box::new(modal));
         self.request_redraw()
     }
@@ -494,6 +546,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
         pane.push_approval_request(exec_request());
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
@@ -514,6 +570,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
 
         // Create an approval modal (active view).
@@ -545,6 +605,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
 
         // Start a running task so the status indicator is active above the composer.
@@ -613,6 +677,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
 
         // Begin a task: show initial status.
@@ -644,6 +712,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
 
         // Activate spinner (status view replaces composer) with no live ring.
@@ -695,6 +767,10 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            keymap: Keymap::default(),
+            editing_mode: EditingMode::default(),
+            status_bar: None,
+            model: String::new(),
         });
 
         pane.set_task_running(true);
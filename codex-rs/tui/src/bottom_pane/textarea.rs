@@ -3,7 +3,6 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::widgets::StatefulWidgetRef;
 use ratatui::widgets::WidgetRef;
@@ -26,6 +25,8 @@ pub(crate) struct TextArea {
     wrap_cache: RefCell<Option<WrapCache>>,
     preferred_col: Option<usize>,
     elements: Vec<TextElement>,
+    /// Modal editing state; `None` when the emacs-style bindings are used.
+    vi: Option<ViState>,
 }
 
 #[derive(Debug, Clone)]
@@ -40,6 +41,20 @@ pub(crate) struct TextAreaState {
     scroll: u16,
 }
 
+/// Mode of the vi bindings enabled by [`TextArea::set_vi_enabled`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ViMode {
+    Insert,
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ViState {
+    mode: ViMode,
+    /// Operator (`d` or `c`) waiting for the motion it applies to.
+    pending_operator: Option<char>,
+}
+
 impl TextArea {
     pub fn new() -> Self {
         Self {
@@ -48,6 +63,7 @@ impl TextArea {
             wrap_cache: RefCell::new(None),
             preferred_col: None,
             elements: Vec::new(),
+            vi: None,
         }
     }
 
@@ -57,6 +73,11 @@ impl TextArea {
         self.wrap_cache.replace(None);
         self.preferred_col = None;
         self.elements.clear();
+        if text.is_empty() {
+            // A cleared composer (e.g. after submitting) starts over in vi
+            // insert mode.
+            self.set_vi_mode(ViMode::Insert);
+        }
     }
 
     pub fn text(&self) -> &str {
@@ -203,6 +224,22 @@ impl TextArea {
     }
 
     pub fn input(&mut self, event: KeyEvent) {
+        match self.vi_mode() {
+            Some(ViMode::Normal) => {
+                self.vi_normal_input(event);
+                return;
+            }
+            Some(ViMode::Insert) if event.code == KeyCode::Esc => {
+                // Like vi, leaving insert mode puts the cursor on the last
+                // inserted character.
+                if self.cursor_pos > self.beginning_of_current_line() {
+                    self.move_cursor_left();
+                }
+                self.set_vi_mode(ViMode::Normal);
+                return;
+            }
+            _ => {}
+        }
         match event {
             // Some terminals (or configurations) send Control key chords as
             // C0 control characters without reporting the CONTROL modifier.
@@ -620,6 +657,140 @@ impl TextArea {
         }
     }
 
+    // ===== Vi mode =====
+
+    /// Switch between the default emacs-style bindings and modal vi bindings.
+    /// Vi editing starts in insert mode.
+    pub(crate) fn set_vi_enabled(&mut self, enabled: bool) {
+        self.vi = enabled.then_some(ViState {
+            mode: ViMode::Insert,
+            pending_operator: None,
+        });
+    }
+
+    /// The current vi mode, or `None` when vi bindings are disabled.
+    pub(crate) fn vi_mode(&self) -> Option<ViMode> {
+        self.vi.map(|vi| vi.mode)
+    }
+
+    fn set_vi_mode(&mut self, mode: ViMode) {
+        if let Some(vi) = self.vi.as_mut() {
+            vi.mode = mode;
+            vi.pending_operator = None;
+        }
+    }
+
+    fn vi_normal_input(&mut self, event: KeyEvent) {
+        if event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return;
+        }
+        let bol = self.beginning_of_current_line();
+        let eol = self.end_of_current_line();
+
+        if let Some(op) = self.vi.as_mut().and_then(|vi| vi.pending_operator.take()) {
+            let range = match event.code {
+                // `dd` removes the line itself, `cc` only its content.
+                KeyCode::Char('d') if op == 'd' => {
+                    if eol < self.text.len() {
+                        bol..eol + 1
+                    } else {
+                        bol.saturating_sub(1)..eol
+                    }
+                }
+                KeyCode::Char('c') if op == 'c' => bol..eol,
+                // `cw` keeps the whitespace after the word, as in vi.
+                KeyCode::Char('w') if op == 'c' => self.cursor_pos..self.end_of_next_word(),
+                KeyCode::Char('w') => self.cursor_pos..self.start_of_next_word(),
+                KeyCode::Char('b') => self.beginning_of_previous_word()..self.cursor_pos,
+                KeyCode::Char('$') => self.cursor_pos..eol,
+                KeyCode::Char('0') => bol..self.cursor_pos,
+                _ => return,
+            };
+            self.replace_range(range, "");
+            if op == 'c' {
+                self.set_vi_mode(ViMode::Insert);
+            } else if event.code == KeyCode::Char('d') {
+                self.set_cursor(self.beginning_of_current_line());
+            }
+            return;
+        }
+
+        match event.code {
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace if self.cursor_pos > bol => {
+                self.move_cursor_left();
+            }
+            KeyCode::Char('l') | KeyCode::Right
+                if self.next_atomic_boundary(self.cursor_pos) < eol =>
+            {
+                self.move_cursor_right();
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.move_cursor_down(),
+            KeyCode::Char('k') | KeyCode::Up => self.move_cursor_up(),
+            KeyCode::Char('w') => self.set_cursor(self.start_of_next_word()),
+            KeyCode::Char('b') => self.set_cursor(self.beginning_of_previous_word()),
+            KeyCode::Char('0') | KeyCode::Home => self.set_cursor(bol),
+            KeyCode::Char('$') | KeyCode::End => {
+                self.set_cursor(eol);
+                if eol > bol {
+                    self.move_cursor_left();
+                }
+            }
+            KeyCode::Char('i') => self.set_vi_mode(ViMode::Insert),
+            KeyCode::Char('a') => {
+                if self.cursor_pos < eol {
+                    self.move_cursor_right();
+                }
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char('I') => {
+                self.set_cursor(bol);
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char('A') => {
+                self.set_cursor(eol);
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char('o') => {
+                self.set_cursor(eol);
+                self.insert_str("\n");
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char('O') => {
+                self.set_cursor(bol);
+                self.insert_str("\n");
+                self.move_cursor_left();
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char('x') | KeyCode::Delete if self.cursor_pos < eol => {
+                self.delete_forward(1);
+            }
+            KeyCode::Char('D') => self.replace_range(self.cursor_pos..eol, ""),
+            KeyCode::Char('C') => {
+                self.replace_range(self.cursor_pos..eol, "");
+                self.set_vi_mode(ViMode::Insert);
+            }
+            KeyCode::Char(op @ ('d' | 'c')) => {
+                if let Some(vi) = self.vi.as_mut() {
+                    vi.pending_operator = Some(op);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Start of the word after the one under the cursor (vi's `w`).
+    fn start_of_next_word(&self) -> usize {
+        let rest = &self.text[self.cursor_pos..];
+        let word_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let next = rest[word_end..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |idx| word_end + idx);
+        self.adjust_pos_out_of_elements(self.cursor_pos + next, false)
+    }
+
     // ===== Text elements support =====
 
     pub fn insert_element(&mut self, text: &str) {
@@ -939,7 +1110,7 @@ impl TextArea {
                 }
                 let styled = &self.text[overlap_start..overlap_end];
                 let x_off = self.text[line_range.start..overlap_start].width() as u16;
-                let style = Style::default().fg(Color::Cyan);
+                let style = Style::default().accent();
                 buf.set_string(area.x + x_off, y, styled, style);
             }
         }
@@ -1268,6 +1439,53 @@ mod tests {
         assert_eq!(t.cursor(), end_second_nl);
     }
 
+    fn press(t: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let code = if c == '\u{1b}' {
+                KeyCode::Esc
+            } else {
+                KeyCode::Char(c)
+            };
+            t.input(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn vi_normal_mode_motions_and_edits() {
+        let mut t = TextArea::new();
+        t.set_vi_enabled(true);
+        press(&mut t, "hello big world\u{1b}");
+        assert_eq!(t.vi_mode(), Some(ViMode::Normal));
+        assert_eq!(t.cursor(), "hello big worl".len());
+
+        // Motions do not insert text.
+        press(&mut t, "0w");
+        assert_eq!(t.cursor(), "hello ".len());
+        press(&mut t, "dw");
+        assert_eq!(t.text(), "hello world");
+        press(&mut t, "$x");
+        assert_eq!(t.text(), "hello worl");
+
+        press(&mut t, "0cwhi\u{1b}");
+        assert_eq!(t.text(), "hi worl");
+        press(&mut t, "Ad!\u{1b}");
+        assert_eq!(t.text(), "hi world!");
+    }
+
+    #[test]
+    fn vi_line_operators() {
+        let mut t = TextArea::new();
+        t.set_vi_enabled(true);
+        press(&mut t, "one\ntwo\nthree\u{1b}");
+        press(&mut t, "kdd");
+        assert_eq!(t.text(), "one\nthree");
+        assert_eq!(t.cursor(), "one\n".len());
+        press(&mut t, "ccsix\u{1b}");
+        assert_eq!(t.text(), "one\nsix");
+        press(&mut t, "kOzero\u{1b}");
+        assert_eq!(t.text(), "zero\none\nsix");
+    }
+
     #[test]
     fn end_of_line_or_down_at_end_of_text() {
         let mut t = ta_with("one\ntwo");
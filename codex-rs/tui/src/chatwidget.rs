@@ -11,7 +11,10 @@ use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::AuxiliaryTask;
+use codex_core::protocol::AuxiliaryTokenCountEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CommitMessageDraftEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -20,12 +23,20 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::InputMessageKind;
+use codex_core::protocol::ListApprovalRulesResponseEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::McpListServersResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpSamplingEndEvent;
+use codex_core::protocol::McpServerStatus;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PlanSummaryEvent;
+use codex_core::protocol::ReasoningEffortChosenEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
@@ -63,6 +74,7 @@ use crate::history_cell::CommandOutput;
 use crate::history_cell::ExecCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::PatchEventType;
+use crate::keymap::Keymap;
 use crate::slash_command::SlashCommand;
 use crate::tui::FrameRequester;
 // streaming internals are provided by crate::streaming and crate::markdown_stream
@@ -110,6 +122,8 @@ pub(crate) struct ChatWidget {
     initial_user_message: Option<UserMessage>,
     total_token_usage: TokenUsage,
     last_token_usage: TokenUsage,
+    /// Tokens used by compaction, titles, commit messages and plan summaries.
+    auxiliary_token_usage: HashMap<AuxiliaryTask, TokenUsage>,
     // Stream lifecycle controller
     stream: StreamController,
     running_commands: HashMap<String, RunningCommand>,
@@ -317,6 +331,21 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_plan_update(update));
     }
 
+    fn on_auxiliary_token_count(&mut self, ev: AuxiliaryTokenCountEvent) {
+        let total = self.auxiliary_token_usage.entry(ev.task).or_default();
+        *total = add_token_usage(total, &ev.token_usage);
+    }
+
+    fn on_commit_message_draft(&mut self, ev: CommitMessageDraftEvent) {
+        self.add_to_history(history_cell::new_commit_message_draft(ev.message));
+        self.request_redraw();
+    }
+
+    fn on_plan_summary(&mut self, ev: PlanSummaryEvent) {
+        self.add_to_history(history_cell::new_plan_summary(ev.summary));
+        self.request_redraw();
+    }
+
     fn on_exec_approval_request(&mut self, id: String, ev: ExecApprovalRequestEvent) {
         let id2 = id.clone();
         let ev2 = ev.clone();
@@ -335,6 +364,32 @@ impl ChatWidget {
         );
     }
 
+    fn on_mcp_tool_call_approval_request(
+        &mut self,
+        id: String,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_tool_call_approval(id, ev),
+            |s| s.handle_mcp_tool_call_approval_now(id2, ev2),
+        );
+    }
+
+    fn on_mcp_sampling_approval_request(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        let id2 = id.clone();
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_sampling_approval(id, ev),
+            |s| s.handle_mcp_sampling_approval_now(id2, ev2),
+        );
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -349,6 +404,9 @@ impl ChatWidget {
     }
 
     fn on_patch_apply_begin(&mut self, event: PatchApplyBeginEvent) {
+        self.app_event_tx.send(AppEvent::RecordFileAccess(
+            event.changes.keys().cloned().collect(),
+        ));
         self.add_to_history(history_cell::new_patch_event(
             PatchEventType::ApplyBegin {
                 auto_approved: event.auto_approved,
@@ -381,6 +439,14 @@ impl ChatWidget {
         self.defer_or_handle(|q| q.push_mcp_end(ev), |s| s.handle_mcp_end_now(ev2));
     }
 
+    fn on_mcp_sampling_end(&mut self, ev: McpSamplingEndEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_mcp_sampling_end(ev),
+            |s| s.handle_mcp_sampling_end_now(ev2),
+        );
+    }
+
     fn on_web_search_begin(&mut self, _ev: WebSearchBeginEvent) {
         self.flush_answer_stream_with_separator();
     }
@@ -550,6 +616,44 @@ impl ChatWidget {
             id,
             reason: ev.reason,
             grant_root: ev.grant_root,
+            changes: ev.changes,
+            cwd: self.config.cwd.clone(),
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
+    pub(crate) fn handle_mcp_tool_call_approval_now(
+        &mut self,
+        id: String,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::McpToolCall {
+            id,
+            server: ev.invocation.server,
+            tool: ev.invocation.tool,
+            arguments: ev.invocation.arguments,
+            reason: ev.reason,
+        };
+        self.bottom_pane.push_approval_request(request);
+        self.request_redraw();
+    }
+
+    pub(crate) fn handle_mcp_sampling_approval_now(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        self.flush_answer_stream_with_separator();
+
+        let request = ApprovalRequest::McpSampling {
+            id,
+            server: ev.server,
+            prompt: ev.prompt,
+            system_prompt: ev.system_prompt,
+            max_tokens: ev.max_tokens,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -610,6 +714,16 @@ impl ChatWidget {
         ));
     }
 
+    pub(crate) fn handle_mcp_sampling_end_now(&mut self, ev: McpSamplingEndEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_completed_mcp_sampling(
+            80,
+            ev.server,
+            ev.duration,
+            ev.result,
+        ));
+    }
+
     fn layout_areas(&self, area: Rect) -> [Rect; 2] {
         Layout::vertical([
             Constraint::Max(
@@ -649,6 +763,10 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                keymap: Keymap::from_config(&config.tui),
+                editing_mode: config.tui.editing_mode,
+                status_bar: config.tui.status_bar.clone(),
+                model: config.model.clone(),
             }),
             active_exec_cell: None,
             config: config.clone(),
@@ -658,6 +776,7 @@ impl ChatWidget {
             ),
             total_token_usage: TokenUsage::default(),
             last_token_usage: TokenUsage::default(),
+            auxiliary_token_usage: HashMap::new(),
             stream: StreamController::new(config),
             running_commands: HashMap::new(),
             task_complete_pending: false,
@@ -702,6 +821,10 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                keymap: Keymap::from_config(&config.tui),
+                editing_mode: config.tui.editing_mode,
+                status_bar: config.tui.status_bar.clone(),
+                model: config.model.clone(),
             }),
             active_exec_cell: None,
             config: config.clone(),
@@ -711,6 +834,7 @@ impl ChatWidget {
             ),
             total_token_usage: TokenUsage::default(),
             last_token_usage: TokenUsage::default(),
+            auxiliary_token_usage: HashMap::new(),
             stream: StreamController::new(config),
             running_commands: HashMap::new(),
             task_complete_pending: false,
@@ -830,6 +954,9 @@ impl ChatWidget {
             SlashCommand::Approvals => {
                 self.open_approvals_popup();
             }
+            SlashCommand::Permissions => {
+                self.submit_op(Op::ListApprovalRules);
+            }
             SlashCommand::Quit => {
                 self.app_event_tx.send(AppEvent::ExitRequest);
             }
@@ -859,6 +986,15 @@ impl ChatWidget {
             SlashCommand::Mention => {
                 self.insert_str("@");
             }
+            SlashCommand::Edit => {
+                self.app_event_tx.send(AppEvent::OpenExternalEditor);
+            }
+            SlashCommand::Commit => {
+                self.submit_op(Op::DraftCommitMessage);
+            }
+            SlashCommand::Plan => {
+                self.submit_op(Op::SummarizePlan);
+            }
             SlashCommand::Status => {
                 self.add_status_output();
             }
@@ -1037,6 +1173,10 @@ impl ChatWidget {
             EventMsg::TaskStarted(_) => self.on_task_started(),
             EventMsg::TaskComplete(TaskCompleteEvent { .. }) => self.on_task_complete(),
             EventMsg::TokenCount(token_usage) => self.on_token_count(token_usage),
+            EventMsg::AuxiliaryTokenCount(ev) => self.on_auxiliary_token_count(ev),
+            EventMsg::ReasoningEffortChosen(ReasoningEffortChosenEvent { effort, .. }) => {
+                self.bottom_pane.set_reasoning_effort(Some(effort))
+            }
             EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
             EventMsg::TurnAborted(ev) => match ev.reason {
                 TurnAbortReason::Interrupted => {
@@ -1047,6 +1187,8 @@ impl ChatWidget {
                 }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
+            EventMsg::CommitMessageDraft(ev) => self.on_commit_message_draft(ev),
+            EventMsg::PlanSummary(ev) => self.on_plan_summary(ev),
             EventMsg::ExecApprovalRequest(ev) => {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.clone().unwrap_or_default(), ev)
@@ -1054,6 +1196,12 @@ impl ChatWidget {
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.clone().unwrap_or_default(), ev)
             }
+            EventMsg::McpToolCallApprovalRequest(ev) => {
+                self.on_mcp_tool_call_approval_request(id.clone().unwrap_or_default(), ev)
+            }
+            EventMsg::McpSamplingApprovalRequest(ev) => {
+                self.on_mcp_sampling_approval_request(id.clone().unwrap_or_default(), ev)
+            }
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1061,11 +1209,16 @@ impl ChatWidget {
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
+            // The end event carries everything worth showing.
+            EventMsg::McpSamplingBegin(_) => {}
+            EventMsg::McpSamplingEnd(ev) => self.on_mcp_sampling_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
+            EventMsg::McpListServersResponse(ev) => self.on_list_mcp_servers(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::ListApprovalRulesResponse(ev) => self.on_list_approval_rules(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
@@ -1146,6 +1299,7 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_status_output(
             &self.config,
             &self.total_token_usage,
+            &self.auxiliary_token_usage,
             &self.session_id,
         ));
     }
@@ -1253,10 +1407,13 @@ impl ChatWidget {
     /// Set the reasoning effort in the widget's config copy.
     pub(crate) fn set_reasoning_effort(&mut self, effort: ReasoningEffortConfig) {
         self.config.model_reasoning_effort = effort;
+        // Choosing an effort turns automatic effort off in core as well.
+        self.config.auto_reasoning_effort = None;
     }
 
     /// Set the model in the widget's config copy.
     pub(crate) fn set_model(&mut self, model: String) {
+        self.bottom_pane.set_model(model.clone());
         self.config.model = model;
     }
 
@@ -1264,7 +1421,7 @@ impl ChatWidget {
         if self.config.mcp_servers.is_empty() {
             self.add_to_history(history_cell::empty_mcp_output());
         } else {
-            self.submit_op(Op::ListMcpTools);
+            self.submit_op(Op::ListMcpServers);
         }
     }
 
@@ -1301,6 +1458,19 @@ impl ChatWidget {
         self.bottom_pane.insert_str(text);
     }
 
+    pub(crate) fn composer_text(&self) -> String {
+        self.bottom_pane.composer_text()
+    }
+
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        self.bottom_pane.apply_external_edit(text);
+    }
+
+    pub(crate) fn add_error_message(&mut self, message: String) {
+        self.add_to_history(history_cell::new_error_event(message));
+        self.request_redraw();
+    }
+
     pub(crate) fn show_esc_backtrack_hint(&mut self) {
         self.bottom_pane.show_esc_backtrack_hint();
     }
@@ -1321,6 +1491,55 @@ impl ChatWidget {
         self.add_to_history(history_cell::new_mcp_tools_output(&self.config, ev.tools));
     }
 
+    /// Show the health of the MCP servers and offer to restart, disable or
+    /// enable them. Core replies to each action with the updated list, which
+    /// shows up here again.
+    fn on_list_mcp_servers(&mut self, ev: McpListServersResponseEvent) {
+        self.add_to_history(history_cell::new_mcp_servers_output(&ev.servers));
+        if ev.servers.is_empty() {
+            return;
+        }
+
+        let mut items: Vec<SelectionItem> = Vec::new();
+        for server in ev.servers {
+            let name = server.name;
+            let enabled = server.status != McpServerStatus::Disabled;
+            let restart_name = name.clone();
+            items.push(SelectionItem {
+                name: format!("Restart {name}"),
+                description: Some(format!("{} · {} tools", server.status, server.tools.len())),
+                is_current: false,
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::RestartMcpServer {
+                        server: restart_name.clone(),
+                    }));
+                })],
+            });
+            items.push(SelectionItem {
+                name: if enabled {
+                    format!("Disable {name}")
+                } else {
+                    format!("Enable {name}")
+                },
+                description: None,
+                is_current: false,
+                actions: vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::SetMcpServerEnabled {
+                        server: name.clone(),
+                        enabled: !enabled,
+                    }));
+                })],
+            });
+        }
+
+        self.bottom_pane.show_selection_view(
+            "MCP Servers".to_string(),
+            None,
+            Some("Press Enter to apply the selected action or Esc to go back".to_string()),
+            items,
+        );
+    }
+
     fn on_list_custom_prompts(&mut self, ev: ListCustomPromptsResponseEvent) {
         let len = ev.custom_prompts.len();
         debug!("received {len} custom prompts");
@@ -1328,6 +1547,45 @@ impl ChatWidget {
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
     }
 
+    /// Show the saved approval rules; selecting one revokes it. Core replies
+    /// to the revocation with the updated list, which reopens this popup.
+    fn on_list_approval_rules(&mut self, ev: ListApprovalRulesResponseEvent) {
+        if ev.rules.is_empty() {
+            self.add_to_history(history_cell::new_approval_rules_empty());
+            return;
+        }
+        let items: Vec<SelectionItem> = ev
+            .rules
+            .into_iter()
+            .map(|rule| {
+                let name = shlex::try_join(rule.prefix.iter().map(String::as_str))
+                    .unwrap_or_else(|_| rule.prefix.join(" "));
+                let description = match &rule.project {
+                    Some(project) => format!("in {}", project.display()),
+                    None => "in every project".to_string(),
+                };
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::CodexOp(Op::RemoveApprovalRule {
+                        rule: rule.clone(),
+                    }));
+                })];
+                SelectionItem {
+                    name: format!("{name} …"),
+                    description: Some(description),
+                    is_current: false,
+                    actions,
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(
+            "Saved Approval Rules".to_string(),
+            Some("Commands starting with these prefixes run without asking".to_string()),
+            Some("Press Enter to revoke the selected rule or Esc to go back".to_string()),
+            items,
+        );
+    }
+
     /// Programmatically submit a user text message as if typed in the
     /// composer. The text will be added to conversation history and sent to
     /// the agent.
@@ -4,6 +4,9 @@ use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::McpSamplingApprovalRequestEvent;
+use codex_core::protocol::McpSamplingEndEvent;
+use codex_core::protocol::McpToolCallApprovalRequestEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyEndEvent;
@@ -14,10 +17,13 @@ use super::ChatWidget;
 pub(crate) enum QueuedInterrupt {
     ExecApproval(String, ExecApprovalRequestEvent),
     ApplyPatchApproval(String, ApplyPatchApprovalRequestEvent),
+    McpToolCallApproval(String, McpToolCallApprovalRequestEvent),
+    McpSamplingApproval(String, McpSamplingApprovalRequestEvent),
     ExecBegin(ExecCommandBeginEvent),
     ExecEnd(ExecCommandEndEvent),
     McpBegin(McpToolCallBeginEvent),
     McpEnd(McpToolCallEndEvent),
+    McpSamplingEnd(McpSamplingEndEvent),
     PatchEnd(PatchApplyEndEvent),
 }
 
@@ -51,6 +57,24 @@ impl InterruptManager {
             .push_back(QueuedInterrupt::ApplyPatchApproval(id, ev));
     }
 
+    pub(crate) fn push_mcp_tool_call_approval(
+        &mut self,
+        id: String,
+        ev: McpToolCallApprovalRequestEvent,
+    ) {
+        self.queue
+            .push_back(QueuedInterrupt::McpToolCallApproval(id, ev));
+    }
+
+    pub(crate) fn push_mcp_sampling_approval(
+        &mut self,
+        id: String,
+        ev: McpSamplingApprovalRequestEvent,
+    ) {
+        self.queue
+            .push_back(QueuedInterrupt::McpSamplingApproval(id, ev));
+    }
+
     pub(crate) fn push_exec_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.queue.push_back(QueuedInterrupt::ExecBegin(ev));
     }
@@ -67,6 +91,10 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::McpEnd(ev));
     }
 
+    pub(crate) fn push_mcp_sampling_end(&mut self, ev: McpSamplingEndEvent) {
+        self.queue.push_back(QueuedInterrupt::McpSamplingEnd(ev));
+    }
+
     pub(crate) fn push_patch_end(&mut self, ev: PatchApplyEndEvent) {
         self.queue.push_back(QueuedInterrupt::PatchEnd(ev));
     }
@@ -78,10 +106,17 @@ impl InterruptManager {
                 QueuedInterrupt::ApplyPatchApproval(id, ev) => {
                     chat.handle_apply_patch_approval_now(id, ev)
                 }
+                QueuedInterrupt::McpToolCallApproval(id, ev) => {
+                    chat.handle_mcp_tool_call_approval_now(id, ev)
+                }
+                QueuedInterrupt::McpSamplingApproval(id, ev) => {
+                    chat.handle_mcp_sampling_approval_now(id, ev)
+                }
                 QueuedInterrupt::ExecBegin(ev) => chat.handle_exec_begin_now(ev),
                 QueuedInterrupt::ExecEnd(ev) => chat.handle_exec_end_now(ev),
                 QueuedInterrupt::McpBegin(ev) => chat.handle_mcp_begin_now(ev),
                 QueuedInterrupt::McpEnd(ev) => chat.handle_mcp_end_now(ev),
+                QueuedInterrupt::McpSamplingEnd(ev) => chat.handle_mcp_sampling_end_now(ev),
                 QueuedInterrupt::PatchEnd(ev) => chat.handle_patch_apply_end_now(ev),
             }
         }
@@ -5,6 +5,7 @@ use codex_core::CodexAuth;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
+use codex_core::config_types::EditingMode;
 use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
 use codex_core::plan_tool::UpdatePlanArgs;
@@ -213,6 +214,10 @@ fn make_chatwidget_manual() -> (
         enhanced_keys_supported: false,
         placeholder_text: "Ask Codex to do anything".to_string(),
         disable_paste_burst: false,
+        keymap: Keymap::default(),
+        editing_mode: EditingMode::default(),
+        status_bar: None,
+        model: String::new(),
     });
     let widget = ChatWidget {
         app_event_tx,
@@ -223,6 +228,7 @@ fn make_chatwidget_manual() -> (
         initial_user_message: None,
         total_token_usage: TokenUsage::default(),
         last_token_usage: TokenUsage::default(),
+        auxiliary_token_usage: HashMap::new(),
         stream: StreamController::new(cfg),
         running_commands: HashMap::new(),
         task_complete_pending: false,
@@ -1368,6 +1374,22 @@ fn plan_update_renders_history_cell() {
     assert!(blob.contains("Write tests"));
 }
 
+#[test]
+fn commit_message_draft_renders_history_cell() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::CommitMessageDraft(CommitMessageDraftEvent {
+            message: "Fix flaky login test\n\nWait for the session cookie.".to_string(),
+        }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let blob = lines_to_single_string(cells.last().expect("commit message cell"));
+    assert!(blob.contains("/commit"), "missing header: {blob:?}");
+    assert!(blob.contains("Fix flaky login test"));
+    assert!(blob.contains("Wait for the session cookie."));
+}
+
 #[test]
 fn stream_error_is_rendered_to_history() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1,4 +1,3 @@
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
@@ -10,12 +9,15 @@ use std::path::PathBuf;
 
 use crate::exec_command::relativize_to_home;
 use crate::history_cell::PatchEventType;
+use crate::render::highlight::highlight_file;
+use crate::theme::Palette;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::FileChange;
 
 const SPACES_AFTER_LINE_NUMBER: usize = 6;
 
 // Internal representation for diff line rendering
+#[derive(Clone, Copy)]
 enum DiffLineType {
     Insert,
     Delete,
@@ -98,9 +100,9 @@ fn render_changes_block(
     fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
         let mut spans = Vec::new();
         spans.push("(".into());
-        spans.push(format!("+{added}").green());
+        spans.push(format!("+{added}").success());
         spans.push(" ".into());
-        spans.push(format!("-{removed}").red());
+        spans.push(format!("-{removed}").error());
         spans.push(")".into());
         spans
     }
@@ -182,7 +184,7 @@ fn render_changes_block(
                 && file_count == 1;
         if !skip_file_header {
             let mut header: Vec<RtSpan<'static>> = Vec::new();
-            header.push("  └ ".dim());
+            header.push("  └ ".secondary());
             header.extend(render_path(&r));
             header.push(" ".into());
             header.extend(render_line_count_summary(r.added, r.removed));
@@ -190,72 +192,18 @@ fn render_changes_block(
         }
 
         match r.change {
-            FileChange::Add { content } => {
-                for (i, raw) in content.lines().enumerate() {
-                    out.extend(push_wrapped_diff_line(
-                        i + 1,
-                        DiffLineType::Insert,
-                        raw,
-                        term_cols,
-                    ));
-                }
-            }
-            FileChange::Delete { content } => {
-                for (i, raw) in content.lines().enumerate() {
-                    out.extend(push_wrapped_diff_line(
-                        i + 1,
-                        DiffLineType::Delete,
-                        raw,
-                        term_cols,
-                    ));
-                }
+            FileChange::Add { .. } | FileChange::Delete { .. } => {
+                out.extend(render_whole_file_lines(&r.path, &r.change, term_cols));
             }
             FileChange::Update { unified_diff, .. } => {
                 if let Ok(patch) = diffy::Patch::from_str(&unified_diff) {
                     let mut is_first_hunk = true;
                     for h in patch.hunks() {
                         if !is_first_hunk {
-                            out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
+                            out.push(RtLine::from(vec!["    ".into(), "⋮".secondary()]));
                         }
                         is_first_hunk = false;
-
-                        let mut old_ln = h.old_range().start();
-                        let mut new_ln = h.new_range().start();
-                        for l in h.lines() {
-                            match l {
-                                diffy::Line::Insert(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        new_ln,
-                                        DiffLineType::Insert,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    new_ln += 1;
-                                }
-                                diffy::Line::Delete(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        old_ln,
-                                        DiffLineType::Delete,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    old_ln += 1;
-                                }
-                                diffy::Line::Context(text) => {
-                                    let s = text.trim_end_matches('\n');
-                                    out.extend(push_wrapped_diff_line(
-                                        new_ln,
-                                        DiffLineType::Context,
-                                        s,
-                                        term_cols,
-                                    ));
-                                    old_ln += 1;
-                                    new_ln += 1;
-                                }
-                            }
-                        }
+                        out.extend(render_hunk_lines(&r.path, h, term_cols));
                     }
                 }
             }
@@ -265,7 +213,100 @@ fn render_changes_block(
     out
 }
 
-fn display_path_for(path: &Path, cwd: &Path) -> String {
+/// Render every line of an added or deleted file. Updates render nothing;
+/// use [`render_hunk_lines`] for their hunks instead.
+pub(crate) fn render_whole_file_lines(
+    path: &Path,
+    change: &FileChange,
+    term_cols: usize,
+) -> Vec<RtLine<'static>> {
+    let (content, kind) = match change {
+        FileChange::Add { content } => (content, DiffLineType::Insert),
+        FileChange::Delete { content } => (content, DiffLineType::Delete),
+        FileChange::Update { .. } => return Vec::new(),
+    };
+    let highlighted = highlight_file(path, content);
+    let mut out = Vec::new();
+    for (i, raw) in content.lines().enumerate() {
+        let spans = highlighted
+            .as_ref()
+            .and_then(|h| h.get(i))
+            .map(Vec::as_slice);
+        out.extend(push_wrapped_diff_line(i + 1, kind, raw, spans, term_cols));
+    }
+    out
+}
+
+/// Render a single hunk of a unified diff with line numbers. `path` is only
+/// used to pick the language for highlighting.
+pub(crate) fn render_hunk_lines(
+    path: &Path,
+    h: &diffy::Hunk<'_, str>,
+    term_cols: usize,
+) -> Vec<RtLine<'static>> {
+    // Highlight the hunk as one snippet, in order, so that constructs spanning
+    // several lines (strings, comments) keep their colors. Removed and added
+    // lines are interleaved, which is close enough for display.
+    let snippet: String = h
+        .lines()
+        .iter()
+        .map(|l| match l {
+            diffy::Line::Insert(text) | diffy::Line::Delete(text) | diffy::Line::Context(text) => {
+                format!("{}\n", text.trim_end_matches('\n'))
+            }
+        })
+        .collect();
+    let highlighted = highlight_file(path, &snippet);
+
+    let mut out = Vec::new();
+    let mut old_ln = h.old_range().start();
+    let mut new_ln = h.new_range().start();
+    for (idx, l) in h.lines().iter().enumerate() {
+        let spans = highlighted
+            .as_ref()
+            .and_then(|hl| hl.get(idx))
+            .map(Vec::as_slice);
+        match l {
+            diffy::Line::Insert(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    new_ln,
+                    DiffLineType::Insert,
+                    s,
+                    spans,
+                    term_cols,
+                ));
+                new_ln += 1;
+            }
+            diffy::Line::Delete(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    old_ln,
+                    DiffLineType::Delete,
+                    s,
+                    spans,
+                    term_cols,
+                ));
+                old_ln += 1;
+            }
+            diffy::Line::Context(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    new_ln,
+                    DiffLineType::Context,
+                    s,
+                    spans,
+                    term_cols,
+                ));
+                old_ln += 1;
+                new_ln += 1;
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn display_path_for(path: &Path, cwd: &Path) -> String {
     let path_in_same_repo = match (get_git_repo_root(cwd), get_git_repo_root(path)) {
         (Some(cwd_repo), Some(path_repo)) => cwd_repo == path_repo,
         _ => false,
@@ -278,7 +319,7 @@ fn display_path_for(path: &Path, cwd: &Path) -> String {
     chosen.display().to_string()
 }
 
-fn calculate_add_remove_from_diff(diff: &str) -> (usize, usize) {
+pub(crate) fn calculate_add_remove_from_diff(diff: &str) -> (usize, usize) {
     if let Ok(patch) = diffy::Patch::from_str(diff) {
         patch
             .hunks()
@@ -295,15 +336,18 @@ fn calculate_add_remove_from_diff(diff: &str) -> (usize, usize) {
     }
 }
 
+/// Render one diff line, wrapped to `term_cols`. When `highlighted` holds the
+/// syntax-highlighted spans of `text`, they replace the flat add/remove color;
+/// the sign keeps it so additions and deletions stay distinguishable.
 fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
     text: &str,
+    highlighted: Option<&[RtSpan<'static>]>,
     term_cols: usize,
 ) -> Vec<RtLine<'static>> {
     let indent = "    ";
     let ln_str = line_number.to_string();
-    let mut remaining_text: &str = text;
 
     // Reserve a fixed number of spaces after the line number so that content starts
     // at a consistent column. Content includes a 1-character diff sign prefix
@@ -318,6 +362,15 @@ fn push_wrapped_diff_line(
         DiffLineType::Delete => ('-', style_del()),
         DiffLineType::Context => (' ', style_context()),
     };
+    let mut remaining: Vec<RtSpan<'static>> = match highlighted {
+        // Removed code is dimmed so it reads as going away.
+        Some(spans) if matches!(kind, DiffLineType::Delete) => spans
+            .iter()
+            .map(|span| span.clone().add_modifier(Modifier::DIM))
+            .collect(),
+        Some(spans) => spans.to_vec(),
+        None => vec![RtSpan::styled(text.to_string(), line_style)],
+    };
     let mut lines: Vec<RtLine<'static>> = Vec::new();
 
     loop {
@@ -325,41 +378,63 @@ fn push_wrapped_diff_line(
         // compute how many columns are available after the prefix, then split
         // at a UTF-8 character boundary so this row's chunk fits exactly.
         let available_content_cols = term_cols.saturating_sub(prefix_cols + 1).max(1);
-        let split_at_byte_index = remaining_text
-            .char_indices()
-            .nth(available_content_cols)
-            .map(|(i, _)| i)
-            .unwrap_or_else(|| remaining_text.len());
-        let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
-        remaining_text = rest;
-
-        if first {
-            // Build gutter (indent + line number + spacing) as a dimmed span
+        let (chunk, rest) = split_spans_at(remaining, available_content_cols);
+        remaining = rest;
+
+        let mut spans = if first {
+            // Build gutter (indent + line number + spacing) as a dimmed span,
+            // followed by a sign ('+'/'-'/' ') styled per diff kind
             let gutter = format!("{indent}{ln_str}{}", " ".repeat(gap_after_ln));
-            // Content with a sign ('+'/'-'/' ') styled per diff kind
-            let content = format!("{sign_char}{chunk}");
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(content, line_style),
-            ]));
             first = false;
+            vec![
+                RtSpan::styled(gutter, style_gutter()),
+                RtSpan::styled(sign_char.to_string(), line_style),
+            ]
         } else {
             // Continuation lines keep a space for the sign column so content aligns
             let gutter = format!("{indent}{} ", " ".repeat(ln_str.len() + gap_after_ln));
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(chunk.to_string(), line_style),
-            ]));
-        }
-        if remaining_text.is_empty() {
+            vec![RtSpan::styled(gutter, style_gutter())]
+        };
+        spans.extend(chunk);
+        lines.push(RtLine::from(spans));
+        if remaining.is_empty() {
             break;
         }
     }
     lines
 }
 
+/// Split `spans` after the first `cols` characters, keeping each piece's style.
+fn split_spans_at(
+    spans: Vec<RtSpan<'static>>,
+    cols: usize,
+) -> (Vec<RtSpan<'static>>, Vec<RtSpan<'static>>) {
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    let mut remaining_cols = cols;
+    for span in spans {
+        if remaining_cols == 0 {
+            tail.push(span);
+            continue;
+        }
+        match span.content.char_indices().nth(remaining_cols) {
+            Some((split_at, _)) => {
+                let (left, right) = span.content.split_at(split_at);
+                head.push(RtSpan::styled(left.to_string(), span.style));
+                tail.push(RtSpan::styled(right.to_string(), span.style));
+                remaining_cols = 0;
+            }
+            None => {
+                remaining_cols -= span.content.chars().count();
+                head.push(span);
+            }
+        }
+    }
+    (head, tail)
+}
+
 fn style_gutter() -> Style {
-    Style::default().add_modifier(Modifier::DIM)
+    Style::default().secondary()
 }
 
 fn style_context() -> Style {
@@ -367,11 +442,11 @@ fn style_context() -> Style {
 }
 
 fn style_add() -> Style {
-    Style::default().fg(Color::Green)
+    Style::default().success()
 }
 
 fn style_del() -> Style {
-    Style::default().fg(Color::Red)
+    Style::default().error()
 }
 
 #[cfg(test)]
@@ -380,6 +455,7 @@ mod tests {
     use insta::assert_snapshot;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use ratatui::style::Color;
     use ratatui::text::Text;
     use ratatui::widgets::Paragraph;
     use ratatui::widgets::WidgetRef;
@@ -755,4 +831,19 @@ mod tests {
 
         snapshot_lines("apply_update_block_relativizes_path", lines, 80, 10);
     }
+
+    #[test]
+    fn highlighted_lines_wrap_without_losing_styles() {
+        let spans = vec![
+            RtSpan::styled("let".to_string(), Style::default().fg(Color::Magenta)),
+            RtSpan::raw(" value = 1;".to_string()),
+        ];
+        let lines = push_wrapped_diff_line(7, DiffLineType::Insert, "", Some(spans.as_slice()), 18);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert_eq!(text, vec!["    7     +let val", "           ue = 1;"]);
+        assert_eq!(lines[0].spans[2].style.fg, Some(Color::Magenta));
+    }
 }
@@ -0,0 +1,82 @@
+//! Editing the composer text in the user's own editor.
+//!
+//! The editor named by `$VISUAL` (or `$EDITOR`) runs on a temporary file while
+//! the terminal is handed back to it; the file's content becomes the new
+//! composer text once the editor exits.
+
+use std::io::Write;
+use std::process::Command;
+
+use color_eyre::eyre::Result;
+use color_eyre::eyre::WrapErr;
+use color_eyre::eyre::eyre;
+
+use crate::tui;
+
+/// The editor program and its arguments, from the first of `$VISUAL` and
+/// `$EDITOR` that is set.
+fn editor_command(visual: Option<String>, editor: Option<String>) -> Result<(String, Vec<String>)> {
+    let spec = [visual, editor]
+        .into_iter()
+        .flatten()
+        .find(|value| !value.trim().is_empty())
+        .ok_or_else(|| eyre!("set $VISUAL or $EDITOR to edit the message in an editor"))?;
+    let mut argv = shlex::split(&spec).unwrap_or_default().into_iter();
+    match argv.next() {
+        Some(program) => Ok((program, argv.collect())),
+        None => Err(eyre!("could not parse editor command `{spec}`")),
+    }
+}
+
+/// Open `text` in the user's editor and return the edited text. The caller
+/// must stop reading terminal events first, since the editor needs stdin.
+pub(crate) fn edit(text: &str) -> Result<String> {
+    let (program, args) =
+        editor_command(std::env::var("VISUAL").ok(), std::env::var("EDITOR").ok())?;
+
+    let mut file = tempfile::Builder::new()
+        .prefix("codex-prompt-")
+        .suffix(".md")
+        .tempfile()
+        .wrap_err("failed to create a temporary file for the editor")?;
+    file.write_all(text.as_bytes())?;
+    // Close our handle so editors that replace the file on save work too.
+    let path = file.into_temp_path();
+
+    tui::restore()?;
+    let status = Command::new(&program).args(&args).arg(&path).status();
+    tui::set_modes()?;
+
+    let status = status.wrap_err_with(|| format!("failed to launch editor `{program}`"))?;
+    if !status.success() {
+        return Err(eyre!("editor `{program}` exited with {status}"));
+    }
+
+    let edited = std::fs::read_to_string(&path)
+        .wrap_err("failed to read the message back from the editor")?;
+    // Editors add a final newline on save; it is never part of the message.
+    Ok(edited.trim_end_matches(['\n', '\r']).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn visual_takes_precedence_and_arguments_are_split() {
+        assert_eq!(
+            editor_command(Some("code --wait".to_string()), Some("vi".to_string())).unwrap(),
+            ("code".to_string(), vec!["--wait".to_string()])
+        );
+        assert_eq!(
+            editor_command(
+                Some(" ".to_string()),
+                Some("'/opt/my editor/bin/ed'".to_string())
+            )
+            .unwrap(),
+            ("/opt/my editor/bin/ed".to_string(), Vec::new())
+        );
+        assert!(editor_command(None, None).is_err());
+    }
+}
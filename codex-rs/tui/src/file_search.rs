@@ -17,9 +17,14 @@
 //!    recent query.
 //! 4. If there is a in-flight search that is not a prefix of the latest thing
 //!    the user typed, it is cancelled.
+//!
+//! Searches are answered from a persistent [`file_search::FileIndex`] of the
+//! working directory (cached under `CODEX_HOME`) once it is ready, and fall
+//! back to walking the directory tree until then.
 
 use codex_file_search as file_search;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -34,6 +39,9 @@ use crate::app_event_sender::AppEventSender;
 const MAX_FILE_SEARCH_RESULTS: NonZeroUsize = NonZeroUsize::new(8).unwrap();
 const NUM_FILE_SEARCH_THREADS: NonZeroUsize = NonZeroUsize::new(2).unwrap();
 
+/// Subdirectory of `CODEX_HOME` that holds the per-workspace file indexes.
+const FILE_INDEX_DIR: &str = "file_index";
+
 /// How long to wait after a keystroke before firing the first search when none
 /// is currently running. Keeps early queries more meaningful.
 const FILE_SEARCH_DEBOUNCE: Duration = Duration::from_millis(100);
@@ -46,6 +54,7 @@ pub(crate) struct FileSearchManager {
     state: Arc<Mutex<SearchState>>,
 
     search_dir: PathBuf,
+    index: Arc<file_search::FileIndex>,
     app_tx: AppEventSender,
 }
 
@@ -66,7 +75,10 @@ struct ActiveSearch {
 }
 
 impl FileSearchManager {
-    pub fn new(search_dir: PathBuf, tx: AppEventSender) -> Self {
+    pub fn new(search_dir: PathBuf, codex_home: &Path, tx: AppEventSender) -> Self {
+        let index =
+            file_search::FileIndex::open(&search_dir, Some(&codex_home.join(FILE_INDEX_DIR)));
+        index.start(NUM_FILE_SEARCH_THREADS);
         Self {
             state: Arc::new(Mutex::new(SearchState {
                 latest_query: String::new(),
@@ -74,10 +86,17 @@ impl FileSearchManager {
                 active_search: None,
             })),
             search_dir,
+            index,
             app_tx: tx,
         }
     }
 
+    /// Ranks `paths` higher in future searches, e.g. because they were just
+    /// mentioned or edited.
+    pub fn record_file_access(&self, paths: &[PathBuf]) {
+        self.index.record_access(paths);
+    }
+
     /// Call whenever the user edits the `@` token.
     pub fn on_user_query(&self, query: String) {
         {
@@ -116,6 +135,7 @@ impl FileSearchManager {
         // debounce timer.
         let state = self.state.clone();
         let search_dir = self.search_dir.clone();
+        let index = self.index.clone();
         let tx_clone = self.app_tx.clone();
         thread::spawn(move || {
             // Always do a minimum debounce, but then poll until the
@@ -148,6 +168,7 @@ impl FileSearchManager {
             FileSearchManager::spawn_file_search(
                 query,
                 search_dir,
+                index,
                 tx_clone,
                 cancellation_token,
                 state,
@@ -158,23 +179,36 @@ impl FileSearchManager {
     fn spawn_file_search(
         query: String,
         search_dir: PathBuf,
+        index: Arc<file_search::FileIndex>,
         tx: AppEventSender,
         cancellation_token: Arc<AtomicBool>,
         search_state: Arc<Mutex<SearchState>>,
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
-            let matches = file_search::run(
-                &query,
-                MAX_FILE_SEARCH_RESULTS,
-                &search_dir,
-                Vec::new(),
-                NUM_FILE_SEARCH_THREADS,
-                cancellation_token.clone(),
-                compute_indices,
-            )
-            .map(|res| res.matches)
-            .unwrap_or_default();
+            let matches = if index.is_ready() {
+                index
+                    .search(
+                        &query,
+                        MAX_FILE_SEARCH_RESULTS,
+                        NUM_FILE_SEARCH_THREADS,
+                        cancellation_token.clone(),
+                        compute_indices,
+                    )
+                    .matches
+            } else {
+                file_search::run(
+                    &query,
+                    MAX_FILE_SEARCH_RESULTS,
+                    &search_dir,
+                    Vec::new(),
+                    NUM_FILE_SEARCH_THREADS,
+                    cancellation_token.clone(),
+                    compute_indices,
+                )
+                .map(|res| res.matches)
+                .unwrap_or_default()
+            };
 
             let is_cancelled = cancellation_token.load(Ordering::Relaxed);
             if !is_cancelled {
@@ -4,6 +4,7 @@ use crate::exec_command::strip_bash_lc_and_escape;
 use crate::markdown::append_markdown;
 use crate::slash_command::SlashCommand;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::theme::Palette;
 use base64::Engine;
 use codex_ansi_escape::ansi_escape_line;
 use codex_common::create_config_summary_entries;
@@ -15,8 +16,11 @@ use codex_core::plan_tool::PlanItemArg;
 use codex_core::plan_tool::StepStatus;
 use codex_core::plan_tool::UpdatePlanArgs;
 use codex_core::project_doc::discover_project_doc_paths;
+use codex_core::protocol::AuxiliaryTask;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpServerStatus;
+use codex_core::protocol::McpServerSummary;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TokenUsage;
@@ -100,14 +104,14 @@ impl HistoryCell for UserHistoryCell {
         );
 
         for line in wrapped {
-            lines.push(vec!["▌".cyan().dim(), line.to_string().dim()].into());
+            lines.push(vec!["▌".accent().dim(), line.to_string().secondary()].into());
         }
         lines
     }
 
     fn transcript_lines(&self) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = Vec::new();
-        lines.push("user".cyan().bold().into());
+        lines.push("user".accent().bold().into());
         lines.extend(self.message.lines().map(|l| l.to_string().into()));
         lines
     }
@@ -157,7 +161,7 @@ impl HistoryCell for AgentMessageCell {
     fn transcript_lines(&self) -> Vec<Line<'static>> {
         let mut out: Vec<Line<'static>> = Vec::new();
         if self.is_first_line {
-            out.push("codex".magenta().bold().into());
+            out.push("codex".codex().bold().into());
         }
         out.extend(self.lines.clone());
         out
@@ -241,7 +245,7 @@ impl HistoryCell for ExecCell {
             let cmd_display = strip_bash_lc_and_escape(&call.command);
             for (i, part) in cmd_display.lines().enumerate() {
                 if i == 0 {
-                    lines.push(vec!["$ ".magenta(), part.to_string().into()].into());
+                    lines.push(vec!["$ ".codex(), part.to_string().into()].into());
                 } else {
                     lines.push(vec!["    ".into(), part.to_string().into()].into());
                 }
@@ -254,14 +258,14 @@ impl HistoryCell for ExecCell {
                     .map(format_duration)
                     .unwrap_or_else(|| "unknown".to_string());
                 let mut result: Line = if output.exit_code == 0 {
-                    Line::from("✓".green().bold())
+                    Line::from("✓".success().bold())
                 } else {
                     Line::from(vec![
-                        "✗".red().bold(),
+                        "✗".error().bold(),
                         format!(" ({})", output.exit_code).into(),
                     ])
                 };
-                result.push_span(format!(" • {duration}").dim());
+                result.push_span(format!(" • {duration}").secondary());
                 lines.push(result);
             }
             lines.push("".into());
@@ -336,7 +340,7 @@ impl ExecCell {
                     "Read",
                     itertools::Itertools::intersperse(
                         names.into_iter().map(|n| n.into()),
-                        ", ".dim(),
+                        ", ".secondary(),
                     )
                     .collect(),
                 )]
@@ -355,7 +359,7 @@ impl ExecCell {
                                 "Search",
                                 match (query, path) {
                                     (Some(q), Some(p)) => {
-                                        vec![q.into(), " in ".dim(), p.into()]
+                                        vec![q.into(), " in ".secondary(), p.into()]
                                     }
                                     (Some(q), None) => vec![q.into()],
                                     _ => vec![cmd.into()],
@@ -380,13 +384,13 @@ impl ExecCell {
                     let mut spans = Vec::with_capacity(line.spans.len() + 1);
                     spans.push(if first {
                         first = false;
-                        "  └ ".dim()
+                        "  └ ".secondary()
                     } else {
                         "    ".into()
                     });
                     if first_sub {
                         first_sub = false;
-                        spans.push(title.cyan());
+                        spans.push(title.accent());
                         spans.push(" ".into());
                     } else {
                         spans.push(" ".repeat(title.width() + 1).into());
@@ -410,8 +414,8 @@ impl ExecCell {
         };
         let success = call.output.as_ref().map(|o| o.exit_code == 0);
         let bullet = match success {
-            Some(true) => "•".green().bold(),
-            Some(false) => "•".red().bold(),
+            Some(true) => "•".success().bold(),
+            Some(false) => "•".error().bold(),
             None => spinner(call.start_time),
         };
         let title = if self.is_active() { "Running" } else { "Ran" };
@@ -449,7 +453,7 @@ impl ExecCell {
                 );
                 lines.extend(wrapped.into_iter().enumerate().map(|(j, l)| {
                     if i == 0 && j == 0 {
-                        vec!["  └ ".dim(), l[4..].to_string().into()].into()
+                        vec!["  └ ".secondary(), l[4..].to_string().into()].into()
                     } else {
                         l.to_string().into()
                     }
@@ -473,11 +477,11 @@ impl ExecCell {
                     lines.extend(wrapped.into_iter().map(|l| {
                         Line::from(vec![
                             if i == 0 && !branch_consumed {
-                                "  └ ".dim()
+                                "  └ ".secondary()
                             } else {
-                                "    ".dim()
+                                "    ".secondary()
                             },
-                            l.to_string().dim(),
+                            l.to_string().secondary(),
                         ])
                     }));
                 }
@@ -664,39 +668,39 @@ pub(crate) fn new_session_info(
 
         let mut lines: Vec<Line<'static>> = Vec::new();
         lines.push(Line::from(vec![
-            ">_ ".dim(),
+            ">_ ".secondary(),
             "You are using OpenAI Codex in".bold(),
-            format!(" {cwd_str}").dim(),
+            format!(" {cwd_str}").secondary(),
         ]));
-        lines.push(Line::from("".dim()));
+        lines.push(Line::from("".secondary()));
         lines.push(Line::from(
-            " To get started, describe a task or try one of these commands:".dim(),
+            " To get started, describe a task or try one of these commands:".secondary(),
         ));
-        lines.push(Line::from("".dim()));
+        lines.push(Line::from("".secondary()));
         if !has_agents_md {
             lines.push(Line::from(vec![
                 " /init".bold(),
-                format!(" - {}", SlashCommand::Init.description()).dim(),
+                format!(" - {}", SlashCommand::Init.description()).secondary(),
             ]));
         }
         lines.push(Line::from(vec![
             " /status".bold(),
-            format!(" - {}", SlashCommand::Status.description()).dim(),
+            format!(" - {}", SlashCommand::Status.description()).secondary(),
         ]));
         lines.push(Line::from(vec![
             " /approvals".bold(),
-            format!(" - {}", SlashCommand::Approvals.description()).dim(),
+            format!(" - {}", SlashCommand::Approvals.description()).secondary(),
         ]));
         lines.push(Line::from(vec![
             " /model".bold(),
-            format!(" - {}", SlashCommand::Model.description()).dim(),
+            format!(" - {}", SlashCommand::Model.description()).secondary(),
         ]));
         PlainHistoryCell { lines }
     } else if config.model == model {
         PlainHistoryCell { lines: Vec::new() }
     } else {
         let lines = vec![
-            "model changed:".magenta().bold().into(),
+            "model changed:".codex().bold().into(),
             format!("requested: {}", config.model).into(),
             format!("used: {model}").into(),
         ];
@@ -737,7 +741,7 @@ fn spinner(start_time: Option<Instant>) -> Span<'static> {
 }
 
 pub(crate) fn new_active_mcp_tool_call(invocation: McpInvocation) -> PlainHistoryCell {
-    let title_line = Line::from(vec!["tool".magenta(), " running...".dim()]);
+    let title_line = Line::from(vec!["tool".codex(), " running...".secondary()]);
     let lines: Vec<Line> = vec![title_line, format_mcp_invocation(invocation.clone())];
 
     PlainHistoryCell { lines }
@@ -802,14 +806,14 @@ pub(crate) fn new_completed_mcp_tool_call(
     let duration = format_duration(duration);
     let status_str = if success { "success" } else { "failed" };
     let title_line = Line::from(vec![
-        "tool".magenta(),
+        "tool".codex(),
         " ".into(),
         if success {
-            status_str.green()
+            status_str.success()
         } else {
-            status_str.red()
+            status_str.error()
         },
-        format!(", duration: {duration}").dim(),
+        format!(", duration: {duration}").secondary(),
     ]);
 
     let mut lines: Vec<Line<'static>> = Vec::new();
@@ -846,28 +850,63 @@ pub(crate) fn new_completed_mcp_tool_call(
                             format!("link: {uri}")
                         }
                     };
-                    lines.push(Line::styled(
-                        line_text,
-                        Style::default().add_modifier(Modifier::DIM),
-                    ));
+                    lines.push(Line::styled(line_text, Style::default().secondary()));
                 }
             }
         }
         Err(e) => {
-            lines.push(vec!["Error: ".red().bold(), e.into()].into());
+            lines.push(vec!["Error: ".error().bold(), e.into()].into());
         }
     };
 
     Box::new(PlainHistoryCell { lines })
 }
 
+pub(crate) fn new_completed_mcp_sampling(
+    num_cols: usize,
+    server: String,
+    duration: Duration,
+    result: Result<String, String>,
+) -> PlainHistoryCell {
+    let duration = format_duration(duration);
+    let status = if result.is_ok() {
+        "success".success()
+    } else {
+        "failed".error()
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        Line::from(vec![
+            "sampling".codex(),
+            " ".into(),
+            status,
+            format!(", duration: {duration}").secondary(),
+        ]),
+        Line::from(vec!["requested by ".secondary(), server.bold()]),
+    ];
+    match result {
+        Ok(text) => {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(
+                format_and_truncate_tool_result(&text, TOOL_CALL_MAX_LINES, num_cols),
+                Style::default().secondary(),
+            ));
+        }
+        Err(e) => {
+            lines.push(vec!["Error: ".error().bold(), e.into()].into());
+        }
+    }
+
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_status_output(
     config: &Config,
     usage: &TokenUsage,
+    auxiliary_usage: &HashMap<AuxiliaryTask, TokenUsage>,
     session_id: &Option<Uuid>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
-    lines.push("/status".magenta().into());
+    lines.push("/status".codex().into());
 
     let config_entries = create_config_summary_entries(config);
     let lookup = |k: &str| -> String {
@@ -1019,14 +1058,48 @@ pub(crate) fn new_status_output(
         "  • Total: ".into(),
         usage.blended_total().to_string().into(),
     ]));
+    // Estimated cost, when the registry knows the model's prices.
+    if let Some(pricing) = &config.model_family.pricing {
+        lines.push(Line::from(vec![
+            "  • Estimated Cost: ".into(),
+            format!("${:.2}", pricing.cost(usage)).into(),
+        ]));
+    }
+    // Jobs that ran alongside the conversation, in a stable order.
+    for (task, label) in [
+        (AuxiliaryTask::Compact, "Compaction"),
+        (AuxiliaryTask::Title, "Titles"),
+        (AuxiliaryTask::CommitMessage, "Commit messages"),
+        (AuxiliaryTask::PlanSummary, "Plan summaries"),
+        (AuxiliaryTask::McpSampling, "MCP sampling"),
+    ] {
+        if let Some(usage) = auxiliary_usage.get(&task) {
+            lines.push(Line::from(vec![
+                format!("  • {label}: ").into(),
+                usage.blended_total().to_string().into(),
+            ]));
+        }
+    }
+
+    PlainHistoryCell { lines }
+}
 
+pub(crate) fn new_commit_message_draft(message: String) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/commit".codex().into(), "".into()];
+    lines.extend(message.lines().map(|line| Line::from(line.to_string())));
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_plan_summary(summary: String) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec!["/plan".codex().into(), "".into()];
+    lines.extend(summary.lines().map(|line| Line::from(line.to_string())));
     PlainHistoryCell { lines }
 }
 
 /// Render a summary of configured MCP servers from the current `Config`.
 pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
     let lines: Vec<Line<'static>> = vec![
-        "/mcp".magenta().into(),
+        "/mcp".codex().into(),
         "".into(),
         vec!["🔌  ".into(), "MCP Tools".bold()].into(),
         "".into(),
@@ -1036,7 +1109,7 @@ pub(crate) fn empty_mcp_output() -> PlainHistoryCell {
             "\u{1b}]8;;https://github.com/openai/codex/blob/main/docs/config.md#mcp_servers\u{7}MCP docs\u{1b}]8;;\u{7}".underlined(),
             " to configure them.".into(),
         ])
-        .style(Style::default().add_modifier(Modifier::DIM)),
+        .style(Style::default().secondary()),
     ];
 
     PlainHistoryCell { lines }
@@ -1048,7 +1121,7 @@ pub(crate) fn new_mcp_tools_output(
     tools: std::collections::HashMap<String, mcp_types::Tool>,
 ) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = vec![
-        "/mcp".magenta().into(),
+        "/mcp".codex().into(),
         "".into(),
         vec!["🔌  ".into(), "MCP Tools".bold()].into(),
         "".into(),
@@ -1088,17 +1161,78 @@ pub(crate) fn new_mcp_tools_output(
     PlainHistoryCell { lines }
 }
 
+/// Render the health of every configured MCP server along with its tools.
+pub(crate) fn new_mcp_servers_output(servers: &[McpServerSummary]) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![
+        "/mcp".codex().into(),
+        "".into(),
+        vec!["🔌  ".into(), "MCP Servers".bold()].into(),
+        "".into(),
+    ];
+
+    for server in servers {
+        let status = server.status.to_string();
+        let status = match server.status {
+            McpServerStatus::Running => status.success(),
+            McpServerStatus::Failed => status.error(),
+            McpServerStatus::Starting | McpServerStatus::Restarting | McpServerStatus::Disabled => {
+                status.secondary()
+            }
+        };
+        lines.push(
+            vec![
+                "  • Server: ".into(),
+                server.name.clone().into(),
+                " (".into(),
+                status,
+                ")".into(),
+            ]
+            .into(),
+        );
+
+        if server.tools.is_empty() {
+            lines.push("    • Tools: (none)".into());
+        } else {
+            let mut names = server.tools.clone();
+            names.sort();
+            lines.push(vec!["    • Tools: ".into(), names.join(", ").into()].into());
+        }
+        if server.restarts > 0 {
+            lines.push(format!("    • Restarts: {}", server.restarts).into());
+        }
+        if let Some(error) = &server.last_error {
+            lines.push(vec!["    • Last error: ".into(), error.clone().error()].into());
+        }
+        lines.push(Line::from(""));
+    }
+
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_approval_rules_empty() -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = vec![
+        "/permissions".codex().into(),
+        "".into(),
+        "  • No saved approval rules. Choose \"Project\" or \"Global\" when approving a command to add one."
+            .italic()
+            .into(),
+        "".into(),
+    ];
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
     // in terminals like Ghostty.
     let lines: Vec<Line<'static>> =
-        vec![vec![padded_emoji("🖐").red().bold(), " ".into(), message.into()].into()];
+        vec![vec![padded_emoji("🖐").error().bold(), " ".into(), message.into()].into()];
     PlainHistoryCell { lines }
 }
 
 pub(crate) fn new_stream_error_event(message: String) -> PlainHistoryCell {
-    let lines: Vec<Line<'static>> = vec![vec![padded_emoji("⚠️").into(), message.dim()].into()];
+    let lines: Vec<Line<'static>> =
+        vec![vec![padded_emoji("⚠️").into(), message.secondary()].into()];
     PlainHistoryCell { lines }
 }
 
@@ -1120,15 +1254,15 @@ impl HistoryCell for PlanUpdateCell {
             let wrap_width = width.saturating_sub(4).max(1) as usize;
             textwrap::wrap(text, wrap_width)
                 .into_iter()
-                .map(|s| s.to_string().dim().italic().into())
+                .map(|s| s.to_string().secondary().italic().into())
                 .collect()
         };
 
         let render_step = |status: &StepStatus, text: &str| -> Vec<Line<'static>> {
             let (box_str, step_style) = match status {
-                StepStatus::Completed => ("✔ ", Style::default().crossed_out().dim()),
-                StepStatus::InProgress => ("□ ", Style::default().cyan().bold()),
-                StepStatus::Pending => ("□ ", Style::default().dim()),
+                StepStatus::Completed => ("✔ ", Style::default().crossed_out().secondary()),
+                StepStatus::InProgress => ("□ ", Style::default().accent().bold()),
+                StepStatus::Pending => ("□ ", Style::default().secondary()),
             };
             let wrap_width = (width as usize)
                 .saturating_sub(4)
@@ -1180,7 +1314,7 @@ impl HistoryCell for PlanUpdateCell {
         };
 
         if self.plan.is_empty() {
-            indented_lines.push(Line::from("(no steps provided)".dim().italic()));
+            indented_lines.push(Line::from("(no steps provided)".secondary().italic()));
         } else {
             for PlanItemArg { step, status } in self.plan.iter() {
                 indented_lines.extend(render_step(status, step));
@@ -1211,7 +1345,7 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
 
     // Failure title
-    lines.push(Line::from("✘ Failed to apply patch".magenta().bold()));
+    lines.push(Line::from("✘ Failed to apply patch".codex().bold()));
 
     if !stderr.trim().is_empty() {
         lines.extend(output_lines(
@@ -1235,7 +1369,7 @@ pub(crate) fn new_reasoning_block(
     config: &Config,
 ) -> TranscriptOnlyHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
-    lines.push(Line::from("thinking".magenta().italic()));
+    lines.push(Line::from("thinking".codex().italic()));
     append_markdown(&full_reasoning_buffer, &mut lines, config);
     TranscriptOnlyHistoryCell { lines }
 }
@@ -1259,11 +1393,11 @@ pub(crate) fn new_reasoning_summary_block(
                 let summary_buffer = full_reasoning_buffer[after_close_idx..].to_string();
 
                 let mut header_lines: Vec<Line<'static>> = Vec::new();
-                header_lines.push(Line::from("Thinking".magenta().italic()));
+                header_lines.push(Line::from("Thinking".codex().italic()));
                 append_markdown(&header_buffer, &mut header_lines, config);
 
                 let mut summary_lines: Vec<Line<'static>> = Vec::new();
-                summary_lines.push(Line::from("Thinking".magenta().bold()));
+                summary_lines.push(Line::from("Thinking".codex().bold()));
                 append_markdown(&summary_buffer, &mut summary_lines, config);
 
                 return vec![
@@ -1313,9 +1447,7 @@ fn output_lines(
             "    "
         };
         line.spans.insert(0, prefix.into());
-        line.spans.iter_mut().for_each(|span| {
-            span.style = span.style.add_modifier(Modifier::DIM);
-        });
+        line.spans.iter_mut().for_each(dim_output_span);
         out.push(line);
     }
 
@@ -1336,9 +1468,7 @@ fn output_lines(
         if include_prefix {
             line.spans.insert(0, "    ".into());
         }
-        line.spans.iter_mut().for_each(|span| {
-            span.style = span.style.add_modifier(Modifier::DIM);
-        });
+        line.spans.iter_mut().for_each(dim_output_span);
         out.push(line);
     }
 
@@ -1356,16 +1486,25 @@ fn format_mcp_invocation<'a>(invocation: McpInvocation) -> Line<'a> {
         .unwrap_or_default();
 
     let invocation_spans = vec![
-        invocation.server.clone().cyan(),
+        invocation.server.clone().accent(),
         ".".into(),
-        invocation.tool.clone().cyan(),
+        invocation.tool.clone().accent(),
         "(".into(),
-        args_str.dim(),
+        args_str.secondary(),
         ")".into(),
     ];
     invocation_spans.into()
 }
 
+/// Shows command output as secondary text, keeping any colors the command
+/// printed itself.
+fn dim_output_span(span: &mut Span<'_>) {
+    span.style = match span.style.fg {
+        Some(_) => span.style.add_modifier(Modifier::DIM),
+        None => span.style.secondary(),
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,220 @@
+//! Key bindings that can be remapped through `[tui.keybindings]`.
+//!
+//! Bindings are written as an optional list of modifiers followed by a key,
+//! separated by `-` or `+`: `ctrl-t`, `alt+up`, `esc`, `y`.
+
+use codex_core::config_types::Tui;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub(crate) const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub(crate) fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_ascii_lowercase();
+        let mut parts: Vec<&str> = spec.split(['-', '+']).collect();
+        let key = parts.pop()?;
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "option" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+        let code = match key {
+            "esc" | "escape" => KeyCode::Esc,
+            "enter" | "return" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    (Some('f'), Some(_)) => KeyCode::F(key[1..].parse().ok()?),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Self { code, modifiers })
+    }
+
+    /// Whether `event` triggers this binding. Shift is ignored because
+    /// terminals disagree on reporting it, so `y` also accepts `Y`.
+    pub(crate) fn matches(&self, event: &KeyEvent) -> bool {
+        let code_matches = match (self.code, event.code) {
+            (KeyCode::Char(a), KeyCode::Char(b)) => a.eq_ignore_ascii_case(&b),
+            (a, b) => a == b,
+        };
+        code_matches
+            && event.modifiers.difference(KeyModifiers::SHIFT)
+                == self.modifiers.difference(KeyModifiers::SHIFT)
+    }
+
+    /// Human-readable form shown in hints, e.g. `Ctrl+T`.
+    pub(crate) fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) if self.modifiers.is_empty() => c.to_string(),
+            KeyCode::Char(c) => c.to_ascii_uppercase().to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Enter => "⏎".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            other => format!("{other:?}"),
+        };
+        label.push_str(&key);
+        label
+    }
+}
+
+/// The remappable bindings, resolved once from the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Keymap {
+    pub(crate) transcript: KeyBinding,
+    pub(crate) backtrack: KeyBinding,
+    pub(crate) external_editor: KeyBinding,
+    pub(crate) approve: KeyBinding,
+    pub(crate) approve_for_session: KeyBinding,
+    pub(crate) deny: KeyBinding,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            transcript: KeyBinding::new(KeyCode::Char('t'), KeyModifiers::CONTROL),
+            backtrack: KeyBinding::new(KeyCode::Esc, KeyModifiers::NONE),
+            external_editor: KeyBinding::new(KeyCode::Char('g'), KeyModifiers::CONTROL),
+            approve: KeyBinding::new(KeyCode::Char('y'), KeyModifiers::NONE),
+            approve_for_session: KeyBinding::new(KeyCode::Char('a'), KeyModifiers::NONE),
+            deny: KeyBinding::new(KeyCode::Char('n'), KeyModifiers::NONE),
+        }
+    }
+}
+
+impl Keymap {
+    pub(crate) fn from_config(tui: &Tui) -> Self {
+        let mut keymap = Self::default();
+        let overrides = &tui.keybindings;
+        for (name, spec, slot) in [
+            ("transcript", &overrides.transcript, &mut keymap.transcript),
+            ("backtrack", &overrides.backtrack, &mut keymap.backtrack),
+            (
+                "external_editor",
+                &overrides.external_editor,
+                &mut keymap.external_editor,
+            ),
+            ("approve", &overrides.approve, &mut keymap.approve),
+            (
+                "approve_for_session",
+                &overrides.approve_for_session,
+                &mut keymap.approve_for_session,
+            ),
+            ("deny", &overrides.deny, &mut keymap.deny),
+        ] {
+            if let Some(spec) = spec {
+                match KeyBinding::parse(spec) {
+                    Some(binding) => *slot = binding,
+                    None => {
+                        warn!("ignoring invalid key binding `{spec}` for tui.keybindings.{name}")
+                    }
+                }
+            }
+        }
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config_types::TuiKeybindings;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_modifiers_and_named_keys() {
+        assert_eq!(
+            KeyBinding::parse("ctrl-t"),
+            Some(KeyBinding::new(KeyCode::Char('t'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            KeyBinding::parse("Alt+Up"),
+            Some(KeyBinding::new(KeyCode::Up, KeyModifiers::ALT))
+        );
+        assert_eq!(
+            KeyBinding::parse("esc"),
+            Some(KeyBinding::new(KeyCode::Esc, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            KeyBinding::parse("f5"),
+            Some(KeyBinding::new(KeyCode::F(5), KeyModifiers::NONE))
+        );
+        assert_eq!(KeyBinding::parse("hyper-x"), None);
+        assert_eq!(KeyBinding::parse("nope"), None);
+    }
+
+    #[test]
+    fn matching_ignores_letter_case() {
+        let binding = KeyBinding::parse("y").unwrap();
+        assert!(binding.matches(&KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE)));
+        assert!(binding.matches(&KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT)));
+        assert!(!binding.matches(&KeyEvent::new(KeyCode::Char('y'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn config_overrides_defaults() {
+        let tui = Tui {
+            keybindings: TuiKeybindings {
+                transcript: Some("ctrl-o".to_string()),
+                deny: Some("not a key".to_string()),
+                ..TuiKeybindings::default()
+            },
+            ..Tui::default()
+        };
+        let keymap = Keymap::from_config(&tui);
+        assert_eq!(keymap.transcript.label(), "Ctrl+O");
+        assert_eq!(keymap.deny, Keymap::default().deny);
+        assert_eq!(keymap.backtrack.label(), "Esc");
+    }
+}
@@ -38,15 +38,18 @@ mod clipboard_paste;
 pub mod custom_terminal;
 mod diff_render;
 mod exec_command;
+mod external_editor;
 mod file_search;
 mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_stream;
 pub mod onboarding;
 mod pager_overlay;
+mod patch_review;
 mod render;
 mod resume_picker;
 mod session_log;
@@ -55,6 +58,7 @@ mod slash_command;
 mod status_indicator_widget;
 mod streaming;
 mod text_formatting;
+mod theme;
 mod tui;
 mod user_approval_widget;
 
@@ -218,7 +222,7 @@ pub async fn run_main(
         .with_filter(env_filter());
 
     if cli.oss {
-        codex_ollama::ensure_oss_ready(&config)
+        codex_ollama::ensure_oss_ready(&mut config)
             .await
             .map_err(|e| std::io::Error::other(format!("OSS setup failed: {e}")))?;
     }
@@ -247,6 +251,10 @@ async fn run_ratatui_app(
         tracing::error!("panic: {info}");
         prev_hook(info);
     }));
+    // Styling applies to everything drawn from here on, onboarding included.
+    render::highlight::init(&config);
+    theme::init(&config);
+
     let mut terminal = tui::init()?;
     terminal.clear()?;
 
@@ -256,6 +264,7 @@ async fn run_ratatui_app(
     // within the TUI scrollback. Building spans keeps styling consistent.
     #[cfg(not(debug_assertions))]
     if let Some(latest_version) = updates::get_upgrade_version(&config) {
+        use crate::theme::Palette as _;
         use ratatui::style::Stylize as _;
         use ratatui::text::Line;
 
@@ -265,7 +274,7 @@ async fn run_ratatui_app(
 
         let mut lines: Vec<Line<'static>> = Vec::new();
         lines.push(Line::from(vec![
-            "✨⬆️ Update available!".bold().cyan(),
+            "✨⬆️ Update available!".bold().accent(),
             " ".into(),
             format!("{current_version} -> {latest_version}.").into(),
         ]));
@@ -274,7 +283,7 @@ async fn run_ratatui_app(
             let npm_cmd = "npm install -g @openai/codex@latest";
             lines.push(Line::from(vec![
                 "Run ".into(),
-                npm_cmd.cyan(),
+                npm_cmd.accent(),
                 " to update.".into(),
             ]));
         } else if cfg!(target_os = "macos")
@@ -283,13 +292,13 @@ async fn run_ratatui_app(
             let brew_cmd = "brew upgrade codex";
             lines.push(Line::from(vec![
                 "Run ".into(),
-                brew_cmd.cyan(),
+                brew_cmd.accent(),
                 " to update.".into(),
             ]));
         } else {
             lines.push(Line::from(vec![
                 "See ".into(),
-                "https://github.com/openai/codex/releases/latest".cyan(),
+                "https://github.com/openai/codex/releases/latest".accent(),
                 " for the latest releases and installation options.".into(),
             ]));
         }
@@ -1,4 +1,5 @@
 use crate::citation_regex::CITATION_REGEX;
+use crate::render::highlight;
 use codex_core::config::Config;
 use codex_core::config_types::UriBasedFileOpener;
 use ratatui::text::Line;
@@ -24,8 +25,8 @@ fn append_markdown_with_opener_and_cwd(
     // when formatted by the markdown renderer/highlighter. To preserve code
     // block content exactly, split the source into "text" and "code" segments:
     // - Render non-code text through `tui_markdown` (with citation rewrite).
-    // - Render code block content verbatim as plain lines without additional
-    //   formatting, preserving leading spaces.
+    // - Render code block content verbatim, preserving leading spaces, and
+    //   only color it when the fence names a language we can highlight.
     for seg in split_text_and_fences(markdown_source) {
         match seg {
             Segment::Text(s) => {
@@ -33,17 +34,25 @@ fn append_markdown_with_opener_and_cwd(
                 let rendered = tui_markdown::from_str(&processed);
                 crate::render::line_utils::push_owned_lines(&rendered.lines, lines);
             }
-            Segment::Code { content, .. } => {
-                // Emit the code content exactly as-is, line by line.
-                // We don't attempt syntax highlighting to avoid whitespace bugs.
-                for line in content.split_inclusive('\n') {
+            Segment::Code { lang, content } => {
+                // Emit the code content exactly as-is, line by line. The
+                // highlighter splits lines the same way and keeps every
+                // character, so it only adds styling.
+                let highlighted = lang
+                    .as_deref()
+                    .and_then(|lang| highlight::highlight_code(lang, &content));
+                for (idx, line) in content.split_inclusive('\n').enumerate() {
                     // split_inclusive keeps the trailing \n; we want lines without it.
                     let line = if let Some(stripped) = line.strip_suffix('\n') {
                         stripped
                     } else {
                         line
                     };
-                    let owned_line: Line<'static> = line.to_string().into();
+                    let owned_line: Line<'static> =
+                        match highlighted.as_ref().and_then(|h| h.get(idx)) {
+                            Some(spans) => Line::from(spans.clone()),
+                            None => line.to_string().into(),
+                        };
                     lines.push(owned_line);
                 }
             }
@@ -101,7 +110,7 @@ fn rewrite_file_citations<'a>(
 
 // Minimal code block splitting.
 // - Recognizes fenced blocks opened by ``` or ~~~ (allowing leading whitespace).
-//   The opening fence may include a language string which we ignore.
+//   The opening fence may include a language string, used for highlighting.
 //   The closing fence must be on its own line (ignoring surrounding whitespace).
 // - Additionally recognizes indented code blocks that begin after a blank line
 //   with a line starting with at least 4 spaces or a tab, and continue for
@@ -109,7 +118,7 @@ fn rewrite_file_citations<'a>(
 enum Segment {
     Text(String),
     Code {
-        _lang: Option<String>,
+        lang: Option<String>,
         content: String,
     },
 }
@@ -201,7 +210,7 @@ fn split_text_and_fences(src: &str) -> Vec<Segment> {
                     if trimmed == fence_token {
                         // End code block: emit segment without fences
                         segments.push(Segment::Code {
-                            _lang: code_lang.take(),
+                            lang: code_lang.take(),
                             content: code_content.clone(),
                         });
                         code_content.clear();
@@ -226,7 +235,7 @@ fn split_text_and_fences(src: &str) -> Vec<Segment> {
                     } else {
                         // Close the indented code block and reprocess this line as normal text.
                         segments.push(Segment::Code {
-                            _lang: None,
+                            lang: None,
                             content: code_content.clone(),
                         });
                         code_content.clear();
@@ -243,7 +252,7 @@ fn split_text_and_fences(src: &str) -> Vec<Segment> {
     if code_mode != CodeMode::None {
         // Unterminated code fence: treat accumulated content as a code segment.
         segments.push(Segment::Code {
-            _lang: code_lang.take(),
+            lang: code_lang.take(),
             content: code_content.clone(),
         });
     } else if !curr_text.is_empty() {
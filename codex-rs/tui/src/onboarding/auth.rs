@@ -11,7 +11,6 @@ use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::prelude::Widget;
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
@@ -27,6 +26,7 @@ use crate::LoginStatus;
 use crate::onboarding::onboarding_screen::KeyboardHandler;
 use crate::onboarding::onboarding_screen::StepStateProvider;
 use crate::shimmer::shimmer_spans;
+use crate::theme::Palette;
 use crate::tui::FrameRequester;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -159,8 +159,8 @@ impl AuthModeWidget {
 
             let line1 = if is_selected {
                 Line::from(vec![
-                    format!("{} {}. ", caret, idx + 1).cyan().dim(),
-                    text.to_string().cyan(),
+                    format!("{} {}. ", caret, idx + 1).accent().dim(),
+                    text.to_string().accent(),
                 ])
             } else {
                 format!("  {}. {text}", idx + 1).into()
@@ -168,11 +168,10 @@ impl AuthModeWidget {
 
             let line2 = if is_selected {
                 Line::from(format!("     {description}"))
-                    .fg(Color::Cyan)
+                    .accent()
                     .add_modifier(Modifier::DIM)
             } else {
-                Line::from(format!("     {description}"))
-                    .style(Style::default().add_modifier(Modifier::DIM))
+                Line::from(format!("     {description}")).style(Style::default().secondary())
             };
 
             vec![line1, line2]
@@ -206,11 +205,11 @@ impl AuthModeWidget {
         lines.push(
             // AE: Following styles.md, this should probably be Cyan because it's a user input tip.
             //     But leaving this for a future cleanup.
-            "  Press Enter to continue".dim().into(),
+            "  Press Enter to continue".secondary().into(),
         );
         if let Some(err) = &self.error {
             lines.push("".into());
-            lines.push(err.as_str().red().into());
+            lines.push(err.as_str().error().into());
         }
 
         Paragraph::new(lines)
@@ -231,11 +230,11 @@ impl AuthModeWidget {
             && !state.auth_url.is_empty()
         {
             lines.push("  If the link doesn't open automatically, open the following link to authenticate:".into());
-            lines.push(vec!["  ".into(), state.auth_url.as_str().cyan().underlined()].into());
+            lines.push(vec!["  ".into(), state.auth_url.as_str().accent().underlined()].into());
             lines.push("".into());
         }
 
-        lines.push("  Press Esc to cancel".dim().into());
+        lines.push("  Press Esc to cancel".secondary().into());
         Paragraph::new(lines)
             .wrap(Wrap { trim: false })
             .render(area, buf);
@@ -243,7 +242,7 @@ impl AuthModeWidget {
 
     fn render_chatgpt_success_message(&self, area: Rect, buf: &mut Buffer) {
         let lines = vec![
-            "✓ Signed in with your ChatGPT account".fg(Color::Green).into(),
+            "✓ Signed in with your ChatGPT account".success().into(),
             "".into(),
             "> Before you start:".into(),
             "".into(),
@@ -252,19 +251,19 @@ impl AuthModeWidget {
                 "  For more details see the ".into(),
                 "\u{1b}]8;;https://github.com/openai/codex\u{7}Codex docs\u{1b}]8;;\u{7}".underlined(),
             ])
-            .dim(),
+            .secondary(),
             "".into(),
             "  Codex can make mistakes".into(),
-            "  Review the code it writes and commands it runs".dim().into(),
+            "  Review the code it writes and commands it runs".secondary().into(),
             "".into(),
             "  Powered by your ChatGPT account".into(),
             Line::from(vec![
                 "  Uses your plan's rate limits and ".into(),
                 "\u{1b}]8;;https://chatgpt.com/#settings\u{7}training data preferences\u{1b}]8;;\u{7}".underlined(),
             ])
-            .dim(),
+            .secondary(),
             "".into(),
-            "  Press Enter to continue".fg(Color::Cyan).into(),
+            "  Press Enter to continue".accent().into(),
         ];
 
         Paragraph::new(lines)
@@ -273,11 +272,7 @@ impl AuthModeWidget {
     }
 
     fn render_chatgpt_success(&self, area: Rect, buf: &mut Buffer) {
-        let lines = vec![
-            "✓ Signed in with your ChatGPT account"
-                .fg(Color::Green)
-                .into(),
-        ];
+        let lines = vec!["✓ Signed in with your ChatGPT account".success().into()];
 
         Paragraph::new(lines)
             .wrap(Wrap { trim: false })
@@ -285,7 +280,7 @@ impl AuthModeWidget {
     }
 
     fn render_env_var_found(&self, area: Rect, buf: &mut Buffer) {
-        let lines = vec!["✓ Using OPENAI_API_KEY".fg(Color::Green).into()];
+        let lines = vec!["✓ Using OPENAI_API_KEY".success().into()];
 
         Paragraph::new(lines)
             .wrap(Wrap { trim: false })
@@ -295,10 +290,10 @@ impl AuthModeWidget {
     fn render_env_var_missing(&self, area: Rect, buf: &mut Buffer) {
         let lines = vec![
             "  To use Codex with the OpenAI API, set OPENAI_API_KEY in your environment"
-                .fg(Color::Cyan)
+                .accent()
                 .into(),
             "".into(),
-            "  Press Enter to return".dim().into(),
+            "  Press Enter to return".secondary().into(),
         ];
 
         Paragraph::new(lines)
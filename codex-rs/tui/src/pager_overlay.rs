@@ -2,6 +2,8 @@ use std::io::Result;
 use std::time::Duration;
 
 use crate::insert_history;
+use crate::keymap::Keymap;
+use crate::theme::Palette;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crossterm::event::KeyCode;
@@ -9,10 +11,8 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::style::Styled;
-use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
 use ratatui::widgets::Paragraph;
@@ -24,8 +24,10 @@ pub(crate) enum Overlay {
 }
 
 impl Overlay {
-    pub(crate) fn new_transcript(lines: Vec<Line<'static>>) -> Self {
-        Self::Transcript(TranscriptOverlay::new(lines))
+    pub(crate) fn new_transcript(lines: Vec<Line<'static>>, keymap: Keymap) -> Self {
+        let mut overlay = TranscriptOverlay::new(lines);
+        overlay.keymap = keymap;
+        Self::Transcript(overlay)
     }
 
     pub(crate) fn new_static_with_title(lines: Vec<Line<'static>>, title: String) -> Self {
@@ -56,7 +58,7 @@ const PAGER_KEY_HINTS: &[(&str, &str)] = &[
 
 // Render a single line of key hints from (key, description) pairs.
 fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&str, &str)]) {
-    let key_hint_style = Style::default().fg(Color::Cyan);
+    let key_hint_style = Style::default().accent();
     let mut spans: Vec<Span<'static>> = vec![" ".into()];
     let mut first = true;
     for (key, desc) in pairs {
@@ -68,7 +70,7 @@ fn render_key_hints(area: Rect, buf: &mut Buffer, pairs: &[(&str, &str)]) {
         spans.push(Span::from(desc.to_string()));
         first = false;
     }
-    Paragraph::new(vec![Line::from(spans).dim()]).render_ref(area, buf);
+    Paragraph::new(vec![Line::from(spans).secondary()]).render_ref(area, buf);
 }
 
 /// Generic widget for rendering a pager view.
@@ -140,10 +142,10 @@ impl PagerView {
 
     fn render_header(&self, area: Rect, buf: &mut Buffer) {
         Span::from("/ ".repeat(area.width as usize / 2))
-            .dim()
+            .secondary()
             .render_ref(area, buf);
         let header = format!("/ {}", self.title);
-        header.dim().render_ref(area, buf);
+        header.secondary().render_ref(area, buf);
     }
 
     // Removed unused render_content_page (replaced by render_content_page_prepared)
@@ -157,7 +159,7 @@ impl PagerView {
                 let add = ((visible + i).min(u16::MAX as usize)) as u16;
                 let y = area.y.saturating_add(add);
                 Span::from("~")
-                    .dim()
+                    .secondary()
                     .render_ref(Rect::new(area.x, y, 1, 1), buf);
             }
         }
@@ -174,7 +176,7 @@ impl PagerView {
         let sep_rect = Rect::new(full_area.x, sep_y, full_area.width, 1);
 
         Span::from("─".repeat(sep_rect.width as usize))
-            .dim()
+            .secondary()
             .render_ref(sep_rect, buf);
         let percent = if wrapped.is_empty() {
             100
@@ -191,7 +193,7 @@ impl PagerView {
         let pct_w = pct_text.chars().count() as u16;
         let pct_x = sep_rect.x + sep_rect.width - pct_w - 1;
         Span::from(pct_text)
-            .dim()
+            .secondary()
             .render_ref(Rect::new(pct_x, sep_rect.y, pct_w, 1), buf);
     }
 
@@ -343,6 +345,7 @@ pub(crate) struct TranscriptOverlay {
     view: PagerView,
     highlight_range: Option<(usize, usize)>,
     is_done: bool,
+    keymap: Keymap,
 }
 
 impl TranscriptOverlay {
@@ -355,6 +358,7 @@ impl TranscriptOverlay {
             ),
             highlight_range: None,
             is_done: false,
+            keymap: Keymap::default(),
         }
     }
 
@@ -371,7 +375,8 @@ impl TranscriptOverlay {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let mut pairs: Vec<(&str, &str)> = vec![("q", "quit"), ("Esc", "edit prev")];
+        let backtrack = self.keymap.backtrack.label();
+        let mut pairs: Vec<(&str, &str)> = vec![("q", "quit"), (backtrack.as_str(), "edit prev")];
         if let Some((start, end)) = self.highlight_range
             && end > start
         {
@@ -395,13 +400,14 @@ impl TranscriptOverlay {
         match event {
             TuiEvent::Key(key_event) => match key_event {
                 KeyEvent {
-                    code: KeyCode::Char('q'),
                     kind: KeyEventKind::Press,
                     ..
+                } if self.keymap.transcript.matches(&key_event) => {
+                    self.is_done = true;
+                    Ok(())
                 }
-                | KeyEvent {
-                    code: KeyCode::Char('t'),
-                    modifiers: crossterm::event::KeyModifiers::CONTROL,
+                KeyEvent {
+                    code: KeyCode::Char('q'),
                     kind: KeyEventKind::Press,
                     ..
                 }
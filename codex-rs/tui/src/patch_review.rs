@@ -0,0 +1,386 @@
+//! Selection of the files and hunks of a proposed patch that should be
+//! applied. Shown by the approval modal when the user chooses to review a
+//! patch instead of approving or rejecting it as a whole.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::protocol::FileChange;
+use codex_core::protocol::RejectedChange;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+use crate::diff_render::calculate_add_remove_from_diff;
+use crate::diff_render::display_path_for;
+use crate::diff_render::render_hunk_lines;
+use crate::diff_render::render_whole_file_lines;
+use crate::theme::Palette;
+
+/// Maximum number of file and hunk rows shown at once.
+const MAX_VISIBLE_ROWS: usize = 8;
+
+/// Maximum number of diff lines previewed for the selected row.
+const MAX_PREVIEW_LINES: usize = 12;
+
+struct FileReview {
+    path: PathBuf,
+    display_path: String,
+    change: FileChange,
+    /// One entry per hunk of an update; empty for added and deleted files.
+    hunks: Vec<HunkReview>,
+    /// Whether an added or deleted file (which has no hunks) is accepted.
+    accepted: bool,
+}
+
+impl FileReview {
+    fn any_accepted(&self) -> bool {
+        if self.hunks.is_empty() {
+            self.accepted
+        } else {
+            self.hunks.iter().any(|h| h.accepted)
+        }
+    }
+
+    fn all_accepted(&self) -> bool {
+        if self.hunks.is_empty() {
+            self.accepted
+        } else {
+            self.hunks.iter().all(|h| h.accepted)
+        }
+    }
+}
+
+struct HunkReview {
+    header: String,
+    accepted: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Row {
+    File(usize),
+    Hunk(usize, usize),
+}
+
+pub(crate) struct PatchReview {
+    files: Vec<FileReview>,
+    rows: Vec<Row>,
+    selected: usize,
+}
+
+impl PatchReview {
+    /// Start a review with every change accepted.
+    pub(crate) fn new(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> Self {
+        let mut paths: Vec<&PathBuf> = changes.keys().collect();
+        paths.sort();
+
+        let mut files = Vec::new();
+        let mut rows = Vec::new();
+        for path in paths {
+            let Some(change) = changes.get(path) else {
+                continue;
+            };
+            let hunks: Vec<HunkReview> = match change {
+                FileChange::Update { unified_diff, .. } => hunk_headers(unified_diff)
+                    .into_iter()
+                    .map(|header| HunkReview {
+                        header,
+                        accepted: true,
+                    })
+                    .collect(),
+                FileChange::Add { .. } | FileChange::Delete { .. } => Vec::new(),
+            };
+            let file_idx = files.len();
+            rows.push(Row::File(file_idx));
+            rows.extend((0..hunks.len()).map(|hunk_idx| Row::Hunk(file_idx, hunk_idx)));
+            files.push(FileReview {
+                path: path.clone(),
+                display_path: display_path_for(path, cwd),
+                change: change.clone(),
+                hunks,
+                accepted: true,
+            });
+        }
+
+        Self {
+            files,
+            rows,
+            selected: 0,
+        }
+    }
+
+    /// Number of changes that can be accepted or rejected independently.
+    /// Reviewing a patch only makes sense when there is more than one.
+    pub(crate) fn selectable_changes(changes: &HashMap<PathBuf, FileChange>) -> usize {
+        changes
+            .values()
+            .map(|change| match change {
+                FileChange::Update { unified_diff, .. } => hunk_headers(unified_diff).len().max(1),
+                FileChange::Add { .. } | FileChange::Delete { .. } => 1,
+            })
+            .sum()
+    }
+
+    pub(crate) fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub(crate) fn move_down(&mut self) {
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Flip the selected hunk, or the whole selected file: a file that is at
+    /// least partially accepted becomes fully rejected, and vice versa.
+    pub(crate) fn toggle_selected(&mut self) {
+        match self.rows.get(self.selected).copied() {
+            Some(Row::File(file_idx)) => {
+                let file = &mut self.files[file_idx];
+                let accept = !file.any_accepted();
+                file.accepted = accept;
+                for hunk in &mut file.hunks {
+                    hunk.accepted = accept;
+                }
+            }
+            Some(Row::Hunk(file_idx, hunk_idx)) => {
+                let hunk = &mut self.files[file_idx].hunks[hunk_idx];
+                hunk.accepted = !hunk.accepted;
+            }
+            None => {}
+        }
+    }
+
+    /// The changes the user rejected, in the form core expects. Empty when
+    /// everything is accepted.
+    pub(crate) fn rejected(&self) -> Vec<RejectedChange> {
+        self.files
+            .iter()
+            .filter(|file| !file.all_accepted())
+            .map(|file| {
+                let hunks = if file.any_accepted() {
+                    Some(
+                        file.hunks
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, hunk)| !hunk.accepted)
+                            .map(|(idx, _)| idx)
+                            .collect(),
+                    )
+                } else {
+                    None
+                };
+                RejectedChange {
+                    path: file.path.clone(),
+                    hunks,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn desired_height(&self, width: u16) -> u16 {
+        let rows = self.rows.len().min(MAX_VISIBLE_ROWS);
+        let preview = self.preview_lines(width).len();
+        (rows + 1 + preview) as u16
+    }
+
+    pub(crate) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let visible = self.rows.len().min(MAX_VISIBLE_ROWS);
+        let start = (self.selected + 1).saturating_sub(visible);
+
+        let mut lines: Vec<Line<'static>> = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(visible)
+            .map(|(idx, row)| self.row_line(*row, idx == self.selected))
+            .collect();
+        lines.push(Line::from(""));
+        lines.extend(self.preview_lines(area.width));
+
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn row_line(&self, row: Row, selected: bool) -> Line<'static> {
+        let line = match row {
+            Row::File(file_idx) => {
+                let file = &self.files[file_idx];
+                let checkbox = match (file.any_accepted(), file.all_accepted()) {
+                    (_, true) => "[x] ",
+                    (true, false) => "[~] ",
+                    (false, false) => "[ ] ",
+                };
+                let (added, removed) = match &file.change {
+                    FileChange::Add { content } => (content.lines().count(), 0),
+                    FileChange::Delete { content } => (0, content.lines().count()),
+                    FileChange::Update { unified_diff, .. } => {
+                        calculate_add_remove_from_diff(unified_diff)
+                    }
+                };
+                let mut spans = vec![checkbox.into(), file.display_path.clone().into()];
+                if let FileChange::Update {
+                    move_path: Some(new_path),
+                    ..
+                } = &file.change
+                {
+                    spans.push(format!(" → {}", new_path.display()).into());
+                }
+                spans.push(" (".into());
+                spans.push(format!("+{added}").success());
+                spans.push(" ".into());
+                spans.push(format!("-{removed}").error());
+                spans.push(")".into());
+                Line::from(spans)
+            }
+            Row::Hunk(file_idx, hunk_idx) => {
+                let hunk = &self.files[file_idx].hunks[hunk_idx];
+                let checkbox = if hunk.accepted { "[x] " } else { "[ ] " };
+                Line::from(vec![
+                    "    ".into(),
+                    checkbox.into(),
+                    hunk.header.clone().secondary(),
+                ])
+            }
+        };
+        if selected {
+            line.style(Style::new().accent())
+        } else {
+            line
+        }
+    }
+
+    fn preview_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let wrap_cols = usize::from(width);
+        let mut lines = match self.rows.get(self.selected).copied() {
+            Some(Row::File(file_idx)) => {
+                let file = &self.files[file_idx];
+                match &file.change {
+                    FileChange::Update { unified_diff, .. } => {
+                        let mut out = Vec::new();
+                        if let Ok(patch) = diffy::Patch::from_str(unified_diff) {
+                            for (idx, hunk) in patch.hunks().iter().enumerate() {
+                                if idx > 0 {
+                                    out.push(Line::from(vec!["    ".into(), "⋮".secondary()]));
+                                }
+                                out.extend(render_hunk_lines(&file.path, hunk, wrap_cols));
+                            }
+                        }
+                        out
+                    }
+                    FileChange::Add { .. } | FileChange::Delete { .. } => {
+                        render_whole_file_lines(&file.path, &file.change, wrap_cols)
+                    }
+                }
+            }
+            Some(Row::Hunk(file_idx, hunk_idx)) => {
+                let file = &self.files[file_idx];
+                match &file.change {
+                    FileChange::Update { unified_diff, .. } => diffy::Patch::from_str(unified_diff)
+                        .ok()
+                        .and_then(|patch| {
+                            patch
+                                .hunks()
+                                .get(hunk_idx)
+                                .map(|hunk| render_hunk_lines(&file.path, hunk, wrap_cols))
+                        })
+                        .unwrap_or_default(),
+                    FileChange::Add { .. } | FileChange::Delete { .. } => Vec::new(),
+                }
+            }
+            None => Vec::new(),
+        };
+
+        if lines.len() > MAX_PREVIEW_LINES {
+            let hidden = lines.len() - (MAX_PREVIEW_LINES - 1);
+            lines.truncate(MAX_PREVIEW_LINES - 1);
+            lines.push(Line::from(format!("    … {hidden} more lines").secondary()));
+        }
+        lines
+    }
+}
+
+/// The `@@` header of each hunk in `unified_diff`. Core numbers hunks the same
+/// way when applying a partial approval.
+fn hunk_headers(unified_diff: &str) -> Vec<String> {
+    unified_diff
+        .lines()
+        .filter(|line| line.starts_with("@@"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn changes() -> HashMap<PathBuf, FileChange> {
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Update {
+                unified_diff: "@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n@@ -9,3 +9,3 @@\n nine\n-ten\n+10\n eleven\n"
+                    .to_string(),
+                move_path: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("/repo/b.txt"),
+            FileChange::Add {
+                content: "new\n".to_string(),
+            },
+        );
+        changes
+    }
+
+    #[test]
+    fn everything_is_accepted_initially() {
+        let review = PatchReview::new(&changes(), Path::new("/repo"));
+        assert_eq!(PatchReview::selectable_changes(&changes()), 3);
+        assert!(review.rejected().is_empty());
+    }
+
+    #[test]
+    fn rejecting_a_hunk_and_a_file() {
+        let mut review = PatchReview::new(&changes(), Path::new("/repo"));
+        // Rows: a.txt, hunk 0, hunk 1, b.txt.
+        review.move_down();
+        review.move_down();
+        review.toggle_selected();
+        review.move_down();
+        review.toggle_selected();
+        assert_eq!(
+            review.rejected(),
+            vec![
+                RejectedChange {
+                    path: PathBuf::from("/repo/a.txt"),
+                    hunks: Some(vec![1]),
+                },
+                RejectedChange {
+                    path: PathBuf::from("/repo/b.txt"),
+                    hunks: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn toggling_a_file_toggles_all_of_its_hunks() {
+        let mut review = PatchReview::new(&changes(), Path::new("/repo"));
+        review.toggle_selected();
+        assert_eq!(
+            review.rejected(),
+            vec![RejectedChange {
+                path: PathBuf::from("/repo/a.txt"),
+                hunks: None,
+            }]
+        );
+        review.toggle_selected();
+        assert!(review.rejected().is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! Syntax highlighting for fenced code blocks and diffs.
+//!
+//! Highlighting is backed by syntect, using the extra syntaxes and themes
+//! bundled with `two-face` (which, unlike syntect's defaults, include
+//! TypeScript and TOML). The theme is picked once at startup from
+//! `tui.syntax_theme`. The default `ansi` theme only uses the terminal's own
+//! palette, so it follows the user's light or dark color scheme; other themes
+//! use RGB colors and are downsampled on terminals without true color.
+//!
+//! Callers fall back to plain, unstyled text whenever highlighting returns
+//! `None`: the theme is `none`, `NO_COLOR` is set, or the language is unknown.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use codex_core::config::Config;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Span;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::FontStyle;
+use syntect::highlighting::Theme;
+use syntect::parsing::SyntaxReference;
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+use tracing::warn;
+use two_face::theme::EmbeddedLazyThemeSet;
+
+/// Theme used when `tui.syntax_theme` is not set.
+const DEFAULT_THEME: &str = "ansi";
+
+/// Value of `tui.syntax_theme` that turns highlighting off.
+const NO_THEME: &str = "none";
+
+/// Code larger than this is shown without highlighting; syntect is not fast
+/// enough to re-highlight it on every streamed commit.
+const MAX_HIGHLIGHT_BYTES: usize = 256 * 1024;
+
+static CONFIGURED_THEME: OnceLock<Option<String>> = OnceLock::new();
+static HIGHLIGHTER: OnceLock<Option<Highlighter>> = OnceLock::new();
+
+struct Highlighter {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    true_color: bool,
+}
+
+/// Pick the theme configured in `config`. Must run before anything is
+/// highlighted; later calls have no effect.
+pub(crate) fn init(config: &Config) {
+    let _ = CONFIGURED_THEME.set(config.tui.syntax_theme.clone());
+}
+
+/// Highlight `code` from a fenced block whose info string is `info`, e.g.
+/// `rust` or `ts,ignore`. Returns the spans of each line of `code` (without
+/// line endings), or `None` if the block should be rendered plain.
+pub(crate) fn highlight_code(info: &str, code: &str) -> Option<Vec<Vec<Span<'static>>>> {
+    let highlighter = highlighter()?;
+    let token = info
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+        .next()
+        .unwrap_or_default();
+    if token.is_empty() {
+        return None;
+    }
+    let syntax = highlighter.syntaxes.find_syntax_by_token(token)?;
+    highlighter.highlight(syntax, code)
+}
+
+/// Highlight `code` taken from the file at `path`, choosing the language from
+/// its extension or, for files like `Makefile`, its name.
+pub(crate) fn highlight_file(path: &Path, code: &str) -> Option<Vec<Vec<Span<'static>>>> {
+    let highlighter = highlighter()?;
+    let syntaxes = &highlighter.syntaxes;
+    let syntax = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| syntaxes.find_syntax_by_extension(ext))
+        .or_else(|| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| syntaxes.find_syntax_by_extension(name))
+        })?;
+    highlighter.highlight(syntax, code)
+}
+
+fn highlighter() -> Option<&'static Highlighter> {
+    HIGHLIGHTER
+        .get_or_init(|| {
+            let configured = CONFIGURED_THEME.get().cloned().flatten();
+            Highlighter::load(configured.as_deref())
+        })
+        .as_ref()
+}
+
+impl Highlighter {
+    fn load(theme_name: Option<&str>) -> Option<Self> {
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return None;
+        }
+        let theme_name = theme_name.unwrap_or(DEFAULT_THEME);
+        if theme_name.eq_ignore_ascii_case(NO_THEME) {
+            return None;
+        }
+
+        let themes = two_face::theme::extra();
+        let find = |name: &str| {
+            EmbeddedLazyThemeSet::theme_names()
+                .iter()
+                .find(|theme| theme.as_name().eq_ignore_ascii_case(name))
+                .map(|theme| themes.get(*theme).clone())
+        };
+        let theme = match find(theme_name) {
+            Some(theme) => theme,
+            None => {
+                warn!("unknown syntax theme `{theme_name}`; using `{DEFAULT_THEME}`");
+                find(DEFAULT_THEME)?
+            }
+        };
+
+        let true_color = supports_color::on_cached(supports_color::Stream::Stdout)
+            .map(|level| level.has_16m)
+            .unwrap_or(false);
+
+        Some(Self {
+            syntaxes: two_face::syntax::extra_newlines(),
+            theme,
+            true_color,
+        })
+    }
+
+    fn highlight(&self, syntax: &SyntaxReference, code: &str) -> Option<Vec<Vec<Span<'static>>>> {
+        if code.len() > MAX_HIGHLIGHT_BYTES {
+            return None;
+        }
+        let mut highlight_lines = HighlightLines::new(syntax, &self.theme);
+        let mut out = Vec::new();
+        for line in LinesWithEndings::from(code) {
+            let regions = highlight_lines.highlight_line(line, &self.syntaxes).ok()?;
+            let spans = regions
+                .into_iter()
+                .filter_map(|(style, text)| {
+                    // Line endings are not part of the rendered line. Every
+                    // other character, including leading whitespace, is kept
+                    // exactly as written.
+                    let text = text.trim_end_matches(['\n', '\r']);
+                    (!text.is_empty()).then(|| Span::styled(text.to_string(), self.style(style)))
+                })
+                .collect();
+            out.push(spans);
+        }
+        Some(out)
+    }
+
+    fn style(&self, style: syntect::highlighting::Style) -> Style {
+        let mut out = Style::default();
+        if let Some(fg) = self.color(style.foreground) {
+            out = out.fg(fg);
+        }
+        if style.font_style.contains(FontStyle::BOLD) {
+            out = out.add_modifier(Modifier::BOLD);
+        }
+        if style.font_style.contains(FontStyle::ITALIC) {
+            out = out.add_modifier(Modifier::ITALIC);
+        }
+        if style.font_style.contains(FontStyle::UNDERLINE) {
+            out = out.add_modifier(Modifier::UNDERLINED);
+        }
+        out
+    }
+
+    /// Map a theme color to a terminal color. The `ansi` theme stores palette
+    /// indices in the red channel with an alpha of 0, and uses an alpha of 1
+    /// for the terminal's default foreground.
+    fn color(&self, color: syntect::highlighting::Color) -> Option<Color> {
+        match color.a {
+            0 => Some(ansi_color(color.r)),
+            1 => None,
+            // Themes other than `ansi` are an explicit opt-in to custom colors.
+            #[allow(clippy::disallowed_methods)]
+            _ if self.true_color => Some(Color::Rgb(color.r, color.g, color.b)),
+            #[allow(clippy::disallowed_methods)]
+            _ => Some(Color::Indexed(rgb_to_ansi256(color.r, color.g, color.b))),
+        }
+    }
+}
+
+fn ansi_color(index: u8) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        15 => Color::White,
+        #[allow(clippy::disallowed_methods)]
+        index => Color::Indexed(index),
+    }
+}
+
+/// Nearest color in the 6x6x6 cube of the 256-color palette.
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |c: u8| -> u8 {
+        if c < 48 {
+            0
+        } else if c < 115 {
+            1
+        } else {
+            (c - 35) / 40
+        }
+    };
+    16 + 36 * level(r) + 6 * level(g) + level(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn ansi_highlighter() -> Highlighter {
+        Highlighter {
+            syntaxes: two_face::syntax::extra_newlines(),
+            theme: two_face::theme::extra()
+                .get(two_face::theme::EmbeddedThemeName::Ansi)
+                .clone(),
+            true_color: false,
+        }
+    }
+
+    fn text_of(lines: &[Vec<Span<'static>>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|spans| spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn highlighting_preserves_text_and_whitespace() {
+        let highlighter = ansi_highlighter();
+        let syntax = highlighter.syntaxes.find_syntax_by_token("rust").unwrap();
+        let code = "fn main() {\n    let x = 1;\n\n\tprintln!(\"{x}\");\n}\n";
+        let lines = highlighter.highlight(syntax, code).unwrap();
+        assert_eq!(
+            text_of(&lines),
+            vec![
+                "fn main() {",
+                "    let x = 1;",
+                "",
+                "\tprintln!(\"{x}\");",
+                "}",
+            ]
+        );
+        assert!(
+            lines[0].iter().any(|span| span.style.fg.is_some()),
+            "keyword should be colored: {:?}",
+            lines[0]
+        );
+    }
+
+    #[test]
+    fn typescript_and_toml_are_recognized() {
+        let highlighter = ansi_highlighter();
+        for token in ["ts", "typescript", "toml", "py", "go"] {
+            assert!(
+                highlighter.syntaxes.find_syntax_by_token(token).is_some(),
+                "{token} should be recognized"
+            );
+        }
+    }
+
+    #[test]
+    fn rgb_colors_are_downsampled() {
+        assert_eq!(rgb_to_ansi256(0, 0, 0), 16);
+        assert_eq!(rgb_to_ansi256(255, 255, 255), 231);
+        assert_eq!(rgb_to_ansi256(255, 0, 0), 196);
+    }
+}
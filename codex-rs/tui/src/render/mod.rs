@@ -1,2 +1,3 @@
+pub mod highlight;
 pub mod line_utils;
 pub mod markdown_utils;
@@ -21,6 +21,7 @@ use ratatui::text::Line;
 use tokio_stream::StreamExt;
 
 use crate::text_formatting::truncate_text;
+use crate::theme::Palette;
 use crate::tui::FrameRequester;
 use crate::tui::Tui;
 use crate::tui::TuiEvent;
@@ -35,8 +36,9 @@ pub enum ResumeSelection {
 }
 
 /// Interactive session picker that lists recorded rollout files with simple
-/// search and pagination. Shows the first user input as the preview, relative
-/// time (e.g., "5 seconds ago"), and the absolute path.
+/// search and pagination. Shows the session's title (or else its first user
+/// input) as the preview, relative time (e.g., "5 seconds ago"), and the
+/// absolute path.
 pub async fn run_resume_picker(tui: &mut Tui, codex_home: &Path) -> Result<ResumeSelection> {
     let alt = AltScreenGuard::enter(tui);
     let mut state = PickerState::new(codex_home.to_path_buf(), alt.tui.frame_requester());
@@ -273,7 +275,10 @@ fn head_to_row(item: &ConversationItem) -> Option<Row> {
         ts = Some(parsed.with_timezone(&Utc));
     }
 
-    let preview = find_first_user_text(&item.head)?;
+    let preview = match &item.title {
+        Some(title) => title.clone(),
+        None => find_first_user_text(&item.head)?,
+    };
     let preview = preview.trim().to_string();
     if preview.is_empty() {
         return None;
@@ -333,13 +338,13 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
 
         // Header
         frame.render_widget_ref(
-            Line::from(vec!["Resume a previous session".bold().cyan()]),
+            Line::from(vec!["Resume a previous session".bold().accent()]),
             header,
         );
 
         // Search line
         let q = if state.query.is_empty() {
-            "Type to search".dim().to_string()
+            "Type to search".secondary().to_string()
         } else {
             format!("Search: {}", state.query)
         };
@@ -355,11 +360,11 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
             "Esc".bold(),
             " to start new  ".into(),
             "Ctrl+C".into(),
-            " to quit  ".dim(),
+            " to quit  ".secondary(),
             "←/a".into(),
-            " prev  ".dim(),
+            " prev  ".secondary(),
             "→/d".into(),
-            " next".dim(),
+            " next".secondary(),
         ]
         .into();
         frame.render_widget_ref(hint_line, hint);
@@ -369,7 +374,7 @@ fn draw_picker(tui: &mut Tui, state: &PickerState) -> std::io::Result<()> {
 fn render_list(frame: &mut crate::custom_terminal::Frame, area: Rect, state: &PickerState) {
     let rows = &state.filtered_rows;
     if rows.is_empty() {
-        frame.render_widget_ref(Line::from("No sessions found".italic().dim()), area);
+        frame.render_widget_ref(Line::from("No sessions found".italic().secondary()), area);
         return;
     }
 
@@ -386,7 +391,7 @@ fn render_list(frame: &mut crate::custom_terminal::Frame, area: Rect, state: &Pi
             .ts
             .map(human_time_ago)
             .unwrap_or_else(|| "".to_string())
-            .dim();
+            .secondary();
         let max_cols = area.width.saturating_sub(6) as usize;
         let preview = truncate_text(&row.preview, max_cols);
 
@@ -485,10 +490,12 @@ mod tests {
         let a = ConversationItem {
             path: PathBuf::from("/tmp/a.jsonl"),
             head: head_with_ts_and_user_text("2025-01-01T00:00:00Z", &["A"]),
+            title: None,
         };
         let b = ConversationItem {
             path: PathBuf::from("/tmp/b.jsonl"),
             head: head_with_ts_and_user_text("2025-01-02T00:00:00Z", &["B"]),
+            title: Some("Add the B feature".to_string()),
         };
         let rows = to_rows(ConversationsPage {
             items: vec![a, b],
@@ -498,7 +505,7 @@ mod tests {
         });
         assert_eq!(rows.len(), 2);
         // Expect the newer timestamp (B) first
-        assert!(rows[0].preview.contains('B'));
+        assert_eq!(rows[0].preview, "Add the B feature");
         assert!(rows[1].preview.contains('A'));
     }
 }
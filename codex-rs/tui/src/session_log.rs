@@ -6,6 +6,7 @@ use std::sync::Mutex;
 
 use codex_core::config::Config;
 use codex_core::protocol::Op;
+use codex_core::redaction::Redactor;
 use once_cell::sync::Lazy;
 use once_cell::sync::OnceCell;
 use serde::Serialize;
@@ -17,12 +18,14 @@ static LOGGER: Lazy<SessionLogger> = Lazy::new(SessionLogger::new);
 
 struct SessionLogger {
     file: OnceCell<Mutex<File>>,
+    redactor: OnceCell<Redactor>,
 }
 
 impl SessionLogger {
     fn new() -> Self {
         Self {
             file: OnceCell::new(),
+            redactor: OnceCell::new(),
         }
     }
 
@@ -45,10 +48,13 @@ impl SessionLogger {
         Ok(())
     }
 
-    fn write_json_line(&self, value: serde_json::Value) {
+    fn write_json_line(&self, mut value: serde_json::Value) {
         let Some(mutex) = self.file.get() else {
             return;
         };
+        if let Some(redactor) = self.redactor.get() {
+            redactor.redact_json(&mut value);
+        }
         let mut guard = match mutex.lock() {
             Ok(g) => g,
             Err(poisoned) => poisoned.into_inner(),
@@ -104,6 +110,16 @@ pub(crate) fn maybe_init(config: &Config) {
         p
     };
 
+    match Redactor::for_disk(&config.redaction, &config.codex_home) {
+        Ok(redactor) => {
+            let _ = LOGGER.redactor.set(redactor);
+        }
+        Err(e) => {
+            tracing::error!("not recording session: {e}");
+            return;
+        }
+    }
+
     if let Err(e) = LOGGER.open(path.clone()) {
         tracing::error!("failed to open session log {:?}: {}", path, e);
         return;
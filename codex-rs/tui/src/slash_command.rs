@@ -14,11 +14,15 @@ pub enum SlashCommand {
     // more frequently used commands should be listed first.
     Model,
     Approvals,
+    Permissions,
     New,
     Init,
     Compact,
     Diff,
+    Commit,
+    Plan,
     Mention,
+    Edit,
     Status,
     Mcp,
     Logout,
@@ -36,11 +40,15 @@ impl SlashCommand {
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Quit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Commit => "draft a commit message for the current changes",
+            SlashCommand::Plan => "summarize progress on the current plan",
             SlashCommand::Mention => "mention a file",
+            SlashCommand::Edit => "write the message in $VISUAL or $EDITOR",
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
-            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Permissions => "list and revoke saved command approval rules",
+            SlashCommand::Mcp => "show MCP server health, restart or disable servers",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "test approval request",
@@ -59,13 +67,17 @@ impl SlashCommand {
             SlashCommand::New
             | SlashCommand::Init
             | SlashCommand::Compact
+            | SlashCommand::Commit
+            | SlashCommand::Plan
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Logout => false,
             SlashCommand::Diff
             | SlashCommand::Mention
+            | SlashCommand::Edit
             | SlashCommand::Status
             | SlashCommand::Mcp
+            | SlashCommand::Permissions
             | SlashCommand::Quit => true,
 
             #[cfg(debug_assertions)]
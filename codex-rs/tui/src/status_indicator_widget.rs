@@ -5,6 +5,7 @@ use std::time::Duration;
 use std::time::Instant;
 
 use codex_core::protocol::Op;
+use codex_protocol::config_types::ReasoningEffort;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -15,6 +16,7 @@ use ratatui::widgets::WidgetRef;
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::shimmer::shimmer_spans;
+use crate::theme::Palette;
 use crate::tui::FrameRequester;
 use textwrap::Options as TwOptions;
 use textwrap::WordSplitter;
@@ -24,6 +26,9 @@ pub(crate) struct StatusIndicatorWidget {
     header: String,
     /// Queued user messages to display under the status line.
     queued_messages: Vec<String>,
+    /// Reasoning effort picked for the running task, when it is picked
+    /// automatically.
+    reasoning_effort: Option<ReasoningEffort>,
 
     start_time: Instant,
     app_event_tx: AppEventSender,
@@ -35,6 +40,7 @@ impl StatusIndicatorWidget {
         Self {
             header: String::from("Working"),
             queued_messages: Vec::new(),
+            reasoning_effort: None,
             start_time: Instant::now(),
 
             app_event_tx,
@@ -81,6 +87,10 @@ impl StatusIndicatorWidget {
         }
     }
 
+    pub(crate) fn set_reasoning_effort(&mut self, effort: Option<ReasoningEffort>) {
+        self.reasoning_effort = effort;
+    }
+
     /// Replace the queued messages displayed beneath the header.
     pub(crate) fn set_queued_messages(&mut self, queued: Vec<String>) {
         self.queued_messages = queued;
@@ -103,11 +113,15 @@ impl WidgetRef for StatusIndicatorWidget {
         // Plain rendering: no borders or padding so the live cell is visually indistinguishable from terminal scrollback.
         let mut spans = vec![" ".into()];
         spans.extend(shimmer_spans(&self.header));
+        let effort = self
+            .reasoning_effort
+            .map(|effort| format!("{effort} effort • "))
+            .unwrap_or_default();
         spans.extend(vec![
             " ".into(),
-            format!("({elapsed}s • ").dim(),
-            "Esc".dim().bold(),
-            " to interrupt)".dim(),
+            format!("({elapsed}s • {effort}").secondary(),
+            "Esc".secondary().bold(),
+            " to interrupt)".secondary(),
         ]);
 
         // Build lines: status, then queued messages, then spacer.
@@ -123,14 +137,15 @@ impl WidgetRef for StatusIndicatorWidget {
             for (i, piece) in wrapped.iter().take(3).enumerate() {
                 let prefix = if i == 0 { " ↳ " } else { "   " };
                 let content = format!("{prefix}{piece}");
-                lines.push(Line::from(content.dim().italic()));
+                lines.push(Line::from(content.secondary().italic()));
             }
             if wrapped.len() > 3 {
-                lines.push(Line::from("   …".dim().italic()));
+                lines.push(Line::from("   …".secondary().italic()));
             }
         }
         if !self.queued_messages.is_empty() {
-            lines.push(Line::from(vec!["   ".into(), "Alt+↑".cyan(), " edit".into()]).dim());
+            lines
+                .push(Line::from(vec!["   ".into(), "Alt+↑".accent(), " edit".into()]).secondary());
         }
 
         let paragraph = Paragraph::new(lines);
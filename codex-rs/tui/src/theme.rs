@@ -0,0 +1,167 @@
+//! Color theme for the interface.
+//!
+//! Widgets only use the handful of color roles described in `styles.md`:
+//! accents, success, errors, Codex and secondary text. They style text through
+//! the [`Palette`] methods, which look up the configured color for each role
+//! when the style is built. Text styled otherwise, such as syntax
+//! highlighting, keeps its own colors.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use codex_core::config::Config;
+use codex_core::config_types::TuiColors;
+use codex_core::config_types::TuiTheme;
+use ratatui::style::Color;
+use ratatui::style::Stylize;
+use tracing::warn;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+static DEFAULT_THEME: Theme = Theme {
+    accent: Color::Cyan,
+    success: Color::Green,
+    error: Color::Red,
+    codex: Color::Magenta,
+    secondary: None,
+};
+
+/// The color of each role.
+#[derive(Debug, Clone, PartialEq)]
+struct Theme {
+    accent: Color,
+    success: Color,
+    error: Color,
+    codex: Color,
+    /// Color used for secondary text instead of dimming it.
+    secondary: Option<Color>,
+}
+
+/// Install the theme configured in `config`. Must run before anything is
+/// styled; later calls have no effect.
+pub(crate) fn init(config: &Config) {
+    let _ = THEME.set(Theme::new(config.tui.theme, &config.tui.colors));
+}
+
+fn theme() -> &'static Theme {
+    THEME.get().unwrap_or(&DEFAULT_THEME)
+}
+
+/// Styles for the color roles in `styles.md`, available on everything that
+/// implements [`Stylize`].
+pub(crate) trait Palette<'a, T>: Stylize<'a, T> {
+    /// User input tips, selection and status indicators.
+    fn accent(self) -> T {
+        self.fg(theme().accent)
+    }
+
+    /// The selected button: the accent as background, for text styled with a
+    /// contrasting foreground such as `Color::Black`.
+    fn on_accent(self) -> T {
+        self.bg(theme().accent)
+    }
+
+    /// Success and additions.
+    fn success(self) -> T {
+        self.fg(theme().success)
+    }
+
+    /// Errors, failures and deletions.
+    fn error(self) -> T {
+        self.fg(theme().error)
+    }
+
+    /// Codex itself.
+    fn codex(self) -> T {
+        self.fg(theme().codex)
+    }
+
+    /// Secondary text: dimmed, unless the theme gives it a color.
+    fn secondary(self) -> T {
+        match theme().secondary {
+            Some(color) => self.fg(color),
+            None => self.dim(),
+        }
+    }
+}
+
+impl<'a, T, U> Palette<'a, T> for U where U: Stylize<'a, T> {}
+
+impl Theme {
+    fn new(base: TuiTheme, colors: &TuiColors) -> Self {
+        let mut theme = match base {
+            TuiTheme::Default => DEFAULT_THEME.clone(),
+            TuiTheme::Light => Theme {
+                accent: Color::Blue,
+                secondary: Some(Color::DarkGray),
+                ..DEFAULT_THEME.clone()
+            },
+        };
+        let parse = |name: &str, value: &Option<String>| {
+            let value = value.as_deref()?;
+            match Color::from_str(value) {
+                Ok(color) => Some(color),
+                Err(_) => {
+                    warn!("ignoring invalid color `{value}` for tui.colors.{name}");
+                    None
+                }
+            }
+        };
+        if let Some(color) = parse("accent", &colors.accent) {
+            theme.accent = color;
+        }
+        if let Some(color) = parse("success", &colors.success) {
+            theme.success = color;
+        }
+        if let Some(color) = parse("error", &colors.error) {
+            theme.error = color;
+        }
+        if let Some(color) = parse("codex", &colors.codex) {
+            theme.codex = color;
+        }
+        if let Some(color) = parse("secondary", &colors.secondary) {
+            theme.secondary = Some(color);
+        }
+        theme
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Style;
+
+    #[test]
+    fn default_palette_uses_the_style_guide_colors() {
+        assert_eq!("x".accent().style, Style::default().cyan());
+        assert_eq!("x".on_accent().style, Style::default().on_cyan());
+        assert_eq!("x".success().style, Style::default().green());
+        assert_eq!("x".error().style, Style::default().red());
+        assert_eq!("x".codex().style, Style::default().magenta());
+        assert_eq!("x".secondary().style, Style::default().dim());
+    }
+
+    #[test]
+    fn light_theme_and_colors_override_roles() {
+        let colors = TuiColors {
+            error: Some("light-red".to_string()),
+            codex: Some("not a color".to_string()),
+            ..TuiColors::default()
+        };
+        assert_eq!(
+            Theme::new(TuiTheme::Light, &colors),
+            Theme {
+                accent: Color::Blue,
+                success: Color::Green,
+                error: Color::LightRed,
+                codex: Color::Magenta,
+                secondary: Some(Color::DarkGray),
+            }
+        );
+        assert_eq!(
+            Theme::new(TuiTheme::Default, &TuiColors::default()),
+            DEFAULT_THEME
+        );
+    }
+}
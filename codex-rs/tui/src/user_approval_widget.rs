@@ -6,14 +6,19 @@
 //! UI to Rust using [`ratatui`]. The goal is feature‑parity for the keyboard
 //! driven workflow – a fully‑fledged visual match is not required.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
+use codex_core::approval_rules::suggest_prefix;
+use codex_core::protocol::ApprovalRuleScope;
+use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::prelude::*;
@@ -30,6 +35,10 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
+use crate::keymap::KeyBinding;
+use crate::keymap::Keymap;
+use crate::patch_review::PatchReview;
+use crate::theme::Palette;
 
 /// Request coming from the agent that needs user approval.
 pub(crate) enum ApprovalRequest {
@@ -42,9 +51,39 @@ pub(crate) enum ApprovalRequest {
         id: String,
         reason: Option<String>,
         grant_root: Option<PathBuf>,
+        changes: HashMap<PathBuf, FileChange>,
+        cwd: PathBuf,
+    },
+    McpToolCall {
+        id: String,
+        server: String,
+        tool: String,
+        arguments: Option<serde_json::Value>,
+        reason: Option<String>,
+    },
+    McpSampling {
+        id: String,
+        server: String,
+        prompt: String,
+        system_prompt: Option<String>,
+        max_tokens: i64,
     },
 }
 
+/// What happens when a [`SelectOption`] is chosen.
+enum SelectAction {
+    /// Send the decision right away.
+    Decide(ReviewDecision),
+    /// Approve, and save a rule approving commands with the same prefix.
+    RememberPrefix(ApprovalRuleScope),
+    /// Let the user edit the command before approving it.
+    EditCommand,
+    /// Let the user explain why the request is denied.
+    Feedback,
+    /// Let the user pick which files and hunks of a patch to apply.
+    ReviewPatch,
+}
+
 /// Options displayed in the *select* mode.
 ///
 /// The `key` is matched case-insensitively.
@@ -52,7 +91,18 @@ struct SelectOption {
     label: Line<'static>,
     description: &'static str,
     key: KeyCode,
-    decision: ReviewDecision,
+    action: SelectAction,
+}
+
+/// Whether the widget is showing the option row or collecting free text.
+enum InputMode {
+    Select,
+    /// Feedback to return to the model together with the denial.
+    Feedback(String),
+    /// The command the user wants to run instead of the proposed one.
+    EditCommand(String),
+    /// Files and hunks of the patch the user is choosing between.
+    ReviewPatch(PatchReview),
 }
 
 static COMMAND_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
@@ -61,19 +111,37 @@ static COMMAND_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and run the command",
             key: KeyCode::Char('y'),
-            decision: ReviewDecision::Approved,
+            action: SelectAction::Decide(ReviewDecision::Approved),
         },
         SelectOption {
             label: Line::from(vec!["A".underlined(), "lways".into()]),
             description: "Approve the command for the remainder of this session",
             key: KeyCode::Char('a'),
-            decision: ReviewDecision::ApprovedForSession,
+            action: SelectAction::Decide(ReviewDecision::ApprovedForSession),
+        },
+        SelectOption {
+            label: Line::from(vec!["P".underlined(), "roject".into()]),
+            description: "Always allow commands starting with this prefix in this project",
+            key: KeyCode::Char('p'),
+            action: SelectAction::RememberPrefix(ApprovalRuleScope::Project),
+        },
+        SelectOption {
+            label: Line::from(vec!["G".underlined(), "lobal".into()]),
+            description: "Always allow commands starting with this prefix in every project",
+            key: KeyCode::Char('g'),
+            action: SelectAction::RememberPrefix(ApprovalRuleScope::User),
+        },
+        SelectOption {
+            label: Line::from(vec!["E".underlined(), "dit".into()]),
+            description: "Edit the command, then run it",
+            key: KeyCode::Char('e'),
+            action: SelectAction::EditCommand,
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not run the command; provide feedback",
             key: KeyCode::Char('n'),
-            decision: ReviewDecision::Abort,
+            action: SelectAction::Feedback,
         },
     ]
 });
@@ -84,27 +152,91 @@ static PATCH_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
             label: Line::from(vec!["Y".underlined(), "es".into()]),
             description: "Approve and apply the changes",
             key: KeyCode::Char('y'),
-            decision: ReviewDecision::Approved,
+            action: SelectAction::Decide(ReviewDecision::Approved),
+        },
+        SelectOption {
+            label: Line::from(vec!["R".underlined(), "eview".into()]),
+            description: "Choose which files and hunks to apply",
+            key: KeyCode::Char('r'),
+            action: SelectAction::ReviewPatch,
         },
         SelectOption {
             label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
             description: "Do not apply the changes; provide feedback",
             key: KeyCode::Char('n'),
-            decision: ReviewDecision::Abort,
+            action: SelectAction::Feedback,
+        },
+    ]
+});
+
+static MCP_TOOL_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
+    vec![
+        SelectOption {
+            label: Line::from(vec!["Y".underlined(), "es".into()]),
+            description: "Approve and call the tool",
+            key: KeyCode::Char('y'),
+            action: SelectAction::Decide(ReviewDecision::Approved),
+        },
+        SelectOption {
+            label: Line::from(vec!["A".underlined(), "lways".into()]),
+            description: "Approve this tool for the remainder of this session",
+            key: KeyCode::Char('a'),
+            action: SelectAction::Decide(ReviewDecision::ApprovedForSession),
+        },
+        SelectOption {
+            label: Line::from(vec!["N".underlined(), "o, provide feedback".into()]),
+            description: "Do not call the tool; provide feedback",
+            key: KeyCode::Char('n'),
+            action: SelectAction::Feedback,
+        },
+    ]
+});
+
+static MCP_SAMPLING_SELECT_OPTIONS: LazyLock<Vec<SelectOption>> = LazyLock::new(|| {
+    vec![
+        SelectOption {
+            label: Line::from(vec!["Y".underlined(), "es".into()]),
+            description: "Let the server use the model for this request",
+            key: KeyCode::Char('y'),
+            action: SelectAction::Decide(ReviewDecision::Approved),
+        },
+        SelectOption {
+            label: Line::from(vec!["A".underlined(), "lways".into()]),
+            description: "Let the server use the model for the remainder of this session",
+            key: KeyCode::Char('a'),
+            action: SelectAction::Decide(ReviewDecision::ApprovedForSession),
+        },
+        SelectOption {
+            label: Line::from(vec!["N".underlined(), "o".into()]),
+            description: "Reject the request",
+            key: KeyCode::Char('n'),
+            action: SelectAction::Decide(ReviewDecision::Denied),
         },
     ]
 });
 
+/// Prompt lines shown before the rest of a sampling request is elided.
+const MAX_SAMPLING_PROMPT_LINES: usize = 8;
+
 /// A modal prompting the user to approve or deny the pending request.
 pub(crate) struct UserApprovalWidget {
     approval_request: ApprovalRequest,
     app_event_tx: AppEventSender,
     confirmation_prompt: Paragraph<'static>,
-    select_options: &'static Vec<SelectOption>,
+    select_options: Vec<&'static SelectOption>,
+
+    /// Prefix offered for a persisted approval rule, if the command is simple
+    /// enough for one.
+    suggested_prefix: Option<Vec<String>>,
 
     /// Currently selected index in *select* mode.
     selected_option: usize,
 
+    input_mode: InputMode,
+
+    /// Shortcuts for approving and denying, which may be remapped.
+    keymap: Keymap,
+
     /// Set to `true` once a decision has been sent – the parent view can then
     /// remove this widget from its queue.
     done: bool,
@@ -115,7 +247,10 @@ fn to_command_display<'a>(
     cmd: String,
     last_line: Vec<Span<'a>>,
 ) -> Vec<Line<'a>> {
-    let command_lines: Vec<Span> = cmd.lines().map(|line| line.to_string().dim()).collect();
+    let command_lines: Vec<Span> = cmd
+        .lines()
+        .map(|line| line.to_string().secondary())
+        .collect();
 
     let mut lines: Vec<Line<'a>> = vec![];
 
@@ -135,6 +270,27 @@ fn to_command_display<'a>(
     lines
 }
 
+/// Turn the text the user typed in *edit* mode into a decision. The edited
+/// text is shell syntax, so it is re-split into argv, or wrapped in `bash -lc`
+/// when the original command was a script or the text does not split cleanly.
+fn edited_command_decision(original: &[String], edited: &str) -> ReviewDecision {
+    let edited = edited.trim();
+    if edited.is_empty() {
+        return ReviewDecision::Abort;
+    }
+    if edited == strip_bash_lc_and_escape(original) {
+        return ReviewDecision::Approved;
+    }
+    let bash_lc = || vec!["bash".to_string(), "-lc".to_string(), edited.to_string()];
+    let command = match original {
+        [bash, flag, _] if bash == "bash" && flag == "-lc" => bash_lc(),
+        _ => shlex::split(edited)
+            .filter(|argv| !argv.is_empty())
+            .unwrap_or_else(bash_lc),
+    };
+    ReviewDecision::ApprovedWithEdits { command }
+}
+
 impl UserApprovalWidget {
     pub(crate) fn new(approval_request: ApprovalRequest, app_event_tx: AppEventSender) -> Self {
         let confirmation_prompt = match &approval_request {
@@ -143,7 +299,7 @@ impl UserApprovalWidget {
             } => {
                 let cmd = strip_bash_lc_and_escape(command);
                 let mut contents: Vec<Line> = to_command_display(
-                    vec!["? ".fg(Color::Cyan), "Codex wants to run ".bold()],
+                    vec!["? ".accent(), "Codex wants to run ".bold()],
                     cmd,
                     vec![],
                 );
@@ -175,17 +331,100 @@ impl UserApprovalWidget {
 
                 Paragraph::new(contents).wrap(Wrap { trim: false })
             }
+            ApprovalRequest::McpToolCall {
+                server,
+                tool,
+                arguments,
+                reason,
+                ..
+            } => {
+                let mut contents: Vec<Line> = vec![Line::from(vec![
+                    "? ".accent(),
+                    "Codex wants to call ".bold(),
+                    format!("{server}/{tool}").secondary(),
+                ])];
+                if let Some(arguments) = arguments {
+                    contents.push(Line::from(vec![
+                        "    ".into(),
+                        arguments.to_string().secondary(),
+                    ]));
+                }
+
+                contents.push(Line::from(""));
+                if let Some(reason) = reason {
+                    contents.push(Line::from(reason.clone().italic()));
+                    contents.push(Line::from(""));
+                }
+                Paragraph::new(contents).wrap(Wrap { trim: false })
+            }
+            ApprovalRequest::McpSampling {
+                server,
+                prompt,
+                system_prompt,
+                max_tokens,
+                ..
+            } => {
+                let mut contents: Vec<Line> = vec![Line::from(vec![
+                    "? ".accent(),
+                    server.clone().secondary(),
+                    " wants to use the model".bold(),
+                    format!(" (up to {max_tokens} tokens)").secondary(),
+                ])];
+                if let Some(system_prompt) = system_prompt {
+                    contents.push(Line::from(vec![
+                        "    system: ".into(),
+                        system_prompt.clone().secondary(),
+                    ]));
+                }
+                let lines: Vec<&str> = prompt.lines().collect();
+                for line in lines.iter().take(MAX_SAMPLING_PROMPT_LINES) {
+                    contents.push(Line::from(vec![
+                        "    ".into(),
+                        line.to_string().secondary(),
+                    ]));
+                }
+                if lines.len() > MAX_SAMPLING_PROMPT_LINES {
+                    contents.push(Line::from(vec!["    ".into(), "…".secondary()]));
+                }
+                contents.push(Line::from(""));
+                Paragraph::new(contents).wrap(Wrap { trim: false })
+            }
+        };
+
+        let (options, suggested_prefix): (&'static Vec<SelectOption>, _) = match &approval_request {
+            ApprovalRequest::Exec { command, .. } => {
+                (&COMMAND_SELECT_OPTIONS, suggest_prefix(command))
+            }
+            ApprovalRequest::ApplyPatch { .. } => (&PATCH_SELECT_OPTIONS, None),
+            ApprovalRequest::McpToolCall { .. } => (&MCP_TOOL_SELECT_OPTIONS, None),
+            ApprovalRequest::McpSampling { .. } => (&MCP_SAMPLING_SELECT_OPTIONS, None),
+        };
+        let reviewable = match &approval_request {
+            ApprovalRequest::ApplyPatch { changes, .. } => {
+                PatchReview::selectable_changes(changes) > 1
+            }
+            ApprovalRequest::Exec { .. }
+            | ApprovalRequest::McpToolCall { .. }
+            | ApprovalRequest::McpSampling { .. } => false,
         };
+        let select_options = options
+            .iter()
+            .filter(|opt| match opt.action {
+                SelectAction::RememberPrefix(_) => suggested_prefix.is_some(),
+                SelectAction::ReviewPatch => reviewable,
+                _ => true,
+            })
+            .collect();
 
         Self {
-            select_options: match &approval_request {
-                ApprovalRequest::Exec { .. } => &COMMAND_SELECT_OPTIONS,
-                ApprovalRequest::ApplyPatch { .. } => &PATCH_SELECT_OPTIONS,
-            },
+            select_options,
+            suggested_prefix,
             approval_request,
             app_event_tx,
             confirmation_prompt,
             selected_option: 0,
+            input_mode: InputMode::Select,
+            keymap: Keymap::default(),
             done: false,
         }
     }
@@ -201,21 +440,66 @@ impl UserApprovalWidget {
     /// captures input while visible, we don’t need to report whether the event
     /// was consumed—callers can assume it always is.
     pub(crate) fn handle_key_event(&mut self, key: KeyEvent) {
-        if key.kind == KeyEventKind::Press {
-            self.handle_select_key(key);
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+        match self.input_mode {
+            InputMode::Select => self.handle_select_key(key),
+            InputMode::Feedback(_) | InputMode::EditCommand(_) => self.handle_input_key(key),
+            InputMode::ReviewPatch(_) => self.handle_review_key(key),
         }
     }
 
-    /// Normalize a key for comparison.
-    /// - For `KeyCode::Char`, converts to lowercase for case-insensitive matching.
-    /// - Other key codes are returned unchanged.
-    fn normalize_keycode(code: KeyCode) -> KeyCode {
-        match code {
-            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
-            other => other,
+    /// Insert pasted text into the feedback or command being edited. Returns
+    /// `false` in *select* mode, where a paste has no meaning.
+    pub(crate) fn handle_paste(&mut self, pasted: String) -> bool {
+        match &mut self.input_mode {
+            InputMode::Select | InputMode::ReviewPatch(_) => false,
+            InputMode::Feedback(text) | InputMode::EditCommand(text) => {
+                text.push_str(&pasted);
+                true
+            }
         }
     }
 
+    /// Use the approval shortcuts from `tui.keybindings`.
+    pub(crate) fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    /// The key that chooses `opt`, taking remapped approval shortcuts into
+    /// account.
+    fn option_binding(&self, opt: &SelectOption) -> KeyBinding {
+        match opt.action {
+            SelectAction::Decide(ReviewDecision::Approved) => self.keymap.approve,
+            SelectAction::Decide(ReviewDecision::ApprovedForSession) => {
+                self.keymap.approve_for_session
+            }
+            SelectAction::Feedback => self.keymap.deny,
+            _ => KeyBinding::new(opt.key, KeyModifiers::NONE),
+        }
+    }
+
+    /// The label of `opt`. When its shortcut was remapped the underlined
+    /// letter no longer applies, so the key is spelled out instead.
+    fn option_label(&self, opt: &SelectOption) -> Line<'static> {
+        let binding = self.option_binding(opt);
+        if binding == KeyBinding::new(opt.key, KeyModifiers::NONE) {
+            return opt.label.clone();
+        }
+        let mut spans: Vec<Span<'static>> = opt
+            .label
+            .spans
+            .iter()
+            .map(|span| {
+                span.clone()
+                    .style(span.style.remove_modifier(Modifier::UNDERLINED))
+            })
+            .collect();
+        spans.push(format!(" ({})", binding.label()).into());
+        Line::from(spans)
+    }
+
     /// Handle Ctrl-C pressed by the user while the modal is visible.
     /// Behaves like pressing Escape: abort the request and close the modal.
     pub(crate) fn on_ctrl_c(&mut self) {
@@ -232,42 +516,132 @@ impl UserApprovalWidget {
                 self.selected_option = (self.selected_option + 1) % self.select_options.len();
             }
             KeyCode::Enter => {
-                let opt = &self.select_options[self.selected_option];
-                self.send_decision(opt.decision);
+                self.choose_option(self.selected_option);
             }
             KeyCode::Esc => {
                 self.send_decision(ReviewDecision::Abort);
             }
-            other => {
-                let normalized = Self::normalize_keycode(other);
-                if let Some(opt) = self
+            _ => {
+                if let Some(idx) = self
                     .select_options
                     .iter()
-                    .find(|opt| Self::normalize_keycode(opt.key) == normalized)
+                    .position(|opt| self.option_binding(opt).matches(&key_event))
                 {
-                    self.send_decision(opt.decision);
+                    self.choose_option(idx);
                 }
             }
         }
     }
 
-    fn send_decision(&mut self, decision: ReviewDecision) {
-        self.send_decision_with_feedback(decision, String::new())
+    fn choose_option(&mut self, idx: usize) {
+        self.selected_option = idx;
+        match &self.select_options[idx].action {
+            SelectAction::Decide(decision) => self.send_decision(decision.clone()),
+            SelectAction::RememberPrefix(scope) => {
+                if let Some(prefix) = self.suggested_prefix.clone() {
+                    self.send_decision(ReviewDecision::ApprovedForPrefix {
+                        prefix,
+                        scope: *scope,
+                    });
+                }
+            }
+            SelectAction::EditCommand => {
+                if let ApprovalRequest::Exec { command, .. } = &self.approval_request {
+                    self.input_mode = InputMode::EditCommand(strip_bash_lc_and_escape(command));
+                }
+            }
+            SelectAction::Feedback => self.input_mode = InputMode::Feedback(String::new()),
+            SelectAction::ReviewPatch => {
+                if let ApprovalRequest::ApplyPatch { changes, cwd, .. } = &self.approval_request {
+                    self.input_mode = InputMode::ReviewPatch(PatchReview::new(changes, cwd));
+                }
+            }
+        }
+    }
+
+    fn handle_review_key(&mut self, key_event: KeyEvent) {
+        let InputMode::ReviewPatch(review) = &mut self.input_mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => review.move_up(),
+            KeyCode::Down | KeyCode::Char('j') => review.move_down(),
+            KeyCode::Char(' ') => review.toggle_selected(),
+            KeyCode::Esc => self.input_mode = InputMode::Select,
+            KeyCode::Enter => {
+                let rejected = review.rejected();
+                let decision = if rejected.is_empty() {
+                    ReviewDecision::Approved
+                } else {
+                    ReviewDecision::ApprovedPartially { rejected }
+                };
+                self.input_mode = InputMode::Select;
+                self.send_decision(decision);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_input_key(&mut self, key_event: KeyEvent) {
+        let text = match &mut self.input_mode {
+            InputMode::Select | InputMode::ReviewPatch(_) => return,
+            InputMode::Feedback(text) | InputMode::EditCommand(text) => text,
+        };
+        match key_event.code {
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                text.push(c);
+            }
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Esc => {
+                // Go back to the option row rather than dropping the request.
+                self.input_mode = InputMode::Select;
+            }
+            KeyCode::Enter => match std::mem::replace(&mut self.input_mode, InputMode::Select) {
+                InputMode::Select | InputMode::ReviewPatch(_) => {}
+                InputMode::Feedback(feedback) => {
+                    let feedback = feedback.trim().to_string();
+                    if feedback.is_empty() {
+                        self.send_decision(ReviewDecision::Abort);
+                    } else {
+                        self.send_decision(ReviewDecision::DeniedWithFeedback { feedback });
+                    }
+                }
+                InputMode::EditCommand(edited) => {
+                    let decision = match &self.approval_request {
+                        ApprovalRequest::Exec { command, .. } => {
+                            edited_command_decision(command, &edited)
+                        }
+                        ApprovalRequest::ApplyPatch { .. }
+                        | ApprovalRequest::McpToolCall { .. }
+                        | ApprovalRequest::McpSampling { .. } => ReviewDecision::Abort,
+                    };
+                    self.send_decision(decision);
+                }
+            },
+            _ => {}
+        }
     }
 
-    fn send_decision_with_feedback(&mut self, decision: ReviewDecision, feedback: String) {
+    fn send_decision(&mut self, decision: ReviewDecision) {
         match &self.approval_request {
             ApprovalRequest::Exec { command, .. } => {
                 let cmd = strip_bash_lc_and_escape(command);
                 // TODO: move this rendering into history_cell.
                 let mut lines: Vec<Line<'static>> = vec![];
 
+                let feedback = match &decision {
+                    ReviewDecision::DeniedWithFeedback { feedback } => feedback.as_str(),
+                    _ => "",
+                };
+
                 // Result line based on decision.
-                match decision {
+                match &decision {
                     ReviewDecision::Approved => {
                         lines.extend(to_command_display(
                             vec![
-                                "✔ ".fg(Color::Green),
+                                "✔ ".success(),
                                 "You ".into(),
                                 "approved".bold(),
                                 " codex to run ".into(),
@@ -276,10 +650,22 @@ impl UserApprovalWidget {
                             vec![" this time".bold()],
                         ));
                     }
+                    ReviewDecision::ApprovedWithEdits { command } => {
+                        lines.extend(to_command_display(
+                            vec![
+                                "✔ ".success(),
+                                "You ".into(),
+                                "edited".bold(),
+                                " the command and approved codex to run ".into(),
+                            ],
+                            strip_bash_lc_and_escape(command),
+                            vec![],
+                        ));
+                    }
                     ReviewDecision::ApprovedForSession => {
                         lines.extend(to_command_display(
                             vec![
-                                "✔ ".fg(Color::Green),
+                                "✔ ".success(),
                                 "You ".into(),
                                 "approved".bold(),
                                 " codex to run ".into(),
@@ -288,10 +674,33 @@ impl UserApprovalWidget {
                             vec![" every time this session".bold()],
                         ));
                     }
-                    ReviewDecision::Denied => {
+                    ReviewDecision::ApprovedForPrefix { prefix, scope } => {
+                        let where_ = match scope {
+                            ApprovalRuleScope::Project => " in this project",
+                            ApprovalRuleScope::User => " in every project",
+                        };
+                        lines.extend(to_command_display(
+                            vec![
+                                "✔ ".success(),
+                                "You ".into(),
+                                "approved".bold(),
+                                " codex to run ".into(),
+                            ],
+                            cmd,
+                            vec![
+                                " and anything starting with ".into(),
+                                strip_bash_lc_and_escape(prefix).bold(),
+                                where_.bold(),
+                            ],
+                        ));
+                    }
+                    // Partial approval only applies to patches.
+                    ReviewDecision::Denied
+                    | ReviewDecision::DeniedWithFeedback { .. }
+                    | ReviewDecision::ApprovedPartially { .. } => {
                         lines.extend(to_command_display(
                             vec![
-                                "✗ ".fg(Color::Red),
+                                "✗ ".error(),
                                 "You ".into(),
                                 "did not approve".bold(),
                                 " codex to run ".into(),
@@ -303,7 +712,7 @@ impl UserApprovalWidget {
                     ReviewDecision::Abort => {
                         lines.extend(to_command_display(
                             vec![
-                                "✗ ".fg(Color::Red),
+                                "✗ ".error(),
                                 "You ".into(),
                                 "canceled".bold(),
                                 " the request to run ".into(),
@@ -328,6 +737,86 @@ impl UserApprovalWidget {
             ApprovalRequest::ApplyPatch { .. } => {
                 // No history line for patch approval decisions.
             }
+            ApprovalRequest::McpToolCall { server, tool, .. } => {
+                let tool = format!("{server}/{tool}").secondary();
+                let mut lines: Vec<Line<'static>> = vec![match &decision {
+                    ReviewDecision::Approved => Line::from(vec![
+                        "✔ ".success(),
+                        "You ".into(),
+                        "approved".bold(),
+                        " codex to call ".into(),
+                        tool,
+                        " this time".bold(),
+                    ]),
+                    ReviewDecision::ApprovedForSession => Line::from(vec![
+                        "✔ ".success(),
+                        "You ".into(),
+                        "approved".bold(),
+                        " codex to call ".into(),
+                        tool,
+                        " every time this session".bold(),
+                    ]),
+                    ReviewDecision::Abort => Line::from(vec![
+                        "✗ ".error(),
+                        "You ".into(),
+                        "canceled".bold(),
+                        " the request to call ".into(),
+                        tool,
+                    ]),
+                    _ => Line::from(vec![
+                        "✗ ".error(),
+                        "You ".into(),
+                        "did not approve".bold(),
+                        " codex to call ".into(),
+                        tool,
+                    ]),
+                }];
+                if let ReviewDecision::DeniedWithFeedback { feedback } = &decision {
+                    lines.push(Line::from("feedback:"));
+                    for l in feedback.lines() {
+                        lines.push(Line::from(l.to_string()));
+                    }
+                }
+
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_user_approval_decision(lines),
+                )));
+            }
+            ApprovalRequest::McpSampling { server, .. } => {
+                let server = server.clone().secondary();
+                let line = match &decision {
+                    ReviewDecision::Approved => Line::from(vec![
+                        "✔ ".success(),
+                        "You ".into(),
+                        "allowed".bold(),
+                        " ".into(),
+                        server,
+                        " to use the model".into(),
+                        " this time".bold(),
+                    ]),
+                    ReviewDecision::ApprovedForSession => Line::from(vec![
+                        "✔ ".success(),
+                        "You ".into(),
+                        "allowed".bold(),
+                        " ".into(),
+                        server,
+                        " to use the model".into(),
+                        " every time this session".bold(),
+                    ]),
+                    _ => Line::from(vec![
+                        "✗ ".error(),
+                        "You ".into(),
+                        "did not allow".bold(),
+                        " ".into(),
+                        server,
+                        " to use the model".into(),
+                    ]),
+                };
+
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_user_approval_decision(vec![line]),
+                )));
+            }
         }
 
         let op = match &self.approval_request {
@@ -339,6 +828,14 @@ impl UserApprovalWidget {
                 id: id.clone(),
                 decision,
             },
+            ApprovalRequest::McpToolCall { id, .. } => Op::McpToolCallApproval {
+                id: id.clone(),
+                decision,
+            },
+            ApprovalRequest::McpSampling { id, .. } => Op::McpSamplingApproval {
+                id: id.clone(),
+                decision,
+            },
         };
 
         self.app_event_tx.send(AppEvent::CodexOp(op));
@@ -356,45 +853,134 @@ impl UserApprovalWidget {
         // - 1 title line ("Allow command?" or "Apply changes?")
         // - 1 buttons line (options rendered horizontally on a single row)
         // - 1 description line (context for the currently selected option)
-        self.get_confirmation_prompt_height(width) + 3
+        //
+        // While reviewing a patch the prompt is replaced by the review list,
+        // framed by the title and a hint line.
+        match &self.input_mode {
+            InputMode::ReviewPatch(review) => review.desired_height(width.saturating_sub(2)) + 2,
+            _ => self.get_confirmation_prompt_height(width) + 3,
+        }
     }
 }
 
 impl WidgetRef for &UserApprovalWidget {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let InputMode::ReviewPatch(review) = &self.input_mode {
+            let [title_area, review_area, hint_area] = Layout::vertical([
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ])
+            .areas(area.inner(Margin::new(1, 0)));
+            Line::from("Select the changes to apply:").render(title_area, buf);
+            review.render(review_area, buf);
+            Line::from("↑/↓ to move · Space to toggle · Enter to apply · Esc to go back")
+                .style(Style::new().italic().secondary())
+                .render(hint_area.inner(Margin::new(1, 0)), buf);
+            Block::bordered()
+                .border_type(BorderType::QuadrantOutside)
+                .border_style(Style::default().accent())
+                .borders(Borders::LEFT)
+                .render_ref(Rect::new(0, area.y, 1, area.height), buf);
+            return;
+        }
+
         let prompt_height = self.get_confirmation_prompt_height(area.width);
         let [prompt_chunk, response_chunk] = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(prompt_height), Constraint::Min(0)])
             .areas(area);
 
+        let [title_area, button_area, description_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(response_chunk.inner(Margin::new(1, 0)));
+
+        self.confirmation_prompt.clone().render(prompt_chunk, buf);
+
+        match &self.input_mode {
+            InputMode::Select => {
+                let title = match &self.approval_request {
+                    ApprovalRequest::Exec { .. } => "Allow command?",
+                    ApprovalRequest::ApplyPatch { .. } => "Apply changes?",
+                    ApprovalRequest::McpToolCall { .. } => "Allow tool call?",
+                    ApprovalRequest::McpSampling { .. } => "Allow model request?",
+                };
+                Line::from(title).render(title_area, buf);
+                self.render_options(button_area, description_area, buf);
+            }
+            InputMode::Feedback(text) => {
+                Line::from("Tell Codex what to do differently:").render(title_area, buf);
+                render_text_input(
+                    text,
+                    "Enter to send · Esc to go back",
+                    button_area,
+                    description_area,
+                    buf,
+                );
+            }
+            // Rendered above, in place of the whole modal.
+            InputMode::ReviewPatch(_) => {}
+            InputMode::EditCommand(text) => {
+                Line::from("Edit the command to run:").render(title_area, buf);
+                render_text_input(
+                    text,
+                    "Enter to approve · Esc to go back",
+                    button_area,
+                    description_area,
+                    buf,
+                );
+            }
+        }
+
+        Block::bordered()
+            .border_type(BorderType::QuadrantOutside)
+            .border_style(Style::default().accent())
+            .borders(Borders::LEFT)
+            .render_ref(
+                Rect::new(0, response_chunk.y, 1, response_chunk.height),
+                buf,
+            );
+    }
+}
+
+impl UserApprovalWidget {
+    fn option_description(&self, opt: &SelectOption) -> String {
+        match (&opt.action, &self.suggested_prefix) {
+            (SelectAction::RememberPrefix(scope), Some(prefix)) => {
+                let prefix = strip_bash_lc_and_escape(prefix);
+                match scope {
+                    ApprovalRuleScope::Project => {
+                        format!("Always allow commands starting with `{prefix}` in this project")
+                    }
+                    ApprovalRuleScope::User => {
+                        format!("Always allow commands starting with `{prefix}` in every project")
+                    }
+                }
+            }
+            _ => opt.description.to_string(),
+        }
+    }
+
+    fn render_options(&self, button_area: Rect, description_area: Rect, buf: &mut Buffer) {
         let lines: Vec<Line> = self
             .select_options
             .iter()
             .enumerate()
             .map(|(idx, opt)| {
                 let style = if idx == self.selected_option {
-                    Style::new().bg(Color::Cyan).fg(Color::Black)
+                    Style::new().on_accent().fg(Color::Black)
                 } else {
-                    Style::new().add_modifier(Modifier::DIM)
+                    Style::new().secondary()
                 };
-                opt.label.clone().alignment(Alignment::Center).style(style)
+                self.option_label(opt)
+                    .alignment(Alignment::Center)
+                    .style(style)
             })
             .collect();
 
-        let [title_area, button_area, description_area] = Layout::vertical([
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Min(0),
-        ])
-        .areas(response_chunk.inner(Margin::new(1, 0)));
-        let title = match &self.approval_request {
-            ApprovalRequest::Exec { .. } => "Allow command?",
-            ApprovalRequest::ApplyPatch { .. } => "Apply changes?",
-        };
-        Line::from(title).render(title_area, buf);
-
-        self.confirmation_prompt.clone().render(prompt_chunk, buf);
         let areas = Layout::horizontal(
             lines
                 .iter()
@@ -407,21 +993,28 @@ impl WidgetRef for &UserApprovalWidget {
             line.render(*area, buf);
         }
 
-        Line::from(self.select_options[self.selected_option].description)
-            .style(Style::new().italic().add_modifier(Modifier::DIM))
+        Line::from(self.option_description(self.select_options[self.selected_option]))
+            .style(Style::new().italic().secondary())
             .render(description_area.inner(Margin::new(1, 0)), buf);
-
-        Block::bordered()
-            .border_type(BorderType::QuadrantOutside)
-            .border_style(Style::default().fg(Color::Cyan))
-            .borders(Borders::LEFT)
-            .render_ref(
-                Rect::new(0, response_chunk.y, 1, response_chunk.height),
-                buf,
-            );
     }
 }
 
+/// Render a single-line text input with a trailing cursor and a hint below.
+/// Long input is scrolled so the end (where the cursor is) stays visible.
+fn render_text_input(text: &str, hint: &str, input_area: Rect, hint_area: Rect, buf: &mut Buffer) {
+    let available = usize::from(input_area.width).saturating_sub(3);
+    let visible: String = {
+        let chars: Vec<char> = text.chars().collect();
+        chars[chars.len().saturating_sub(available)..]
+            .iter()
+            .collect()
+    };
+    Line::from(vec!["> ".accent(), visible.into(), "█".secondary()]).render(input_area, buf);
+    Line::from(hint.to_string())
+        .style(Style::new().italic().secondary())
+        .render(hint_area.inner(Margin::new(1, 0)), buf);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,4 +1072,308 @@ mod tests {
             })
         )));
     }
+
+    #[test]
+    fn remapped_shortcuts_replace_the_defaults() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "3".to_string(),
+            command: vec!["echo".to_string()],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.set_keymap(Keymap {
+            approve: KeyBinding::new(KeyCode::Char('o'), KeyModifiers::NONE),
+            ..Keymap::default()
+        });
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!widget.is_complete());
+        let label = widget.option_label(widget.select_options[0]);
+        assert_eq!(label.to_string(), "Yes (o)");
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        assert!(drain_ops(&mut rx).iter().any(|op| matches!(
+            op,
+            Op::ExecApproval {
+                decision: ReviewDecision::Approved,
+                ..
+            }
+        )));
+    }
+
+    fn drain_ops(rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>) -> Vec<Op> {
+        let mut ops = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(op) = ev {
+                ops.push(op);
+            }
+        }
+        ops
+    }
+
+    fn type_text(widget: &mut UserApprovalWidget, text: &str) {
+        for c in text.chars() {
+            widget.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn deny_with_feedback_sends_feedback() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "3".to_string(),
+            command: vec!["rm".to_string(), "-rf".to_string(), "build".to_string()],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert!(!widget.is_complete());
+        type_text(&mut widget, "use cargo clean");
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::ExecApproval {
+                decision: ReviewDecision::DeniedWithFeedback { feedback },
+                ..
+            }] if feedback == "use cargo clean"
+        ));
+    }
+
+    #[test]
+    fn mcp_tool_call_can_be_approved_for_the_session() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::McpToolCall {
+            id: "6".to_string(),
+            server: "tickets".to_string(),
+            tool: "delete_ticket".to_string(),
+            arguments: Some(serde_json::json!({ "id": 42 })),
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        assert_eq!(widget.select_options.len(), 3);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::McpToolCallApproval {
+                id,
+                decision: ReviewDecision::ApprovedForSession,
+            }] if id == "6"
+        ));
+    }
+
+    #[test]
+    fn mcp_sampling_can_be_rejected() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::McpSampling {
+            id: "7".to_string(),
+            server: "tickets".to_string(),
+            prompt: "user: summarize ticket 42".to_string(),
+            system_prompt: None,
+            max_tokens: 100,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::McpSamplingApproval {
+                id,
+                decision: ReviewDecision::Denied,
+            }] if id == "7"
+        ));
+    }
+
+    #[test]
+    fn review_rejects_selected_hunk() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Update {
+                unified_diff: "@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n@@ -9,3 +9,3 @@\n nine\n-ten\n+10\n eleven\n"
+                    .to_string(),
+                move_path: None,
+            },
+        );
+        let req = ApprovalRequest::ApplyPatch {
+            id: "5".to_string(),
+            reason: None,
+            grant_root: None,
+            changes,
+            cwd: PathBuf::from("/repo"),
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        // Rows: a.txt, hunk 0, hunk 1.
+        widget.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::PatchApproval {
+                decision: ReviewDecision::ApprovedPartially { rejected },
+                ..
+            }] if rejected.len() == 1 && rejected[0].hunks == Some(vec![0])
+        ));
+    }
+
+    #[test]
+    fn empty_feedback_aborts() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::ApplyPatch {
+            id: "4".to_string(),
+            reason: None,
+            grant_root: None,
+            changes: HashMap::new(),
+            cwd: PathBuf::from("/repo"),
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::PatchApproval {
+                decision: ReviewDecision::Abort,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn esc_in_feedback_returns_to_options() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "5".to_string(),
+            command: vec!["echo".to_string()],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!widget.is_complete());
+        assert!(drain_ops(&mut rx).is_empty());
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(widget.is_complete());
+    }
+
+    #[test]
+    fn edited_command_is_approved_with_edits() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "6".to_string(),
+            command: vec!["ls".to_string(), "-l".to_string()],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        type_text(&mut widget, "a");
+        assert!(widget.handle_paste(" 'my dir'".to_string()));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        let expected = vec!["ls".to_string(), "-a".to_string(), "my dir".to_string()];
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::ExecApproval {
+                decision: ReviewDecision::ApprovedWithEdits { command },
+                ..
+            }] if *command == expected
+        ));
+    }
+
+    #[test]
+    fn edited_command_decision_handles_scripts_and_no_op_edits() {
+        let original = vec!["bash".to_string(), "-lc".to_string(), "echo hi".to_string()];
+        assert_eq!(
+            edited_command_decision(&original, "echo bye && ls"),
+            ReviewDecision::ApprovedWithEdits {
+                command: vec![
+                    "bash".to_string(),
+                    "-lc".to_string(),
+                    "echo bye && ls".to_string()
+                ],
+            }
+        );
+        assert_eq!(
+            edited_command_decision(&original, "echo hi"),
+            ReviewDecision::Approved
+        );
+        assert_eq!(
+            edited_command_decision(&original, "  "),
+            ReviewDecision::Abort
+        );
+    }
+
+    #[test]
+    fn project_shortcut_approves_suggested_prefix() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "7".to_string(),
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "npm test -- --watch=false".to_string(),
+            ],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        assert!(widget.is_complete());
+        let ops = drain_ops(&mut rx);
+        let expected = vec!["npm".to_string(), "test".to_string()];
+        assert!(matches!(
+            ops.as_slice(),
+            [Op::ExecApproval {
+                decision: ReviewDecision::ApprovedForPrefix {
+                    prefix,
+                    scope: ApprovalRuleScope::Project,
+                },
+                ..
+            }] if *prefix == expected
+        ));
+    }
+
+    #[test]
+    fn prefix_options_are_hidden_for_complex_scripts() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let req = ApprovalRequest::Exec {
+            id: "8".to_string(),
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "npm test > out.txt".to_string(),
+            ],
+            reason: None,
+        };
+        let mut widget = UserApprovalWidget::new(req, tx);
+        assert!(
+            widget
+                .select_options
+                .iter()
+                .all(|opt| !matches!(opt.action, SelectAction::RememberPrefix(_)))
+        );
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert!(!widget.is_complete());
+    }
 }